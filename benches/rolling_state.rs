@@ -0,0 +1,112 @@
+//! Rolling state throughput benchmark
+//!
+//! Compares the incremental `TokenRollingState` against a naive reference
+//! that mirrors the previous implementation: one cloned `Vec` per window,
+//! `retain` on every eviction and full rescans to compute metrics.
+//!
+//! Run with `cargo bench --bench rolling_state`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use solflow::{
+    state::{TokenRollingState, DEFAULT_WINDOWS},
+    types::{TradeDirection, TradeEvent},
+};
+use std::collections::HashSet;
+
+/// Previous design: every window owns a copy of its trades
+struct NaiveRollingState {
+    windows: Vec<(i64, Vec<TradeEvent>)>,
+}
+
+impl NaiveRollingState {
+    fn new() -> Self {
+        Self {
+            windows: DEFAULT_WINDOWS.iter().map(|&secs| (secs, Vec::new())).collect(),
+        }
+    }
+
+    fn add_trade(&mut self, trade: TradeEvent) {
+        for (_, trades) in &mut self.windows {
+            trades.push(trade.clone());
+        }
+    }
+
+    fn evict_old_trades(&mut self, now: i64) {
+        for (secs, trades) in &mut self.windows {
+            let cutoff = now - *secs;
+            trades.retain(|t| t.timestamp >= cutoff);
+        }
+    }
+
+    fn compute(&self) -> (f64, usize) {
+        let mut net_flow = 0.0;
+        for (_, trades) in &self.windows {
+            for trade in trades {
+                match trade.direction {
+                    TradeDirection::Buy => net_flow += trade.sol_amount,
+                    TradeDirection::Sell => net_flow -= trade.sol_amount,
+                    TradeDirection::Unknown => {}
+                }
+            }
+        }
+        let wallets: HashSet<&str> = self.windows[1].1.iter().map(|t| t.user_account.as_str()).collect();
+        (net_flow, wallets.len())
+    }
+}
+
+/// Hot token: one trade every 500ms from a pool of 400 wallets
+fn make_trades(count: usize) -> Vec<TradeEvent> {
+    (0..count)
+        .map(|i| TradeEvent {
+            timestamp: 1_700_000_000 + (i / 2) as i64,
+            mint: "bench_mint".to_string(),
+            direction: if i % 3 == 0 { TradeDirection::Sell } else { TradeDirection::Buy },
+            sol_amount: 0.1 + (i % 17) as f64 * 0.05,
+            token_amount: 1_000_000.0,
+            token_decimals: 6,
+            user_account: format!("wallet_{}", i % 400),
+            source_program: if i % 25 == 0 { "JupiterDCA" } else { "PumpSwap" }.to_string(),
+            is_dca: i % 25 == 0,
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn bench_rolling_state(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rolling_state");
+    group.sample_size(10);
+
+    for &count in &[1_000usize, 10_000] {
+        let trades = make_trades(count);
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(BenchmarkId::new("incremental", count), &trades, |b, trades| {
+            b.iter(|| {
+                let mut state = TokenRollingState::new("bench_mint".to_string());
+                for trade in trades {
+                    let now = trade.timestamp;
+                    state.add_trade(trade.clone());
+                    state.evict_old_trades(now);
+                    black_box(state.compute_rolling_metrics());
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("naive", count), &trades, |b, trades| {
+            b.iter(|| {
+                let mut state = NaiveRollingState::new();
+                for trade in trades {
+                    let now = trade.timestamp;
+                    state.add_trade(trade.clone());
+                    state.evict_old_trades(now);
+                    black_box(state.compute());
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_rolling_state);
+criterion_main!(benches);
@@ -0,0 +1,47 @@
+/**
+ * GET /api/candles?mint=X&interval=60&limit=120
+ * Returns closed OHLCV candles for a token, oldest first
+ */
+
+import { NextResponse } from 'next/server';
+import { getTokenCandles } from '@/lib/server/db';
+import type { CandlesResponse } from '@/lib/types';
+
+export const dynamic = 'force-dynamic';
+
+const INTERVALS = [1, 60, 300, 3600];
+
+export async function GET(request: Request) {
+  try {
+    const { searchParams } = new URL(request.url);
+    const mint = searchParams.get('mint');
+    const interval = parseInt(searchParams.get('interval') || '60');
+    const limit = parseInt(searchParams.get('limit') || '120');
+
+    if (!mint) {
+      return NextResponse.json(
+        { error: 'Missing mint parameter' },
+        { status: 400 }
+      );
+    }
+
+    if (!INTERVALS.includes(interval)) {
+      return NextResponse.json(
+        { error: `Invalid interval (expected one of ${INTERVALS.join(', ')})` },
+        { status: 400 }
+      );
+    }
+
+    const response: CandlesResponse = {
+      candles: getTokenCandles(mint, interval, limit),
+    };
+
+    return NextResponse.json(response);
+  } catch (error) {
+    console.error('Candles API error:', error);
+    return NextResponse.json(
+      { error: 'Failed to fetch candles' },
+      { status: 500 }
+    );
+  }
+}
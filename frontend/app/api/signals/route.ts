@@ -1,6 +1,8 @@
 /**
  * GET /api/signals?mint=X
- * Returns signals for a specific token or recent signals across all tokens
+ * Returns signals for a specific token or recent signals across all tokens.
+ * Only entered/updated rows are returned; pass includeExited=true with a mint
+ * for its full history.
  */
 
 import { NextResponse } from 'next/server';
@@ -16,12 +18,13 @@ export async function GET(request: Request) {
     const minStrength = parseFloat(searchParams.get('minStrength') || '0.0');
     const limit = parseInt(searchParams.get('limit') || '50');
     const minAge = parseInt(searchParams.get('minAge') || '1800');
+    const includeExited = searchParams.get('includeExited') === 'true';
 
     let signals;
 
     if (mint) {
       // Get signals for specific token
-      signals = getTokenSignals(mint, limit);
+      signals = getTokenSignals(mint, limit, includeExited);
     } else {
       // Get recent signals across all tokens
       signals = getRecentSignals(minStrength, limit, minAge);
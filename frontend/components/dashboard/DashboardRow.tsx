@@ -67,6 +67,14 @@ export function DashboardRow({ token, isFollowed }: DashboardRowProps) {
             {token.name}
           </div>
         )}
+        {token.creator_risk != null && token.creator_risk >= 0.5 && (
+          <div
+            className="text-xs text-red-500"
+            title={`Creator: ${token.creator_tokens_dead ?? 0} of ${token.creator_tokens_finished ?? 0} past tokens went to zero`}
+          >
+            ⚠️ dev risk {Math.round(token.creator_risk * 100)}%
+          </div>
+        )}
       </td>
 
       {/* Net Flow 15m */}
@@ -136,7 +144,7 @@ export function DashboardRow({ token, isFollowed }: DashboardRowProps) {
 
       {/* Sparkline */}
       <td className="px-3 py-2">
-        <FlowSparkline flows={netFlows} />
+        <FlowSparkline flows={netFlows} prices={token.price_closes} />
       </td>
     </tr>
   );
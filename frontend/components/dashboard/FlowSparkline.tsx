@@ -1,16 +1,21 @@
 /**
  * Flow Sparkline - Client Component
- * Minimal SVG sparkline showing flow trend across 6 windows
+ * Minimal SVG sparkline showing the price trend from our own 5m candles,
+ * falling back to the flow trend across windows when no candles exist yet
  */
 
 'use client';
 
 interface FlowSparklineProps {
   flows: number[]; // [60s, 300s, 900s, 3600s, 7200s, 14400s]
+  prices?: number[]; // candle closes, oldest first
 }
 
-export function FlowSparkline({ flows }: FlowSparklineProps) {
-  if (!flows || flows.length === 0) {
+export function FlowSparkline({ flows, prices }: FlowSparklineProps) {
+  const showPrices = !!prices && prices.length >= 2;
+  const values = showPrices && prices ? prices : flows;
+
+  if (!values || values.length === 0) {
     return <div className="w-20 h-8" />;
   }
 
@@ -18,23 +23,28 @@ export function FlowSparkline({ flows }: FlowSparklineProps) {
   const height = 32;
   const padding = 2;
 
-  // Normalize values
-  const maxAbsValue = Math.max(...flows.map(Math.abs), 1);
-  const points = flows.map((value, i) => {
-    const x = padding + (i / (flows.length - 1)) * (width - 2 * padding);
-    const normalizedValue = value / maxAbsValue;
-    const y = height / 2 - (normalizedValue * (height / 2 - padding));
+  // Normalize values: prices span min..max, flows are centered on zero
+  const minPrice = Math.min(...values);
+  const priceRange = Math.max(...values) - minPrice;
+  const maxAbsValue = Math.max(...values.map(Math.abs), 1);
+  const points = values.map((value, i) => {
+    const x = padding + (i / Math.max(values.length - 1, 1)) * (width - 2 * padding);
+    const y = showPrices
+      ? priceRange > 0
+        ? height - padding - ((value - minPrice) / priceRange) * (height - 2 * padding)
+        : height / 2
+      : height / 2 - (value / maxAbsValue) * (height / 2 - padding);
     return `${x},${y}`;
   });
 
-  const pathData = `M ${points.join(' L ')}`;
-
   // Determine overall trend color
-  const lastFlow = flows[flows.length - 1];
+  const trend = showPrices
+    ? values[values.length - 1] - values[0]
+    : values[values.length - 1];
   const strokeColor =
-    lastFlow > 0
+    trend > 0
       ? 'rgb(34, 197, 94)' // green
-      : lastFlow < 0
+      : trend < 0
       ? 'rgb(239, 68, 68)' // red
       : 'rgb(156, 163, 175)'; // gray
 
@@ -46,17 +56,19 @@ export function FlowSparkline({ flows }: FlowSparklineProps) {
       viewBox={`0 0 ${width} ${height}`}
     >
       {/* Zero line */}
-      <line
-        x1={padding}
-        y1={height / 2}
-        x2={width - padding}
-        y2={height / 2}
-        stroke="currentColor"
-        strokeWidth="1"
-        strokeOpacity="0.1"
-      />
+      {!showPrices && (
+        <line
+          x1={padding}
+          y1={height / 2}
+          x2={width - padding}
+          y2={height / 2}
+          stroke="currentColor"
+          strokeWidth="1"
+          strokeOpacity="0.1"
+        />
+      )}
 
-      {/* Flow line */}
+      {/* Price / flow line */}
       <polyline
         points={points.join(' ')}
         fill="none"
@@ -11,6 +11,12 @@ import {
   Users,
   Activity,
   AlertTriangle,
+  Wallet,
+  Anchor,
+  TrendingDown,
+  Shuffle,
+  Crosshair,
+  UserX,
 } from 'lucide-react';
 import { formatStrength } from '@/lib/client/format';
 
@@ -45,6 +51,36 @@ const signalConfig = {
     icon: AlertTriangle,
     className: 'bg-red-500/10 text-red-500 border-red-500/30',
   },
+  SMART_MONEY_ENTRY: {
+    label: 'Smart $',
+    icon: Wallet,
+    className: 'bg-yellow-500/10 text-yellow-500 border-yellow-500/30',
+  },
+  WHALE_ACCUMULATION: {
+    label: 'Whale Buy',
+    icon: Anchor,
+    className: 'bg-cyan-500/10 text-cyan-500 border-cyan-500/30',
+  },
+  WHALE_DISTRIBUTION: {
+    label: 'Whale Dump',
+    icon: TrendingDown,
+    className: 'bg-pink-500/10 text-pink-500 border-pink-500/30',
+  },
+  WASH_TRADING: {
+    label: 'Wash',
+    icon: Shuffle,
+    className: 'bg-zinc-500/10 text-zinc-400 border-zinc-500/30',
+  },
+  SNIPER_DUMP: {
+    label: 'Sniper Dump',
+    icon: Crosshair,
+    className: 'bg-rose-500/10 text-rose-500 border-rose-500/30',
+  },
+  DEV_SELL: {
+    label: 'Dev Sell',
+    icon: UserX,
+    className: 'bg-red-600/10 text-red-600 border-red-600/40',
+  },
 };
 
 export function SignalBadge({ type, strength }: SignalBadgeProps) {
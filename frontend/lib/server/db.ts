@@ -9,6 +9,7 @@ import type {
   TokenRollingMetrics,
   TokenSignal,
   TokenTrade,
+  TokenCandle,
   DashboardToken,
 } from '../types';
 
@@ -78,15 +79,20 @@ export function getDashboardTokens(
         tm.market_cap,
         tm.token_age,
         (SELECT signal_type 
-         FROM token_signals 
+         FROM dashboard_signals 
          WHERE mint = trm.mint 
-         ORDER BY timestamp DESC LIMIT 1) as latest_signal_type,
+         ORDER BY priority DESC, strength DESC LIMIT 1) as latest_signal_type,
         (SELECT strength 
-         FROM token_signals 
+         FROM dashboard_signals 
          WHERE mint = trm.mint 
-         ORDER BY timestamp DESC LIMIT 1) as latest_signal_strength
+         ORDER BY priority DESC, strength DESC LIMIT 1) as latest_signal_strength,
+        cr.risk_score as creator_risk,
+        cr.tokens_finished as creator_tokens_finished,
+        cr.tokens_dead as creator_tokens_dead
       FROM token_rolling_metrics trm
       LEFT JOIN token_metadata tm ON trm.mint = tm.mint
+      LEFT JOIN creator_tokens ct ON trm.mint = ct.mint
+      LEFT JOIN creator_reputation cr ON ct.creator = cr.creator
       WHERE trm.updated_at >= ?
         AND NOT EXISTS (SELECT 1 FROM blocklist WHERE mint = trm.mint)
       ORDER BY trm.net_flow_300s DESC
@@ -95,6 +101,7 @@ export function getDashboardTokens(
 
     const stmt = db.prepare(query);
     const rows = stmt.all(cutoffTime, limit) as any[];
+    const sparklines = getPriceSparklines(rows.map((row) => row.mint));
 
     return rows.map((row) => ({
     mint: row.mint,
@@ -119,6 +126,10 @@ export function getDashboardTokens(
     token_age: row.token_age !== null ? row.token_age : null,
     latest_signal_type: row.latest_signal_type || null,
     latest_signal_strength: row.latest_signal_strength || null,
+    price_closes: sparklines[row.mint],
+    creator_risk: row.creator_risk ?? null,
+    creator_tokens_finished: row.creator_tokens_finished ?? null,
+    creator_tokens_dead: row.creator_tokens_dead ?? null,
   }));
   } catch (error) {
     console.error('getDashboardTokens error:', error);
@@ -196,18 +207,27 @@ export function getTokenMetrics(mint: string): TokenRollingMetrics | null {
   }
 }
 
-export function getTokenSignals(mint: string, limit: number = 20): TokenSignal[] {
+/**
+ * Signals of one token, newest first. `exited` rows (a signal ending) are
+ * left out unless `includeExited` is set, so only live signals are listed.
+ */
+export function getTokenSignals(
+  mint: string,
+  limit: number = 20,
+  includeExited: boolean = false
+): TokenSignal[] {
   try {
     const db = getDb();
     const query = `
       SELECT * FROM token_signals 
       WHERE mint = ? 
+        AND (? OR event != 'exited')
       ORDER BY timestamp DESC 
       LIMIT ?
     `;
 
     const stmt = db.prepare(query);
-    const rows = stmt.all(mint, limit) as any[];
+    const rows = stmt.all(mint, includeExited ? 1 : 0, limit) as any[];
 
     return rows.map((row) => ({
       id: row.id,
@@ -218,6 +238,7 @@ export function getTokenSignals(mint: string, limit: number = 20): TokenSignal[]
       timestamp: row.timestamp,
       metadata: JSON.parse(row.metadata || '{}'),
       created_at: row.created_at,
+      event: row.event ?? 'entered',
     }));
   } catch (error) {
     console.error('getTokenSignals error:', error);
@@ -254,6 +275,93 @@ export function getTokenTrades(mint: string, limit: number = 50): TokenTrade[] {
   }
 }
 
+// ═══════════════════════════════════════════════════════════════════════
+// Candle Queries
+// ═══════════════════════════════════════════════════════════════════════
+
+export function getTokenCandles(
+  mint: string,
+  intervalSecs: number = 60,
+  limit: number = 120
+): TokenCandle[] {
+  try {
+    const db = getDb();
+    const query = `
+      SELECT * FROM (
+        SELECT * FROM token_candles
+        WHERE mint = ? AND interval_secs = ?
+        ORDER BY open_time DESC
+        LIMIT ?
+      )
+      ORDER BY open_time ASC
+    `;
+
+    const stmt = db.prepare(query);
+    const rows = stmt.all(mint, intervalSecs, limit) as any[];
+
+    return rows.map((row) => ({
+      mint: row.mint,
+      interval_secs: row.interval_secs,
+      open_time: row.open_time,
+      open: row.open,
+      high: row.high,
+      low: row.low,
+      close: row.close,
+      volume_sol: row.volume_sol,
+      buy_volume_sol: row.buy_volume_sol,
+      sell_volume_sol: row.sell_volume_sol,
+      trade_count: row.trade_count,
+    }));
+  } catch (error) {
+    console.error('getTokenCandles error:', error);
+    return [];
+  }
+}
+
+/**
+ * Last `points` candle closes per mint (oldest first), for dashboard sparklines
+ */
+export function getPriceSparklines(
+  mints: string[],
+  intervalSecs: number = 300,
+  points: number = 12
+): Record<string, number[]> {
+  if (mints.length === 0) return {};
+
+  try {
+    const db = getDb();
+    const placeholders = mints.map(() => '?').join(',');
+    const query = `
+      SELECT mint, close FROM (
+        SELECT
+          mint,
+          open_time,
+          close,
+          ROW_NUMBER() OVER (PARTITION BY mint ORDER BY open_time DESC) AS rn
+        FROM token_candles
+        WHERE interval_secs = ? AND mint IN (${placeholders})
+      )
+      WHERE rn <= ?
+      ORDER BY mint, open_time ASC
+    `;
+
+    const stmt = db.prepare(query);
+    const rows = stmt.all(intervalSecs, ...mints, points) as any[];
+
+    const result: Record<string, number[]> = {};
+
+    for (const row of rows) {
+      if (!result[row.mint]) result[row.mint] = [];
+      result[row.mint].push(row.close);
+    }
+
+    return result;
+  } catch (error) {
+    console.error('getPriceSparklines error:', error);
+    return {};
+  }
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // Signal Queries
 // ═══════════════════════════════════════════════════════════════════════
@@ -271,6 +379,7 @@ export function getRecentSignals(
       SELECT * FROM token_signals 
       WHERE timestamp >= ?
         AND strength >= ?
+        AND event != 'exited'
       ORDER BY strength DESC, timestamp DESC
       LIMIT ?
     `;
@@ -287,6 +396,7 @@ export function getRecentSignals(
       timestamp: row.timestamp,
       metadata: JSON.parse(row.metadata || '{}'),
       created_at: row.created_at,
+      event: row.event ?? 'entered',
     }));
   } catch (error) {
     console.error('getRecentSignals error:', error);
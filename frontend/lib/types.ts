@@ -35,6 +35,7 @@ export interface TokenRollingMetrics {
   
   // Advanced metrics (300s window)
   unique_wallets_300s: number;
+  unique_clusters_300s: number; // wallets sharing a funder count once
   bot_wallets_300s: number;
   bot_trades_300s: number;
   bot_flow_300s: number;
@@ -56,12 +57,32 @@ export interface TokenTrade {
   is_dca: boolean;
 }
 
+export interface TokenCandle {
+  mint: string;
+  interval_secs: number; // 1, 60, 300 or 3600
+  open_time: number;
+  open: number; // SOL per token
+  high: number;
+  low: number;
+  close: number;
+  volume_sol: number;
+  buy_volume_sol: number;
+  sell_volume_sol: number;
+  trade_count: number;
+}
+
 export type SignalType = 
   | 'BREAKOUT'
   | 'REACCUMULATION'
   | 'FOCUSED_BUYERS'
   | 'PERSISTENCE'
-  | 'FLOW_REVERSAL';
+  | 'FLOW_REVERSAL'
+  | 'SMART_MONEY_ENTRY'
+  | 'WHALE_ACCUMULATION'
+  | 'WHALE_DISTRIBUTION'
+  | 'WASH_TRADING'
+  | 'SNIPER_DUMP'
+  | 'DEV_SELL';
 
 export interface TokenSignal {
   id: number;
@@ -72,8 +93,11 @@ export interface TokenSignal {
   timestamp: number;
   metadata: SignalMetadata;
   created_at: number;
+  event: SignalEvent; // state transition this row records
 }
 
+export type SignalEvent = 'entered' | 'updated' | 'exited';
+
 // ═══════════════════════════════════════════════════════════════════════
 // Signal Metadata Types
 // ═══════════════════════════════════════════════════════════════════════
@@ -135,8 +159,12 @@ export interface DashboardToken extends TokenRollingMetrics {
   price_usd?: number | null;
   market_cap?: number | null;
   token_age?: number | null;
-  latest_signal_type: SignalType | null;
+  latest_signal_type: SignalType | null; // strongest active signal
   latest_signal_strength: number | null;
+  price_closes?: number[]; // last 5m candle closes, oldest first
+  creator_risk?: number | null; // 0-1, creator's past tokens going to zero
+  creator_tokens_finished?: number | null;
+  creator_tokens_dead?: number | null;
 }
 
 export interface DashboardResponse {
@@ -151,6 +179,10 @@ export interface TokenDetailResponse {
   trades: TokenTrade[];
 }
 
+export interface CandlesResponse {
+  candles: TokenCandle[];
+}
+
 export interface SignalsResponse {
   signals: TokenSignal[];
 }
@@ -246,6 +278,48 @@ export const SIGNAL_CONFIGS: Record<SignalType, SignalBadgeConfig> = {
     bgColor: 'bg-red-500/10',
     textColor: 'text-red-500',
   },
+  SMART_MONEY_ENTRY: {
+    type: 'SMART_MONEY_ENTRY',
+    color: 'yellow',
+    icon: 'Wallet',
+    bgColor: 'bg-yellow-500/10',
+    textColor: 'text-yellow-500',
+  },
+  WHALE_ACCUMULATION: {
+    type: 'WHALE_ACCUMULATION',
+    color: 'cyan',
+    icon: 'Anchor',
+    bgColor: 'bg-cyan-500/10',
+    textColor: 'text-cyan-500',
+  },
+  WHALE_DISTRIBUTION: {
+    type: 'WHALE_DISTRIBUTION',
+    color: 'pink',
+    icon: 'TrendingDown',
+    bgColor: 'bg-pink-500/10',
+    textColor: 'text-pink-500',
+  },
+  WASH_TRADING: {
+    type: 'WASH_TRADING',
+    color: 'zinc',
+    icon: 'Shuffle',
+    bgColor: 'bg-zinc-500/10',
+    textColor: 'text-zinc-400',
+  },
+  SNIPER_DUMP: {
+    type: 'SNIPER_DUMP',
+    color: 'rose',
+    icon: 'Crosshair',
+    bgColor: 'bg-rose-500/10',
+    textColor: 'text-rose-500',
+  },
+  DEV_SELL: {
+    type: 'DEV_SELL',
+    color: 'red',
+    icon: 'UserX',
+    bgColor: 'bg-red-600/10',
+    textColor: 'text-red-600',
+  },
 };
 
 export const STRENGTH_THRESHOLDS = {
@@ -8,8 +8,25 @@
 -- Schema includes:
 --   • token_metadata           - Token information and launch data
 --   • token_rolling_metrics    - Real-time rolling metrics (Phase 4/5)
+--   • token_window_metrics     - Per-window rolling metrics (configurable windows)
+--   • token_venue_metrics      - Per-window metrics broken down by venue
 --   • token_trades             - Append-only trade event log (Phase 5)
+--   • token_candles            - Closed OHLCV candles per mint (1s/1m/5m/1h)
+--   • wallet_positions         - Per-wallet position, cost basis and realized PnL
+--   • token_holder_metrics     - Holder profit / cost basis / overhang per mint
+--   • wallet_scores            - Wallet performance scores and smart-money labels
+--   • wallet_bot_scores        - Bot classifier profile and score per wallet
+--   • market_metrics           - Market-wide aggregates and regime time series
+--   • pool_liquidity           - AMM pool reserve and liquidity history
+--   • token_launches           - Token / pool creations with sniper supply shares
+--   • launch_snipers           - Wallets that sniped a launch and their position
+--   • creator_tokens           - Launched tokens, their creator's sells and outcome
+--   • creator_reputation       - Per-creator dead-token share and median lifetime
 --   • token_signals            - Signal detection engine (Phase 6)
+--   • signal_states            - Per-mint signal state machine (active / cooled down)
+--   • dashboard_signals (view) - Active states plus recent DEV_SELL / SNIPER_DUMP
+--   • blocklist                - Mints ignored by dashboard and ingestion
+--   • followed_tokens          - Followed mints (price polling, allowlist mode)
 -- 
 -- ═══════════════════════════════════════════════════════════════════════
 
@@ -74,7 +91,20 @@ CREATE TABLE IF NOT EXISTS token_rolling_metrics (
     -- DCA metrics (300s window)
     dca_flow_300s               REAL NOT NULL DEFAULT 0.0,
     dca_unique_wallets_300s     INTEGER NOT NULL DEFAULT 0,
-    dca_ratio_300s              REAL NOT NULL DEFAULT 0.0
+    dca_ratio_300s              REAL NOT NULL DEFAULT 0.0,
+
+    -- Smart-money metrics (300s window)
+    smart_money_buys_300s       INTEGER NOT NULL DEFAULT 0,
+    smart_money_flow_300s       REAL NOT NULL DEFAULT 0.0,
+    smart_money_wallets_300s    INTEGER NOT NULL DEFAULT 0,
+
+    -- Wallets sharing a funder count once (300s window)
+    unique_clusters_300s        INTEGER NOT NULL DEFAULT 0,
+
+    -- Pool liquidity (SOL, both sides) and 300s net flow relative to it
+    -- NULL when the mint's pool reserves are unknown
+    liquidity_sol               REAL,
+    flow_liquidity_ratio_300s   REAL
 );
 
 -- Index for time-based queries
@@ -85,11 +115,117 @@ CREATE INDEX IF NOT EXISTS idx_rolling_metrics_updated_at
 CREATE INDEX IF NOT EXISTS idx_rolling_metrics_net_flow_300s
     ON token_rolling_metrics (net_flow_300s DESC);
 
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: token_window_metrics
+-- ═══════════════════════════════════════════════════════════════════════
+-- Full metric family for every configured window (SOLFLOW_WINDOWS)
+-- One row per (mint, window_secs), UPSERT on each trade event
+-- token_rolling_metrics above is kept filled for the dashboard
+
+CREATE TABLE IF NOT EXISTS token_window_metrics (
+    mint                TEXT NOT NULL,
+    window_secs         INTEGER NOT NULL,
+    updated_at          INTEGER NOT NULL,
+
+    -- Flow and trade counts
+    net_flow            REAL NOT NULL DEFAULT 0.0,
+    buy_count           INTEGER NOT NULL DEFAULT 0,
+    sell_count          INTEGER NOT NULL DEFAULT 0,
+    unique_wallets      INTEGER NOT NULL DEFAULT 0,
+
+    -- Gross volume (SOL); volume = buy_volume + sell_volume
+    buy_volume          REAL NOT NULL DEFAULT 0.0,
+    sell_volume         REAL NOT NULL DEFAULT 0.0,
+    volume              REAL NOT NULL DEFAULT 0.0,
+
+    -- Bot metrics
+    bot_wallets         INTEGER NOT NULL DEFAULT 0,
+    bot_trades          INTEGER NOT NULL DEFAULT 0,
+    bot_flow            REAL NOT NULL DEFAULT 0.0,
+
+    -- DCA metrics
+    dca_buys            INTEGER NOT NULL DEFAULT 0,
+    dca_flow            REAL NOT NULL DEFAULT 0.0,
+    dca_unique_wallets  INTEGER NOT NULL DEFAULT 0,
+    dca_ratio           REAL NOT NULL DEFAULT 0.0,
+
+    -- Smart-money metrics (wallets labelled smart_money in wallet_scores)
+    smart_money_buys    INTEGER NOT NULL DEFAULT 0,
+    smart_money_flow    REAL NOT NULL DEFAULT 0.0,
+    smart_money_wallets INTEGER NOT NULL DEFAULT 0,
+
+    -- Trade-size distribution (SOL, sketch quantiles with 2% relative error)
+    buy_size_p50        REAL NOT NULL DEFAULT 0.0,
+    buy_size_p90        REAL NOT NULL DEFAULT 0.0,
+    buy_size_p99        REAL NOT NULL DEFAULT 0.0,
+    sell_size_p50       REAL NOT NULL DEFAULT 0.0,
+    sell_size_p90       REAL NOT NULL DEFAULT 0.0,
+    sell_size_p99       REAL NOT NULL DEFAULT 0.0,
+
+    -- Trades per size class (retail < 1 SOL, mid < 10 SOL, whale >= 10 SOL)
+    retail_buys         INTEGER NOT NULL DEFAULT 0,
+    mid_buys            INTEGER NOT NULL DEFAULT 0,
+    whale_buys          INTEGER NOT NULL DEFAULT 0,
+    retail_sells        INTEGER NOT NULL DEFAULT 0,
+    mid_sells           INTEGER NOT NULL DEFAULT 0,
+    whale_sells         INTEGER NOT NULL DEFAULT 0,
+
+    -- Venue (source program) with the most volume, and its volume share
+    dominant_venue      TEXT NOT NULL DEFAULT '',
+    dominant_venue_share REAL NOT NULL DEFAULT 0.0,
+
+    -- Wash trading (round trips / reversing legs among few wallets);
+    -- organic_* exclude the suspected wash volume and wallets
+    wash_score          REAL NOT NULL DEFAULT 0.0,
+    wash_volume         REAL NOT NULL DEFAULT 0.0,
+    wash_wallets        INTEGER NOT NULL DEFAULT 0,
+    wash_trades         INTEGER NOT NULL DEFAULT 0,
+    organic_volume      REAL NOT NULL DEFAULT 0.0,
+    organic_wallets     INTEGER NOT NULL DEFAULT 0,
+
+    -- Distinct funding clusters (wallets sharing a root funder count once)
+    unique_clusters     INTEGER NOT NULL DEFAULT 0,
+
+    -- 1 while the window still covers time before trade history is complete
+    warming             INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (mint, window_secs)
+);
+
+-- Index for ranking tokens within one window
+CREATE INDEX IF NOT EXISTS idx_window_metrics_window_net_flow
+    ON token_window_metrics (window_secs, net_flow DESC);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: token_venue_metrics
+-- ═══════════════════════════════════════════════════════════════════════
+-- Per-window metrics broken down by venue (source_program), e.g. bonding
+-- curve vs AMM; all rows of a mint are replaced on every metrics update
+-- dominant: 1 for the venue with the most volume in the window
+
+CREATE TABLE IF NOT EXISTS token_venue_metrics (
+    mint                TEXT NOT NULL,
+    window_secs         INTEGER NOT NULL,
+    venue               TEXT NOT NULL,
+    updated_at          INTEGER NOT NULL,
+    net_flow            REAL NOT NULL DEFAULT 0.0,
+    buy_volume          REAL NOT NULL DEFAULT 0.0,
+    sell_volume         REAL NOT NULL DEFAULT 0.0,
+    volume              REAL NOT NULL DEFAULT 0.0,
+    buy_count           INTEGER NOT NULL DEFAULT 0,
+    sell_count          INTEGER NOT NULL DEFAULT 0,
+    unique_wallets      INTEGER NOT NULL DEFAULT 0,
+    volume_share        REAL NOT NULL DEFAULT 0.0,
+    dominant            INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (mint, window_secs, venue)
+);
+
 -- ═══════════════════════════════════════════════════════════════════════
 -- TABLE: token_trades
 -- ═══════════════════════════════════════════════════════════════════════
 -- Append-only trade event log for historical analysis
 -- Stores all trades with bot/DCA flags from Phase 4
+-- Replayed on startup to rebuild the last 4h of rolling state
 
 CREATE TABLE IF NOT EXISTS token_trades (
     id                  INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -99,7 +235,21 @@ CREATE TABLE IF NOT EXISTS token_trades (
     side                TEXT NOT NULL,  -- 'buy' or 'sell'
     sol_amount          REAL NOT NULL,
     is_bot              INTEGER NOT NULL DEFAULT 0,  -- 0 = false, 1 = true
-    is_dca              INTEGER NOT NULL DEFAULT 0   -- 0 = false, 1 = true
+    is_dca              INTEGER NOT NULL DEFAULT 0,  -- 0 = false, 1 = true
+    is_smart_money      INTEGER NOT NULL DEFAULT 0,  -- wallet labelled smart_money at ingestion
+    is_wash             INTEGER NOT NULL DEFAULT 0,  -- reverses the wallet's previous trade (wash leg)
+    funding_cluster     TEXT,                        -- root funder of the wallet at ingestion (NULL: none known)
+    price_impact        REAL,                        -- relative spot price change (NULL: reserves unknown)
+
+    -- Transaction context used by the bot classifier
+    slot                INTEGER NOT NULL DEFAULT 0,
+    fee_lamports        INTEGER NOT NULL DEFAULT 0,  -- incl. priority fee
+    tip_lamports        INTEGER NOT NULL DEFAULT 0,  -- Jito tip
+
+    -- Needed to rebuild rolling state on startup (warm start)
+    source_program      TEXT NOT NULL DEFAULT '',    -- e.g. 'PumpSwap', 'JupiterDCA'
+    token_amount        REAL NOT NULL DEFAULT 0.0,
+    token_decimals      INTEGER NOT NULL DEFAULT 0
 );
 
 -- Index for mint-based queries (get trades for specific token)
@@ -118,11 +268,271 @@ CREATE INDEX IF NOT EXISTS idx_trades_is_dca
 CREATE INDEX IF NOT EXISTS idx_trades_mint_timestamp
     ON token_trades (mint, timestamp DESC);
 
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: token_candles
+-- ═══════════════════════════════════════════════════════════════════════
+-- OHLCV candles built from trade execution prices (SOL per whole token)
+-- Written once per candle when it closes on event time
+-- Pruned per interval: 1s after 1h, 1m after 1d, 5m after 7d, 1h after 90d
+
+CREATE TABLE IF NOT EXISTS token_candles (
+    mint                TEXT NOT NULL,
+    interval_secs       INTEGER NOT NULL,   -- 1, 60, 300 or 3600
+    open_time           INTEGER NOT NULL,   -- bucket start (unix seconds)
+
+    open                REAL NOT NULL,
+    high                REAL NOT NULL,
+    low                 REAL NOT NULL,
+    close               REAL NOT NULL,
+
+    volume_sol          REAL NOT NULL DEFAULT 0.0,
+    buy_volume_sol      REAL NOT NULL DEFAULT 0.0,
+    sell_volume_sol     REAL NOT NULL DEFAULT 0.0,
+    trade_count         INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (mint, interval_secs, open_time)
+);
+
+-- Index for retention pruning
+CREATE INDEX IF NOT EXISTS idx_candles_interval_open_time
+    ON token_candles (interval_secs, open_time);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: wallet_positions
+-- ═══════════════════════════════════════════════════════════════════════
+-- Position of each wallet in each mint (average cost basis)
+-- UPSERT from periodic snapshots; closed positions pruned after 30 days
+
+CREATE TABLE IF NOT EXISTS wallet_positions (
+    mint                TEXT NOT NULL,
+    wallet              TEXT NOT NULL,
+    tokens              REAL NOT NULL DEFAULT 0.0,  -- whole tokens held
+    cost_basis_sol      REAL NOT NULL DEFAULT 0.0,  -- SOL paid for tokens held
+    realized_pnl_sol    REAL NOT NULL DEFAULT 0.0,
+    buy_count           INTEGER NOT NULL DEFAULT 0,
+    sell_count          INTEGER NOT NULL DEFAULT 0,
+    last_trade_ts       INTEGER NOT NULL,
+    updated_at          INTEGER NOT NULL,
+
+    PRIMARY KEY (mint, wallet)
+);
+
+-- Index for per-wallet lookups across mints
+CREATE INDEX IF NOT EXISTS idx_wallet_positions_wallet
+    ON wallet_positions (wallet);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: token_holder_metrics
+-- ═══════════════════════════════════════════════════════════════════════
+-- Holder metrics at the latest trade price, one row per mint
+-- snapshot_ts: event time up to which wallet_positions is complete
+
+CREATE TABLE IF NOT EXISTS token_holder_metrics (
+    mint                        TEXT PRIMARY KEY,
+    snapshot_ts                 INTEGER NOT NULL,
+    price_sol                   REAL NOT NULL DEFAULT 0.0,
+    holders                     INTEGER NOT NULL DEFAULT 0,
+    holders_in_profit           INTEGER NOT NULL DEFAULT 0,
+    holders_in_profit_pct       REAL NOT NULL DEFAULT 0.0,
+    unrealized_pnl_sol          REAL NOT NULL DEFAULT 0.0,
+    realized_pnl_sol            REAL NOT NULL DEFAULT 0.0,
+    top_holders_cost_basis_sol  REAL NOT NULL DEFAULT 0.0,
+    top_holders_avg_cost        REAL NOT NULL DEFAULT 0.0,
+    overhang_sol                REAL NOT NULL DEFAULT 0.0,
+    updated_at                  INTEGER NOT NULL
+);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: wallet_scores
+-- ═══════════════════════════════════════════════════════════════════════
+-- Wallet performance over the scoring lookback, recomputed periodically
+-- entries: first buys per mint whose outcome is known (pumped or horizon passed)
+-- hits: entries followed by a 2x within 24h (5m candle highs)
+-- avg_entry_delay_secs: first buy minus the mint's first recorded trade
+-- label: 'smart_money', 'neutral' or 'unprofitable'
+
+CREATE TABLE IF NOT EXISTS wallet_scores (
+    wallet                  TEXT PRIMARY KEY,
+    score                   REAL NOT NULL DEFAULT 0.0,
+    label                   TEXT NOT NULL DEFAULT 'neutral',
+    realized_pnl_sol        REAL NOT NULL DEFAULT 0.0,
+    entries                 INTEGER NOT NULL DEFAULT 0,
+    hits                    INTEGER NOT NULL DEFAULT 0,
+    hit_rate                REAL NOT NULL DEFAULT 0.0,
+    avg_entry_delay_secs    REAL NOT NULL DEFAULT 0.0,
+    updated_at              INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_scores_label_score
+    ON wallet_scores (label, score DESC);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: wallet_bot_scores
+-- ═══════════════════════════════════════════════════════════════════════
+-- Bot classifier state per wallet, UPSERT from periodic snapshots
+-- score is as of last_trade_ts; it decays while the wallet is inactive
+-- Profiles traded within the last 24h are restored on startup
+
+CREATE TABLE IF NOT EXISTS wallet_bot_scores (
+    wallet              TEXT PRIMARY KEY,
+    score               REAL NOT NULL DEFAULT 0.0,
+    trades              INTEGER NOT NULL DEFAULT 0,
+    last_trade_ts       INTEGER NOT NULL,
+    interval_mean       REAL NOT NULL DEFAULT 0.0,
+    interval_var        REAL NOT NULL DEFAULT 0.0,
+    intervals           INTEGER NOT NULL DEFAULT 0,
+    last_sol_amount     REAL NOT NULL DEFAULT 0.0,
+    fixed_size_rate     REAL NOT NULL DEFAULT 0.0,
+    same_slot_rate      REAL NOT NULL DEFAULT 0.0,
+    fee_tip_rate        REAL NOT NULL DEFAULT 0.0,
+    updated_at          INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_bot_scores_last_trade
+    ON wallet_bot_scores (last_trade_ts);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: market_metrics
+-- ═══════════════════════════════════════════════════════════════════════
+-- Market-wide aggregates over the last hour across all mints and venues,
+-- appended every SOLFLOW_MARKET_SNAPSHOT_SECS (event time)
+-- activity: geometric mean of volume / baseline and wallets / baseline
+-- regime: 'dead', 'quiet', 'normal', 'hot' or 'euphoric'
+-- The latest non-warming baselines are restored on startup
+
+CREATE TABLE IF NOT EXISTS market_metrics (
+    timestamp           INTEGER PRIMARY KEY,
+    net_flow            REAL NOT NULL DEFAULT 0.0,
+    volume              REAL NOT NULL DEFAULT 0.0,
+    trades              INTEGER NOT NULL DEFAULT 0,
+    active_mints        INTEGER NOT NULL DEFAULT 0,
+    new_mints           INTEGER NOT NULL DEFAULT 0,
+    unique_wallets      INTEGER NOT NULL DEFAULT 0,
+    baseline_volume     REAL NOT NULL DEFAULT 0.0,
+    baseline_wallets    REAL NOT NULL DEFAULT 0.0,
+    activity            REAL NOT NULL DEFAULT 1.0,
+    regime              TEXT NOT NULL DEFAULT 'normal',
+    warming             INTEGER NOT NULL DEFAULT 0
+);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: pool_liquidity
+-- ═══════════════════════════════════════════════════════════════════════
+-- Latest reserves of each changed pool, appended every
+-- SOLFLOW_LIQUIDITY_SNAPSHOT_SECS (event time of the last reserve update)
+-- liquidity_sol values both sides at the spot price (2 * quote reserve)
+
+CREATE TABLE IF NOT EXISTS pool_liquidity (
+    mint                TEXT NOT NULL,
+    timestamp           INTEGER NOT NULL,
+    slot                INTEGER NOT NULL DEFAULT 0,
+    base_reserve        REAL NOT NULL,                 -- whole tokens
+    quote_reserve_sol   REAL NOT NULL,
+    spot_price          REAL NOT NULL,                 -- SOL per whole token
+    liquidity_sol       REAL NOT NULL,
+
+    PRIMARY KEY (mint, timestamp)
+);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: token_launches
+-- ═══════════════════════════════════════════════════════════════════════
+-- Token creations (Pumpfun) and pool creations (PumpSwap) that drew snipers,
+-- UPSERT from periodic snapshots; shares are of the 1B token supply
+-- Launches with sniper activity in the last 24h are restored on startup
+
+CREATE TABLE IF NOT EXISTS token_launches (
+    mint                TEXT PRIMARY KEY,
+    creator             TEXT NOT NULL,
+    source_program      TEXT NOT NULL,
+    timestamp           INTEGER NOT NULL,
+    slot                INTEGER NOT NULL DEFAULT 0,
+    tip_lamports        INTEGER NOT NULL DEFAULT 0,    -- Jito tip of the creation
+    sniper_count        INTEGER NOT NULL DEFAULT 0,
+    bundled_count       INTEGER NOT NULL DEFAULT 0,
+    sniper_supply_share REAL NOT NULL DEFAULT 0.0,     -- bought by snipers
+    sniper_held_share   REAL NOT NULL DEFAULT 0.0,     -- still held by snipers
+    last_activity       INTEGER NOT NULL,
+    updated_at          INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_launches_last_activity
+    ON token_launches (last_activity);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: launch_snipers
+-- ═══════════════════════════════════════════════════════════════════════
+-- Buyers within the first slots of a launch (kind: 'bundled', 'same_slot'
+-- or 'early') and their position since (whole tokens, SOL)
+
+CREATE TABLE IF NOT EXISTS launch_snipers (
+    mint                TEXT NOT NULL,
+    wallet              TEXT NOT NULL,
+    kind                TEXT NOT NULL,
+    first_slot          INTEGER NOT NULL DEFAULT 0,
+    bought_tokens       REAL NOT NULL DEFAULT 0.0,
+    sold_tokens         REAL NOT NULL DEFAULT 0.0,
+    bought_sol          REAL NOT NULL DEFAULT 0.0,
+    sold_sol            REAL NOT NULL DEFAULT 0.0,
+
+    PRIMARY KEY (mint, wallet)
+);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: creator_tokens
+-- ═══════════════════════════════════════════════════════════════════════
+-- One row per launched token, UPSERT while live and once finished
+-- status: 'live', 'dead' (price went to zero) or 'survived'
+-- Prices are SOL per whole token; dev_* columns are whole tokens
+-- Tokens launched in the last 30 days are restored on startup
+
+CREATE TABLE IF NOT EXISTS creator_tokens (
+    mint                TEXT PRIMARY KEY,
+    creator             TEXT NOT NULL,
+    source_program      TEXT NOT NULL,
+    launched_at         INTEGER NOT NULL,
+    last_trade          INTEGER NOT NULL,
+    peak_price          REAL NOT NULL DEFAULT 0.0,
+    last_price          REAL NOT NULL DEFAULT 0.0,
+    dev_bought_tokens   REAL NOT NULL DEFAULT 0.0,
+    dev_sold_tokens     REAL NOT NULL DEFAULT 0.0,
+    dev_moved_tokens    REAL NOT NULL DEFAULT 0.0,    -- left without a sell
+    dev_sells           INTEGER NOT NULL DEFAULT 0,
+    status              TEXT NOT NULL DEFAULT 'live',
+    updated_at          INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_creator_tokens_creator
+    ON creator_tokens (creator);
+
+CREATE INDEX IF NOT EXISTS idx_creator_tokens_launched_at
+    ON creator_tokens (launched_at);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: creator_reputation
+-- ═══════════════════════════════════════════════════════════════════════
+-- Track record of each creator over its launches in the last 30 days
+-- risk_score: 0.0 - 1.0 (dead share and short lifetimes, see creators.rs)
+
+CREATE TABLE IF NOT EXISTS creator_reputation (
+    creator               TEXT PRIMARY KEY,
+    tokens_launched       INTEGER NOT NULL DEFAULT 0,
+    tokens_finished       INTEGER NOT NULL DEFAULT 0,
+    tokens_dead           INTEGER NOT NULL DEFAULT 0,
+    median_lifetime_secs  INTEGER,                     -- NULL until a token finished
+    dev_sells             INTEGER NOT NULL DEFAULT 0,
+    risk_score            REAL NOT NULL DEFAULT 0.0,
+    last_launch           INTEGER NOT NULL,
+    updated_at            INTEGER NOT NULL
+);
+
 -- ═══════════════════════════════════════════════════════════════════════
 -- TABLE: token_signals
 -- ═══════════════════════════════════════════════════════════════════════
 -- Phase 6: Enhanced Signals Engine
 -- Stores detected trading signals with strength, window, and metadata
+-- event: 'entered' | 'updated' | 'exited' (see signal_state.rs); one row per
+-- transition rather than per evaluation
 
 CREATE TABLE IF NOT EXISTS token_signals (
     id              INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -132,7 +542,8 @@ CREATE TABLE IF NOT EXISTS token_signals (
     window          TEXT NOT NULL,
     timestamp       INTEGER NOT NULL,
     metadata        TEXT,
-    created_at      INTEGER DEFAULT (strftime('%s', 'now'))
+    created_at      INTEGER DEFAULT (strftime('%s', 'now')),
+    event           TEXT NOT NULL DEFAULT 'entered'
 );
 
 -- Optimized indexes for signal queries
@@ -151,11 +562,53 @@ CREATE INDEX IF NOT EXISTS idx_token_signals_strength
 CREATE INDEX IF NOT EXISTS idx_token_signals_mint_timestamp 
     ON token_signals(mint, timestamp DESC);
 
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: signal_states
+-- ═══════════════════════════════════════════════════════════════════════
+-- State of each (mint, signal type) pair that is active or cooling down,
+-- restored at startup. Inactive pairs have no row.
+-- state: 'active' | 'cooled_down'
+
+CREATE TABLE IF NOT EXISTS signal_states (
+    mint              TEXT NOT NULL,
+    signal_type       TEXT NOT NULL,
+    state             TEXT NOT NULL,
+    strength          REAL NOT NULL,            -- latest qualifying firing
+    window            TEXT NOT NULL,
+    metadata          TEXT,
+    emitted_strength  REAL NOT NULL,            -- strength of the last event
+    entered_at        INTEGER NOT NULL,
+    last_seen         INTEGER NOT NULL,
+    last_emitted      INTEGER NOT NULL,
+    cooldown_until    INTEGER NOT NULL DEFAULT 0,
+    updated_at        INTEGER NOT NULL,
+    PRIMARY KEY (mint, signal_type)
+);
+
+-- ═══════════════════════════════════════════════════════════════════════
+-- VIEW: dashboard_signals
+-- ═══════════════════════════════════════════════════════════════════════
+-- Candidates for a token's latest signal on the dashboard: active states plus
+-- DEV_SELL / SNIPER_DUMP events from the last 15 minutes. Events bypass the
+-- state machine and never appear in signal_states, so they are taken from
+-- token_signals and ranked ahead of states (priority 1 vs 0).
+
+CREATE VIEW IF NOT EXISTS dashboard_signals AS
+    SELECT mint, signal_type, strength, 0 AS priority
+    FROM signal_states
+    WHERE state = 'active'
+    UNION ALL
+    SELECT mint, signal_type, strength, 1 AS priority
+    FROM token_signals
+    WHERE signal_type IN ('DEV_SELL', 'SNIPER_DUMP')
+      AND timestamp >= CAST(strftime('%s', 'now') AS INTEGER) - 900;
+
 -- ═══════════════════════════════════════════════════════════════════════
 -- TABLE: blocklist
 -- ═══════════════════════════════════════════════════════════════════════
 -- Stores token mints that should be filtered from dashboard queries
 -- Used to hide spam tokens, rugs, or other unwanted tokens
+-- Also enforced at ingestion time (reloaded periodically by the processor)
 
 CREATE TABLE IF NOT EXISTS blocklist (
     mint            TEXT PRIMARY KEY,
@@ -166,6 +619,21 @@ CREATE TABLE IF NOT EXISTS blocklist (
 CREATE INDEX IF NOT EXISTS idx_blocklist_added_at 
     ON blocklist(added_at DESC);
 
+-- ═══════════════════════════════════════════════════════════════════════
+-- TABLE: followed_tokens
+-- ═══════════════════════════════════════════════════════════════════════
+-- Tracks which tokens are being followed for continuous price polling
+-- In allowlist mode (SOLFLOW_ALLOWLIST_MODE=1) only these mints are ingested
+
+CREATE TABLE IF NOT EXISTS followed_tokens (
+    mint                TEXT PRIMARY KEY,
+    created_at          INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    last_fetch_at       INTEGER DEFAULT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_followed_tokens_last_fetch
+    ON followed_tokens (last_fetch_at);
+
 -- ═══════════════════════════════════════════════════════════════════════
 -- END OF SCHEMA
 -- ═══════════════════════════════════════════════════════════════════════
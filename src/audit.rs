@@ -0,0 +1,557 @@
+//! Shadow recompute audit of rolling metrics
+//!
+//! The window engine (`state`) maintains every metric incrementally: trades
+//! are added to running aggregates and subtracted again on eviction. A bug in
+//! either path, or float drift, silently skews everything downstream. The
+//! audit recomputes each mint's metrics from scratch from its raw trade
+//! buffer with an independent reference implementation and diffs them
+//! against the incremental values.
+//!
+//! A trade is inside a window if its timestamp is at or after the window's
+//! last eviction cutoff; the reference applies that rule directly instead of
+//! following the window cursors.
+//!
+//! Audits run on the shards every `SOLFLOW_AUDIT_SECS`. Mismatches are
+//! logged as one JSON report per mint.
+
+use crate::{
+    shards::ShardRouter,
+    sketch::{SizeClass, SizeSketch, SIZE_QUANTILES},
+    state::{RollingMetrics, TokenRollingState, VenueMetrics, WindowMetrics},
+    types::{TradeDirection, TradeEvent},
+    wash::{WalletFlow, WashStats},
+    watermark::EventClock,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Relative tolerance for float metrics (sums drift with add/remove order)
+pub const FLOAT_TOLERANCE: f64 = 1e-6;
+
+/// A named metric and how to read it
+type Field<T, V> = (&'static str, fn(&T) -> V);
+
+/// Count metrics of a window, compared exactly
+const COUNT_FIELDS: [Field<WindowMetrics, i32>; 18] = [
+    ("buy_count", |w| w.buy_count),
+    ("sell_count", |w| w.sell_count),
+    ("unique_wallets", |w| w.unique_wallets),
+    ("unique_clusters", |w| w.unique_clusters),
+    ("bot_wallets", |w| w.bot_wallets),
+    ("bot_trades", |w| w.bot_trades),
+    ("dca_buys", |w| w.dca_buys),
+    ("dca_unique_wallets", |w| w.dca_unique_wallets),
+    ("smart_money_buys", |w| w.smart_money_buys),
+    ("smart_money_wallets", |w| w.smart_money_wallets),
+    ("retail_buys", |w| w.retail_buys),
+    ("mid_buys", |w| w.mid_buys),
+    ("whale_buys", |w| w.whale_buys),
+    ("retail_sells", |w| w.retail_sells),
+    ("mid_sells", |w| w.mid_sells),
+    ("whale_sells", |w| w.whale_sells),
+    ("wash_wallets", |w| w.wash_wallets),
+    ("wash_trades", |w| w.wash_trades),
+];
+
+/// Float metrics of a window, compared within `FLOAT_TOLERANCE`
+const SUM_FIELDS: [Field<WindowMetrics, f64>; 17] = [
+    ("net_flow_sol", |w| w.net_flow_sol),
+    ("buy_volume_sol", |w| w.buy_volume_sol),
+    ("sell_volume_sol", |w| w.sell_volume_sol),
+    ("volume_sol", |w| w.volume_sol),
+    ("bot_flow_sol", |w| w.bot_flow_sol),
+    ("dca_flow_sol", |w| w.dca_flow_sol),
+    ("dca_ratio", |w| w.dca_ratio),
+    ("smart_money_flow_sol", |w| w.smart_money_flow_sol),
+    ("buy_size_p50", |w| w.buy_size_p50),
+    ("buy_size_p90", |w| w.buy_size_p90),
+    ("buy_size_p99", |w| w.buy_size_p99),
+    ("sell_size_p50", |w| w.sell_size_p50),
+    ("sell_size_p90", |w| w.sell_size_p90),
+    ("sell_size_p99", |w| w.sell_size_p99),
+    ("wash_score", |w| w.wash_score),
+    ("wash_volume_sol", |w| w.wash_volume_sol),
+    ("warming", |w| w.warming as i32 as f64),
+];
+
+/// Metrics of one venue in a window
+const VENUE_FIELDS: [Field<VenueMetrics, f64>; 9] = [
+    ("net_flow_sol", |v| v.net_flow_sol),
+    ("buy_volume_sol", |v| v.buy_volume_sol),
+    ("sell_volume_sol", |v| v.sell_volume_sol),
+    ("volume_sol", |v| v.volume_sol),
+    ("buy_count", |v| v.buy_count as f64),
+    ("sell_count", |v| v.sell_count as f64),
+    ("unique_wallets", |v| v.unique_wallets as f64),
+    ("volume_share", |v| v.volume_share),
+    ("dominant", |v| v.dominant as i32 as f64),
+];
+
+/// One metric whose incremental value differs from the reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub window_secs: i64,
+    /// Venue of per-venue metrics
+    pub venue: Option<String>,
+    pub field: &'static str,
+    /// Recomputed from the trade buffer
+    pub expected: f64,
+    /// Maintained incrementally
+    pub actual: f64,
+}
+
+/// Audit of one mint
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditReport {
+    pub mint: String,
+    /// Event time of the audit
+    pub audited_at: i64,
+    /// Trades in the mint's buffer
+    pub trades: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl AuditReport {
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Structured form of the report, for logs
+    pub fn to_json(&self) -> Value {
+        json!({
+            "mint": self.mint,
+            "audited_at": self.audited_at,
+            "trades": self.trades,
+            "mismatches": self
+                .mismatches
+                .iter()
+                .map(|m| json!({
+                    "window_secs": m.window_secs,
+                    "venue": m.venue,
+                    "field": m.field,
+                    "expected": m.expected,
+                    "actual": m.actual,
+                }))
+                .collect::<Vec<Value>>(),
+        })
+    }
+}
+
+/// Outcome of auditing a set of mints; only mismatched mints are reported
+#[derive(Debug, Clone, Default)]
+pub struct AuditRun {
+    pub mints: usize,
+    pub trades: usize,
+    pub reports: Vec<AuditReport>,
+}
+
+impl AuditRun {
+    /// Add another shard's run to this one
+    pub fn merge(&mut self, other: AuditRun) {
+        self.mints += other.mints;
+        self.trades += other.trades;
+        self.reports.extend(other.reports);
+    }
+}
+
+/// Signed SOL flow of a trade (buys in, sells out)
+fn flow(trade: &TradeEvent) -> f64 {
+    match trade.direction {
+        TradeDirection::Buy => trade.sol_amount,
+        TradeDirection::Sell => -trade.sol_amount,
+        TradeDirection::Unknown => 0.0,
+    }
+}
+
+/// Sum of SOL amounts, starting at +0.0 like the running aggregates (an
+/// empty `f64` `sum` is -0.0, which sorts below it)
+fn sum_sol(values: impl Iterator<Item = f64>) -> f64 {
+    values.fold(0.0, |total, value| total + value)
+}
+
+/// Metrics of one window recomputed from the trades inside it
+pub fn reference_window(window_secs: i64, trades: &[&TradeEvent], warming: bool) -> WindowMetrics {
+    let buys: Vec<&TradeEvent> = trades.iter().copied().filter(|t| t.direction == TradeDirection::Buy).collect();
+    let sells: Vec<&TradeEvent> = trades.iter().copied().filter(|t| t.direction == TradeDirection::Sell).collect();
+    let wallets_where = |keep: fn(&TradeEvent) -> bool| {
+        trades.iter().filter(|t| keep(t)).map(|t| t.user_account.as_str()).collect::<HashSet<_>>().len() as i32
+    };
+    let flow_where = |keep: fn(&TradeEvent) -> bool| sum_sol(trades.iter().filter(|t| keep(t)).map(|t| flow(t)));
+    let count_where = |side: &[&TradeEvent], keep: &dyn Fn(&TradeEvent) -> bool| side.iter().filter(|t| keep(t)).count() as i32;
+    let sizes = |side: &[&TradeEvent]| {
+        let mut sketch = SizeSketch::default();
+        side.iter().for_each(|t| sketch.add(t.sol_amount));
+        sketch.quantiles(&SIZE_QUANTILES)
+    };
+
+    let net_flow_sol = sum_sol(trades.iter().map(|t| flow(t)));
+    let buy_volume_sol = sum_sol(buys.iter().map(|t| t.sol_amount));
+    let sell_volume_sol = sum_sol(sells.iter().map(|t| t.sol_amount));
+    let volume_sol = buy_volume_sol + sell_volume_sol;
+    let dca_flow_sol = flow_where(|t| t.is_dca);
+
+    let mut flows: HashMap<String, WalletFlow> = HashMap::new();
+    for trade in trades {
+        flows.entry(trade.user_account.clone()).or_default().apply(trade, 1.0);
+    }
+    let wash_legs = trades.iter().filter(|t| t.is_wash).count() as i32;
+    let wash_leg_sol = sum_sol(trades.iter().filter(|t| t.is_wash).map(|t| t.sol_amount));
+    let wash = WashStats::analyse(&flows, wash_legs, wash_leg_sol, trades.len(), volume_sol);
+
+    let [buy_size_p50, buy_size_p90, buy_size_p99] = sizes(&buys);
+    let [sell_size_p50, sell_size_p90, sell_size_p99] = sizes(&sells);
+    let class = |class: SizeClass| move |t: &TradeEvent| SizeClass::of(t.sol_amount) == class;
+
+    WindowMetrics {
+        window_secs,
+        net_flow_sol,
+        buy_count: buys.len() as i32,
+        sell_count: sells.len() as i32,
+        unique_wallets: wallets_where(|_| true),
+        unique_clusters: trades.iter().map(|t| t.cluster()).collect::<HashSet<_>>().len() as i32,
+        buy_volume_sol,
+        sell_volume_sol,
+        volume_sol,
+        bot_wallets: wallets_where(|t| t.is_bot),
+        bot_trades: trades.iter().filter(|t| t.is_bot).count() as i32,
+        bot_flow_sol: flow_where(|t| t.is_bot),
+        dca_buys: count_where(&buys, &|t| t.source_program == "JupiterDCA"),
+        dca_flow_sol,
+        dca_unique_wallets: wallets_where(|t| t.is_dca),
+        dca_ratio: if net_flow_sol != 0.0 { dca_flow_sol / net_flow_sol } else { 0.0 },
+        smart_money_buys: count_where(&buys, &|t| t.is_smart_money),
+        smart_money_flow_sol: flow_where(|t| t.is_smart_money),
+        smart_money_wallets: wallets_where(|t| t.is_smart_money),
+        buy_size_p50,
+        buy_size_p90,
+        buy_size_p99,
+        sell_size_p50,
+        sell_size_p90,
+        sell_size_p99,
+        retail_buys: count_where(&buys, &class(SizeClass::Retail)),
+        mid_buys: count_where(&buys, &class(SizeClass::Mid)),
+        whale_buys: count_where(&buys, &class(SizeClass::Whale)),
+        retail_sells: count_where(&sells, &class(SizeClass::Retail)),
+        mid_sells: count_where(&sells, &class(SizeClass::Mid)),
+        whale_sells: count_where(&sells, &class(SizeClass::Whale)),
+        wash_score: wash.score,
+        wash_volume_sol: wash.volume_sol,
+        wash_wallets: wash.wallets,
+        wash_trades: wash.trades,
+        warming,
+    }
+}
+
+/// Per-venue metrics of one window recomputed from the trades inside it,
+/// keyed by venue
+pub fn reference_venues(window_secs: i64, trades: &[&TradeEvent]) -> HashMap<String, VenueMetrics> {
+    let total_volume = sum_sol(
+        trades
+            .iter()
+            .filter(|t| t.direction != TradeDirection::Unknown)
+            .map(|t| t.sol_amount),
+    );
+    let mut by_venue: HashMap<&str, Vec<&TradeEvent>> = HashMap::new();
+    for trade in trades {
+        by_venue.entry(trade.source_program.as_str()).or_default().push(trade);
+    }
+
+    let mut venues: HashMap<String, VenueMetrics> = by_venue
+        .into_iter()
+        .map(|(venue, trades)| {
+            let side = |direction| trades.iter().filter(move |t| t.direction == direction);
+            let buy_volume_sol = sum_sol(side(TradeDirection::Buy).map(|t| t.sol_amount));
+            let sell_volume_sol = sum_sol(side(TradeDirection::Sell).map(|t| t.sol_amount));
+            let volume_sol = buy_volume_sol + sell_volume_sol;
+            let metrics = VenueMetrics {
+                window_secs,
+                venue: venue.to_string(),
+                net_flow_sol: sum_sol(trades.iter().map(|t| flow(t))),
+                buy_volume_sol,
+                sell_volume_sol,
+                volume_sol,
+                buy_count: side(TradeDirection::Buy).count() as i32,
+                sell_count: side(TradeDirection::Sell).count() as i32,
+                unique_wallets: trades.iter().map(|t| t.user_account.as_str()).collect::<HashSet<_>>().len() as i32,
+                volume_share: if total_volume > 0.0 { volume_sol / total_volume } else { 0.0 },
+                dominant: false,
+            };
+            (venue.to_string(), metrics)
+        })
+        .collect();
+
+    // Most volume wins; ties go to the first venue by name
+    let dominant = venues
+        .values()
+        .max_by(|a, b| a.volume_sol.total_cmp(&b.volume_sol).then_with(|| b.venue.cmp(&a.venue)))
+        .map(|v| v.venue.clone());
+    if let Some(venue) = dominant.and_then(|venue| venues.get_mut(&venue)) {
+        venue.dominant = true;
+    }
+    venues
+}
+
+fn differs(expected: f64, actual: f64) -> bool {
+    (expected - actual).abs() > FLOAT_TOLERANCE * expected.abs().max(actual.abs()).max(1.0)
+}
+
+/// Diff incremental metrics against the reference
+///
+/// Windows and venues missing on either side are reported with the field
+/// `present`.
+pub fn diff_metrics(
+    expected: &RollingMetrics,
+    expected_venues: &HashMap<(i64, String), VenueMetrics>,
+    actual: &RollingMetrics,
+) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    let mut push = |window_secs, venue: Option<&str>, field, expected, actual| {
+        mismatches.push(Mismatch {
+            window_secs,
+            venue: venue.map(str::to_string),
+            field,
+            expected,
+            actual,
+        })
+    };
+
+    for reference in &expected.windows {
+        let secs = reference.window_secs;
+        let Some(window) = actual.get(secs) else {
+            push(secs, None, "present", 1.0, 0.0);
+            continue;
+        };
+        for (field, get) in COUNT_FIELDS {
+            if get(reference) != get(window) {
+                push(secs, None, field, get(reference) as f64, get(window) as f64);
+            }
+        }
+        for (field, get) in SUM_FIELDS {
+            if differs(get(reference), get(window)) {
+                push(secs, None, field, get(reference), get(window));
+            }
+        }
+    }
+
+    for ((secs, venue), reference) in expected_venues {
+        let Some(metrics) = actual.venues(*secs).find(|v| &v.venue == venue) else {
+            push(*secs, Some(venue), "present", 1.0, 0.0);
+            continue;
+        };
+        for (field, get) in VENUE_FIELDS {
+            if differs(get(reference), get(metrics)) {
+                push(*secs, Some(venue), field, get(reference), get(metrics));
+            }
+        }
+    }
+    for metrics in &actual.venues {
+        if !expected_venues.contains_key(&(metrics.window_secs, metrics.venue.clone())) {
+            push(metrics.window_secs, Some(&metrics.venue), "present", 0.0, 1.0);
+        }
+    }
+
+    mismatches
+}
+
+/// Recompute a mint's metrics from its trade buffer and diff them against
+/// the incremental ones
+pub fn audit_state(state: &TokenRollingState, now: i64) -> AuditReport {
+    let mut expected = RollingMetrics {
+        liquidity_sol: state.liquidity_sol,
+        ..Default::default()
+    };
+    let mut expected_venues = HashMap::new();
+
+    for secs in state.window_secs() {
+        let cutoff = state.window_cutoff(secs).unwrap_or(i64::MIN);
+        let trades: Vec<&TradeEvent> = state.buffered_trades().filter(|t| t.timestamp >= cutoff).collect();
+
+        expected.windows.push(reference_window(secs, &trades, cutoff < state.history_start));
+        for (venue, metrics) in reference_venues(secs, &trades) {
+            expected_venues.insert((secs, venue), metrics);
+        }
+    }
+
+    AuditReport {
+        mint: state.mint.clone(),
+        audited_at: now,
+        trades: state.trade_count(),
+        mismatches: diff_metrics(&expected, &expected_venues, &state.compute_rolling_metrics()),
+    }
+}
+
+/// Audit every state, keeping the reports of mismatched mints
+pub fn audit_states<'a>(states: impl IntoIterator<Item = &'a TokenRollingState>, now: i64) -> AuditRun {
+    let mut run = AuditRun::default();
+
+    for state in states {
+        let report = audit_state(state, now);
+        run.mints += 1;
+        run.trades += report.trades;
+        if !report.is_clean() {
+            run.reports.push(report);
+        }
+    }
+
+    run
+}
+
+/// Background loop that audits every shard every `interval`
+///
+/// Audits are skipped until the event clock has a time.
+pub async fn run_audit_loop(shards: Arc<ShardRouter>, clock: Arc<EventClock>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    // The first tick completes immediately; nothing to audit yet
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+        let run = shards.audit(now).await;
+
+        for report in &run.reports {
+            log::warn!("🔍 AUDIT MISMATCH | {}", report.to_json());
+        }
+        log::info!(
+            "🔍 AUDIT | Mints: {} | Trades: {} | Mismatched mints: {}",
+            run.mints,
+            run.trades,
+            run.reports.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn trade(timestamp: i64, wallet: &str, direction: TradeDirection, sol_amount: f64) -> TradeEvent {
+        TradeEvent {
+            user_account: wallet.to_string(),
+            ..TradeEvent::test("mint", timestamp, direction, sol_amount)
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_drifted_fields() {
+        let mut state = TokenRollingState::with_windows("mint".to_string(), &[60, 300]);
+        state.add_trade(trade(1_000, "w1", TradeDirection::Buy, 2.0));
+        state.add_trade(trade(1_030, "w2", TradeDirection::Sell, 1.0));
+        state.evict_old_trades(1_070);
+        assert!(audit_state(&state, 1_070).is_clean());
+
+        // Same reference, tampered incremental values
+        let reference = state.compute_rolling_metrics();
+        let venues: HashMap<(i64, String), VenueMetrics> = reference
+            .venues
+            .iter()
+            .map(|v| ((v.window_secs, v.venue.clone()), v.clone()))
+            .collect();
+        let mut actual = reference.clone();
+        actual.window_mut(60).buy_count += 1;
+        actual.window_mut(300).net_flow_sol += 0.5;
+        actual.venues.retain(|v| v.window_secs != 300);
+
+        let mismatches = diff_metrics(&reference, &venues, &actual);
+        let fields: Vec<(i64, &str)> = mismatches.iter().map(|m| (m.window_secs, m.field)).collect();
+        assert_eq!(fields, vec![(60, "buy_count"), (300, "net_flow_sol"), (300, "present")]);
+        assert_eq!(mismatches[2].venue.as_deref(), Some("PumpSwap"));
+
+        let report = AuditReport {
+            mint: "mint".to_string(),
+            audited_at: 1_070,
+            trades: 2,
+            mismatches,
+        };
+        assert_eq!(report.to_json()["mismatches"][1]["expected"], json!(1.0));
+    }
+
+    /// One step of a fuzzed trade sequence
+    #[derive(Debug, Clone)]
+    enum Op {
+        /// Trade `lag` seconds behind the clock; sizes are multiples of 1/8
+        /// SOL so sums are exact
+        Trade {
+            lag: i64,
+            wallet: u8,
+            direction: TradeDirection,
+            eighths: u16,
+            bot: bool,
+            dca: bool,
+            smart: bool,
+            venue: u8,
+            cluster: Option<u8>,
+        },
+        /// Move the clock forward and evict
+        Advance(i64),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let direction = prop_oneof![
+            Just(TradeDirection::Buy),
+            Just(TradeDirection::Sell),
+            Just(TradeDirection::Unknown)
+        ];
+        let trade = (
+            prop_oneof![4 => Just(0i64), 1 => 0i64..400],
+            0u8..8,
+            direction,
+            0u16..800,
+            any::<(bool, bool, bool)>(),
+            0u8..3,
+            prop::option::of(0u8..3),
+        )
+            .prop_map(|(lag, wallet, direction, eighths, (bot, dca, smart), venue, cluster)| Op::Trade {
+                lag,
+                wallet,
+                direction,
+                eighths,
+                bot,
+                dca,
+                smart,
+                venue,
+                cluster,
+            });
+
+        prop_oneof![5 => trade, 1 => (0i64..1_000).prop_map(Op::Advance)]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_incremental_metrics_match_reference(ops in prop::collection::vec(op(), 1..150)) {
+            let mut state = TokenRollingState::with_windows("mint".to_string(), &[60, 300, 900])
+                .with_history_start(1_000);
+            let mut clock = 1_000i64;
+
+            for op in ops {
+                match op {
+                    Op::Trade { lag, wallet, direction, eighths, bot, dca, smart, venue, cluster } => {
+                        let mut t = trade(clock - lag, &format!("w{}", wallet), direction, eighths as f64 / 8.0);
+                        t.is_bot = bot;
+                        t.is_dca = dca;
+                        t.is_smart_money = smart;
+                        t.source_program = ["PumpSwap", "Moonshot", "JupiterDCA"][venue as usize].to_string();
+                        t.funding_cluster = cluster.map(|c| format!("funder{}", c));
+                        state.add_trade(t);
+                    }
+                    Op::Advance(secs) => {
+                        clock += secs;
+                        state.evict_old_trades(clock);
+                        state.close_candles(clock);
+                    }
+                }
+
+                let report = audit_state(&state, clock);
+                prop_assert!(report.is_clean(), "{}", report.to_json());
+            }
+        }
+    }
+}
@@ -0,0 +1,425 @@
+//! Multi-feature bot classifier
+//!
+//! Every ingested trade updates its wallet's `BotProfile` (across all mints)
+//! and yields a bot score in 0..1. Trades of wallets scoring at or above the
+//! threshold are flagged `is_bot` before they are persisted and enter rolling
+//! state, so `token_trades`, the window bot metrics and warm start replays all
+//! see the same flag.
+//!
+//! Features, each in 0..1:
+//! - cadence: regularity of the time between the wallet's trades
+//!   (1 - coefficient of variation of an EWMA of the intervals)
+//! - fixed size: rate of trades repeating the previous size (round SOL
+//!   amounts count half)
+//! - same slot: rate of trades landing in a slot where the wallet already
+//!   traded the opposite side
+//! - breadth: distinct mints traded within the last hour
+//! - fee/tip: rate of trades paying a Jito tip or a high priority fee
+//!
+//! The score is an EWMA of the per-trade weighted feature sum, scaled down
+//! for wallets with few trades, and decays with `SCORE_HALF_LIFE_SECS` while
+//! the wallet is inactive. Profiles are persisted to `wallet_bot_scores` and
+//! restored on startup; profiles idle for `PROFILE_IDLE_SECS` are dropped
+//! from memory (their decayed score is negligible by then).
+
+use crate::{db::WriteRequest, types::{TradeDirection, TradeEvent}, watermark::EventClock};
+use dashmap::DashMap;
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+
+/// Jito tip accounts; SOL sent to them is a tip
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbgzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// Default score at or above which a wallet's trades are flagged `is_bot`
+pub const DEFAULT_BOT_THRESHOLD: f64 = 0.4;
+
+/// Half-life of an inactive wallet's score (seconds)
+pub const SCORE_HALF_LIFE_SECS: f64 = 6.0 * 3_600.0;
+
+/// Profiles without a trade for this long are dropped from memory (seconds)
+pub const PROFILE_IDLE_SECS: i64 = 86_400;
+
+/// Trades needed before the score is trusted in full
+const MIN_TRADES: u32 = 5;
+
+/// Intervals needed before cadence is scored
+const MIN_INTERVALS: u32 = 3;
+
+/// Weight of the newest trade in the score and feature EWMAs
+const ALPHA: f64 = 0.2;
+
+/// Window and saturation point of the breadth feature
+const BREADTH_WINDOW_SECS: i64 = 3_600;
+const BREADTH_MINTS: usize = 20;
+
+/// Fees at or above this count as a high priority fee (0.001 SOL)
+const PRIORITY_FEE_LAMPORTS: u64 = 1_000_000;
+
+/// Feature weights (sum to 1.0)
+const W_CADENCE: f64 = 0.3;
+const W_FIXED_SIZE: f64 = 0.2;
+const W_SAME_SLOT: f64 = 0.2;
+const W_BREADTH: f64 = 0.15;
+const W_FEE_TIP: f64 = 0.15;
+
+/// Lamports paid to Jito tip accounts, from the transaction's static account
+/// keys and balances
+pub fn jito_tip_lamports<K: ToString>(keys: &[K], pre_balances: &[u64], post_balances: &[u64]) -> u64 {
+    keys.iter()
+        .enumerate()
+        .filter(|(_, key)| JITO_TIP_ACCOUNTS.contains(&key.to_string().as_str()))
+        .map(|(i, _)| {
+            let pre = pre_balances.get(i).copied().unwrap_or(0);
+            let post = post_balances.get(i).copied().unwrap_or(0);
+            post.saturating_sub(pre)
+        })
+        .sum()
+}
+
+/// Feature values of one trade (each 0..1)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BotFeatures {
+    pub cadence: f64,
+    pub fixed_size: f64,
+    pub same_slot: f64,
+    pub breadth: f64,
+    pub fee_tip: f64,
+}
+
+impl BotFeatures {
+    fn weighted(&self) -> f64 {
+        self.cadence * W_CADENCE
+            + self.fixed_size * W_FIXED_SIZE
+            + self.same_slot * W_SAME_SLOT
+            + self.breadth * W_BREADTH
+            + self.fee_tip * W_FEE_TIP
+    }
+}
+
+/// Per-wallet classifier state
+///
+/// Public fields are persisted to `wallet_bot_scores`; slot and breadth
+/// tracking start empty after a restore.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BotProfile {
+    /// Score as of `last_trade_ts` (see `score_at` for the decayed value)
+    pub score: f64,
+    pub trades: u32,
+    pub last_trade_ts: i64,
+    /// EWMA of seconds between trades and its variance
+    pub interval_mean: f64,
+    pub interval_var: f64,
+    pub intervals: u32,
+    pub last_sol_amount: f64,
+    /// EWMA rates of the per-trade fixed size, same slot and fee/tip events
+    pub fixed_size_rate: f64,
+    pub same_slot_rate: f64,
+    pub fee_tip_rate: f64,
+
+    last_slot: u64,
+    /// Directions traded in `last_slot` (bit 0: buy, bit 1: sell)
+    slot_sides: u8,
+    /// Mints traded recently, with the time they were last traded
+    recent_mints: VecDeque<(String, i64)>,
+}
+
+impl BotProfile {
+    /// Score decayed to `now`
+    pub fn score_at(&self, now: i64) -> f64 {
+        let idle = (now - self.last_trade_ts).max(0) as f64;
+        self.score * 0.5f64.powf(idle / SCORE_HALF_LIFE_SECS)
+    }
+
+    /// Update the profile with a trade, returning its features
+    fn observe(&mut self, trade: &TradeEvent) -> BotFeatures {
+        let mut features = BotFeatures::default();
+
+        // Cadence
+        if self.trades > 0 && trade.timestamp >= self.last_trade_ts {
+            let interval = (trade.timestamp - self.last_trade_ts) as f64;
+            let diff = interval - self.interval_mean;
+            let incr = ALPHA * diff;
+            self.interval_mean += incr;
+            self.interval_var = (1.0 - ALPHA) * (self.interval_var + diff * incr);
+            self.intervals += 1;
+        }
+        if self.intervals >= MIN_INTERVALS {
+            let cv = self.interval_var.sqrt() / self.interval_mean.max(1.0);
+            features.cadence = (1.0 - cv).clamp(0.0, 1.0);
+        }
+
+        // Fixed or round size
+        let repeated = self.last_sol_amount > 0.0
+            && (trade.sol_amount - self.last_sol_amount).abs() <= self.last_sol_amount * 0.01;
+        let cents = trade.sol_amount * 100.0;
+        let round = trade.sol_amount > 0.0 && (cents - cents.round()).abs() < 1e-6;
+        let size_event = if repeated { 1.0 } else if round { 0.5 } else { 0.0 };
+        self.fixed_size_rate += ALPHA * (size_event - self.fixed_size_rate);
+        self.last_sol_amount = trade.sol_amount;
+        features.fixed_size = self.fixed_size_rate;
+
+        // Buy and sell in the same slot
+        let side = match trade.direction {
+            TradeDirection::Buy => 1,
+            TradeDirection::Sell => 2,
+            TradeDirection::Unknown => 0,
+        };
+        if trade.slot > 0 {
+            if trade.slot != self.last_slot {
+                self.last_slot = trade.slot;
+                self.slot_sides = 0;
+            }
+            let round_trip = side != 0 && self.slot_sides & !side != 0;
+            self.slot_sides |= side;
+            self.same_slot_rate += ALPHA * (round_trip as u8 as f64 - self.same_slot_rate);
+        }
+        features.same_slot = self.same_slot_rate;
+
+        // Breadth across mints
+        self.recent_mints.retain(|(mint, _)| *mint != trade.mint);
+        self.recent_mints.push_back((trade.mint.clone(), trade.timestamp));
+        let since = trade.timestamp - BREADTH_WINDOW_SECS;
+        self.recent_mints.retain(|&(_, ts)| ts >= since);
+        while self.recent_mints.len() > BREADTH_MINTS {
+            self.recent_mints.pop_front();
+        }
+        features.breadth = (self.recent_mints.len() - 1) as f64 / (BREADTH_MINTS - 1) as f64;
+
+        // Tips and priority fees
+        let paid = trade.tip_lamports > 0 || trade.fee_lamports >= PRIORITY_FEE_LAMPORTS;
+        self.fee_tip_rate += ALPHA * (paid as u8 as f64 - self.fee_tip_rate);
+        features.fee_tip = self.fee_tip_rate;
+
+        // Score: decay to this trade, then blend in the new evidence
+        self.trades += 1;
+        let confidence = (self.trades as f64 / MIN_TRADES as f64).min(1.0);
+        let decayed = self.score_at(trade.timestamp);
+        self.score = decayed + ALPHA * (features.weighted() * confidence - decayed);
+        self.last_trade_ts = self.last_trade_ts.max(trade.timestamp);
+
+        features
+    }
+}
+
+/// Shared per-wallet bot profiles consulted on every extracted trade
+#[derive(Debug)]
+pub struct BotClassifier {
+    profiles: DashMap<String, BotProfile>,
+    /// Wallets whose profile changed since the last `take_dirty`
+    dirty: DashMap<String, ()>,
+    threshold: f64,
+}
+
+impl Default for BotClassifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_BOT_THRESHOLD)
+    }
+}
+
+impl BotClassifier {
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            profiles: DashMap::new(),
+            dirty: DashMap::new(),
+            threshold,
+        }
+    }
+
+    /// Update the trading wallet's profile and return its new score
+    pub fn observe(&self, trade: &TradeEvent) -> f64 {
+        let mut profile = self.profiles.entry(trade.user_account.clone()).or_default();
+        profile.observe(trade);
+        self.dirty.insert(trade.user_account.clone(), ());
+        profile.score
+    }
+
+    /// Score the trade and set its `is_bot` flag
+    pub fn classify(&self, trade: &mut TradeEvent) -> f64 {
+        let score = self.observe(trade);
+        trade.is_bot = score >= self.threshold;
+        score
+    }
+
+    /// Score of `wallet` decayed to `now` (0.0 if unknown)
+    pub fn score(&self, wallet: &str, now: i64) -> f64 {
+        self.profiles.get(wallet).map_or(0.0, |p| p.score_at(now))
+    }
+
+    /// Number of profiles held in memory
+    pub fn len(&self) -> usize {
+        self.profiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+
+    /// Changed profiles since the last call, clearing the dirty set
+    pub fn take_dirty(&self) -> Vec<(String, BotProfile)> {
+        let wallets: Vec<String> = self.dirty.iter().map(|e| e.key().clone()).collect();
+        wallets
+            .into_iter()
+            .filter_map(|wallet| {
+                self.dirty.remove(&wallet);
+                let profile = self.profiles.get(&wallet)?.clone();
+                Some((wallet, profile))
+            })
+            .collect()
+    }
+
+    /// Load persisted profiles (not marked dirty)
+    pub fn restore(&self, profiles: Vec<(String, BotProfile)>) {
+        for (wallet, profile) in profiles {
+            self.profiles.insert(wallet, profile);
+        }
+    }
+
+    /// Drop profiles idle for `PROFILE_IDLE_SECS` at `now`, returning how many
+    pub fn prune(&self, now: i64) -> usize {
+        let before = self.profiles.len();
+        self.profiles
+            .retain(|wallet, p| p.last_trade_ts >= now - PROFILE_IDLE_SECS || self.dirty.contains_key(wallet));
+        before - self.profiles.len()
+    }
+}
+
+/// Background loop that persists changed profiles and prunes idle ones
+///
+/// Skipped until the event clock has a time.
+pub async fn run_persist_loop(
+    classifier: Arc<BotClassifier>,
+    writer: mpsc::Sender<WriteRequest>,
+    clock: Arc<EventClock>,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+
+        let profiles = classifier.take_dirty();
+        let pruned = classifier.prune(now);
+        if !profiles.is_empty() || pruned > 0 {
+            log::debug!(
+                "🤖 BOT PROFILES | Persisting: {} | Pruned: {} | In memory: {}",
+                profiles.len(),
+                pruned,
+                classifier.len()
+            );
+        }
+
+        if profiles.is_empty() {
+            continue;
+        }
+        if let Err(e) = writer.send(WriteRequest::BotProfiles(profiles)).await {
+            log::warn!("⚠️  Failed to send bot profiles to writer: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(timestamp: i64, mint: &str, direction: TradeDirection, sol_amount: f64) -> TradeEvent {
+        TradeEvent {
+            token_amount: 1_000_000.0,
+            fee_lamports: 5_000,
+            ..TradeEvent::test(mint, timestamp, direction, sol_amount)
+        }
+    }
+
+    #[test]
+    fn test_active_human_is_not_flagged() {
+        let classifier = BotClassifier::default();
+
+        // Three quick trades (the old threshold rule), then irregular ones
+        let mut t = trade(1_000, "m1", TradeDirection::Buy, 0.734);
+        assert!(classifier.classify(&mut t) < 0.1);
+        let offsets = [12, 40, 400, 460, 2_000, 2_100, 5_000];
+        let sizes = [1.318, 0.2731, 2.0517, 0.913, 0.4412, 1.7093, 0.3881];
+        for (offset, size) in offsets.iter().zip(sizes) {
+            let mut t = trade(1_000 + offset, "m1", TradeDirection::Buy, size);
+            classifier.classify(&mut t);
+            assert!(!t.is_bot);
+        }
+    }
+
+    #[test]
+    fn test_regular_fixed_size_bot_is_flagged_and_decays() {
+        let classifier = BotClassifier::default();
+
+        let mut flagged = false;
+        for i in 0..30 {
+            let mint = format!("m{}", i % 8);
+            let mut t = trade(1_000 + i * 10, &mint, TradeDirection::Buy, 0.25);
+            t.tip_lamports = 100_000;
+            classifier.classify(&mut t);
+            flagged = t.is_bot;
+        }
+        assert!(flagged);
+
+        let score = classifier.score("wallet", 1_290);
+        assert!(score >= DEFAULT_BOT_THRESHOLD);
+        let later = classifier.score("wallet", 1_290 + SCORE_HALF_LIFE_SECS as i64);
+        assert!((later - score / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_same_slot_round_trip_feature() {
+        let mut profile = BotProfile::default();
+        let mut buy = trade(1_000, "m1", TradeDirection::Buy, 1.0);
+        buy.slot = 42;
+        let mut sell = trade(1_000, "m1", TradeDirection::Sell, 1.1);
+        sell.slot = 42;
+
+        assert_eq!(profile.observe(&buy).same_slot, 0.0);
+        assert!((profile.observe(&sell).same_slot - ALPHA).abs() < 1e-12);
+
+        // Next slot starts clean
+        buy.slot = 43;
+        assert!(profile.observe(&buy).same_slot < ALPHA);
+    }
+
+    #[test]
+    fn test_dirty_restore_and_prune() {
+        let classifier = BotClassifier::default();
+        classifier.observe(&trade(1_000, "m1", TradeDirection::Buy, 1.0));
+
+        let dirty = classifier.take_dirty();
+        assert_eq!(dirty.len(), 1);
+        assert_eq!(dirty[0].1.trades, 1);
+        assert!(classifier.take_dirty().is_empty());
+
+        let restored = BotClassifier::default();
+        restored.restore(dirty);
+        assert_eq!(restored.len(), 1);
+        assert!(restored.take_dirty().is_empty());
+
+        assert_eq!(restored.prune(1_000 + PROFILE_IDLE_SECS), 0);
+        assert_eq!(restored.prune(1_001 + PROFILE_IDLE_SECS), 1);
+        assert!(restored.is_empty());
+    }
+
+    #[test]
+    fn test_jito_tip_lamports() {
+        let keys = ["payer", JITO_TIP_ACCOUNTS[3], "pool"];
+        let pre = [10_000_000, 5_000, 1];
+        let post = [8_900_000, 1_005_000, 1];
+        assert_eq!(jito_tip_lamports(&keys, &pre, &post), 1_000_000);
+        assert_eq!(jito_tip_lamports(&keys[..1], &pre, &post), 0);
+    }
+}
@@ -0,0 +1,278 @@
+//! OHLCV candles per mint
+//!
+//! Prices are derived from each trade's execution: SOL paid or received per
+//! whole token (`token_amount` is in base units, scaled by `token_decimals`).
+//! A `CandleBook` holds the open candles of every interval for one mint.
+//! Candles close on event time (see `watermark`) and are then persisted to
+//! `token_candles`; trades that land in an already closed candle are counted
+//! and dropped.
+
+use crate::types::{TradeDirection, TradeEvent};
+use std::collections::VecDeque;
+
+/// Candle intervals in seconds (1s, 1m, 5m, 1h)
+pub const CANDLE_INTERVALS: [i64; 4] = [1, 60, 300, 3600];
+
+/// How long closed candles are kept in `token_candles`, per interval (seconds)
+pub const CANDLE_RETENTION: [(i64, i64); 4] = [
+    (1, 3_600),
+    (60, 86_400),
+    (300, 7 * 86_400),
+    (3600, 90 * 86_400),
+];
+
+/// Execution price of a trade in SOL per whole token
+///
+/// None when the trade carries no token amount.
+pub fn execution_price(trade: &TradeEvent) -> Option<f64> {
+    let tokens = trade.whole_tokens();
+    if tokens > 0.0 && trade.sol_amount > 0.0 {
+        Some(trade.sol_amount / tokens)
+    } else {
+        None
+    }
+}
+
+/// One OHLCV candle (prices in SOL per token, volumes in SOL)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candle {
+    pub mint: String,
+    pub interval_secs: i64,
+    /// Bucket start, a multiple of `interval_secs`
+    pub open_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume_sol: f64,
+    pub buy_volume_sol: f64,
+    pub sell_volume_sol: f64,
+    pub trade_count: u32,
+
+    /// Timestamps of the trades that set `open` and `close`, so out-of-order
+    /// trades inside the bucket do not overwrite them
+    first_trade_ts: i64,
+    last_trade_ts: i64,
+}
+
+impl Candle {
+    fn new(mint: &str, interval_secs: i64, open_time: i64, trade: &TradeEvent, price: f64) -> Self {
+        let mut candle = Self {
+            mint: mint.to_string(),
+            interval_secs,
+            open_time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume_sol: 0.0,
+            buy_volume_sol: 0.0,
+            sell_volume_sol: 0.0,
+            trade_count: 0,
+            first_trade_ts: trade.timestamp,
+            last_trade_ts: trade.timestamp,
+        };
+        candle.add(trade, price);
+        candle
+    }
+
+    fn add(&mut self, trade: &TradeEvent, price: f64) {
+        if trade.timestamp < self.first_trade_ts {
+            self.first_trade_ts = trade.timestamp;
+            self.open = price;
+        }
+        if trade.timestamp >= self.last_trade_ts {
+            self.last_trade_ts = trade.timestamp;
+            self.close = price;
+        }
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+
+        self.volume_sol += trade.sol_amount;
+        match trade.direction {
+            TradeDirection::Buy => self.buy_volume_sol += trade.sol_amount,
+            TradeDirection::Sell => self.sell_volume_sol += trade.sol_amount,
+            TradeDirection::Unknown => {}
+        }
+        self.trade_count += 1;
+    }
+}
+
+/// Open candles of one interval, oldest first
+#[derive(Debug, Clone)]
+struct CandleSeries {
+    interval_secs: i64,
+    open: VecDeque<Candle>,
+    /// Buckets starting before this are closed
+    closed_before: i64,
+}
+
+impl CandleSeries {
+    fn bucket(&self, timestamp: i64) -> i64 {
+        timestamp - timestamp.rem_euclid(self.interval_secs)
+    }
+}
+
+/// Open candles of every interval for one mint
+#[derive(Debug, Clone)]
+pub struct CandleBook {
+    mint: String,
+    series: Vec<CandleSeries>,
+
+    /// Trades dropped because their candle had already closed
+    pub late_trades: u64,
+}
+
+impl CandleBook {
+    pub fn new(mint: &str) -> Self {
+        Self {
+            mint: mint.to_string(),
+            series: CANDLE_INTERVALS
+                .iter()
+                .map(|&interval_secs| CandleSeries {
+                    interval_secs,
+                    open: VecDeque::new(),
+                    closed_before: i64::MIN,
+                })
+                .collect(),
+            late_trades: 0,
+        }
+    }
+
+    /// Add a trade to the open candle of every interval
+    ///
+    /// Trades without an execution price are ignored.
+    pub fn add(&mut self, trade: &TradeEvent) {
+        let Some(price) = execution_price(trade) else {
+            return;
+        };
+
+        for series in &mut self.series {
+            let open_time = series.bucket(trade.timestamp);
+            if open_time < series.closed_before {
+                self.late_trades += 1;
+                continue;
+            }
+
+            match series.open.binary_search_by_key(&open_time, |c| c.open_time) {
+                Ok(pos) => series.open[pos].add(trade, price),
+                Err(pos) => series.open.insert(
+                    pos,
+                    Candle::new(&self.mint, series.interval_secs, open_time, trade, price),
+                ),
+            }
+        }
+    }
+
+    /// True if `close(now)` would close at least one candle
+    pub fn is_due(&self, now: i64) -> bool {
+        self.series
+            .iter()
+            .any(|s| s.open.front().is_some_and(|c| c.open_time < s.bucket(now)))
+    }
+
+    /// Close every candle whose bucket ended at or before `now`
+    ///
+    /// Returned candles are ordered by interval, then by open time.
+    pub fn close(&mut self, now: i64) -> Vec<Candle> {
+        let mut closed = Vec::new();
+
+        for series in &mut self.series {
+            series.closed_before = series.closed_before.max(series.bucket(now));
+            while series
+                .open
+                .front()
+                .is_some_and(|c| c.open_time < series.closed_before)
+            {
+                closed.extend(series.open.pop_front());
+            }
+        }
+
+        closed
+    }
+
+    /// Current (open) candle of `interval_secs`, if any
+    pub fn current(&self, interval_secs: i64) -> Option<&Candle> {
+        self.series
+            .iter()
+            .find(|s| s.interval_secs == interval_secs)
+            .and_then(|s| s.open.back())
+    }
+
+    /// Approximate heap footprint in bytes
+    pub fn approx_bytes(&self) -> usize {
+        let open: usize = self.series.iter().map(|s| s.open.len()).sum();
+        self.series.len() * std::mem::size_of::<CandleSeries>()
+            + open * (std::mem::size_of::<Candle>() + self.mint.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(timestamp: i64, direction: TradeDirection, sol_amount: f64, tokens: f64) -> TradeEvent {
+        TradeEvent {
+            token_amount: tokens * 1_000_000.0,
+            ..TradeEvent::test("mint", timestamp, direction, sol_amount)
+        }
+    }
+
+    #[test]
+    fn test_execution_price_uses_decimals() {
+        // 1 SOL for 1,000 whole tokens (1e9 base units at 6 decimals)
+        let t = trade(1_000, TradeDirection::Buy, 1.0, 1_000.0);
+        assert!((execution_price(&t).unwrap() - 0.001).abs() < 1e-12);
+
+        let mut empty = t.clone();
+        empty.token_amount = 0.0;
+        assert_eq!(execution_price(&empty), None);
+    }
+
+    #[test]
+    fn test_candle_ohlcv_with_out_of_order_trade() {
+        let mut book = CandleBook::new("mint");
+        book.add(&trade(1_000, TradeDirection::Buy, 2.0, 1_000.0)); // 0.002
+        book.add(&trade(1_015, TradeDirection::Sell, 1.0, 250.0)); // 0.004
+        book.add(&trade(1_010, TradeDirection::Buy, 0.5, 500.0)); // 0.001, late in bucket
+        book.add(&trade(1_005, TradeDirection::Buy, 3.0, 1_000.0)); // 0.003
+
+        let candle = book.current(60).unwrap();
+        assert_eq!(candle.open_time, 960);
+        assert_eq!(candle.open, 0.002);
+        assert_eq!(candle.close, 0.004);
+        assert_eq!(candle.high, 0.004);
+        assert_eq!(candle.low, 0.001);
+        assert_eq!(candle.volume_sol, 6.5);
+        assert_eq!(candle.buy_volume_sol, 5.5);
+        assert_eq!(candle.sell_volume_sol, 1.0);
+        assert_eq!(candle.trade_count, 4);
+    }
+
+    #[test]
+    fn test_close_on_event_time_and_drop_late_trades() {
+        let mut book = CandleBook::new("mint");
+        book.add(&trade(1_000, TradeDirection::Buy, 1.0, 1_000.0));
+        book.add(&trade(1_001, TradeDirection::Buy, 1.0, 1_000.0));
+
+        assert!(!book.is_due(1_000));
+        assert!(book.is_due(1_001));
+
+        // Closes the 1s candle of 1_000 only
+        let closed = book.close(1_001);
+        assert_eq!(closed.len(), 1);
+        assert_eq!((closed[0].interval_secs, closed[0].open_time), (1, 1_000));
+
+        // 1_000 is closed for 1s candles, still open for longer intervals
+        book.add(&trade(1_000, TradeDirection::Sell, 1.0, 1_000.0));
+        assert_eq!(book.late_trades, 1);
+        assert_eq!(book.current(60).unwrap().trade_count, 3);
+
+        let closed = book.close(1_020);
+        let intervals: Vec<i64> = closed.iter().map(|c| c.interval_secs).collect();
+        assert_eq!(intervals, vec![1, 60]);
+        assert_eq!(closed[1].trade_count, 3);
+        assert!(book.current(60).is_none());
+        assert!(book.current(300).is_some());
+    }
+}
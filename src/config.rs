@@ -0,0 +1,284 @@
+//! Runtime configuration
+//!
+//! All tunables are read from environment variables (the same `.env` file that
+//! provides `GEYSER_URL` and `SOLFLOW_DB_PATH`). Missing or unparsable values
+//! fall back to the defaults documented on each field.
+
+use crate::{
+    bot_classifier::DEFAULT_BOT_THRESHOLD, signal_state::SignalStateConfig, snipers::DEFAULT_SNIPE_SLOTS,
+    state::DEFAULT_WINDOWS,
+};
+use std::{env, str::FromStr};
+
+/// Process-wide configuration loaded once at startup
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// `SOLFLOW_FILTER_REFRESH_SECS`: how often the blocklist/allowlist is
+    /// reloaded from SQLite (default: 30)
+    pub filter_refresh_secs: u64,
+
+    /// `SOLFLOW_ALLOWLIST_MODE`: when true, only mints present in
+    /// `followed_tokens` are ingested (default: false)
+    pub allowlist_mode: bool,
+
+    /// `SOLFLOW_VENUES`: comma-separated venues to subscribe to
+    /// (default: `pumpswap,pumpfun,moonshot,bonkswap,jupiter_dca`). Token
+    /// launches and their creators come from `pumpfun`
+    pub venues: Vec<String>,
+
+    /// `SOLFLOW_WINDOWS`: comma-separated rolling window durations in seconds
+    /// (default: `60,300,900,3600,7200,14400`). The 60s bot detection window
+    /// is always tracked; signals read the 60s, 300s and 900s windows.
+    pub windows: Vec<i64>,
+
+    /// `SOLFLOW_SWEEP_INTERVAL_SECS`: how often idle mints are dropped from
+    /// rolling state (default: 60)
+    pub sweep_interval_secs: u64,
+
+    /// `SOLFLOW_STATE_BUDGET_MB`: approximate memory budget for rolling state;
+    /// the coldest mints are dropped when it is exceeded, 0 disables
+    /// (default: 1024)
+    pub state_budget_mb: usize,
+
+    /// `SOLFLOW_WARM_START`: rebuild rolling state from `token_trades` on
+    /// startup (default: true)
+    pub warm_start: bool,
+
+    /// `SOLFLOW_DECAY_TICK_SECS`: how often windows of quiet mints are
+    /// evicted, re-published and re-evaluated (default: 5)
+    pub decay_tick_secs: u64,
+
+    /// `SOLFLOW_ALLOWED_LATENESS_SECS`: how far a trade may lag the newest
+    /// trade of its venue and still enter rolling state; older trades are
+    /// quarantined (default: 30)
+    pub allowed_lateness_secs: i64,
+
+    /// `SOLFLOW_POSITION_SNAPSHOT_SECS`: how often changed wallet positions
+    /// and holder metrics are persisted (default: 30)
+    pub position_snapshot_secs: u64,
+
+    /// `SOLFLOW_WALLET_SCORE_SECS`: how often wallets are rescored and the
+    /// smart-money set is refreshed (default: 900)
+    pub wallet_score_secs: u64,
+
+    /// `SOLFLOW_BOT_SCORE_THRESHOLD`: bot classifier score (0..1) at or above
+    /// which a wallet's trades are flagged `is_bot` (default: 0.4)
+    pub bot_score_threshold: f64,
+
+    /// `SOLFLOW_BOT_PROFILE_SECS`: how often changed bot classifier profiles
+    /// are persisted and idle ones dropped from memory (default: 60)
+    pub bot_profile_secs: u64,
+
+    /// `SOLFLOW_MARKET_SNAPSHOT_SECS`: how often market aggregates are
+    /// snapshotted, the regime reclassified and `market_metrics` appended
+    /// (default: 60)
+    pub market_snapshot_secs: u64,
+
+    /// `SOLFLOW_FUNDING_GRAPH`: subscribe to System Program SOL transfers and
+    /// cluster wallets by common funder; adds a large share of transactions
+    /// to the Geyser stream. When false every wallet is its own cluster
+    /// (default: true)
+    pub funding_graph: bool,
+
+    /// `SOLFLOW_SNIPE_SLOTS`: slots after a token or pool creation in which
+    /// buyers other than the creator count as snipers (default: 5)
+    pub snipe_slots: u64,
+
+    /// `SOLFLOW_SNIPER_PERSIST_SECS`: how often changed launches are
+    /// persisted and stale ones dropped from memory (default: 30)
+    pub sniper_persist_secs: u64,
+
+    /// `SOLFLOW_CREATOR_PERSIST_SECS`: how often created tokens are checked
+    /// for being finished and changed tokens and creator reputations are
+    /// persisted (default: 60)
+    pub creator_persist_secs: u64,
+
+    /// `SOLFLOW_LIQUIDITY_SNAPSHOT_SECS`: how often the latest reserves of
+    /// changed pools are appended to `pool_liquidity` (default: 60)
+    pub liquidity_snapshot_secs: u64,
+
+    /// `SOLFLOW_SHARDS`: number of single-task shards rolling state is split
+    /// into by mint hash (default: 4)
+    pub shards: usize,
+
+    /// `SOLFLOW_SHARD_METRICS_SECS`: how often per-shard queue depth and
+    /// throughput, and trades quarantined since the last report, are logged
+    /// (default: 30)
+    pub shard_metrics_secs: u64,
+
+    /// `SOLFLOW_AUDIT_SECS`: how often every mint's metrics are recomputed
+    /// from its trades and diffed against the incremental values; 0
+    /// disables the audit (default: 300)
+    pub audit_secs: u64,
+
+    /// `SOLFLOW_RULES_PATH`: JSON file of declarative signal rules replacing
+    /// the built-in ones (default: unset, built-in rules; see
+    /// `config/signal_rules.json`)
+    pub rules_path: Option<String>,
+
+    /// `SOLFLOW_RULES_RELOAD_SECS`: how often the rules file is checked for
+    /// changes (default: 5)
+    pub rules_reload_secs: u64,
+
+    /// Per-mint signal state machine (see `signal_state`):
+    /// - `SOLFLOW_SIGNAL_ENTER_STRENGTH`: strength a signal needs to enter
+    ///   (default: 0.25)
+    /// - `SOLFLOW_SIGNAL_EXIT_STRENGTH`: strength that keeps it active
+    ///   (default: 0.15)
+    /// - `SOLFLOW_SIGNAL_UPDATE_DELTA`: strength change that emits an update
+    ///   (default: 0.1)
+    /// - `SOLFLOW_SIGNAL_UPDATE_SECS`: minimum seconds between updates
+    ///   (default: 30)
+    /// - `SOLFLOW_SIGNAL_EXIT_SECS`: seconds without a qualifying firing
+    ///   before it exits (default: 60)
+    /// - `SOLFLOW_SIGNAL_COOLDOWN_SECS`: seconds after exiting before it can
+    ///   enter again (default: 300)
+    pub signal_states: SignalStateConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            filter_refresh_secs: 30,
+            allowlist_mode: false,
+            venues: ["pumpswap", "pumpfun", "moonshot", "bonkswap", "jupiter_dca"]
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+            windows: DEFAULT_WINDOWS.to_vec(),
+            sweep_interval_secs: 60,
+            state_budget_mb: 1024,
+            warm_start: true,
+            decay_tick_secs: 5,
+            allowed_lateness_secs: 30,
+            position_snapshot_secs: 30,
+            wallet_score_secs: 900,
+            bot_score_threshold: DEFAULT_BOT_THRESHOLD,
+            bot_profile_secs: 60,
+            market_snapshot_secs: 60,
+            funding_graph: true,
+            snipe_slots: DEFAULT_SNIPE_SLOTS,
+            sniper_persist_secs: 30,
+            creator_persist_secs: 60,
+            liquidity_snapshot_secs: 60,
+            shards: 4,
+            shard_metrics_secs: 30,
+            audit_secs: 300,
+            rules_path: None,
+            rules_reload_secs: 5,
+            signal_states: SignalStateConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Load configuration from the environment, falling back to defaults
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        Self {
+            filter_refresh_secs: env_or("SOLFLOW_FILTER_REFRESH_SECS", defaults.filter_refresh_secs),
+            allowlist_mode: env_flag("SOLFLOW_ALLOWLIST_MODE", defaults.allowlist_mode),
+            venues: env_list("SOLFLOW_VENUES").unwrap_or(defaults.venues),
+            windows: env_windows("SOLFLOW_WINDOWS").unwrap_or(defaults.windows),
+            sweep_interval_secs: env_or("SOLFLOW_SWEEP_INTERVAL_SECS", defaults.sweep_interval_secs),
+            state_budget_mb: env_or("SOLFLOW_STATE_BUDGET_MB", defaults.state_budget_mb),
+            warm_start: env_flag("SOLFLOW_WARM_START", defaults.warm_start),
+            decay_tick_secs: env_or("SOLFLOW_DECAY_TICK_SECS", defaults.decay_tick_secs),
+            allowed_lateness_secs: env_or("SOLFLOW_ALLOWED_LATENESS_SECS", defaults.allowed_lateness_secs),
+            position_snapshot_secs: env_or("SOLFLOW_POSITION_SNAPSHOT_SECS", defaults.position_snapshot_secs),
+            wallet_score_secs: env_or("SOLFLOW_WALLET_SCORE_SECS", defaults.wallet_score_secs),
+            bot_score_threshold: env_or("SOLFLOW_BOT_SCORE_THRESHOLD", defaults.bot_score_threshold),
+            bot_profile_secs: env_or("SOLFLOW_BOT_PROFILE_SECS", defaults.bot_profile_secs),
+            market_snapshot_secs: env_or("SOLFLOW_MARKET_SNAPSHOT_SECS", defaults.market_snapshot_secs),
+            funding_graph: env_flag("SOLFLOW_FUNDING_GRAPH", defaults.funding_graph),
+            snipe_slots: env_or("SOLFLOW_SNIPE_SLOTS", defaults.snipe_slots),
+            sniper_persist_secs: env_or("SOLFLOW_SNIPER_PERSIST_SECS", defaults.sniper_persist_secs),
+            creator_persist_secs: env_or("SOLFLOW_CREATOR_PERSIST_SECS", defaults.creator_persist_secs),
+            liquidity_snapshot_secs: env_or("SOLFLOW_LIQUIDITY_SNAPSHOT_SECS", defaults.liquidity_snapshot_secs),
+            shards: env_or("SOLFLOW_SHARDS", defaults.shards).max(1),
+            shard_metrics_secs: env_or("SOLFLOW_SHARD_METRICS_SECS", defaults.shard_metrics_secs),
+            audit_secs: env_or("SOLFLOW_AUDIT_SECS", defaults.audit_secs),
+            rules_path: env::var("SOLFLOW_RULES_PATH")
+                .ok()
+                .map(|path| path.trim().to_string())
+                .filter(|path| !path.is_empty()),
+            rules_reload_secs: env_or("SOLFLOW_RULES_RELOAD_SECS", defaults.rules_reload_secs),
+            signal_states: signal_state_from_env(defaults.signal_states),
+        }
+    }
+}
+
+/// Signal state machine thresholds; the exit strength is capped at the enter
+/// strength
+fn signal_state_from_env(defaults: SignalStateConfig) -> SignalStateConfig {
+    let enter_strength = env_or("SOLFLOW_SIGNAL_ENTER_STRENGTH", defaults.enter_strength);
+    SignalStateConfig {
+        enter_strength,
+        exit_strength: env_or("SOLFLOW_SIGNAL_EXIT_STRENGTH", defaults.exit_strength).min(enter_strength),
+        update_delta: env_or("SOLFLOW_SIGNAL_UPDATE_DELTA", defaults.update_delta),
+        update_secs: env_or("SOLFLOW_SIGNAL_UPDATE_SECS", defaults.update_secs),
+        exit_secs: env_or("SOLFLOW_SIGNAL_EXIT_SECS", defaults.exit_secs),
+        cooldown_secs: env_or("SOLFLOW_SIGNAL_COOLDOWN_SECS", defaults.cooldown_secs),
+    }
+}
+
+/// Parse an environment variable, logging and falling back to `default` on error
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    match env::var(key) {
+        Ok(raw) => match raw.trim().parse() {
+            Ok(value) => value,
+            Err(_) => {
+                log::warn!("⚠️  Invalid value for {}: {:?}, using default", key, raw);
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+/// Parse a boolean flag (`1`/`true`/`yes`/`on`, case-insensitive)
+fn env_flag(key: &str, default: bool) -> bool {
+    match env::var(key) {
+        Ok(raw) => matches!(raw.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"),
+        Err(_) => default,
+    }
+}
+
+/// Parse a comma-separated list, ignoring empty entries
+fn env_list(key: &str) -> Option<Vec<String>> {
+    let raw = env::var(key).ok()?;
+    let items: Vec<String> = raw
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect();
+
+    if items.is_empty() {
+        None
+    } else {
+        Some(items)
+    }
+}
+
+/// Parse a comma-separated list of positive window durations (seconds)
+///
+/// Invalid entries are logged and skipped; returns None if nothing valid remains.
+fn env_windows(key: &str) -> Option<Vec<i64>> {
+    let windows: Vec<i64> = env_list(key)?
+        .iter()
+        .filter_map(|item| match item.parse::<i64>() {
+            Ok(secs) if secs > 0 => Some(secs),
+            _ => {
+                log::warn!("⚠️  Ignoring invalid window in {}: {:?}", key, item);
+                None
+            }
+        })
+        .collect();
+
+    if windows.is_empty() {
+        None
+    } else {
+        Some(windows)
+    }
+}
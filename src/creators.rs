@@ -0,0 +1,588 @@
+//! Creator (dev) wallet tracking and reputation
+//!
+//! The creator of a mint is taken from its `LaunchEvent` (Pumpfun `create`,
+//! PumpSwap `create_pool`). Pools pump.fun creates when it migrates a token
+//! are skipped: their signer is the migration authority shared by every
+//! migrated token, not the dev, who is only known from the `create`. While the token is live, the creator's trades are
+//! watched, as are drops in the creator's token balance that no sell explains
+//! (supply moved to other wallets in transactions the pipeline sees). Both are
+//! `DEV_SELL` signals.
+//!
+//! A token is finished once it has not traded for `TOKEN_IDLE_SECS` or is
+//! older than `TOKEN_MAX_AGE_SECS`. Its outcome feeds the creator's
+//! reputation: a token whose last price is at most `DEAD_PRICE_FRACTION` of
+//! its peak (or that never traded) went to zero, and its lifetime is the time
+//! from launch to its last trade. `CreatorReputation::risk_score` turns the
+//! share of dead tokens and their median lifetime into a risk factor that is
+//! attached to the creator's signals.
+//!
+//! Tokens are persisted to `creator_tokens` and reputations to
+//! `creator_reputation`; tokens launched in the last `REPUTATION_MEMORY_SECS`
+//! are restored on startup and rebuild the reputations.
+
+use crate::{
+    db::WriteRequest,
+    signals::Signal,
+    snipers::LAUNCH_SUPPLY_TOKENS,
+    types::{LaunchEvent, TradeDirection, TradeEvent},
+    watermark::EventClock,
+};
+use dashmap::DashMap;
+use serde_json::{json, Value};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+
+/// Creator sells and moves below this share of supply are ignored
+pub const DEV_MOVE_MIN_SHARE: f64 = 0.001;
+
+/// A token without trades for this long is finished
+pub const TOKEN_IDLE_SECS: i64 = 21_600;
+
+/// A token older than this is finished even if it still trades
+pub const TOKEN_MAX_AGE_SECS: i64 = 7 * 86_400;
+
+/// Last price at or below this fraction of the peak counts as zero
+pub const DEAD_PRICE_FRACTION: f64 = 0.1;
+
+/// Launches older than this no longer count towards reputation
+pub const REPUTATION_MEMORY_SECS: i64 = 30 * 86_400;
+
+/// Median lifetime at or below which a creator's tokens count as short-lived
+pub const SHORT_LIFETIME_SECS: i64 = 3_600;
+
+/// Finished tokens needed for a reputation to carry its full weight
+const MIN_REPUTATION_TOKENS: u32 = 3;
+
+/// What the creator did with its supply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DevAction {
+    /// Sold through a venue
+    Sell,
+    /// Token balance dropped without a sell
+    Transfer,
+}
+
+impl DevAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DevAction::Sell => "sell",
+            DevAction::Transfer => "transfer",
+        }
+    }
+}
+
+/// Lifecycle of a created token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenStatus {
+    Live,
+    /// Finished after its price went to zero (or without trading)
+    Dead,
+    /// Finished with its price above `DEAD_PRICE_FRACTION` of the peak
+    Survived,
+}
+
+impl TokenStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenStatus::Live => "live",
+            TokenStatus::Dead => "dead",
+            TokenStatus::Survived => "survived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "live" => Some(TokenStatus::Live),
+            "dead" => Some(TokenStatus::Dead),
+            "survived" => Some(TokenStatus::Survived),
+            _ => None,
+        }
+    }
+}
+
+/// A launched token and what its creator did with it (whole tokens, SOL per
+/// whole token)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatedToken {
+    pub mint: String,
+    pub creator: String,
+    pub source_program: String,
+    pub launched_at: i64,
+    /// Event time of the last trade (the launch time before any)
+    pub last_trade: i64,
+    pub peak_price: f64,
+    pub last_price: f64,
+    pub dev_bought_tokens: f64,
+    pub dev_sold_tokens: f64,
+    pub dev_moved_tokens: f64,
+    pub dev_sells: u32,
+    pub status: TokenStatus,
+}
+
+impl CreatedToken {
+    pub fn new(launch: &LaunchEvent) -> Self {
+        Self {
+            mint: launch.mint.clone(),
+            creator: launch.creator.clone(),
+            source_program: launch.source_program.clone(),
+            launched_at: launch.timestamp,
+            last_trade: launch.timestamp,
+            peak_price: 0.0,
+            last_price: 0.0,
+            dev_bought_tokens: 0.0,
+            dev_sold_tokens: 0.0,
+            dev_moved_tokens: 0.0,
+            dev_sells: 0,
+            status: TokenStatus::Live,
+        }
+    }
+
+    /// Share of supply the creator sold or moved
+    pub fn dev_out_share(&self) -> f64 {
+        (self.dev_sold_tokens + self.dev_moved_tokens) / LAUNCH_SUPPLY_TOKENS
+    }
+
+    /// Seconds from launch to the last trade
+    pub fn lifetime_secs(&self) -> i64 {
+        self.last_trade - self.launched_at
+    }
+
+    /// Price went to zero, or the token never traded
+    pub fn is_dead(&self) -> bool {
+        self.peak_price <= 0.0 || self.last_price <= DEAD_PRICE_FRACTION * self.peak_price
+    }
+
+    /// Status at `now`: finished once idle or too old
+    fn status_at(&self, now: i64) -> TokenStatus {
+        let finished = self.last_trade < now - TOKEN_IDLE_SECS || self.launched_at < now - TOKEN_MAX_AGE_SECS;
+        match (finished, self.is_dead()) {
+            (false, _) => TokenStatus::Live,
+            (true, true) => TokenStatus::Dead,
+            (true, false) => TokenStatus::Survived,
+        }
+    }
+}
+
+/// Track record of a creator over its recent launches
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CreatorReputation {
+    pub creator: String,
+    pub tokens_launched: u32,
+    pub tokens_finished: u32,
+    pub tokens_dead: u32,
+    pub dev_sells: u32,
+    pub last_launch: i64,
+    /// Lifetimes (seconds) of finished tokens
+    pub lifetimes: Vec<i64>,
+}
+
+impl CreatorReputation {
+    fn new(creator: &str) -> Self {
+        Self {
+            creator: creator.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Count a finished token
+    fn finish(&mut self, token: &CreatedToken) {
+        self.tokens_finished += 1;
+        if token.status == TokenStatus::Dead {
+            self.tokens_dead += 1;
+        }
+        self.lifetimes.push(token.lifetime_secs());
+    }
+
+    /// Share of finished tokens that went to zero
+    pub fn dead_share(&self) -> f64 {
+        if self.tokens_finished == 0 {
+            return 0.0;
+        }
+        self.tokens_dead as f64 / self.tokens_finished as f64
+    }
+
+    /// Median lifetime of finished tokens
+    pub fn median_lifetime_secs(&self) -> Option<i64> {
+        if self.lifetimes.is_empty() {
+            return None;
+        }
+        let mut lifetimes = self.lifetimes.clone();
+        lifetimes.sort_unstable();
+        Some(lifetimes[lifetimes.len() / 2])
+    }
+
+    /// Risk that the creator's next token goes to zero (0.0 ..= 1.0)
+    ///
+    /// Weighs the dead share (0.7) and short lifetimes (0.3), discounted
+    /// until `MIN_REPUTATION_TOKENS` tokens finished.
+    pub fn risk_score(&self) -> f64 {
+        let Some(median) = self.median_lifetime_secs() else {
+            return 0.0;
+        };
+        let short_lived = 1.0 - (median as f64 / SHORT_LIFETIME_SECS as f64).min(1.0);
+        let confidence = (self.tokens_finished as f64 / MIN_REPUTATION_TOKENS as f64).min(1.0);
+        (confidence * (0.7 * self.dead_share() + 0.3 * short_lived)).clamp(0.0, 1.0)
+    }
+}
+
+/// Shared creator tracker consulted on every extracted trade
+#[derive(Debug, Default)]
+pub struct CreatorTracker {
+    /// Live tokens by mint
+    tokens: DashMap<String, CreatedToken>,
+    reputations: DashMap<String, CreatorReputation>,
+    /// Live mints changed since the last `take_dirty`
+    dirty_tokens: DashMap<String, ()>,
+    /// Creators whose reputation changed since the last `take_dirty`
+    dirty_creators: DashMap<String, ()>,
+}
+
+impl CreatorTracker {
+    /// Start tracking a launch; returns false if the mint is already tracked
+    pub fn record_launch(&self, launch: &LaunchEvent) -> bool {
+        if launch.migration || self.tokens.contains_key(&launch.mint) {
+            return false;
+        }
+
+        let mut reputation = self
+            .reputations
+            .entry(launch.creator.clone())
+            .or_insert_with(|| CreatorReputation::new(&launch.creator));
+        reputation.tokens_launched += 1;
+        reputation.last_launch = reputation.last_launch.max(launch.timestamp);
+
+        self.tokens.insert(launch.mint.clone(), CreatedToken::new(launch));
+        self.dirty_tokens.insert(launch.mint.clone(), ());
+        self.dirty_creators.insert(launch.creator.clone(), ());
+        true
+    }
+
+    /// Update a live token's price and its creator's position with a trade
+    ///
+    /// Returns a snapshot of the token when the trade is a creator sell of at
+    /// least `DEV_MOVE_MIN_SHARE` of supply.
+    pub fn observe(&self, trade: &TradeEvent) -> Option<CreatedToken> {
+        let mut token = self.tokens.get_mut(&trade.mint)?;
+        let tokens = trade.whole_tokens();
+
+        if tokens > 0.0 {
+            let price = trade.sol_amount / tokens;
+            token.peak_price = token.peak_price.max(price);
+            token.last_price = price;
+        }
+        token.last_trade = token.last_trade.max(trade.timestamp);
+        self.dirty_tokens.insert(trade.mint.clone(), ());
+
+        if trade.user_account != token.creator {
+            return None;
+        }
+        match trade.direction {
+            TradeDirection::Buy => {
+                token.dev_bought_tokens += tokens;
+                None
+            }
+            TradeDirection::Sell => {
+                token.dev_sold_tokens += tokens;
+                token.dev_sells += 1;
+                self.count_dev_sell(&token.creator);
+                (tokens / LAUNCH_SUPPLY_TOKENS >= DEV_MOVE_MIN_SHARE).then(|| token.clone())
+            }
+            TradeDirection::Unknown => None,
+        }
+    }
+
+    /// Record tokens leaving `owner`'s balance of `mint` without a sell
+    ///
+    /// Returns a snapshot of the token when `owner` is its creator and the
+    /// move is at least `DEV_MOVE_MIN_SHARE` of supply.
+    pub fn observe_transfer(&self, owner: &str, mint: &str, tokens: f64) -> Option<CreatedToken> {
+        if tokens / LAUNCH_SUPPLY_TOKENS < DEV_MOVE_MIN_SHARE {
+            return None;
+        }
+        let mut token = self.tokens.get_mut(mint)?;
+        if token.creator != owner {
+            return None;
+        }
+
+        token.dev_moved_tokens += tokens;
+        self.dirty_tokens.insert(mint.to_string(), ());
+        Some(token.clone())
+    }
+
+    fn count_dev_sell(&self, creator: &str) {
+        if let Some(mut reputation) = self.reputations.get_mut(creator) {
+            reputation.dev_sells += 1;
+            self.dirty_creators.insert(creator.to_string(), ());
+        }
+    }
+
+    /// Creator of a live token
+    pub fn creator_of(&self, mint: &str) -> Option<String> {
+        self.tokens.get(mint).map(|token| token.creator.clone())
+    }
+
+    /// Snapshot of a creator's reputation
+    pub fn reputation(&self, creator: &str) -> Option<CreatorReputation> {
+        self.reputations.get(creator).map(|r| r.clone())
+    }
+
+    /// Record the creator and its risk score in the metadata of signals for
+    /// live tokens whose creator has finished tokens
+    pub fn annotate(&self, signals: &mut [Signal]) {
+        for signal in signals {
+            let Some(creator) = self.creator_of(&signal.mint) else {
+                continue;
+            };
+            let Some(reputation) = self.reputation(&creator) else {
+                continue;
+            };
+            if reputation.tokens_finished == 0 {
+                continue;
+            }
+            if let Value::Object(metadata) = &mut signal.metadata {
+                metadata.insert("creator".to_string(), json!(creator));
+                metadata.insert("creator_risk".to_string(), json!(reputation.risk_score()));
+            }
+        }
+    }
+
+    /// Number of live tokens
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Finish tokens idle or too old at `now` and drop reputations without
+    /// launches in `REPUTATION_MEMORY_SECS`, returning the finished tokens
+    pub fn finish(&self, now: i64) -> Vec<CreatedToken> {
+        let mut finished = Vec::new();
+        self.tokens.retain(|_, token| {
+            let status = token.status_at(now);
+            if status == TokenStatus::Live {
+                return true;
+            }
+            token.status = status;
+            finished.push(token.clone());
+            false
+        });
+
+        for token in &finished {
+            self.dirty_tokens.remove(&token.mint);
+            if let Some(mut reputation) = self.reputations.get_mut(&token.creator) {
+                reputation.finish(token);
+                self.dirty_creators.insert(token.creator.clone(), ());
+            }
+        }
+
+        let live: HashSet<String> = self.tokens.iter().map(|token| token.creator.clone()).collect();
+        self.reputations.retain(|creator, reputation| {
+            reputation.last_launch >= now - REPUTATION_MEMORY_SECS || live.contains(creator)
+        });
+
+        finished
+    }
+
+    /// Changed live tokens and reputations since the last call, clearing the
+    /// dirty sets
+    pub fn take_dirty(&self) -> (Vec<CreatedToken>, Vec<CreatorReputation>) {
+        let mints: Vec<String> = self.dirty_tokens.iter().map(|e| e.key().clone()).collect();
+        let tokens = mints
+            .into_iter()
+            .filter_map(|mint| {
+                self.dirty_tokens.remove(&mint);
+                self.tokens.get(&mint).map(|t| t.clone())
+            })
+            .collect();
+
+        let creators: Vec<String> = self.dirty_creators.iter().map(|e| e.key().clone()).collect();
+        let reputations = creators
+            .into_iter()
+            .filter_map(|creator| {
+                self.dirty_creators.remove(&creator);
+                self.reputation(&creator)
+            })
+            .collect();
+
+        (tokens, reputations)
+    }
+
+    /// Load persisted tokens (not marked dirty): live ones are tracked again,
+    /// finished ones rebuild their creators' reputations
+    pub fn restore(&self, tokens: Vec<CreatedToken>) {
+        for token in tokens {
+            let mut reputation = self
+                .reputations
+                .entry(token.creator.clone())
+                .or_insert_with(|| CreatorReputation::new(&token.creator));
+            reputation.tokens_launched += 1;
+            reputation.dev_sells += token.dev_sells;
+            reputation.last_launch = reputation.last_launch.max(token.launched_at);
+
+            if token.status == TokenStatus::Live {
+                self.tokens.insert(token.mint.clone(), token);
+            } else {
+                reputation.finish(&token);
+            }
+        }
+    }
+}
+
+/// Background loop that finishes stale tokens and persists changed tokens and
+/// reputations
+///
+/// Skipped until the event clock has a time.
+pub async fn run_persist_loop(
+    tracker: Arc<CreatorTracker>,
+    writer: mpsc::Sender<WriteRequest>,
+    clock: Arc<EventClock>,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+
+        let mut tokens = tracker.finish(now);
+        let finished = tokens.len();
+        let (live, reputations) = tracker.take_dirty();
+        tokens.extend(live);
+
+        if tokens.is_empty() && reputations.is_empty() {
+            continue;
+        }
+        log::debug!(
+            "👤 CREATORS | Tokens: {} ({} finished) | Reputations: {} | Live: {}",
+            tokens.len(),
+            finished,
+            reputations.len(),
+            tracker.len()
+        );
+
+        if let Err(e) = writer.send(WriteRequest::Creators { tokens, reputations }).await {
+            log::warn!("⚠️  Failed to send creators to writer: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signals::SignalType;
+
+    fn launch(mint: &str, timestamp: i64) -> LaunchEvent {
+        LaunchEvent {
+            mint: mint.to_string(),
+            creator: "dev".to_string(),
+            source_program: "Pumpfun".to_string(),
+            timestamp,
+            slot: 0,
+            tip_lamports: 0,
+            migration: false,
+        }
+    }
+
+    fn trade(mint: &str, wallet: &str, direction: TradeDirection, timestamp: i64, tokens: f64, sol_amount: f64) -> TradeEvent {
+        TradeEvent {
+            token_amount: tokens * 1_000_000.0,
+            user_account: wallet.to_string(),
+            source_program: "Pumpfun".to_string(),
+            ..TradeEvent::test(mint, timestamp, direction, sol_amount)
+        }
+    }
+
+    #[test]
+    fn test_creator_sells_and_moves_are_reported() {
+        let tracker = CreatorTracker::default();
+        assert!(tracker.record_launch(&launch("mint", 1_000)));
+        assert!(!tracker.record_launch(&launch("mint", 2_000)));
+
+        assert!(tracker.observe(&trade("mint", "dev", TradeDirection::Buy, 1_000, 5e7, 1.0)).is_none());
+        assert!(tracker.observe(&trade("mint", "other", TradeDirection::Sell, 1_010, 5e7, 1.0)).is_none());
+        // Dust sells are counted but not reported
+        assert!(tracker.observe(&trade("mint", "dev", TradeDirection::Sell, 1_020, 1e5, 0.002)).is_none());
+
+        let token = tracker.observe(&trade("mint", "dev", TradeDirection::Sell, 1_030, 2e7, 0.4)).unwrap();
+        assert_eq!(token.dev_sells, 2);
+        assert!(tracker.observe_transfer("other", "mint", 2e7).is_none());
+        let token = tracker.observe_transfer("dev", "mint", 2e7).unwrap();
+        assert!((token.dev_out_share() - 0.0401).abs() < 1e-9);
+        assert_eq!(tracker.reputation("dev").unwrap().dev_sells, 2);
+    }
+
+    #[test]
+    fn test_migration_pool_is_not_a_creator() {
+        let tracker = CreatorTracker::default();
+        let migration = LaunchEvent {
+            creator: "migration_authority".to_string(),
+            source_program: "PumpSwap".to_string(),
+            migration: true,
+            ..launch("migrated", 5_000)
+        };
+
+        // A migration seen without the create attributes the token to no one
+        assert!(!tracker.record_launch(&migration));
+        assert!(tracker.reputation("migration_authority").is_none());
+        let sell = trade("migrated", "migration_authority", TradeDirection::Sell, 5_010, 2e7, 0.4);
+        assert!(tracker.observe(&sell).is_none());
+
+        // After the create, the dev stays the creator through the migration
+        assert!(tracker.record_launch(&launch("mint", 1_000)));
+        assert!(!tracker.record_launch(&LaunchEvent { mint: "mint".to_string(), ..migration }));
+        assert!(tracker.observe(&trade("mint", "dev", TradeDirection::Sell, 5_020, 2e7, 0.4)).is_some());
+        assert_eq!(tracker.reputation("dev").unwrap().tokens_launched, 1);
+        assert!(tracker.reputation("migration_authority").is_none());
+    }
+
+    #[test]
+    fn test_finished_tokens_build_reputation() {
+        let tracker = CreatorTracker::default();
+        for (i, mint) in ["a", "b", "c"].iter().enumerate() {
+            tracker.record_launch(&launch(mint, 1_000));
+            tracker.observe(&trade(mint, "buyer", TradeDirection::Buy, 1_100, 1e6, 1.0));
+            // a and b crash, c holds its price
+            let exit = if i < 2 { 0.05 } else { 0.9 };
+            tracker.observe(&trade(mint, "buyer", TradeDirection::Sell, 1_000 + 600 * (i as i64 + 1), 1e6, exit));
+        }
+
+        assert!(tracker.finish(2_000).is_empty());
+        let finished = tracker.finish(2_801 + TOKEN_IDLE_SECS);
+        assert_eq!(finished.len(), 3);
+        assert!(tracker.is_empty());
+
+        let reputation = tracker.reputation("dev").unwrap();
+        assert_eq!((reputation.tokens_finished, reputation.tokens_dead), (3, 2));
+        assert_eq!(reputation.median_lifetime_secs(), Some(1_200));
+        let expected = 0.7 * 2.0 / 3.0 + 0.3 * (1.0 - 1_200.0 / SHORT_LIFETIME_SECS as f64);
+        assert!((reputation.risk_score() - expected).abs() < 1e-9);
+
+        // Restoring the persisted tokens rebuilds the same reputation
+        let restored = CreatorTracker::default();
+        restored.restore(finished);
+        assert_eq!(restored.reputation("dev"), Some(reputation));
+    }
+
+    #[test]
+    fn test_annotate_adds_creator_risk() {
+        let tracker = CreatorTracker::default();
+        tracker.record_launch(&launch("old", 1_000));
+        tracker.finish(1_000 + TOKEN_IDLE_SECS + 1);
+        tracker.record_launch(&launch("new", 30_000));
+
+        let mut signals = vec![
+            Signal::new("new".to_string(), SignalType::Breakout, 0.8, "300s".to_string(), 0, json!({})),
+            Signal::new("untracked".to_string(), SignalType::Breakout, 0.8, "300s".to_string(), 0, json!({})),
+        ];
+        tracker.annotate(&mut signals);
+
+        assert_eq!(signals[0].metadata["creator"], "dev");
+        assert!(signals[0].metadata["creator_risk"].as_f64().unwrap() > 0.0);
+        assert_eq!(signals[1].metadata, json!({}));
+    }
+}
@@ -3,10 +3,22 @@
 //! Real-time SQLite persistence for rolling metrics and trade events.
 //! Non-blocking async write loop with batching support.
 
-use rusqlite::{Connection, params};
-use std::{env, error::Error};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::{collections::{HashMap, HashSet}, env, error::Error};
 use tokio::sync::mpsc;
-use crate::{state::RollingMetrics, types::TradeEvent, signals::Signal};
+use crate::{
+    bot_classifier::BotProfile,
+    candles::{Candle, CANDLE_RETENTION},
+    market::MarketSnapshot,
+    creators::{CreatedToken, CreatorReputation, TokenStatus},
+    positions::{Position, PositionSnapshot, WalletPositions},
+    snipers::{Launch, SniperKind, SniperPosition},
+    state::RollingMetrics,
+    types::{LaunchEvent, PoolReserves, TradeEvent},
+    signal_state::{SignalPhase, SignalState},
+    signals::{Signal, SignalType},
+    wallets::{WalletEntry, WalletScore},
+};
 
 pub use crate::sqlite_pragma;
 
@@ -19,8 +31,38 @@ pub enum WriteRequest {
     Trade(TradeEvent),
     /// Phase 6: Append signal event to signals table
     Signal(Signal),
+    /// UPSERT changed signal states; inactive ones are deleted
+    SignalStates(Vec<SignalState>),
+    /// UPSERT a closed OHLCV candle
+    Candle(Candle),
+    /// UPSERT holder metrics and changed wallet positions of a token
+    Positions(PositionSnapshot),
+    /// UPSERT a scoring run's wallet scores
+    WalletScores(Vec<WalletScore>),
+    /// UPSERT changed bot classifier profiles
+    BotProfiles(Vec<(String, BotProfile)>),
+    /// Append a market-wide snapshot
+    MarketMetrics(MarketSnapshot),
+    /// UPSERT changed launches and their snipers
+    Launches(Vec<Launch>),
+    /// Append reserves of changed pools to the liquidity history
+    Liquidity(Vec<PoolReserves>),
+    /// UPSERT changed or finished created tokens and creator reputations
+    Creators {
+        tokens: Vec<CreatedToken>,
+        reputations: Vec<CreatorReputation>,
+    },
 }
 
+/// How often the write loop prunes rows past their retention
+const RETENTION_INTERVAL: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Closed wallet positions are kept this long after their last trade (seconds)
+const CLOSED_POSITION_RETENTION_SECS: i64 = 30 * 86_400;
+
+/// Market snapshots are kept this long (seconds)
+const MARKET_METRICS_RETENTION_SECS: i64 = 30 * 86_400;
+
 /// Initialize database with single consolidated schema
 pub fn init_database() -> Result<(), Box<dyn Error>> {
     let db_path = env::var("SOLFLOW_DB_PATH")
@@ -31,6 +73,8 @@ pub fn init_database() -> Result<(), Box<dyn Error>> {
     sqlite_pragma::apply_optimized_pragmas(&conn)?;
     
     apply_initial_schema(&conn)?;
+
+    migrate_schema(&conn)?;
     
     // Debug: Verify token_signals schema
     log::info!("🔍 Verifying token_signals schema:");
@@ -52,6 +96,76 @@ fn apply_initial_schema(conn: &Connection) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Columns added after a table was first released
+///
+/// `CREATE TABLE IF NOT EXISTS` leaves existing tables untouched, so databases
+/// created by older builds get these columns through `ALTER TABLE`.
+const ADDED_COLUMNS: &[(&str, &str, &str)] = &[
+    ("token_trades", "source_program", "TEXT NOT NULL DEFAULT ''"),
+    ("token_trades", "token_amount", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_trades", "token_decimals", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "warming", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "buy_volume", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "sell_volume", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "volume", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_trades", "is_smart_money", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "smart_money_buys", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "smart_money_flow", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "smart_money_wallets", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_rolling_metrics", "smart_money_buys_300s", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_rolling_metrics", "smart_money_flow_300s", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_rolling_metrics", "smart_money_wallets_300s", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_trades", "slot", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_trades", "fee_lamports", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_trades", "tip_lamports", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_trades", "is_wash", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "buy_size_p50", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "buy_size_p90", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "buy_size_p99", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "sell_size_p50", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "sell_size_p90", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "sell_size_p99", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "retail_buys", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "mid_buys", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "whale_buys", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "retail_sells", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "mid_sells", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "whale_sells", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "dominant_venue", "TEXT NOT NULL DEFAULT ''"),
+    ("token_window_metrics", "dominant_venue_share", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "wash_score", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "wash_volume", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "wash_wallets", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "wash_trades", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_window_metrics", "organic_volume", "REAL NOT NULL DEFAULT 0.0"),
+    ("token_window_metrics", "organic_wallets", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_trades", "funding_cluster", "TEXT"),
+    ("token_window_metrics", "unique_clusters", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_rolling_metrics", "unique_clusters_300s", "INTEGER NOT NULL DEFAULT 0"),
+    ("token_trades", "price_impact", "REAL"),
+    ("token_rolling_metrics", "liquidity_sol", "REAL"),
+    ("token_rolling_metrics", "flow_liquidity_ratio_300s", "REAL"),
+    ("token_signals", "event", "TEXT NOT NULL DEFAULT 'entered'"),
+];
+
+/// Add any column from `ADDED_COLUMNS` missing from an existing table
+fn migrate_schema(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    for (table, column, decl) in ADDED_COLUMNS {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|r| r.ok())
+            .any(|name| name == *column);
+
+        if !exists {
+            log::info!("🔧 Adding column {}.{}", table, column);
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl), [])?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Verify token_signals table schema (Phase 6 verification)
 fn verify_signals_schema(conn: &Connection) -> Result<(), Box<dyn Error>> {
     let mut stmt = conn.prepare("PRAGMA table_info(token_signals)")?;
@@ -70,7 +184,7 @@ fn verify_signals_schema(conn: &Connection) -> Result<(), Box<dyn Error>> {
     // Verify expected Phase 6 columns exist
     let expected_columns = vec![
         "id", "mint", "signal_type", "strength", 
-        "window", "timestamp", "metadata", "created_at"
+        "window", "timestamp", "metadata", "created_at", "event"
     ];
     
     for expected in expected_columns {
@@ -95,9 +209,17 @@ fn verify_signals_schema(conn: &Connection) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-/// UPSERT rolling metrics into token_rolling_metrics table
+/// UPSERT rolling metrics for a token
+///
+/// Every tracked window is written to `token_window_metrics` (one row per
+/// mint and window) and its venue breakdown to `token_venue_metrics`, which
+/// is replaced as a whole so venues that left every window disappear. The
+/// fixed-column `token_rolling_metrics` row read by the
+/// dashboard is kept up to date from the 60s…14400s windows; windows that
+/// are not tracked are written as zero.
 pub fn write_aggregated_state(conn: &Connection, mint: &str, metrics: &RollingMetrics) -> Result<(), Box<dyn Error>> {
     let now = chrono::Utc::now().timestamp();
+    let w300 = metrics.window(300);
     
     conn.execute(
         "INSERT INTO token_rolling_metrics (
@@ -105,8 +227,10 @@ pub fn write_aggregated_state(conn: &Connection, mint: &str, metrics: &RollingMe
             net_flow_60s, net_flow_300s, net_flow_900s, 
             net_flow_3600s, net_flow_7200s, net_flow_14400s,
             unique_wallets_300s, bot_wallets_300s, bot_trades_300s, bot_flow_300s,
-            dca_flow_300s, dca_unique_wallets_300s, dca_ratio_300s
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+            dca_flow_300s, dca_unique_wallets_300s, dca_ratio_300s,
+            smart_money_buys_300s, smart_money_flow_300s, smart_money_wallets_300s,
+            unique_clusters_300s, liquidity_sol, flow_liquidity_ratio_300s
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)
         ON CONFLICT(mint) DO UPDATE SET
             updated_at = excluded.updated_at,
             net_flow_60s = excluded.net_flow_60s,
@@ -121,24 +245,170 @@ pub fn write_aggregated_state(conn: &Connection, mint: &str, metrics: &RollingMe
             bot_flow_300s = excluded.bot_flow_300s,
             dca_flow_300s = excluded.dca_flow_300s,
             dca_unique_wallets_300s = excluded.dca_unique_wallets_300s,
-            dca_ratio_300s = excluded.dca_ratio_300s",
+            dca_ratio_300s = excluded.dca_ratio_300s,
+            smart_money_buys_300s = excluded.smart_money_buys_300s,
+            smart_money_flow_300s = excluded.smart_money_flow_300s,
+            smart_money_wallets_300s = excluded.smart_money_wallets_300s,
+            unique_clusters_300s = excluded.unique_clusters_300s,
+            liquidity_sol = excluded.liquidity_sol,
+            flow_liquidity_ratio_300s = excluded.flow_liquidity_ratio_300s",
         params![
             mint, now,
-            metrics.net_flow_60s_sol,
-            metrics.net_flow_300s_sol,
-            metrics.net_flow_900s_sol,
-            metrics.net_flow_3600s_sol,
-            metrics.net_flow_7200s_sol,
-            metrics.net_flow_14400s_sol,
-            metrics.unique_wallets_300s,
-            metrics.bot_wallets_count_300s,
-            metrics.bot_trades_count_300s,
-            metrics.bot_flow_300s_sol,
-            metrics.dca_flow_300s_sol,
-            metrics.dca_unique_wallets_300s,
-            metrics.dca_ratio_300s,
+            metrics.window(60).net_flow_sol,
+            w300.net_flow_sol,
+            metrics.window(900).net_flow_sol,
+            metrics.window(3600).net_flow_sol,
+            metrics.window(7200).net_flow_sol,
+            metrics.window(14400).net_flow_sol,
+            w300.unique_wallets,
+            w300.bot_wallets,
+            w300.bot_trades,
+            w300.bot_flow_sol,
+            w300.dca_flow_sol,
+            w300.dca_unique_wallets,
+            w300.dca_ratio,
+            w300.smart_money_buys,
+            w300.smart_money_flow_sol,
+            w300.smart_money_wallets,
+            w300.unique_clusters,
+            metrics.liquidity_sol,
+            metrics.flow_liquidity_ratio(300),
         ],
     )?;
+
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO token_window_metrics (
+            mint, window_secs, updated_at,
+            net_flow, buy_count, sell_count, unique_wallets,
+            bot_wallets, bot_trades, bot_flow,
+            dca_buys, dca_flow, dca_unique_wallets, dca_ratio, warming,
+            buy_volume, sell_volume, volume,
+            smart_money_buys, smart_money_flow, smart_money_wallets,
+            buy_size_p50, buy_size_p90, buy_size_p99, sell_size_p50, sell_size_p90, sell_size_p99,
+            retail_buys, mid_buys, whale_buys, retail_sells, mid_sells, whale_sells,
+            dominant_venue, dominant_venue_share,
+            wash_score, wash_volume, wash_wallets, wash_trades, organic_volume, organic_wallets,
+            unique_clusters
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21,
+            ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35, ?36, ?37, ?38, ?39, ?40, ?41,
+            ?42)
+        ON CONFLICT(mint, window_secs) DO UPDATE SET
+            updated_at = excluded.updated_at,
+            net_flow = excluded.net_flow,
+            buy_count = excluded.buy_count,
+            sell_count = excluded.sell_count,
+            unique_wallets = excluded.unique_wallets,
+            bot_wallets = excluded.bot_wallets,
+            bot_trades = excluded.bot_trades,
+            bot_flow = excluded.bot_flow,
+            dca_buys = excluded.dca_buys,
+            dca_flow = excluded.dca_flow,
+            dca_unique_wallets = excluded.dca_unique_wallets,
+            dca_ratio = excluded.dca_ratio,
+            warming = excluded.warming,
+            buy_volume = excluded.buy_volume,
+            sell_volume = excluded.sell_volume,
+            volume = excluded.volume,
+            smart_money_buys = excluded.smart_money_buys,
+            smart_money_flow = excluded.smart_money_flow,
+            smart_money_wallets = excluded.smart_money_wallets,
+            buy_size_p50 = excluded.buy_size_p50,
+            buy_size_p90 = excluded.buy_size_p90,
+            buy_size_p99 = excluded.buy_size_p99,
+            sell_size_p50 = excluded.sell_size_p50,
+            sell_size_p90 = excluded.sell_size_p90,
+            sell_size_p99 = excluded.sell_size_p99,
+            retail_buys = excluded.retail_buys,
+            mid_buys = excluded.mid_buys,
+            whale_buys = excluded.whale_buys,
+            retail_sells = excluded.retail_sells,
+            mid_sells = excluded.mid_sells,
+            whale_sells = excluded.whale_sells,
+            dominant_venue = excluded.dominant_venue,
+            dominant_venue_share = excluded.dominant_venue_share,
+            wash_score = excluded.wash_score,
+            wash_volume = excluded.wash_volume,
+            wash_wallets = excluded.wash_wallets,
+            wash_trades = excluded.wash_trades,
+            organic_volume = excluded.organic_volume,
+            organic_wallets = excluded.organic_wallets,
+            unique_clusters = excluded.unique_clusters",
+    )?;
+
+    for window in &metrics.windows {
+        let dominant = metrics.dominant_venue(window.window_secs);
+        stmt.execute(params![
+            mint,
+            window.window_secs,
+            now,
+            window.net_flow_sol,
+            window.buy_count,
+            window.sell_count,
+            window.unique_wallets,
+            window.bot_wallets,
+            window.bot_trades,
+            window.bot_flow_sol,
+            window.dca_buys,
+            window.dca_flow_sol,
+            window.dca_unique_wallets,
+            window.dca_ratio,
+            window.warming as i32,
+            window.buy_volume_sol,
+            window.sell_volume_sol,
+            window.volume_sol,
+            window.smart_money_buys,
+            window.smart_money_flow_sol,
+            window.smart_money_wallets,
+            window.buy_size_p50,
+            window.buy_size_p90,
+            window.buy_size_p99,
+            window.sell_size_p50,
+            window.sell_size_p90,
+            window.sell_size_p99,
+            window.retail_buys,
+            window.mid_buys,
+            window.whale_buys,
+            window.retail_sells,
+            window.mid_sells,
+            window.whale_sells,
+            dominant.map_or("", |v| v.venue.as_str()),
+            dominant.map_or(0.0, |v| v.volume_share),
+            window.wash_score,
+            window.wash_volume_sol,
+            window.wash_wallets,
+            window.wash_trades,
+            window.organic_volume_sol(),
+            window.organic_wallets(),
+            window.unique_clusters,
+        ])?;
+    }
+
+    conn.execute("DELETE FROM token_venue_metrics WHERE mint = ?1", params![mint])?;
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO token_venue_metrics (
+            mint, window_secs, venue, updated_at,
+            net_flow, buy_volume, sell_volume, volume, buy_count, sell_count,
+            unique_wallets, volume_share, dominant
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+    )?;
+
+    for venue in &metrics.venues {
+        stmt.execute(params![
+            mint,
+            venue.window_secs,
+            venue.venue,
+            now,
+            venue.net_flow_sol,
+            venue.buy_volume_sol,
+            venue.sell_volume_sol,
+            venue.volume_sol,
+            venue.buy_count,
+            venue.sell_count,
+            venue.unique_wallets,
+            venue.volume_share,
+            venue.dominant as i32,
+        ])?;
+    }
     
     Ok(())
 }
@@ -152,8 +422,11 @@ pub fn append_trade(conn: &Connection, event: &TradeEvent) -> Result<(), Box<dyn
     };
     
     conn.execute(
-        "INSERT INTO token_trades (mint, timestamp, wallet, side, sol_amount, is_bot, is_dca)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO token_trades (
+            mint, timestamp, wallet, side, sol_amount, is_bot, is_dca,
+            source_program, token_amount, token_decimals, is_smart_money,
+            slot, fee_lamports, tip_lamports, is_wash, funding_cluster, price_impact
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
         params![
             event.mint,
             event.timestamp,
@@ -162,6 +435,16 @@ pub fn append_trade(conn: &Connection, event: &TradeEvent) -> Result<(), Box<dyn
             event.sol_amount,
             event.is_bot as i32,
             event.is_dca as i32,
+            event.source_program,
+            event.token_amount,
+            event.token_decimals,
+            event.is_smart_money as i32,
+            event.slot as i64,
+            event.fee_lamports as i64,
+            event.tip_lamports as i64,
+            event.is_wash as i32,
+            event.funding_cluster,
+            event.price_impact,
         ],
     )?;
     
@@ -174,8 +457,8 @@ pub fn write_signal(conn: &Connection, signal: &Signal) -> Result<(), Box<dyn Er
     
     conn.execute(
         "INSERT INTO token_signals (
-            mint, signal_type, strength, window, timestamp, metadata
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            mint, signal_type, strength, window, timestamp, metadata, event
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         params![
             signal.mint,
             signal.signal_type.as_str(),
@@ -183,12 +466,725 @@ pub fn write_signal(conn: &Connection, signal: &Signal) -> Result<(), Box<dyn Er
             signal.window,
             signal.timestamp,
             metadata_str,
+            signal.event.as_str(),
         ],
     )?;
     
     Ok(())
 }
 
+/// Write changed signal states to signal_states, deleting inactive ones
+pub fn write_signal_states(conn: &Connection, states: &[SignalState]) -> Result<(), Box<dyn Error>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut upsert = conn.prepare_cached(
+        "INSERT OR REPLACE INTO signal_states (
+            mint, signal_type, state, strength, window, metadata, emitted_strength,
+            entered_at, last_seen, last_emitted, cooldown_until, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+    )?;
+    let mut delete = conn.prepare_cached("DELETE FROM signal_states WHERE mint = ?1 AND signal_type = ?2")?;
+
+    for state in states {
+        if state.phase == SignalPhase::Inactive {
+            delete.execute(params![state.mint, state.signal_type.as_str()])?;
+            continue;
+        }
+        upsert.execute(params![
+            state.mint,
+            state.signal_type.as_str(),
+            state.phase.as_str(),
+            state.strength,
+            state.window,
+            state.metadata.to_string(),
+            state.emitted_strength,
+            state.entered_at,
+            state.last_seen,
+            state.last_emitted,
+            state.cooldown_until,
+            now,
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Load persisted signal states (active and cooling down)
+pub fn load_signal_states(conn: &Connection) -> Result<Vec<SignalState>, Box<dyn Error>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT mint, signal_type, state, strength, window, metadata, emitted_strength,
+                entered_at, last_seen, last_emitted, cooldown_until
+         FROM signal_states",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, f64>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+            row.get::<_, f64>(6)?,
+            [row.get::<_, i64>(7)?, row.get(8)?, row.get(9)?, row.get(10)?],
+        ))
+    })?;
+
+    let mut states = Vec::new();
+    for row in rows {
+        let (mint, signal_type, phase, strength, window, metadata, emitted_strength, times) = row?;
+        let (Some(signal_type), Some(phase)) = (SignalType::parse(&signal_type), SignalPhase::parse(&phase)) else {
+            log::warn!("⚠️  Unknown signal state {} / {} for {}", signal_type, phase, mint);
+            continue;
+        };
+        let [entered_at, last_seen, last_emitted, cooldown_until] = times;
+        states.push(SignalState {
+            mint,
+            signal_type,
+            phase,
+            strength,
+            window,
+            metadata: serde_json::from_str(&metadata).unwrap_or_else(|_| serde_json::json!({})),
+            emitted_strength,
+            entered_at,
+            last_seen,
+            last_emitted,
+            cooldown_until,
+        });
+    }
+
+    Ok(states)
+}
+
+/// Write a closed candle to token_candles
+///
+/// Upserts so that a candle rebuilt after a restart replaces the stored one.
+pub fn write_candle(conn: &Connection, candle: &Candle) -> Result<(), Box<dyn Error>> {
+    conn.prepare_cached(
+        "INSERT INTO token_candles (
+            mint, interval_secs, open_time, open, high, low, close,
+            volume_sol, buy_volume_sol, sell_volume_sol, trade_count
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+        ON CONFLICT(mint, interval_secs, open_time) DO UPDATE SET
+            open = excluded.open,
+            high = excluded.high,
+            low = excluded.low,
+            close = excluded.close,
+            volume_sol = excluded.volume_sol,
+            buy_volume_sol = excluded.buy_volume_sol,
+            sell_volume_sol = excluded.sell_volume_sol,
+            trade_count = excluded.trade_count",
+    )?
+    .execute(params![
+        candle.mint,
+        candle.interval_secs,
+        candle.open_time,
+        candle.open,
+        candle.high,
+        candle.low,
+        candle.close,
+        candle.volume_sol,
+        candle.buy_volume_sol,
+        candle.sell_volume_sol,
+        candle.trade_count,
+    ])?;
+
+    Ok(())
+}
+
+/// Delete candles older than their interval's retention (`CANDLE_RETENTION`)
+///
+/// Returns the number of deleted rows.
+pub fn prune_candles(conn: &Connection, now: i64) -> Result<usize, Box<dyn Error>> {
+    let mut deleted = 0;
+    for (interval_secs, retention_secs) in CANDLE_RETENTION {
+        deleted += conn.execute(
+            "DELETE FROM token_candles WHERE interval_secs = ?1 AND open_time < ?2",
+            params![interval_secs, now - retention_secs],
+        )?;
+    }
+    Ok(deleted)
+}
+
+/// Write a position snapshot: holder metrics plus the changed positions
+pub fn write_position_snapshot(conn: &Connection, snapshot: &PositionSnapshot) -> Result<(), Box<dyn Error>> {
+    let now = chrono::Utc::now().timestamp();
+    let metrics = &snapshot.metrics;
+
+    conn.prepare_cached(
+        "INSERT INTO token_holder_metrics (
+            mint, snapshot_ts, price_sol, holders, holders_in_profit, holders_in_profit_pct,
+            unrealized_pnl_sol, realized_pnl_sol, top_holders_cost_basis_sol,
+            top_holders_avg_cost, overhang_sol, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        ON CONFLICT(mint) DO UPDATE SET
+            snapshot_ts = excluded.snapshot_ts,
+            price_sol = excluded.price_sol,
+            holders = excluded.holders,
+            holders_in_profit = excluded.holders_in_profit,
+            holders_in_profit_pct = excluded.holders_in_profit_pct,
+            unrealized_pnl_sol = excluded.unrealized_pnl_sol,
+            realized_pnl_sol = excluded.realized_pnl_sol,
+            top_holders_cost_basis_sol = excluded.top_holders_cost_basis_sol,
+            top_holders_avg_cost = excluded.top_holders_avg_cost,
+            overhang_sol = excluded.overhang_sol,
+            updated_at = excluded.updated_at",
+    )?
+    .execute(params![
+        snapshot.mint,
+        snapshot.snapshot_ts,
+        metrics.price_sol,
+        metrics.holders,
+        metrics.holders_in_profit,
+        metrics.holders_in_profit_pct,
+        metrics.unrealized_pnl_sol,
+        metrics.realized_pnl_sol,
+        metrics.top_holders_cost_basis_sol,
+        metrics.top_holders_avg_cost,
+        metrics.overhang_sol,
+        now,
+    ])?;
+
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO wallet_positions (
+            mint, wallet, tokens, cost_basis_sol, realized_pnl_sol,
+            buy_count, sell_count, last_trade_ts, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        ON CONFLICT(mint, wallet) DO UPDATE SET
+            tokens = excluded.tokens,
+            cost_basis_sol = excluded.cost_basis_sol,
+            realized_pnl_sol = excluded.realized_pnl_sol,
+            buy_count = excluded.buy_count,
+            sell_count = excluded.sell_count,
+            last_trade_ts = excluded.last_trade_ts,
+            updated_at = excluded.updated_at",
+    )?;
+
+    for (wallet, position) in &snapshot.positions {
+        stmt.execute(params![
+            snapshot.mint,
+            wallet,
+            position.tokens,
+            position.cost_basis_sol,
+            position.realized_pnl_sol,
+            position.buy_count,
+            position.sell_count,
+            position.last_trade_ts,
+            now,
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Persisted positions of a mint and the event time they are complete up to
+///
+/// None if the mint was never snapshotted.
+pub fn load_positions(conn: &Connection, mint: &str) -> Result<Option<(i64, WalletPositions)>, Box<dyn Error>> {
+    let snapshot_ts: Option<i64> = conn
+        .query_row(
+            "SELECT snapshot_ts FROM token_holder_metrics WHERE mint = ?1",
+            params![mint],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(snapshot_ts) = snapshot_ts else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn.prepare_cached(
+        "SELECT wallet, tokens, cost_basis_sol, realized_pnl_sol, buy_count, sell_count, last_trade_ts
+         FROM wallet_positions WHERE mint = ?1",
+    )?;
+    let positions = stmt
+        .query_map(params![mint], |row| {
+            Ok((
+                row.get(0)?,
+                Position {
+                    tokens: row.get(1)?,
+                    cost_basis_sol: row.get(2)?,
+                    realized_pnl_sol: row.get(3)?,
+                    buy_count: row.get(4)?,
+                    sell_count: row.get(5)?,
+                    last_trade_ts: row.get(6)?,
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Some((snapshot_ts, positions)))
+}
+
+/// Delete closed positions whose last trade is past the retention
+pub fn prune_positions(conn: &Connection, now: i64) -> Result<usize, Box<dyn Error>> {
+    Ok(conn.execute(
+        "DELETE FROM wallet_positions WHERE tokens = 0 AND last_trade_ts < ?1",
+        params![now - CLOSED_POSITION_RETENTION_SECS],
+    )?)
+}
+
+/// Append a market-wide snapshot
+pub fn write_market_snapshot(conn: &Connection, snapshot: &MarketSnapshot) -> Result<(), Box<dyn Error>> {
+    let mut stmt = conn.prepare_cached(
+        "INSERT OR REPLACE INTO market_metrics (
+            timestamp, net_flow, volume, trades, active_mints, new_mints, unique_wallets,
+            baseline_volume, baseline_wallets, activity, regime, warming
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+    )?;
+
+    stmt.execute(params![
+        snapshot.timestamp,
+        snapshot.net_flow_sol,
+        snapshot.volume_sol,
+        snapshot.trades,
+        snapshot.active_mints,
+        snapshot.new_mints,
+        snapshot.unique_wallets,
+        snapshot.baseline_volume_sol,
+        snapshot.baseline_wallets,
+        snapshot.activity,
+        snapshot.regime.as_str(),
+        snapshot.warming as i32,
+    ])?;
+
+    Ok(())
+}
+
+/// Volume and wallet baselines of the latest settled market snapshot
+pub fn load_market_baseline(conn: &Connection) -> Result<Option<(f64, f64)>, Box<dyn Error>> {
+    Ok(conn
+        .query_row(
+            "SELECT baseline_volume, baseline_wallets FROM market_metrics
+             WHERE warming = 0 ORDER BY timestamp DESC LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?)
+}
+
+/// Delete market snapshots past the retention
+pub fn prune_market_metrics(conn: &Connection, now: i64) -> Result<usize, Box<dyn Error>> {
+    Ok(conn.execute(
+        "DELETE FROM market_metrics WHERE timestamp < ?1",
+        params![now - MARKET_METRICS_RETENTION_SECS],
+    )?)
+}
+
+/// First buy of every (wallet, mint) pair since `since`, with the data
+/// needed to score it
+///
+/// - `launch_ts`: the mint's first recorded trade (stand-in for its creation)
+/// - `peak_price`: highest 5m candle high from the entry's bucket until
+///   `horizon_secs` after it, None without candles
+pub fn load_wallet_entries(conn: &Connection, since: i64, horizon_secs: i64) -> Result<Vec<WalletEntry>, Box<dyn Error>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT e.wallet, e.mint, e.timestamp, e.sol_amount, e.token_amount, e.token_decimals,
+                (SELECT MIN(f.timestamp) FROM token_trades f WHERE f.mint = e.mint),
+                (SELECT MAX(c.high) FROM token_candles c
+                 WHERE c.mint = e.mint AND c.interval_secs = 300
+                   AND c.open_time > e.timestamp - 300 AND c.open_time <= e.timestamp + ?2)
+         FROM (
+             SELECT wallet, mint, timestamp, sol_amount, token_amount, token_decimals,
+                    ROW_NUMBER() OVER (PARTITION BY wallet, mint ORDER BY timestamp, id) AS n
+             FROM token_trades
+             WHERE side = 'buy' AND timestamp >= ?1
+         ) e
+         WHERE e.n = 1",
+    )?;
+
+    let entries = stmt
+        .query_map(params![since, horizon_secs], |row| {
+            let sol_amount: f64 = row.get(3)?;
+            let token_amount: f64 = row.get(4)?;
+            let token_decimals: u8 = row.get(5)?;
+            let tokens = token_amount / 10f64.powi(token_decimals as i32);
+
+            Ok(WalletEntry {
+                wallet: row.get(0)?,
+                mint: row.get(1)?,
+                entry_ts: row.get(2)?,
+                entry_price: if tokens > 0.0 { sol_amount / tokens } else { 0.0 },
+                launch_ts: row.get(6)?,
+                peak_price: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+/// Realized PnL per wallet summed over all persisted positions
+pub fn load_realized_pnl(conn: &Connection) -> Result<HashMap<String, f64>, Box<dyn Error>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT wallet, SUM(realized_pnl_sol) FROM wallet_positions GROUP BY wallet",
+    )?;
+    let pnl = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(pnl)
+}
+
+/// UPSERT wallet scores
+pub fn write_wallet_scores(conn: &Connection, scores: &[WalletScore]) -> Result<(), Box<dyn Error>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO wallet_scores (
+            wallet, score, label, realized_pnl_sol, entries, hits, hit_rate,
+            avg_entry_delay_secs, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        ON CONFLICT(wallet) DO UPDATE SET
+            score = excluded.score,
+            label = excluded.label,
+            realized_pnl_sol = excluded.realized_pnl_sol,
+            entries = excluded.entries,
+            hits = excluded.hits,
+            hit_rate = excluded.hit_rate,
+            avg_entry_delay_secs = excluded.avg_entry_delay_secs,
+            updated_at = excluded.updated_at",
+    )?;
+
+    for score in scores {
+        stmt.execute(params![
+            score.wallet,
+            score.score,
+            score.label.as_str(),
+            score.realized_pnl_sol,
+            score.entries,
+            score.hits,
+            score.hit_rate,
+            score.avg_entry_delay_secs,
+            now,
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// UPSERT bot classifier profiles
+pub fn write_bot_profiles(conn: &Connection, profiles: &[(String, BotProfile)]) -> Result<(), Box<dyn Error>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO wallet_bot_scores (
+            wallet, score, trades, last_trade_ts, interval_mean, interval_var, intervals,
+            last_sol_amount, fixed_size_rate, same_slot_rate, fee_tip_rate, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        ON CONFLICT(wallet) DO UPDATE SET
+            score = excluded.score,
+            trades = excluded.trades,
+            last_trade_ts = excluded.last_trade_ts,
+            interval_mean = excluded.interval_mean,
+            interval_var = excluded.interval_var,
+            intervals = excluded.intervals,
+            last_sol_amount = excluded.last_sol_amount,
+            fixed_size_rate = excluded.fixed_size_rate,
+            same_slot_rate = excluded.same_slot_rate,
+            fee_tip_rate = excluded.fee_tip_rate,
+            updated_at = excluded.updated_at",
+    )?;
+
+    for (wallet, profile) in profiles {
+        stmt.execute(params![
+            wallet,
+            profile.score,
+            profile.trades,
+            profile.last_trade_ts,
+            profile.interval_mean,
+            profile.interval_var,
+            profile.intervals,
+            profile.last_sol_amount,
+            profile.fixed_size_rate,
+            profile.same_slot_rate,
+            profile.fee_tip_rate,
+            now,
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Load bot classifier profiles of wallets that traded since `since`
+pub fn load_bot_profiles(conn: &Connection, since: i64) -> Result<Vec<(String, BotProfile)>, Box<dyn Error>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT wallet, score, trades, last_trade_ts, interval_mean, interval_var, intervals,
+                last_sol_amount, fixed_size_rate, same_slot_rate, fee_tip_rate
+         FROM wallet_bot_scores WHERE last_trade_ts >= ?1",
+    )?;
+    let profiles = stmt
+        .query_map(params![since], |row| {
+            let mut profile = BotProfile::default();
+            profile.score = row.get(1)?;
+            profile.trades = row.get(2)?;
+            profile.last_trade_ts = row.get(3)?;
+            profile.interval_mean = row.get(4)?;
+            profile.interval_var = row.get(5)?;
+            profile.intervals = row.get(6)?;
+            profile.last_sol_amount = row.get(7)?;
+            profile.fixed_size_rate = row.get(8)?;
+            profile.same_slot_rate = row.get(9)?;
+            profile.fee_tip_rate = row.get(10)?;
+            Ok((row.get(0)?, profile))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(profiles)
+}
+
+/// Append pool reserves to `pool_liquidity`
+pub fn write_liquidity(conn: &Connection, pools: &[PoolReserves]) -> Result<(), Box<dyn Error>> {
+    let mut stmt = conn.prepare_cached(
+        "INSERT OR REPLACE INTO pool_liquidity (
+            mint, timestamp, slot, base_reserve, quote_reserve_sol, spot_price, liquidity_sol
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+
+    for pool in pools {
+        stmt.execute(params![
+            pool.mint,
+            pool.timestamp,
+            pool.slot as i64,
+            pool.base_reserve,
+            pool.quote_reserve_sol,
+            pool.spot_price(),
+            pool.liquidity_sol(),
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// UPSERT launches with their sniper shares and sniper positions
+pub fn write_launches(conn: &Connection, launches: &[Launch]) -> Result<(), Box<dyn Error>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut launch_stmt = conn.prepare_cached(
+        "INSERT INTO token_launches (
+            mint, creator, source_program, timestamp, slot, tip_lamports,
+            sniper_count, bundled_count, sniper_supply_share, sniper_held_share,
+            last_activity, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        ON CONFLICT(mint) DO UPDATE SET
+            sniper_count = excluded.sniper_count,
+            bundled_count = excluded.bundled_count,
+            sniper_supply_share = excluded.sniper_supply_share,
+            sniper_held_share = excluded.sniper_held_share,
+            last_activity = excluded.last_activity,
+            updated_at = excluded.updated_at",
+    )?;
+    let mut sniper_stmt = conn.prepare_cached(
+        "INSERT INTO launch_snipers (
+            mint, wallet, kind, first_slot, bought_tokens, sold_tokens, bought_sol, sold_sol
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        ON CONFLICT(mint, wallet) DO UPDATE SET
+            bought_tokens = excluded.bought_tokens,
+            sold_tokens = excluded.sold_tokens,
+            bought_sol = excluded.bought_sol,
+            sold_sol = excluded.sold_sol",
+    )?;
+
+    for launch in launches {
+        let event = &launch.event;
+        launch_stmt.execute(params![
+            event.mint,
+            event.creator,
+            event.source_program,
+            event.timestamp,
+            event.slot as i64,
+            event.tip_lamports as i64,
+            launch.snipers.len() as i64,
+            launch.count(SniperKind::Bundled) as i64,
+            launch.sniper_supply_share(),
+            launch.sniper_held_share(),
+            launch.last_activity,
+            now,
+        ])?;
+
+        for (wallet, sniper) in &launch.snipers {
+            sniper_stmt.execute(params![
+                event.mint,
+                wallet,
+                sniper.kind.as_str(),
+                sniper.first_slot as i64,
+                sniper.bought_tokens,
+                sniper.sold_tokens,
+                sniper.bought_sol,
+                sniper.sold_sol,
+            ])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Load launches with sniper activity since `since`, with their snipers
+pub fn load_launches(conn: &Connection, since: i64) -> Result<Vec<Launch>, Box<dyn Error>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT mint, creator, source_program, timestamp, slot, tip_lamports, last_activity
+         FROM token_launches WHERE last_activity >= ?1",
+    )?;
+    let mut launches: HashMap<String, Launch> = stmt
+        .query_map(params![since], |row| {
+            let slot: i64 = row.get(4)?;
+            let tip_lamports: i64 = row.get(5)?;
+            let event = LaunchEvent {
+                mint: row.get(0)?,
+                creator: row.get(1)?,
+                source_program: row.get(2)?,
+                timestamp: row.get(3)?,
+                slot: slot as u64,
+                tip_lamports: tip_lamports as u64,
+                migration: false,
+            };
+            let mut launch = Launch::new(event);
+            launch.last_activity = row.get(6)?;
+            Ok((launch.event.mint.clone(), launch))
+        })?
+        .collect::<Result<_, _>>()?;
+
+    let mut stmt = conn.prepare_cached(
+        "SELECT s.mint, s.wallet, s.kind, s.first_slot, s.bought_tokens, s.sold_tokens, s.bought_sol, s.sold_sol
+         FROM launch_snipers s JOIN token_launches l ON l.mint = s.mint
+         WHERE l.last_activity >= ?1",
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        let kind: String = row.get(2)?;
+        let first_slot: i64 = row.get(3)?;
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            kind,
+            SniperPosition {
+                kind: SniperKind::Early,
+                first_slot: first_slot as u64,
+                bought_tokens: row.get(4)?,
+                sold_tokens: row.get(5)?,
+                bought_sol: row.get(6)?,
+                sold_sol: row.get(7)?,
+            },
+        ))
+    })?;
+    for row in rows {
+        let (mint, wallet, kind, mut sniper) = row?;
+        let Some(kind) = SniperKind::parse(&kind) else {
+            log::warn!("⚠️  Unknown sniper kind {:?} for {} on {}", kind, wallet, mint);
+            continue;
+        };
+        sniper.kind = kind;
+        if let Some(launch) = launches.get_mut(&mint) {
+            launch.snipers.insert(wallet, sniper);
+        }
+    }
+
+    Ok(launches.into_values().collect())
+}
+
+/// UPSERT created tokens and creator reputations
+pub fn write_creators(
+    conn: &Connection,
+    tokens: &[CreatedToken],
+    reputations: &[CreatorReputation],
+) -> Result<(), Box<dyn Error>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut token_stmt = conn.prepare_cached(
+        "INSERT INTO creator_tokens (
+            mint, creator, source_program, launched_at, last_trade, peak_price, last_price,
+            dev_bought_tokens, dev_sold_tokens, dev_moved_tokens, dev_sells, status, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+        ON CONFLICT(mint) DO UPDATE SET
+            last_trade = excluded.last_trade,
+            peak_price = excluded.peak_price,
+            last_price = excluded.last_price,
+            dev_bought_tokens = excluded.dev_bought_tokens,
+            dev_sold_tokens = excluded.dev_sold_tokens,
+            dev_moved_tokens = excluded.dev_moved_tokens,
+            dev_sells = excluded.dev_sells,
+            status = excluded.status,
+            updated_at = excluded.updated_at",
+    )?;
+    for token in tokens {
+        token_stmt.execute(params![
+            token.mint,
+            token.creator,
+            token.source_program,
+            token.launched_at,
+            token.last_trade,
+            token.peak_price,
+            token.last_price,
+            token.dev_bought_tokens,
+            token.dev_sold_tokens,
+            token.dev_moved_tokens,
+            token.dev_sells,
+            token.status.as_str(),
+            now,
+        ])?;
+    }
+
+    let mut reputation_stmt = conn.prepare_cached(
+        "INSERT OR REPLACE INTO creator_reputation (
+            creator, tokens_launched, tokens_finished, tokens_dead, median_lifetime_secs,
+            dev_sells, risk_score, last_launch, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+    for reputation in reputations {
+        reputation_stmt.execute(params![
+            reputation.creator,
+            reputation.tokens_launched,
+            reputation.tokens_finished,
+            reputation.tokens_dead,
+            reputation.median_lifetime_secs(),
+            reputation.dev_sells,
+            reputation.risk_score(),
+            reputation.last_launch,
+            now,
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Load created tokens launched since `since`
+pub fn load_creator_tokens(conn: &Connection, since: i64) -> Result<Vec<CreatedToken>, Box<dyn Error>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT mint, creator, source_program, launched_at, last_trade, peak_price, last_price,
+                dev_bought_tokens, dev_sold_tokens, dev_moved_tokens, dev_sells, status
+         FROM creator_tokens WHERE launched_at >= ?1",
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        let status: String = row.get(11)?;
+        Ok((
+            CreatedToken {
+                mint: row.get(0)?,
+                creator: row.get(1)?,
+                source_program: row.get(2)?,
+                launched_at: row.get(3)?,
+                last_trade: row.get(4)?,
+                peak_price: row.get(5)?,
+                last_price: row.get(6)?,
+                dev_bought_tokens: row.get(7)?,
+                dev_sold_tokens: row.get(8)?,
+                dev_moved_tokens: row.get(9)?,
+                dev_sells: row.get(10)?,
+                status: TokenStatus::Live,
+            },
+            status,
+        ))
+    })?;
+
+    let mut tokens = Vec::new();
+    for row in rows {
+        let (mut token, status) = row?;
+        let Some(status) = TokenStatus::parse(&status) else {
+            log::warn!("⚠️  Unknown token status {:?} for {}", status, token.mint);
+            continue;
+        };
+        token.status = status;
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Load all wallets labelled smart money
+pub fn load_smart_money_wallets(conn: &Connection) -> Result<HashSet<String>, Box<dyn Error>> {
+    load_string_set(conn, "SELECT wallet FROM wallet_scores WHERE label = 'smart_money'")
+}
+
 /// Phase 6: Get recent trades for a token within a time window
 ///
 /// Used by signals engine to compute wallet concentration and other metrics.
@@ -204,44 +1200,121 @@ pub fn get_recent_trades(conn: &Connection, mint: &str, window_seconds: i64) ->
     let now = chrono::Utc::now().timestamp();
     let cutoff = now - window_seconds;
     
-    let mut stmt = conn.prepare(
-        "SELECT mint, timestamp, wallet, side, sol_amount, is_bot, is_dca
-         FROM token_trades
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM token_trades
          WHERE mint = ?1 AND timestamp >= ?2
-         ORDER BY timestamp DESC"
-    )?;
+         ORDER BY timestamp DESC",
+        TRADE_COLUMNS
+    ))?;
     
-    let trades = stmt.query_map(params![mint, cutoff], |row| {
-        let side: String = row.get(3)?;
-        let direction = match side.as_str() {
-            "buy" => crate::types::TradeDirection::Buy,
-            "sell" => crate::types::TradeDirection::Sell,
-            _ => crate::types::TradeDirection::Unknown,
-        };
-        
-        let is_bot: i32 = row.get(5)?;
-        let is_dca: i32 = row.get(6)?;
-        
-        Ok(TradeEvent {
-            mint: row.get(0)?,
-            timestamp: row.get(1)?,
-            user_account: row.get(2)?,
-            direction,
-            sol_amount: row.get(4)?,
-            token_amount: 0.0, // Not stored in DB
-            token_decimals: 0, // Not stored in DB
-            source_program: if is_dca == 1 { "JupiterDCA" } else { "Unknown" }.to_string(),
-            is_bot: is_bot == 1,
-            is_dca: is_dca == 1,
-        })
-    })?;
+    let trades = stmt
+        .query_map(params![mint, cutoff], trade_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
     
-    let mut result = Vec::new();
-    for trade in trades {
-        result.push(trade?);
+    Ok(trades)
+}
+
+/// Load every trade with `timestamp >= since`, oldest first
+///
+/// Used to rebuild rolling state on startup.
+pub fn load_trades_since(conn: &Connection, since: i64) -> Result<Vec<TradeEvent>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM token_trades
+         WHERE timestamp >= ?1
+         ORDER BY timestamp ASC, id ASC",
+        TRADE_COLUMNS
+    ))?;
+
+    let trades = stmt
+        .query_map(params![since], trade_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(trades)
+}
+
+/// Oldest and newest trade timestamps in token_trades, if any
+pub fn trade_history_bounds(conn: &Connection) -> Result<Option<(i64, i64)>, Box<dyn Error>> {
+    let bounds: (Option<i64>, Option<i64>) = conn.query_row(
+        "SELECT MIN(timestamp), MAX(timestamp) FROM token_trades",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(match bounds {
+        (Some(oldest), Some(newest)) => Some((oldest, newest)),
+        _ => None,
+    })
+}
+
+/// Columns read by `trade_from_row`, in order
+const TRADE_COLUMNS: &str =
+    "mint, timestamp, wallet, side, sol_amount, is_bot, is_dca, source_program, token_amount, token_decimals, is_smart_money, \
+     slot, fee_lamports, tip_lamports, is_wash, funding_cluster, price_impact";
+
+/// Map a token_trades row (selected with `TRADE_COLUMNS`) back to a TradeEvent
+///
+/// Rows written before source_program was stored have an empty value; they
+/// fall back to the old inference from the DCA flag.
+fn trade_from_row(row: &rusqlite::Row) -> rusqlite::Result<TradeEvent> {
+    let side: String = row.get(3)?;
+    let direction = match side.as_str() {
+        "buy" => crate::types::TradeDirection::Buy,
+        "sell" => crate::types::TradeDirection::Sell,
+        _ => crate::types::TradeDirection::Unknown,
+    };
+
+    let is_bot: i32 = row.get(5)?;
+    let is_dca: i32 = row.get(6)?;
+    let is_smart_money: i32 = row.get(10)?;
+    let slot: i64 = row.get(11)?;
+    let fee_lamports: i64 = row.get(12)?;
+    let tip_lamports: i64 = row.get(13)?;
+    let is_wash: i32 = row.get(14)?;
+
+    let mut source_program: String = row.get(7)?;
+    if source_program.is_empty() {
+        source_program = if is_dca == 1 { "JupiterDCA" } else { "Unknown" }.to_string();
     }
-    
-    Ok(result)
+
+    Ok(TradeEvent {
+        mint: row.get(0)?,
+        timestamp: row.get(1)?,
+        user_account: row.get(2)?,
+        direction,
+        sol_amount: row.get(4)?,
+        token_amount: row.get(8)?,
+        token_decimals: row.get(9)?,
+        source_program,
+        is_bot: is_bot == 1,
+        is_dca: is_dca == 1,
+        is_smart_money: is_smart_money == 1,
+        slot: slot as u64,
+        fee_lamports: fee_lamports as u64,
+        tip_lamports: tip_lamports as u64,
+        is_wash: is_wash == 1,
+        funding_cluster: row.get(15)?,
+        price_impact: row.get(16)?,
+    })
+}
+
+/// Load all blocked mints from the blocklist table
+pub fn load_blocklist(conn: &Connection) -> Result<HashSet<String>, Box<dyn Error>> {
+    load_string_set(conn, "SELECT mint FROM blocklist")
+}
+
+/// Load all followed mints from the followed_tokens table
+pub fn load_followed_tokens(conn: &Connection) -> Result<HashSet<String>, Box<dyn Error>> {
+    load_string_set(conn, "SELECT mint FROM followed_tokens")
+}
+
+fn load_string_set(conn: &Connection, query: &str) -> Result<HashSet<String>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(query)?;
+
+    let mints = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<HashSet<_>, _>>()?;
+
+    Ok(mints)
 }
 
 /// Background write loop for async batching
@@ -270,6 +1343,7 @@ pub async fn run_write_loop(mut rx: mpsc::Receiver<WriteRequest>) {
     let mut batch = Vec::with_capacity(100);
     let mut last_flush = std::time::Instant::now();
     let flush_interval = std::time::Duration::from_millis(100);
+    let mut retention_ticker = tokio::time::interval(RETENTION_INTERVAL);
     
     loop {
         tokio::select! {
@@ -294,6 +1368,25 @@ pub async fn run_write_loop(mut rx: mpsc::Receiver<WriteRequest>) {
                     last_flush = std::time::Instant::now();
                 }
             }
+            // Candle and closed position retention
+            _ = retention_ticker.tick() => {
+                let now = chrono::Utc::now().timestamp();
+                match prune_candles(&conn, now) {
+                    Ok(0) => {}
+                    Ok(deleted) => log::debug!("🕯️  Pruned {} expired candles", deleted),
+                    Err(e) => log::warn!("⚠️  Failed to prune candles: {}", e),
+                }
+                match prune_positions(&conn, now) {
+                    Ok(0) => {}
+                    Ok(deleted) => log::debug!("💼 Pruned {} closed positions", deleted),
+                    Err(e) => log::warn!("⚠️  Failed to prune positions: {}", e),
+                }
+                match prune_market_metrics(&conn, now) {
+                    Ok(0) => {}
+                    Ok(deleted) => log::debug!("🌡️  Pruned {} market snapshots", deleted),
+                    Err(e) => log::warn!("⚠️  Failed to prune market metrics: {}", e),
+                }
+            }
         }
     }
 }
@@ -323,6 +1416,56 @@ fn flush_batch(conn: &Connection, batch: &mut Vec<WriteRequest>) -> Result<(), B
                     log::warn!("⚠️  Failed to write signal for {}: {}", signal.mint, e);
                 }
             }
+            WriteRequest::SignalStates(states) => {
+                if let Err(e) = write_signal_states(&tx, &states) {
+                    log::warn!("⚠️  Failed to write {} signal states: {}", states.len(), e);
+                }
+            }
+            WriteRequest::Candle(candle) => {
+                if let Err(e) = write_candle(&tx, &candle) {
+                    log::warn!("⚠️  Failed to write candle for {}: {}", candle.mint, e);
+                }
+            }
+            WriteRequest::Positions(snapshot) => {
+                if let Err(e) = write_position_snapshot(&tx, &snapshot) {
+                    log::warn!("⚠️  Failed to write positions for {}: {}", snapshot.mint, e);
+                }
+            }
+            WriteRequest::BotProfiles(profiles) => {
+                if let Err(e) = write_bot_profiles(&tx, &profiles) {
+                    log::warn!("⚠️  Failed to write {} bot profiles: {}", profiles.len(), e);
+                }
+            }
+            WriteRequest::WalletScores(scores) => {
+                if let Err(e) = write_wallet_scores(&tx, &scores) {
+                    log::warn!("⚠️  Failed to write {} wallet scores: {}", scores.len(), e);
+                }
+            }
+            WriteRequest::MarketMetrics(snapshot) => {
+                if let Err(e) = write_market_snapshot(&tx, &snapshot) {
+                    log::warn!("⚠️  Failed to write market snapshot: {}", e);
+                }
+            }
+            WriteRequest::Launches(launches) => {
+                if let Err(e) = write_launches(&tx, &launches) {
+                    log::warn!("⚠️  Failed to write {} launches: {}", launches.len(), e);
+                }
+            }
+            WriteRequest::Liquidity(pools) => {
+                if let Err(e) = write_liquidity(&tx, &pools) {
+                    log::warn!("⚠️  Failed to write {} liquidity snapshots: {}", pools.len(), e);
+                }
+            }
+            WriteRequest::Creators { tokens, reputations } => {
+                if let Err(e) = write_creators(&tx, &tokens, &reputations) {
+                    log::warn!(
+                        "⚠️  Failed to write {} created tokens / {} creators: {}",
+                        tokens.len(),
+                        reputations.len(),
+                        e
+                    );
+                }
+            }
         }
     }
     
@@ -334,7 +1477,7 @@ fn flush_batch(conn: &Connection, batch: &mut Vec<WriteRequest>) -> Result<(), B
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{state::RollingMetrics, types::{TradeDirection, TradeEvent}};
+    use crate::{state::{RollingMetrics, VenueMetrics, WindowMetrics}, types::{TradeDirection, TradeEvent}};
     use rusqlite::Connection;
 
     fn create_test_db() -> Connection {
@@ -348,45 +1491,64 @@ mod tests {
     
     fn create_test_metrics() -> RollingMetrics {
         RollingMetrics {
-            net_flow_60s_sol: 10.0,
-            net_flow_300s_sol: 50.0,
-            net_flow_900s_sol: 150.0,
-            net_flow_3600s_sol: 500.0,
-            net_flow_7200s_sol: 800.0,
-            net_flow_14400s_sol: 1200.0,
-            buy_count_60s: 5,
-            sell_count_60s: 2,
-            buy_count_300s: 20,
-            sell_count_300s: 10,
-            buy_count_900s: 50,
-            sell_count_900s: 30,
-            unique_wallets_300s: 15,
-            bot_wallets_count_300s: 2,
-            bot_trades_count_300s: 5,
-            bot_flow_300s_sol: 8.0,
-            dca_buys_60s: 1,
-            dca_buys_300s: 3,
-            dca_buys_900s: 8,
-            dca_buys_3600s: 20,
-            dca_buys_14400s: 40,
-            dca_flow_300s_sol: 12.0,
-            dca_unique_wallets_300s: 3,
-            dca_ratio_300s: 0.24,
+            windows: vec![
+            WindowMetrics {
+                window_secs: 60,
+                net_flow_sol: 10.0,
+                buy_count: 5,
+                sell_count: 2,
+                dca_buys: 1,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 300,
+                net_flow_sol: 50.0,
+                buy_count: 20,
+                sell_count: 10,
+                unique_wallets: 15,
+                bot_wallets: 2,
+                bot_trades: 5,
+                bot_flow_sol: 8.0,
+                dca_buys: 3,
+                dca_flow_sol: 12.0,
+                dca_unique_wallets: 3,
+                dca_ratio: 0.24,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 900,
+                net_flow_sol: 150.0,
+                buy_count: 50,
+                sell_count: 30,
+                dca_buys: 8,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 3600,
+                net_flow_sol: 500.0,
+                dca_buys: 20,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 7200,
+                net_flow_sol: 800.0,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 14400,
+                net_flow_sol: 1200.0,
+                dca_buys: 40,
+                ..Default::default()
+            },
+            ],
+            ..Default::default()
         }
     }
     
     fn create_test_trade(timestamp: i64) -> TradeEvent {
         TradeEvent {
-            timestamp,
-            mint: "test_mint".to_string(),
-            direction: TradeDirection::Buy,
-            sol_amount: 5.0,
-            token_amount: 1000.0,
-            token_decimals: 6,
             user_account: "test_wallet".to_string(),
-            source_program: "PumpSwap".to_string(),
-            is_bot: false,
-            is_dca: false,
+            ..TradeEvent::test("test_mint", timestamp, TradeDirection::Buy, 5.0)
         }
     }
 
@@ -433,8 +1595,8 @@ mod tests {
         write_aggregated_state(&conn, "test_mint", &metrics).unwrap();
         
         // Update metrics
-        metrics.net_flow_300s_sol = 100.0;
-        metrics.unique_wallets_300s = 25;
+        metrics.window_mut(300).net_flow_sol = 100.0;
+        metrics.window_mut(300).unique_wallets = 25;
         
         // UPSERT (should update, not insert)
         write_aggregated_state(&conn, "test_mint", &metrics).unwrap();
@@ -454,6 +1616,128 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn test_write_aggregated_state_per_window_rows() {
+        let conn = create_test_db();
+        let mut metrics = create_test_metrics();
+        metrics.window_mut(30).net_flow_sol = 2.5;
+        metrics.window_mut(30).buy_count = 3;
+        metrics.window_mut(30).buy_volume_sol = 4.0;
+        metrics.window_mut(30).sell_volume_sol = 1.5;
+        metrics.window_mut(30).volume_sol = 5.5;
+
+        write_aggregated_state(&conn, "test_mint", &metrics).unwrap();
+
+        let count: i32 = conn
+            .query_row("SELECT COUNT(*) FROM token_window_metrics WHERE mint = 'test_mint'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 7);
+
+        let row: (f64, i32) = conn
+            .query_row(
+                "SELECT net_flow, buy_count FROM token_window_metrics WHERE mint = 'test_mint' AND window_secs = 30",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(row, (2.5, 3));
+
+        let volume: (f64, f64, f64) = conn
+            .query_row(
+                "SELECT buy_volume, sell_volume, volume FROM token_window_metrics WHERE mint = 'test_mint' AND window_secs = 30",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(volume, (4.0, 1.5, 5.5));
+
+        // Legacy row still filled from the fixed windows
+        let net_flow_900s: f64 = conn
+            .query_row("SELECT net_flow_900s FROM token_rolling_metrics WHERE mint = 'test_mint'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(net_flow_900s, 150.0);
+    }
+
+    #[test]
+    fn test_write_aggregated_state_venue_rows() {
+        let conn = create_test_db();
+        let mut metrics = create_test_metrics();
+        metrics.venues = vec![
+            VenueMetrics {
+                window_secs: 300,
+                venue: "Raydium".to_string(),
+                volume_sol: 30.0,
+                volume_share: 0.75,
+                dominant: true,
+                ..Default::default()
+            },
+            VenueMetrics {
+                window_secs: 300,
+                venue: "PumpSwap".to_string(),
+                volume_sol: 10.0,
+                volume_share: 0.25,
+                ..Default::default()
+            },
+        ];
+
+        write_aggregated_state(&conn, "test_mint", &metrics).unwrap();
+
+        let dominant: (String, f64) = conn
+            .query_row(
+                "SELECT dominant_venue, dominant_venue_share FROM token_window_metrics
+                 WHERE mint = 'test_mint' AND window_secs = 300",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(dominant, ("Raydium".to_string(), 0.75));
+
+        // PumpSwap left every window: its row is dropped on the next write
+        metrics.venues.truncate(1);
+        write_aggregated_state(&conn, "test_mint", &metrics).unwrap();
+        let venues: Vec<String> = conn
+            .prepare("SELECT venue FROM token_venue_metrics WHERE mint = 'test_mint'")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(venues, vec!["Raydium".to_string()]);
+    }
+
+    #[test]
+    fn test_migrate_schema_adds_trade_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE token_trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                mint TEXT NOT NULL, timestamp INTEGER NOT NULL, wallet TEXT NOT NULL,
+                side TEXT NOT NULL, sol_amount REAL NOT NULL,
+                is_bot INTEGER NOT NULL DEFAULT 0, is_dca INTEGER NOT NULL DEFAULT 0
+            );
+            INSERT INTO token_trades (mint, timestamp, wallet, side, sol_amount, is_dca)
+                VALUES ('legacy', 1000, 'w1', 'buy', 1.0, 1);",
+        )
+        .unwrap();
+        conn.execute_batch(include_str!("../sql/00_initial.sql")).unwrap();
+
+        migrate_schema(&conn).unwrap();
+        // Idempotent
+        migrate_schema(&conn).unwrap();
+
+        append_trade(&conn, &create_test_trade(1010)).unwrap();
+
+        let trades = load_trades_since(&conn, 0).unwrap();
+        assert_eq!(trades.len(), 2);
+        // Legacy row: source inferred from the DCA flag
+        assert_eq!(trades[0].source_program, "JupiterDCA");
+        assert_eq!(trades[1].source_program, "PumpSwap");
+        assert_eq!(trades[1].token_amount, 1000.0);
+        assert_eq!(trades[1].token_decimals, 6);
+
+        assert_eq!(trade_history_bounds(&conn).unwrap(), Some((1000, 1010)));
+    }
+
     #[test]
     fn test_append_trade() {
         let conn = create_test_db();
@@ -588,6 +1872,314 @@ mod tests {
         assert_eq!(sides[2], "unknown");
     }
 
+    #[test]
+    fn test_write_and_prune_candles() {
+        let conn = create_test_db();
+        let mut book = crate::candles::CandleBook::new("test_mint");
+        let trade = create_test_trade(1_000);
+        book.add(&trade);
+
+        // Closes the 1s and 1m candles
+        let closed = book.close(1_060);
+        assert_eq!(closed.len(), 2);
+        for candle in &closed {
+            write_candle(&conn, candle).unwrap();
+        }
+        // Rewriting the same candle upserts
+        write_candle(&conn, &closed[0]).unwrap();
+
+        let (count, volume): (i64, f64) = conn
+            .query_row(
+                "SELECT COUNT(*), SUM(volume_sol) FROM token_candles WHERE mint = 'test_mint'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(volume, trade.sol_amount * 2.0);
+
+        // 1s candles expire after an hour, 1m candles after a day
+        assert_eq!(prune_candles(&conn, 1_000 + 3_601).unwrap(), 1);
+        assert_eq!(prune_candles(&conn, 1_000 + 86_400).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_bot_profiles_round_trip() {
+        let conn = create_test_db();
+
+        let mut active = BotProfile::default();
+        active.score = 0.7;
+        active.trades = 42;
+        active.last_trade_ts = 10_000;
+        active.fee_tip_rate = 0.9;
+        let mut stale = active.clone();
+        stale.last_trade_ts = 1_000;
+
+        write_bot_profiles(&conn, &[("active".to_string(), active.clone()), ("stale".to_string(), stale)]).unwrap();
+        active.score = 0.8;
+        write_bot_profiles(&conn, &[("active".to_string(), active.clone())]).unwrap();
+
+        let loaded = load_bot_profiles(&conn, 5_000).unwrap();
+        assert_eq!(loaded, vec![("active".to_string(), active)]);
+    }
+
+    #[test]
+    fn test_launches_round_trip() {
+        let conn = create_test_db();
+
+        let event = LaunchEvent {
+            mint: "mint_a".to_string(),
+            creator: "dev".to_string(),
+            source_program: "Pumpfun".to_string(),
+            timestamp: 10_000,
+            slot: 100,
+            tip_lamports: 5_000,
+            migration: false,
+        };
+        let mut launch = Launch::new(event.clone());
+        let mut sniper = SniperPosition {
+            kind: SniperKind::Bundled,
+            first_slot: 100,
+            bought_tokens: 2e8,
+            sold_tokens: 0.0,
+            bought_sol: 5.0,
+            sold_sol: 0.0,
+        };
+        launch.snipers.insert("sniper".to_string(), sniper.clone());
+        let mut stale = Launch::new(LaunchEvent { mint: "mint_b".to_string(), timestamp: 1_000, ..event });
+        stale.snipers.insert("sniper".to_string(), sniper.clone());
+
+        write_launches(&conn, &[launch.clone(), stale]).unwrap();
+        sniper.sold_tokens = 1e8;
+        sniper.sold_sol = 4.0;
+        launch.snipers.insert("sniper".to_string(), sniper);
+        launch.last_activity = 10_500;
+        write_launches(&conn, &[launch.clone()]).unwrap();
+
+        assert_eq!(load_launches(&conn, 5_000).unwrap(), vec![launch]);
+
+        let held: f64 = conn
+            .query_row("SELECT sniper_held_share FROM token_launches WHERE mint = 'mint_a'", [], |row| row.get(0))
+            .unwrap();
+        assert!((held - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_creators_round_trip() {
+        let conn = create_test_db();
+
+        let token = CreatedToken {
+            mint: "mint_a".to_string(),
+            creator: "dev".to_string(),
+            source_program: "Pumpfun".to_string(),
+            launched_at: 10_000,
+            last_trade: 10_600,
+            peak_price: 2e-8,
+            last_price: 1e-9,
+            dev_bought_tokens: 5e7,
+            dev_sold_tokens: 5e7,
+            dev_moved_tokens: 0.0,
+            dev_sells: 2,
+            status: TokenStatus::Live,
+        };
+        let old = CreatedToken { mint: "mint_b".to_string(), launched_at: 1_000, ..token.clone() };
+        let reputation = CreatorReputation {
+            creator: "dev".to_string(),
+            tokens_launched: 2,
+            tokens_finished: 1,
+            tokens_dead: 1,
+            dev_sells: 4,
+            last_launch: 10_000,
+            lifetimes: vec![600],
+        };
+        write_creators(&conn, &[token.clone(), old], &[reputation]).unwrap();
+
+        let finished = CreatedToken { status: TokenStatus::Dead, ..token };
+        write_creators(&conn, std::slice::from_ref(&finished), &[]).unwrap();
+        assert_eq!(load_creator_tokens(&conn, 5_000).unwrap(), vec![finished]);
+
+        let (median, risk): (Option<i64>, f64) = conn
+            .query_row(
+                "SELECT median_lifetime_secs, risk_score FROM creator_reputation WHERE creator = 'dev'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(median, Some(600));
+        assert!(risk > 0.0);
+    }
+
+    #[test]
+    fn test_signal_events_and_states_round_trip() {
+        let conn = create_test_db();
+
+        let mut signal = Signal::new(
+            "mint_a".to_string(),
+            SignalType::Breakout,
+            0.6,
+            "300s".to_string(),
+            1_000,
+            serde_json::json!({ "net_flow_300s": 12.5 }),
+        );
+        signal.event = crate::signal_state::SignalEvent::Exited;
+        write_signal(&conn, &signal).unwrap();
+        let event: String = conn
+            .query_row("SELECT event FROM token_signals WHERE mint = 'mint_a'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(event, "exited");
+
+        let active = SignalState {
+            mint: "mint_a".to_string(),
+            signal_type: SignalType::Breakout,
+            phase: SignalPhase::Active,
+            strength: 0.6,
+            window: "300s".to_string(),
+            metadata: serde_json::json!({ "net_flow_300s": 12.5 }),
+            emitted_strength: 0.5,
+            entered_at: 900,
+            last_seen: 1_000,
+            last_emitted: 950,
+            cooldown_until: 0,
+        };
+        let cooled = SignalState {
+            signal_type: SignalType::FlowReversal,
+            phase: SignalPhase::CooledDown,
+            cooldown_until: 1_300,
+            ..active.clone()
+        };
+        write_signal_states(&conn, &[active.clone(), cooled.clone()]).unwrap();
+        let mut loaded = load_signal_states(&conn).unwrap();
+        loaded.sort_by_key(|s| s.signal_type.as_str());
+        assert_eq!(loaded, vec![active, cooled.clone()]);
+
+        let inactive = SignalState { phase: SignalPhase::Inactive, ..cooled };
+        write_signal_states(&conn, &[inactive]).unwrap();
+        assert_eq!(load_signal_states(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_dashboard_signals_rank_recent_events_first() {
+        let conn = create_test_db();
+        let now = chrono::Utc::now().timestamp();
+        let latest = |conn: &Connection| -> Option<(String, f64)> {
+            conn.query_row(
+                "SELECT signal_type, strength FROM dashboard_signals
+                 WHERE mint = 'mint_a'
+                 ORDER BY priority DESC, strength DESC LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .unwrap()
+        };
+
+        let active = SignalState {
+            mint: "mint_a".to_string(),
+            signal_type: SignalType::Breakout,
+            phase: SignalPhase::Active,
+            strength: 0.9,
+            window: "300s".to_string(),
+            metadata: serde_json::json!({}),
+            emitted_strength: 0.9,
+            entered_at: now,
+            last_seen: now,
+            last_emitted: now,
+            cooldown_until: 0,
+        };
+        write_signal_states(&conn, &[active]).unwrap();
+        assert_eq!(latest(&conn), Some(("BREAKOUT".to_string(), 0.9)));
+
+        // An event that aged out of the lookback leaves the state in front
+        let stale = Signal::new(
+            "mint_a".to_string(),
+            SignalType::DevSell,
+            0.5,
+            "event".to_string(),
+            now - 3_600,
+            serde_json::json!({}),
+        );
+        write_signal(&conn, &stale).unwrap();
+        assert_eq!(latest(&conn), Some(("BREAKOUT".to_string(), 0.9)));
+
+        // A recent event outranks a stronger active state
+        let dump = Signal::new(
+            "mint_a".to_string(),
+            SignalType::SniperDump,
+            0.4,
+            "event".to_string(),
+            now - 60,
+            serde_json::json!({}),
+        );
+        write_signal(&conn, &dump).unwrap();
+        assert_eq!(latest(&conn), Some(("SNIPER_DUMP".to_string(), 0.4)));
+    }
+
+    #[test]
+    fn test_market_baseline_skips_warming_snapshots() {
+        let conn = create_test_db();
+        assert_eq!(load_market_baseline(&conn).unwrap(), None);
+
+        let settled = MarketSnapshot {
+            timestamp: 1_000,
+            volume_sol: 120.0,
+            baseline_volume_sol: 100.0,
+            baseline_wallets: 40.0,
+            activity: 1.1,
+            ..Default::default()
+        };
+        write_market_snapshot(&conn, &settled).unwrap();
+        write_market_snapshot(&conn, &MarketSnapshot { timestamp: 1_060, warming: true, ..Default::default() }).unwrap();
+
+        assert_eq!(load_market_baseline(&conn).unwrap(), Some((100.0, 40.0)));
+        assert_eq!(prune_market_metrics(&conn, 1_030 + MARKET_METRICS_RETENTION_SECS).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_position_snapshot_round_trip() {
+        let conn = create_test_db();
+        let mut book = crate::positions::PositionBook::default();
+        book.apply(&create_test_trade(1_000));
+
+        assert_eq!(load_positions(&conn, "test_mint").unwrap(), None);
+
+        let snapshot = PositionSnapshot {
+            mint: "test_mint".to_string(),
+            snapshot_ts: 1_010,
+            metrics: book.holder_metrics(),
+            positions: book.take_dirty(),
+        };
+        write_position_snapshot(&conn, &snapshot).unwrap();
+
+        let (snapshot_ts, positions) = load_positions(&conn, "test_mint").unwrap().unwrap();
+        assert_eq!(snapshot_ts, 1_010);
+        assert_eq!(positions, snapshot.positions);
+
+        let holders: i64 = conn
+            .query_row("SELECT holders FROM token_holder_metrics WHERE mint = 'test_mint'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(holders, 1);
+
+        // Open positions are never pruned
+        assert_eq!(prune_positions(&conn, 1_000 + CLOSED_POSITION_RETENTION_SECS + 1).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_load_blocklist_and_followed_tokens() {
+        let conn = create_test_db();
+
+        conn.execute("INSERT INTO blocklist (mint, reason) VALUES ('blocked_mint', 'spam')", []).unwrap();
+        conn.execute("INSERT INTO followed_tokens (mint) VALUES ('followed_a')", []).unwrap();
+        conn.execute("INSERT INTO followed_tokens (mint) VALUES ('followed_b')", []).unwrap();
+
+        let blocked = load_blocklist(&conn).unwrap();
+        let followed = load_followed_tokens(&conn).unwrap();
+
+        assert_eq!(blocked.len(), 1);
+        assert!(blocked.contains("blocked_mint"));
+        assert_eq!(followed.len(), 2);
+        assert!(followed.contains("followed_a"));
+    }
+
     #[test]
     fn test_write_loop_batch_size() {
         // This test verifies batching logic (unit test, not integration)
@@ -0,0 +1,230 @@
+//! Timer-driven metric decay
+//!
+//! Windows are otherwise evicted only when a new trade for the same mint
+//! arrives, so a token that goes quiet keeps its last metrics in SQLite and
+//! signals that depend on silence (flow reversal, exhaustion) never fire.
+//!
+//! `refresh_mint` + `publish_update` are the single per-mint update path used
+//! by the shards both on every trade and on every decay tick (for mints whose
+//! windows changed or whose candles are due). `run_decay_loop` only
+//! broadcasts the tick; each shard decays the mints it owns.
+//!
+//! The tick runs on the shared `EventClock` rather than the wall clock, so
+//! trade-driven and timer-driven eviction agree on "now" and a stalled stream
+//! does not empty every window.
+
+use crate::{
+    candles::Candle,
+    db::WriteRequest,
+    market::MarketTracker,
+    rules::RuleSet,
+    shards::{ShardCommand, ShardRouter},
+    signals::{self, Signal, SignalContext},
+    state::{RollingMetrics, TokenRollingState},
+    types::TradeEvent,
+    watermark::EventClock,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+
+/// Metrics and signals computed for one mint
+#[derive(Debug, Clone)]
+pub struct MintUpdate {
+    pub mint: String,
+    pub metrics: RollingMetrics,
+    pub signals: Vec<Signal>,
+    /// Candles closed by this update
+    pub candles: Vec<Candle>,
+}
+
+/// Evict windows and close candles at `now`, recompute metrics and evaluate
+/// `rules` and the coded signals for one mint against the market `context`
+///
+/// Runs on the shard that owns `state`; nothing here awaits.
+pub fn refresh_mint(
+    state: &mut TokenRollingState,
+    now: i64,
+    rules: &RuleSet,
+    context: &SignalContext,
+) -> MintUpdate {
+    state.evict_old_trades(now);
+    let candles = state.close_candles(now);
+
+    // Phase 4: Compute rolling metrics (checked by the periodic `audit`)
+    let metrics = state.compute_rolling_metrics();
+
+    // Phase 6: Evaluate signals
+    // Get recent trades from in-memory rolling state (not DB) for performance
+    let recent_trades: Vec<TradeEvent> = state.window_trades(300).cloned().collect();
+    let signals = signals::evaluate_signals(&state.mint, &metrics, &recent_trades, rules, now, context);
+
+    MintUpdate {
+        mint: state.mint.clone(),
+        metrics,
+        signals,
+        candles,
+    }
+}
+
+/// Send an update's metrics, signals and closed candles to the database writer
+pub async fn publish_update(writer: &mpsc::Sender<WriteRequest>, update: MintUpdate) {
+    // Phase 5: Send metrics to database writer (non-blocking)
+    if let Err(e) = writer
+        .send(WriteRequest::Metrics {
+            mint: update.mint,
+            metrics: update.metrics,
+        })
+        .await
+    {
+        log::warn!("⚠️  Failed to send metrics to writer: {}", e);
+    }
+
+    for signal in update.signals {
+        publish_signal(writer, signal).await;
+    }
+
+    for candle in update.candles {
+        if let Err(e) = writer.send(WriteRequest::Candle(candle)).await {
+            log::warn!("⚠️  Failed to send candle to writer: {}", e);
+        }
+    }
+}
+
+/// Log a signal and send it to the database writer
+pub async fn publish_signal(writer: &mpsc::Sender<WriteRequest>, signal: Signal) {
+    log::info!(
+        "🔔 SIGNAL | Mint: {} | Type: {:?} | Event: {} | Strength: {:.2} | Window: {} | Metadata: {}",
+        signal.mint,
+        signal.signal_type,
+        signal.event.as_str(),
+        signal.strength,
+        signal.window,
+        signal.metadata
+    );
+
+    if let Err(e) = writer.send(WriteRequest::Signal(signal)).await {
+        log::warn!("⚠️  Failed to send signal to writer: {}", e);
+    }
+}
+
+/// Evict every mint at `now` and refresh the ones whose windows changed or
+/// whose candles are due
+pub fn tick<'a>(
+    states: impl IntoIterator<Item = &'a mut TokenRollingState>,
+    now: i64,
+    rules: &RuleSet,
+    context: &SignalContext,
+) -> Vec<MintUpdate> {
+    let mut updates = Vec::new();
+
+    for state in states {
+        if state.evict_old_trades(now) > 0 || state.candles.is_due(now) {
+            updates.push(refresh_mint(state, now, rules, context));
+        }
+    }
+
+    updates
+}
+
+/// Background loop that decays idle windows every `interval`
+///
+/// Ticks are skipped until the event clock has a time. The tick is queued
+/// behind each shard's pending trades, so it never evicts ahead of them.
+pub async fn run_decay_loop(
+    shards: Arc<ShardRouter>,
+    clock: Arc<EventClock>,
+    market: Arc<MarketTracker>,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+        let context = market.context();
+        shards.broadcast(|| ShardCommand::Decay { now, context }).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TradeDirection;
+    use std::collections::HashMap;
+
+    fn trade(mint: &str, timestamp: i64, sol_amount: f64) -> TradeEvent {
+        TradeEvent {
+            user_account: format!("wallet_{}", timestamp),
+            ..TradeEvent::test(mint, timestamp, TradeDirection::Buy, sol_amount)
+        }
+    }
+
+    #[test]
+    fn test_tick_refreshes_only_changed_mints() {
+        let mut states = HashMap::new();
+        for (mint, ts) in [("quiet", 1_000), ("active", 1_090)] {
+            let mut state = TokenRollingState::new(mint.to_string());
+            state.add_trade(trade(mint, ts, 2.0));
+            state.evict_old_trades(ts);
+            states.insert(mint.to_string(), state);
+        }
+
+        let mut updates = tick(states.values_mut(), 1_100, &RuleSet::default(), &SignalContext::default());
+        updates.sort_by(|a, b| a.mint.cmp(&b.mint));
+
+        // "active" only closed candles, its windows are unchanged
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].mint, "active");
+        assert_eq!(updates[0].metrics.window(60).net_flow_sol, 2.0);
+        assert_eq!(updates[0].candles.len(), 1);
+
+        assert_eq!(updates[1].mint, "quiet");
+        // 60s flow decayed to zero, 300s still holds the trade
+        assert_eq!(updates[1].metrics.window(60).net_flow_sol, 0.0);
+        assert_eq!(updates[1].metrics.window(300).net_flow_sol, 2.0);
+        // 1s and 1m candles of the quiet mint closed
+        let intervals: Vec<i64> = updates[1].candles.iter().map(|c| c.interval_secs).collect();
+        assert_eq!(intervals, vec![1, 60]);
+
+        // Nothing else expires until the next window boundary
+        assert!(tick(states.values_mut(), 1_101, &RuleSet::default(), &SignalContext::default()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_publish_update_sends_metrics() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut state = TokenRollingState::new("mint".to_string());
+        state.add_trade(trade("mint", 1_000, 1.0));
+
+        publish_update(&tx, refresh_mint(&mut state, 1_000, &RuleSet::default(), &SignalContext::default())).await;
+
+        match rx.recv().await {
+            Some(WriteRequest::Metrics { mint, metrics }) => {
+                assert_eq!(mint, "mint");
+                assert_eq!(metrics.window(60).buy_count, 1);
+            }
+            other => panic!("expected metrics, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_update_sends_closed_candles() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut state = TokenRollingState::new("mint".to_string());
+        state.add_trade(trade("mint", 1_000, 1.0));
+
+        // Event time moved past the 1s and 1m buckets of the trade
+        publish_update(&tx, refresh_mint(&mut state, 1_060, &RuleSet::default(), &SignalContext::default())).await;
+
+        let mut candles = Vec::new();
+        while let Ok(request) = rx.try_recv() {
+            if let WriteRequest::Candle(candle) = request {
+                candles.push((candle.interval_secs, candle.open_time));
+            }
+        }
+        assert_eq!(candles, vec![(1, 1_000), (60, 960)]);
+    }
+}
@@ -0,0 +1,387 @@
+//! Wallet funding graph
+//!
+//! Sybil buyers look like many unique wallets, but they are usually fresh
+//! wallets funded from one source minutes before they buy. System Program SOL
+//! transfers are ingested by `FundingProcessor`, and the first transfer of at
+//! least `MIN_FUNDING_LAMPORTS` into a wallet is kept as its funding edge.
+//! Following edges up to `MAX_FUNDING_HOPS` yields the wallet's root funder,
+//! which is the wallet's cluster: wallets sharing a root count once in
+//! `unique_clusters`.
+//!
+//! Wallets are funded before their first trade, so every transfer waits in a
+//! pending set for `PENDING_SECS`; when the wallet trades a tracked mint, its
+//! funding chain is promoted to the graph and kept while it is used (idle
+//! edges are dropped after `FUNDING_MEMORY_SECS`). When the pending set is at
+//! `MAX_PENDING`, the oldest pending transfers are evicted to make room. Funders with more than
+//! `MAX_FUNDER_FANOUT` recipients (exchanges, faucets) are hubs and never
+//! form a cluster.
+
+use crate::watermark::EventClock;
+use async_trait::async_trait;
+use carbon_core::{
+    deserialize::ArrangeAccounts,
+    error::CarbonResult,
+    instruction::InstructionProcessorInputType,
+    metrics::MetricsCollection,
+    processor::Processor,
+};
+use carbon_system_program_decoder::instructions::{transfer_sol::TransferSol, SystemProgramInstruction};
+use dashmap::DashMap;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+/// Transfers below this do not create a funding edge (0.01 SOL)
+pub const MIN_FUNDING_LAMPORTS: u64 = 10_000_000;
+
+/// Funding edges followed to find a wallet's root funder
+pub const MAX_FUNDING_HOPS: usize = 3;
+
+/// Funders with more recipients than this are hubs, not cluster roots
+pub const MAX_FUNDER_FANOUT: u32 = 500;
+
+/// Seconds a transfer into a wallet that has not traded is kept
+pub const PENDING_SECS: i64 = 3_600;
+
+/// Seconds a promoted edge is kept after its last use
+pub const FUNDING_MEMORY_SECS: i64 = 86_400;
+
+/// Cap on pending transfers; the oldest are evicted beyond it
+const MAX_PENDING: usize = 1_000_000;
+
+/// First sizeable SOL transfer into a wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundingEdge {
+    pub funder: String,
+    pub lamports: u64,
+    /// Time of the transfer
+    pub timestamp: i64,
+    /// Last time the edge resolved a trading wallet's cluster
+    pub last_used: i64,
+}
+
+/// Funding edges of wallets trading tracked mints, plus recent transfers
+#[derive(Debug)]
+pub struct FundingGraph {
+    /// Promoted edges (funding chains of wallets that traded), by recipient
+    edges: DashMap<String, FundingEdge>,
+    /// Recent transfers not yet part of a trading wallet's chain, by recipient
+    pending: DashMap<String, FundingEdge>,
+    /// Recipients per funder, across edges and pending transfers
+    fanout: DashMap<String, u32>,
+    /// Pending recipients in insertion order, with their transfer time;
+    /// entries already promoted or pruned are skipped when popped
+    pending_order: Mutex<VecDeque<(String, i64)>>,
+    max_pending: usize,
+    /// Pending transfers evicted to stay under `max_pending`, since last taken
+    evicted: AtomicU64,
+}
+
+impl Default for FundingGraph {
+    fn default() -> Self {
+        Self::with_max_pending(MAX_PENDING)
+    }
+}
+
+impl FundingGraph {
+    fn with_max_pending(max_pending: usize) -> Self {
+        Self {
+            edges: DashMap::new(),
+            pending: DashMap::new(),
+            fanout: DashMap::new(),
+            pending_order: Mutex::new(VecDeque::new()),
+            max_pending,
+            evicted: AtomicU64::new(0),
+        }
+    }
+
+    /// Record a SOL transfer, returning true if it became a funding edge
+    ///
+    /// Only the first sizeable transfer into a wallet is kept.
+    pub fn record_transfer(&self, funder: &str, wallet: &str, lamports: u64, timestamp: i64) -> bool {
+        if lamports < MIN_FUNDING_LAMPORTS || funder == wallet {
+            return false;
+        }
+        if self.edges.contains_key(wallet) || self.pending.contains_key(wallet) {
+            return false;
+        }
+
+        let mut order = self.pending_order.lock().unwrap();
+        while self.pending.len() >= self.max_pending {
+            let Some((oldest, queued_at)) = order.pop_front() else {
+                break;
+            };
+            if let Some((_, edge)) = self.pending.remove_if(&oldest, |_, edge| edge.timestamp == queued_at) {
+                self.release_funder(&edge.funder);
+                self.evicted.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        order.push_back((wallet.to_string(), timestamp));
+        drop(order);
+
+        self.pending.insert(
+            wallet.to_string(),
+            FundingEdge {
+                funder: funder.to_string(),
+                lamports,
+                timestamp,
+                last_used: timestamp,
+            },
+        );
+        *self.fanout.entry(funder.to_string()).or_insert(0) += 1;
+        true
+    }
+
+    /// Root funder of a wallet trading at `now` (None if it has no funder
+    /// or is funded by a hub)
+    ///
+    /// Promotes the wallet's funding chain from pending and marks it used.
+    pub fn cluster_for_trade(&self, wallet: &str, now: i64) -> Option<String> {
+        let mut root = None;
+        let mut current = wallet.to_string();
+
+        for _ in 0..MAX_FUNDING_HOPS {
+            let funder = match self.edges.get_mut(&current) {
+                Some(mut edge) => {
+                    edge.last_used = edge.last_used.max(now);
+                    edge.funder.clone()
+                }
+                None => {
+                    let Some((recipient, mut edge)) = self.pending.remove(&current) else {
+                        break;
+                    };
+                    edge.last_used = edge.last_used.max(now);
+                    let funder = edge.funder.clone();
+                    self.edges.insert(recipient, edge);
+                    funder
+                }
+            };
+
+            if self.is_hub(&funder) || funder == wallet {
+                break;
+            }
+            root = Some(funder.clone());
+            current = funder;
+        }
+
+        root
+    }
+
+    /// True if `funder` funded more than `MAX_FUNDER_FANOUT` wallets
+    pub fn is_hub(&self, funder: &str) -> bool {
+        self.fanout.get(funder).is_some_and(|count| *count > MAX_FUNDER_FANOUT)
+    }
+
+    /// Number of promoted edges
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Number of pending transfers
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Number of pending transfers evicted since the last call
+    pub fn take_evicted(&self) -> u64 {
+        self.evicted.swap(0, Ordering::Relaxed)
+    }
+
+    /// Drop one recipient from a funder's fanout
+    fn release_funder(&self, funder: &str) {
+        if let Some(mut count) = self.fanout.get_mut(funder) {
+            *count = count.saturating_sub(1);
+        }
+        self.fanout.remove_if(funder, |_, count| *count == 0);
+    }
+
+    /// Drop pending transfers older than `PENDING_SECS` and edges unused for
+    /// `FUNDING_MEMORY_SECS` at `now`, returning how many were dropped
+    pub fn prune(&self, now: i64) -> usize {
+        let mut funders = Vec::new();
+        self.pending.retain(|_, edge| {
+            let keep = edge.timestamp >= now - PENDING_SECS;
+            if !keep {
+                funders.push(edge.funder.clone());
+            }
+            keep
+        });
+        self.pending_order
+            .lock()
+            .unwrap()
+            .retain(|(wallet, _)| self.pending.contains_key(wallet));
+        self.edges.retain(|_, edge| {
+            let keep = edge.last_used >= now - FUNDING_MEMORY_SECS;
+            if !keep {
+                funders.push(edge.funder.clone());
+            }
+            keep
+        });
+
+        for funder in &funders {
+            if let Some(mut count) = self.fanout.get_mut(funder) {
+                *count = count.saturating_sub(1);
+            }
+        }
+        self.fanout.retain(|_, count| *count > 0);
+
+        funders.len()
+    }
+}
+
+/// Feeds System Program SOL transfers into the funding graph
+pub struct FundingProcessor {
+    pub graph: Arc<FundingGraph>,
+}
+
+impl FundingProcessor {
+    pub fn new(graph: Arc<FundingGraph>) -> Self {
+        Self { graph }
+    }
+}
+
+#[async_trait]
+impl Processor for FundingProcessor {
+    type InputType = InstructionProcessorInputType<SystemProgramInstruction>;
+
+    async fn process(
+        &mut self,
+        data: Self::InputType,
+        _metrics: Arc<MetricsCollection>,
+    ) -> CarbonResult<()> {
+        let (metadata, decoded_instruction, _nested_instructions, _raw_instruction) = &data;
+
+        let SystemProgramInstruction::TransferSol(transfer) = &decoded_instruction.data else {
+            return Ok(());
+        };
+        let Some(accounts) = TransferSol::arrange_accounts(&decoded_instruction.accounts) else {
+            return Ok(());
+        };
+        let Some(timestamp) = metadata.transaction_metadata.block_time else {
+            return Ok(());
+        };
+
+        let funder = accounts.source.to_string();
+        let wallet = accounts.destination.to_string();
+        if self.graph.record_transfer(&funder, &wallet, transfer.amount, timestamp) {
+            log::trace!(
+                "🕸️  FUNDING | {} -> {} | {:.4} SOL",
+                funder,
+                wallet,
+                transfer.amount as f64 / 1_000_000_000.0
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Background loop that prunes stale transfers and idle edges
+///
+/// Skipped until the event clock has a time.
+pub async fn run_prune_loop(graph: Arc<FundingGraph>, clock: Arc<EventClock>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+
+        let pruned = graph.prune(now);
+        let evicted = graph.take_evicted();
+        if pruned > 0 || evicted > 0 {
+            log::debug!(
+                "🕸️  FUNDING GRAPH | Pruned: {} | Evicted: {} | Edges: {} | Pending: {}",
+                pruned,
+                evicted,
+                graph.len(),
+                graph.pending_len()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_wallets_sharing_a_funder_cluster() {
+        let graph = FundingGraph::default();
+        assert!(graph.record_transfer("main", "relay", 10 * SOL, 900));
+        for wallet in ["a", "b", "c"] {
+            assert!(graph.record_transfer("relay", wallet, SOL, 1_000));
+        }
+        // Dust and later transfers do not create or replace edges
+        assert!(!graph.record_transfer("other", "d", 1_000, 1_000));
+        assert!(!graph.record_transfer("other", "a", SOL, 1_010));
+
+        // Chains resolve to the root funder and are promoted when used
+        assert_eq!(graph.cluster_for_trade("a", 1_100).as_deref(), Some("main"));
+        assert_eq!(graph.cluster_for_trade("b", 1_100).as_deref(), Some("main"));
+        assert_eq!(graph.cluster_for_trade("d", 1_100), None);
+        assert_eq!((graph.len(), graph.pending_len()), (3, 1));
+    }
+
+    #[test]
+    fn test_hub_funders_do_not_cluster() {
+        let graph = FundingGraph::default();
+        for i in 0..=MAX_FUNDER_FANOUT {
+            graph.record_transfer("exchange", &format!("wallet{}", i), SOL, 1_000);
+        }
+        graph.record_transfer("wallet0", "fresh", SOL, 1_010);
+
+        assert!(graph.is_hub("exchange"));
+        assert_eq!(graph.cluster_for_trade("wallet1", 1_100), None);
+        // The chain stops below the hub
+        assert_eq!(graph.cluster_for_trade("fresh", 1_100).as_deref(), Some("wallet0"));
+    }
+
+    #[test]
+    fn test_prune_drops_stale_transfers_and_idle_edges() {
+        let graph = FundingGraph::default();
+        graph.record_transfer("funder", "a", SOL, 1_000);
+        graph.record_transfer("funder", "b", SOL, 1_000);
+        graph.cluster_for_trade("a", 1_100);
+
+        // b never traded; a's edge is still in use
+        assert_eq!(graph.prune(1_000 + PENDING_SECS + 1), 1);
+        assert_eq!((graph.len(), graph.pending_len()), (1, 0));
+
+        assert_eq!(graph.prune(1_100 + FUNDING_MEMORY_SECS + 1), 1);
+        assert!(graph.is_empty());
+        assert!(graph.fanout.is_empty());
+    }
+
+    #[test]
+    fn test_full_pending_set_evicts_oldest() {
+        let graph = FundingGraph::with_max_pending(2);
+        graph.record_transfer("funder", "a", SOL, 1_000);
+        graph.record_transfer("funder", "b", SOL, 1_010);
+        // a is promoted, so it no longer counts against the cap
+        graph.cluster_for_trade("a", 1_020);
+        assert!(graph.record_transfer("other", "c", SOL, 1_030));
+        assert!(graph.record_transfer("other", "d", SOL, 1_040));
+
+        // b was the oldest pending transfer
+        assert_eq!(graph.pending_len(), 2);
+        assert_eq!(graph.cluster_for_trade("b", 1_050), None);
+        assert_eq!(graph.cluster_for_trade("d", 1_050).as_deref(), Some("other"));
+        assert_eq!(graph.take_evicted(), 1);
+        assert_eq!(graph.take_evicted(), 0);
+        assert_eq!(*graph.fanout.get("funder").unwrap(), 1);
+    }
+}
@@ -5,3 +5,25 @@ pub mod signals;
 pub mod state;
 pub mod trade_extractor;
 pub mod processor;
+pub mod config;
+pub mod mint_filter;
+pub mod trade_source;
+pub mod sweeper;
+pub mod warm_start;
+pub mod decay;
+pub mod watermark;
+pub mod candles;
+pub mod positions;
+pub mod wallets;
+pub mod bot_classifier;
+pub mod sketch;
+pub mod market;
+pub mod wash;
+pub mod funding;
+pub mod snipers;
+pub mod creators;
+pub mod liquidity;
+pub mod shards;
+pub mod audit;
+pub mod rules;
+pub mod signal_state;
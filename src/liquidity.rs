@@ -0,0 +1,247 @@
+//! AMM pool reserves and liquidity
+//!
+//! Venues that know a pool's reserves report them with the trade
+//! (`Extraction::reserves`, always the state right after the trade): PumpSwap
+//! events carry them, and PumpSwap instructions read them from the pool
+//! vaults' post-transaction token balances. The latest reserves per mint give
+//! its spot price and liquidity (both sides valued in SOL).
+//!
+//! Every trade on a mint with known reserves gets a `price_impact`: exact for
+//! trades that reported reserves (post vs. pre-trade spot price), estimated
+//! with the constant-product curve for trades on other venues. The rolling
+//! state carries the liquidity so signals can normalize flow by it, since
+//! 10 SOL into a 20 SOL pool moves price far more than into a 2,000 SOL pool.
+//!
+//! Changed pools are appended to `pool_liquidity` every snapshot interval.
+
+use crate::{
+    db::WriteRequest,
+    types::{PoolReserves, TradeDirection, TradeEvent},
+    watermark::EventClock,
+};
+use dashmap::DashMap;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+
+/// Pools without a reserve update for this long are dropped from memory
+pub const LIQUIDITY_IDLE_SECS: i64 = 86_400;
+
+/// 300s net flow / liquidity at which flow signals keep their strength;
+/// larger ratios strengthen them, smaller ones weaken them
+pub const FLOW_LIQUIDITY_REFERENCE: f64 = 0.05;
+
+/// Spot price change caused by `trade`, given the reserves right after it
+///
+/// The pre-trade reserves are recovered by undoing the trade.
+pub fn price_impact(after: &PoolReserves, trade: &TradeEvent) -> Option<f64> {
+    let tokens = trade.whole_tokens();
+    let (base_before, quote_before) = match trade.direction {
+        TradeDirection::Buy => (after.base_reserve + tokens, after.quote_reserve_sol - trade.sol_amount),
+        TradeDirection::Sell => (after.base_reserve - tokens, after.quote_reserve_sol + trade.sol_amount),
+        TradeDirection::Unknown => return None,
+    };
+    if base_before <= 0.0 || quote_before <= 0.0 || after.spot_price() <= 0.0 {
+        return None;
+    }
+
+    Some(after.spot_price() / (quote_before / base_before) - 1.0)
+}
+
+/// Spot price change `trade` would cause on a constant-product pool with
+/// reserves `before`
+pub fn estimated_impact(before: &PoolReserves, trade: &TradeEvent) -> Option<f64> {
+    if before.base_reserve <= 0.0 || before.quote_reserve_sol <= 0.0 {
+        return None;
+    }
+
+    // Price scales with (quote after / quote before)^2 along x * y = k
+    match trade.direction {
+        TradeDirection::Buy => Some(((before.quote_reserve_sol + trade.sol_amount) / before.quote_reserve_sol).powi(2) - 1.0),
+        TradeDirection::Sell => {
+            Some((before.base_reserve / (before.base_reserve + trade.whole_tokens())).powi(2) - 1.0)
+        }
+        TradeDirection::Unknown => None,
+    }
+}
+
+/// Latest reserves of every pool seen, by mint
+#[derive(Debug, Default)]
+pub struct LiquidityTracker {
+    pools: DashMap<String, PoolReserves>,
+    /// Mints whose reserves changed since the last `take_dirty`
+    dirty: DashMap<String, ()>,
+}
+
+impl LiquidityTracker {
+    /// Price impact of a trade, recording the reserves it reported
+    ///
+    /// Without reported reserves the impact is estimated from the mint's
+    /// latest reserves, which are left unchanged.
+    pub fn observe(&self, trade: &TradeEvent, reported: Option<PoolReserves>) -> Option<f64> {
+        let Some(reserves) = reported else {
+            return self.pools.get(&trade.mint).and_then(|before| estimated_impact(&before, trade));
+        };
+
+        let impact = price_impact(&reserves, trade);
+        self.record(reserves);
+        impact
+    }
+
+    /// Store reserves unless newer ones are known for the pool
+    pub fn record(&self, reserves: PoolReserves) {
+        if let Some(current) = self.pools.get(&reserves.mint) {
+            if (current.timestamp, current.slot) > (reserves.timestamp, reserves.slot) {
+                return;
+            }
+        }
+        self.dirty.insert(reserves.mint.clone(), ());
+        self.pools.insert(reserves.mint.clone(), reserves);
+    }
+
+    /// Latest reserves of a mint's pool
+    pub fn get(&self, mint: &str) -> Option<PoolReserves> {
+        self.pools.get(mint).map(|r| r.clone())
+    }
+
+    /// Liquidity of a mint's pool in SOL
+    pub fn liquidity_sol(&self, mint: &str) -> Option<f64> {
+        self.pools.get(mint).map(|r| r.liquidity_sol())
+    }
+
+    /// Number of pools held in memory
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    /// Changed pools since the last call, clearing the dirty set
+    pub fn take_dirty(&self) -> Vec<PoolReserves> {
+        let mints: Vec<String> = self.dirty.iter().map(|e| e.key().clone()).collect();
+        mints
+            .into_iter()
+            .filter_map(|mint| {
+                self.dirty.remove(&mint);
+                self.get(&mint)
+            })
+            .collect()
+    }
+
+    /// Drop pools not updated for `LIQUIDITY_IDLE_SECS` at `now`, returning
+    /// how many
+    pub fn prune(&self, now: i64) -> usize {
+        let before = self.pools.len();
+        self.pools
+            .retain(|mint, reserves| reserves.timestamp >= now - LIQUIDITY_IDLE_SECS || self.dirty.contains_key(mint));
+        before - self.pools.len()
+    }
+}
+
+/// Background loop that appends changed pools to the liquidity history and
+/// prunes idle ones
+///
+/// Skipped until the event clock has a time.
+pub async fn run_snapshot_loop(
+    tracker: Arc<LiquidityTracker>,
+    writer: mpsc::Sender<WriteRequest>,
+    clock: Arc<EventClock>,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+
+        let pools = tracker.take_dirty();
+        let pruned = tracker.prune(now);
+        if !pools.is_empty() || pruned > 0 {
+            log::debug!(
+                "💧 LIQUIDITY | Snapshots: {} | Pruned: {} | Pools: {}",
+                pools.len(),
+                pruned,
+                tracker.len()
+            );
+        }
+
+        if pools.is_empty() {
+            continue;
+        }
+        if let Err(e) = writer.send(WriteRequest::Liquidity(pools)).await {
+            log::warn!("⚠️  Failed to send liquidity snapshots to writer: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reserves(base_reserve: f64, quote_reserve_sol: f64, timestamp: i64) -> PoolReserves {
+        PoolReserves {
+            mint: "mint".to_string(),
+            base_reserve,
+            quote_reserve_sol,
+            timestamp,
+            slot: 0,
+        }
+    }
+
+    fn trade(direction: TradeDirection, sol_amount: f64, tokens: f64) -> TradeEvent {
+        TradeEvent {
+            token_amount: tokens * 1_000_000.0,
+            ..TradeEvent::test("mint", 1_000, direction, sol_amount)
+        }
+    }
+
+    #[test]
+    fn test_same_flow_moves_thin_pools_more() {
+        // 10 SOL into a 20 SOL pool (10 SOL side) vs. a 2,000 SOL pool
+        let buy = trade(TradeDirection::Buy, 10.0, 0.0);
+        let thin = estimated_impact(&reserves(1e9, 10.0, 0), &buy).unwrap();
+        let deep = estimated_impact(&reserves(1e9, 1_000.0, 0), &buy).unwrap();
+
+        assert!((thin - 3.0).abs() < 1e-9);
+        assert!((deep - 0.0201).abs() < 1e-9);
+        assert_eq!(reserves(1e9, 10.0, 0).liquidity_sol(), 20.0);
+
+        let sell = trade(TradeDirection::Sell, 0.0, 1e9);
+        assert!((estimated_impact(&reserves(1e9, 10.0, 0), &sell).unwrap() + 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reported_reserves_give_exact_impact() {
+        // 100 SOL / 1B tokens; a 10 SOL buy takes 1e9 - 1e11 / 110 tokens
+        let tokens = 1e9 - 1e11 / 110.0;
+        let buy = trade(TradeDirection::Buy, 10.0, tokens);
+        let after = reserves(1e9 - tokens, 110.0, 1_000);
+
+        let tracker = LiquidityTracker::default();
+        let impact = tracker.observe(&buy, Some(after.clone())).unwrap();
+        assert!((impact - 0.21).abs() < 1e-9);
+        assert_eq!(tracker.get("mint"), Some(after));
+
+        // Trades without reserves are estimated against the latest ones
+        assert!(tracker.observe(&buy, None).is_some());
+        assert!(tracker.observe(&trade(TradeDirection::Buy, 1.0, 1.0), None).unwrap() < impact);
+    }
+
+    #[test]
+    fn test_stale_reserves_are_ignored_and_idle_pools_pruned() {
+        let tracker = LiquidityTracker::default();
+        tracker.record(reserves(1e9, 100.0, 2_000));
+        tracker.record(reserves(1e9, 50.0, 1_000));
+        assert_eq!(tracker.liquidity_sol("mint"), Some(200.0));
+        assert_eq!(tracker.take_dirty().len(), 1);
+        assert!(tracker.take_dirty().is_empty());
+
+        assert_eq!(tracker.prune(2_000 + LIQUIDITY_IDLE_SECS), 0);
+        assert_eq!(tracker.prune(2_001 + LIQUIDITY_IDLE_SECS), 1);
+        assert!(tracker.is_empty());
+    }
+}
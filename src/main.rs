@@ -1,8 +1,30 @@
 mod processor;
 mod state;
 mod trade_extractor;
+mod trade_source;
 mod types;
 mod signals;
+mod config;
+mod mint_filter;
+mod sweeper;
+mod warm_start;
+mod decay;
+mod watermark;
+mod candles;
+mod positions;
+mod wallets;
+mod bot_classifier;
+mod sketch;
+mod market;
+mod wash;
+mod funding;
+mod snipers;
+mod creators;
+mod liquidity;
+mod shards;
+mod audit;
+mod rules;
+mod signal_state;
 pub mod sqlite_pragma;
 pub mod db;
 
@@ -15,12 +37,15 @@ use {
     carbon_yellowstone_grpc_datasource::{
         BlockFilters, YellowstoneGrpcClientConfig, YellowstoneGrpcGeyserClient,
     },
-    carbon_pump_swap_decoder::{PumpSwapDecoder, PROGRAM_ID as PUMPSWAP_PID},
-    carbon_moonshot_decoder::{MoonshotDecoder, PROGRAM_ID as MOONSHOT_PID},
-    carbon_bonkswap_decoder::{BonkswapDecoder, PROGRAM_ID as BONKSWAP_PID},
-    carbon_jupiter_dca_decoder::{JupiterDcaDecoder, PROGRAM_ID as JUPITER_DCA_PID},
     yellowstone_grpc_proto::geyser::{CommitmentLevel, SubscribeRequestFilterTransactions},
-    crate::{processor::NetSolFlowProcessor, state::TokenRollingState, trade_extractor::TradeExtractor},
+    crate::{
+        bot_classifier::BotClassifier, config::Config, creators::CreatorTracker, liquidity::LiquidityTracker,
+        funding::{FundingGraph, FundingProcessor},
+        market::MarketTracker, mint_filter::MintFilter,
+        processor::ProcessorContext, rules::SignalRules, shards::{ShardConfig, ShardRouter}, snipers::SniperTracker,
+        state::TokenRollingState, trade_source::SourceRegistry,
+        wallets::WalletRegistry, watermark::{EventClock, QuarantineStats},
+    },
 };
 
 #[tokio::main]
@@ -31,29 +56,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     log::info!("🗄️  Initializing database");
     db::init_database().expect("Failed to initialize database");
 
+    let config = Config::from_env();
+
     let geyser_url = env::var("GEYSER_URL").expect("GEYSER_URL not set");
     let x_token = env::var("X_TOKEN").expect("X_TOKEN not set");
 
     log::info!("🚀 Initializing SolFlow Pipeline");
     log::info!("📡 Connecting to Geyser: {}", geyser_url);
 
+    let venues = SourceRegistry::builtin().enabled(&config.venues);
+    if venues.is_empty() {
+        return Err("No venues enabled (check SOLFLOW_VENUES)".into());
+    }
+
     let mut transaction_filters = HashMap::new();
     transaction_filters.insert(
         "solflow_filter".to_string(),
         SubscribeRequestFilterTransactions {
             vote: Some(false),
             failed: Some(false),
-            account_include: vec![
-                PUMPSWAP_PID.to_string(),
-                MOONSHOT_PID.to_string(),
-                BONKSWAP_PID.to_string(),
-                JUPITER_DCA_PID.to_string(),
-            ],
+            account_include: venues.program_ids(),
             ..Default::default()
         },
     );
+    if config.funding_graph {
+        transaction_filters.insert(
+            "solflow_funding".to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                account_include: vec![carbon_system_program_decoder::PROGRAM_ID.to_string()],
+                ..Default::default()
+            },
+        );
+    }
 
-    log::info!("🎯 Filtering for 4 DEX Program IDs (PumpSwap + Others)");
+    log::info!("🎯 Filtering for {} DEX Program IDs: {:?}", venues.len(), venues.names());
 
     let client = YellowstoneGrpcGeyserClient::new(
         geyser_url,
@@ -67,7 +105,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     let seen_signatures = Arc::new(DashMap::new());
-    let rolling_states: Arc<DashMap<String, TokenRollingState>> = Arc::new(DashMap::new());
+    let mut rolling_states: HashMap<String, TokenRollingState> = HashMap::new();
+
+    let windows = state::normalize_windows(&config.windows);
+    log::info!("🪟 Rolling windows: {:?}", windows);
+    for secs in signals::SIGNAL_WINDOWS {
+        if !windows.contains(&secs) {
+            log::warn!("⚠️  Window {}s is not tracked; signals will read it as zero", secs);
+        }
+    }
+
+    // Windows, the decay tick and the sweeper all run on event time
+    let event_clock = Arc::new(EventClock::default());
+
+    // Rebuild the last hours of rolling state from token_trades
+    let now = chrono::Utc::now().timestamp();
+    let history_start = if config.warm_start {
+        let conn = rusqlite::Connection::open(env::var("SOLFLOW_DB_PATH")?)?;
+        let report = warm_start::rebuild_rolling_states(&conn, &mut rolling_states, &windows, now)?;
+        if let Some(ts) = report.latest_event_ts {
+            event_clock.advance(ts);
+        }
+        report.history_start
+    } else {
+        log::info!("♻️  Warm start disabled, windows warm up from now");
+        now
+    };
 
     // Phase 5: Create channel for database writes
     let (writer_tx, writer_rx) = tokio::sync::mpsc::channel(1000);
@@ -78,46 +141,265 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         db::run_write_loop(writer_rx).await;
     });
 
-    log::info!("🔧 Building Pipeline with 4 DEX Decoders + Trade Extraction Layer");
-
-    Pipeline::builder()
-        .datasource(client)
-        .instruction(
-            PumpSwapDecoder,
-            NetSolFlowProcessor::new(
-                seen_signatures.clone(),
-                rolling_states.clone(),
-                TradeExtractor::extract_from_pumpswap,
-                writer_tx.clone(),
-            ),
-        )
-        .instruction(
-            MoonshotDecoder,
-            NetSolFlowProcessor::new(
-                seen_signatures.clone(),
-                rolling_states.clone(),
-                TradeExtractor::extract_from_moonshot,
-                writer_tx.clone(),
-            ),
-        )
-        .instruction(
-            BonkswapDecoder,
-            NetSolFlowProcessor::new(
-                seen_signatures.clone(),
-                rolling_states.clone(),
-                TradeExtractor::extract_from_bonkswap,
-                writer_tx.clone(),
-            ),
-        )
-        .instruction(
-            JupiterDcaDecoder,
-            NetSolFlowProcessor::new(
-                seen_signatures.clone(),
-                rolling_states.clone(),
-                TradeExtractor::extract_from_jupiter_dca,
-                writer_tx.clone(),
-            ),
-        )
+    // Blocklist / allowlist enforced at ingestion, reloaded from SQLite.
+    // Loaded before the pipeline is built so the first trades are filtered
+    // (in allowlist mode an empty filter would reject everything)
+    let mint_filter = Arc::new(MintFilter::new(config.allowlist_mode));
+    {
+        let conn = rusqlite::Connection::open(env::var("SOLFLOW_DB_PATH")?)?;
+        let (blocked, followed) = mint_filter.refresh(&conn)?;
+        log::info!("🚫 Loaded mint filter | Blocked: {} | Followed: {}", blocked, followed);
+    }
+    log::info!(
+        "🚫 Spawning mint filter refresh loop (every {}s, allowlist mode: {})",
+        config.filter_refresh_secs,
+        config.allowlist_mode
+    );
+    tokio::spawn(mint_filter::run_refresh_loop(
+        mint_filter.clone(),
+        std::time::Duration::from_secs(config.filter_refresh_secs),
+    ));
+
+    // Market-wide aggregates and regime; baselines survive restarts
+    let market = Arc::new(MarketTracker::default());
+    {
+        let conn = rusqlite::Connection::open(env::var("SOLFLOW_DB_PATH")?)?;
+        if let Some((volume, wallets)) = db::load_market_baseline(&conn)? {
+            log::info!("🌡️  Restored market baseline ({:.1} SOL, {:.0} wallets per hour)", volume, wallets);
+            market.restore_baseline(volume, wallets);
+        }
+    }
+    log::info!("🌡️  Spawning market regime loop (every {}s)", config.market_snapshot_secs);
+    tokio::spawn(market::run_market_loop(
+        market.clone(),
+        writer_tx.clone(),
+        event_clock.clone(),
+        std::time::Duration::from_secs(config.market_snapshot_secs),
+    ));
+
+    // Creator reputations are rebuilt from tokens launched in the last 30 days
+    let creators = Arc::new(CreatorTracker::default());
+    {
+        let conn = rusqlite::Connection::open(env::var("SOLFLOW_DB_PATH")?)?;
+        let tokens = db::load_creator_tokens(&conn, now - creators::REPUTATION_MEMORY_SECS)?;
+        log::info!("👤 Restored {} created tokens", tokens.len());
+        creators.restore(tokens);
+    }
+    log::info!("👤 Spawning creator persistence (every {}s)", config.creator_persist_secs);
+    tokio::spawn(creators::run_persist_loop(
+        creators.clone(),
+        writer_tx.clone(),
+        event_clock.clone(),
+        std::time::Duration::from_secs(config.creator_persist_secs),
+    ));
+
+    // Declarative signal rules; a broken rules file fails startup, later
+    // edits that fail to parse are logged and ignored
+    let signal_rules = Arc::new(SignalRules::new(config.rules_path.as_ref().map(Into::into)));
+    match &config.rules_path {
+        Some(path) => {
+            signal_rules.reload()?;
+            log::info!(
+                "📐 Loaded {} signal rules from {} (checked every {}s)",
+                signal_rules.current().len(),
+                path,
+                config.rules_reload_secs
+            );
+            tokio::spawn(rules::run_reload_loop(
+                signal_rules.clone(),
+                std::time::Duration::from_secs(config.rules_reload_secs),
+            ));
+        }
+        None => log::info!("📐 Using {} built-in signal rules", signal_rules.current().len()),
+    }
+
+    // Active and cooling-down signals carry over restarts
+    let signal_states = {
+        let conn = rusqlite::Connection::open(env::var("SOLFLOW_DB_PATH")?)?;
+        db::load_signal_states(&conn)?
+    };
+    log::info!(
+        "🔔 Restored {} signal states (enter {:.2} / exit {:.2}, exit after {}s, cooldown {}s)",
+        signal_states.len(),
+        config.signal_states.enter_strength,
+        config.signal_states.exit_strength,
+        config.signal_states.exit_secs,
+        config.signal_states.cooldown_secs
+    );
+
+    // Rolling state is split into single-task shards by mint hash
+    log::info!(
+        "🧩 Spawning {} rolling state shards ({} mints restored)",
+        config.shards,
+        rolling_states.len()
+    );
+    let shards = Arc::new(ShardRouter::spawn(
+        config.shards,
+        rolling_states,
+        signal_states,
+        ShardConfig {
+            windows: windows.into(),
+            history_start,
+            writer: writer_tx.clone(),
+            creators: creators.clone(),
+            rules: signal_rules,
+            signal_states: config.signal_states,
+            db_path: Some(env::var("SOLFLOW_DB_PATH")?),
+        },
+    ));
+    tokio::spawn(shards::run_metrics_loop(
+        shards.clone(),
+        std::time::Duration::from_secs(config.shard_metrics_secs),
+    ));
+
+    // Drop idle mints and keep rolling state under the memory budget
+    let sweeper_stats = Arc::new(sweeper::SweeperStats::default());
+    log::info!(
+        "🧹 Spawning rolling state sweeper (every {}s, budget: {} MB)",
+        config.sweep_interval_secs,
+        config.state_budget_mb
+    );
+    tokio::spawn(sweeper::run_sweeper(
+        shards.clone(),
+        event_clock.clone(),
+        std::time::Duration::from_secs(config.sweep_interval_secs),
+        config.state_budget_mb * 1024 * 1024,
+        sweeper_stats,
+    ));
+
+    // Shadow recompute of every mint's metrics against the incremental engine
+    if config.audit_secs > 0 {
+        log::info!("🔍 Spawning metrics audit (every {}s)", config.audit_secs);
+        tokio::spawn(audit::run_audit_loop(
+            shards.clone(),
+            event_clock.clone(),
+            std::time::Duration::from_secs(config.audit_secs),
+        ));
+    } else {
+        log::info!("🔍 Metrics audit disabled");
+    }
+
+    // Decay quiet mints: evict, re-publish and re-evaluate on a timer
+    log::info!("⏱️  Spawning decay tick (every {}s)", config.decay_tick_secs);
+    tokio::spawn(decay::run_decay_loop(
+        shards.clone(),
+        event_clock.clone(),
+        market.clone(),
+        std::time::Duration::from_secs(config.decay_tick_secs),
+    ));
+
+    // Persist wallet positions and holder metrics
+    log::info!("💼 Spawning position snapshot loop (every {}s)", config.position_snapshot_secs);
+    tokio::spawn(positions::run_snapshot_loop(
+        shards.clone(),
+        event_clock.clone(),
+        std::time::Duration::from_secs(config.position_snapshot_secs),
+    ));
+
+    // Score wallets and keep the smart-money set used to flag trades
+    let wallet_registry = Arc::new(WalletRegistry::default());
+    log::info!("🧠 Spawning wallet scoring loop (every {}s)", config.wallet_score_secs);
+    tokio::spawn(wallets::run_scoring_loop(
+        wallet_registry.clone(),
+        writer_tx.clone(),
+        std::time::Duration::from_secs(config.wallet_score_secs),
+    ));
+
+    // Bot scores persist across restarts; restore wallets active in the last day
+    let bot_classifier = Arc::new(BotClassifier::new(config.bot_score_threshold));
+    {
+        let conn = rusqlite::Connection::open(env::var("SOLFLOW_DB_PATH")?)?;
+        let profiles = db::load_bot_profiles(&conn, now - bot_classifier::PROFILE_IDLE_SECS)?;
+        log::info!("🤖 Restored {} bot classifier profiles", profiles.len());
+        bot_classifier.restore(profiles);
+    }
+    log::info!(
+        "🤖 Spawning bot profile persistence (every {}s, threshold: {})",
+        config.bot_profile_secs,
+        config.bot_score_threshold
+    );
+    tokio::spawn(bot_classifier::run_persist_loop(
+        bot_classifier.clone(),
+        writer_tx.clone(),
+        event_clock.clone(),
+        std::time::Duration::from_secs(config.bot_profile_secs),
+    ));
+
+    // Funding edges from System Program transfers cluster sybil wallets
+    let funding = Arc::new(FundingGraph::default());
+    log::info!("🕸️  Funding graph: {}", if config.funding_graph { "enabled" } else { "disabled" });
+    tokio::spawn(funding::run_prune_loop(
+        funding.clone(),
+        event_clock.clone(),
+        std::time::Duration::from_secs(config.sweep_interval_secs),
+    ));
+
+    // Launch snipers; launches with recent sniper activity survive restarts
+    let snipers = Arc::new(SniperTracker::new(config.snipe_slots));
+    {
+        let conn = rusqlite::Connection::open(env::var("SOLFLOW_DB_PATH")?)?;
+        let launches = db::load_launches(&conn, now - snipers::LAUNCH_IDLE_SECS)?;
+        log::info!("🎯 Restored {} launches with snipers", launches.len());
+        snipers.restore(launches);
+    }
+    log::info!(
+        "🎯 Spawning launch persistence (every {}s, snipe window: {} slots)",
+        config.sniper_persist_secs,
+        config.snipe_slots
+    );
+    tokio::spawn(snipers::run_persist_loop(
+        snipers.clone(),
+        writer_tx.clone(),
+        event_clock.clone(),
+        std::time::Duration::from_secs(config.sniper_persist_secs),
+    ));
+
+    // Pool reserves from swaps: price impact per trade and liquidity history
+    let liquidity = Arc::new(LiquidityTracker::default());
+    log::info!("💧 Spawning liquidity snapshot loop (every {}s)", config.liquidity_snapshot_secs);
+    tokio::spawn(liquidity::run_snapshot_loop(
+        liquidity.clone(),
+        writer_tx,
+        event_clock.clone(),
+        std::time::Duration::from_secs(config.liquidity_snapshot_secs),
+    ));
+
+    // Trades kept out of rolling state, reported every metrics interval
+    let quarantine = Arc::new(QuarantineStats::default());
+
+    let ctx = ProcessorContext {
+        seen_signatures,
+        shards,
+        mint_filter,
+        wallets: wallet_registry,
+        bot_classifier,
+        market,
+        funding: funding.clone(),
+        snipers,
+        creators,
+        liquidity,
+        event_clock,
+        quarantine: quarantine.clone(),
+        allowed_lateness: config.allowed_lateness_secs,
+    };
+
+    log::info!("🕰️  Allowed lateness: {}s per venue", config.allowed_lateness_secs);
+    tokio::spawn(watermark::run_report_loop(
+        quarantine,
+        std::time::Duration::from_secs(config.shard_metrics_secs),
+    ));
+
+    log::info!("🔧 Building Pipeline with {} DEX Decoders + Trade Extraction Layer", venues.len());
+
+    let mut builder = venues.attach_all(Pipeline::builder().datasource(client), &ctx);
+    if config.funding_graph {
+        builder = builder.instruction(
+            carbon_system_program_decoder::SystemProgramDecoder,
+            FundingProcessor::new(funding),
+        );
+    }
+
+    builder
         .build()?
         .run()
         .await?;
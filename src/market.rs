@@ -0,0 +1,411 @@
+//! Market-wide aggregates and regime detection
+//!
+//! Signal thresholds are absolute, so the same flow reads very differently in
+//! a euphoric market and a dead one. `MarketTracker` aggregates every admitted
+//! trade across all mints and venues over the last `MARKET_WINDOW_SECS`:
+//! net flow, gross volume, active mints, new mints and unique wallets.
+//!
+//! Each snapshot compares volume and unique wallets with a slow baseline
+//! (EWMA, `BASELINE_HALF_LIFE_SECS`); the geometric mean of both ratios is the
+//! market activity, which classifies a `MarketRegime`. Snapshots are persisted
+//! to `market_metrics`, and the latest regime is handed to the signal
+//! evaluators through a `SignalContext`.
+
+use crate::{
+    db::WriteRequest,
+    signals::SignalContext,
+    types::{TradeDirection, TradeEvent},
+    watermark::EventClock,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+/// Rolling window of the market aggregates (seconds)
+pub const MARKET_WINDOW_SECS: i64 = 3600;
+
+/// A mint counts as new on its first trade after this long without one
+/// (seconds); also how long first-seen times are remembered
+pub const NEW_MINT_MEMORY_SECS: i64 = 86_400;
+
+/// Half-life of the volume and wallet baselines (seconds)
+pub const BASELINE_HALF_LIFE_SECS: f64 = 86_400.0;
+
+/// Activity below which the market is dead
+const DEAD_ACTIVITY: f64 = 0.4;
+/// Activity below which the market is quiet
+const QUIET_ACTIVITY: f64 = 0.8;
+/// Activity at or above which the market is hot
+const HOT_ACTIVITY: f64 = 1.5;
+/// Activity at or above which the market is euphoric
+const EUPHORIC_ACTIVITY: f64 = 2.5;
+
+/// Market regime, from activity relative to the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MarketRegime {
+    Dead,
+    Quiet,
+    #[default]
+    Normal,
+    Hot,
+    Euphoric,
+}
+
+impl MarketRegime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarketRegime::Dead => "dead",
+            MarketRegime::Quiet => "quiet",
+            MarketRegime::Normal => "normal",
+            MarketRegime::Hot => "hot",
+            MarketRegime::Euphoric => "euphoric",
+        }
+    }
+
+    pub fn classify(activity: f64) -> Self {
+        if activity < DEAD_ACTIVITY {
+            MarketRegime::Dead
+        } else if activity < QUIET_ACTIVITY {
+            MarketRegime::Quiet
+        } else if activity < HOT_ACTIVITY {
+            MarketRegime::Normal
+        } else if activity < EUPHORIC_ACTIVITY {
+            MarketRegime::Hot
+        } else {
+            MarketRegime::Euphoric
+        }
+    }
+
+    /// Factor applied to signal strength: absolute thresholds are easy to hit
+    /// in a hot market and hard in a dead one
+    pub fn strength_multiplier(&self) -> f64 {
+        match self {
+            MarketRegime::Dead => 1.3,
+            MarketRegime::Quiet => 1.15,
+            MarketRegime::Normal => 1.0,
+            MarketRegime::Hot => 0.85,
+            MarketRegime::Euphoric => 0.7,
+        }
+    }
+}
+
+/// Market aggregates at one point in event time
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MarketSnapshot {
+    pub timestamp: i64,
+    /// Buy SOL minus sell SOL across all mints
+    pub net_flow_sol: f64,
+    pub volume_sol: f64,
+    pub trades: u32,
+    /// Mints traded in the window
+    pub active_mints: u32,
+    /// Mints first seen in the window
+    pub new_mints: u32,
+    pub unique_wallets: u32,
+    pub baseline_volume_sol: f64,
+    pub baseline_wallets: f64,
+    /// Geometric mean of volume / baseline and wallets / baseline
+    pub activity: f64,
+    pub regime: MarketRegime,
+    /// True until the tracker has seen a full window of trades; the regime
+    /// stays `Normal` and the baselines are not updated meanwhile
+    pub warming: bool,
+}
+
+/// Flow, volume and trade count of one second
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    ts: i64,
+    net_flow_sol: f64,
+    volume_sol: f64,
+    trades: u32,
+}
+
+#[derive(Debug, Default)]
+struct MarketState {
+    /// Per-second buckets, oldest first
+    buckets: VecDeque<Bucket>,
+    /// Last trade time per wallet
+    wallets: HashMap<String, i64>,
+    /// Last trade time per mint
+    mints: HashMap<String, i64>,
+    /// First trade time per mint (forgotten after `NEW_MINT_MEMORY_SECS` idle)
+    first_seen: HashMap<String, i64>,
+    /// Time of the first recorded trade
+    started_at: Option<i64>,
+    baseline_volume_sol: f64,
+    baseline_wallets: f64,
+    last_snapshot: Option<i64>,
+}
+
+impl MarketState {
+    fn record(&mut self, trade: &TradeEvent) {
+        let ts = trade.timestamp;
+        let flow = match trade.direction {
+            TradeDirection::Buy => trade.sol_amount,
+            TradeDirection::Sell => -trade.sol_amount,
+            TradeDirection::Unknown => 0.0,
+        };
+
+        self.started_at.get_or_insert(ts);
+
+        // Buckets stay ordered; late trades land in the newest bucket
+        match self.buckets.back_mut() {
+            Some(bucket) if bucket.ts >= ts => {
+                bucket.net_flow_sol += flow;
+                bucket.volume_sol += trade.sol_amount;
+                bucket.trades += 1;
+            }
+            _ => self.buckets.push_back(Bucket {
+                ts,
+                net_flow_sol: flow,
+                volume_sol: trade.sol_amount,
+                trades: 1,
+            }),
+        }
+
+        let last = self.wallets.entry(trade.user_account.clone()).or_insert(ts);
+        *last = (*last).max(ts);
+        let last = self.mints.entry(trade.mint.clone()).or_insert(ts);
+        *last = (*last).max(ts);
+        self.first_seen.entry(trade.mint.clone()).or_insert(ts);
+    }
+
+    fn prune(&mut self, now: i64) {
+        let cutoff = now - MARKET_WINDOW_SECS;
+        while self.buckets.front().is_some_and(|b| b.ts <= cutoff) {
+            self.buckets.pop_front();
+        }
+        self.wallets.retain(|_, ts| *ts > cutoff);
+
+        let memory_cutoff = now - NEW_MINT_MEMORY_SECS;
+        self.mints.retain(|_, ts| *ts > memory_cutoff);
+        let mints = &self.mints;
+        self.first_seen.retain(|mint, _| mints.contains_key(mint));
+    }
+
+    fn snapshot(&mut self, now: i64) -> MarketSnapshot {
+        self.prune(now);
+
+        let cutoff = now - MARKET_WINDOW_SECS;
+        let (net_flow_sol, volume_sol, trades) = self
+            .buckets
+            .iter()
+            .fold((0.0, 0.0, 0), |(flow, volume, trades), b| {
+                (flow + b.net_flow_sol, volume + b.volume_sol, trades + b.trades)
+            });
+        let active_mints = self.mints.values().filter(|&&ts| ts > cutoff).count() as u32;
+        let new_mints = self.first_seen.values().filter(|&&ts| ts > cutoff).count() as u32;
+        let unique_wallets = self.wallets.len() as u32;
+
+        let warming = self.started_at.is_none_or(|started| now - started < MARKET_WINDOW_SECS);
+
+        if !warming {
+            if self.baseline_volume_sol <= 0.0 || self.baseline_wallets <= 0.0 {
+                self.baseline_volume_sol = volume_sol;
+                self.baseline_wallets = unique_wallets as f64;
+            } else {
+                let elapsed = self.last_snapshot.map_or(0, |ts| (now - ts).max(0)) as f64;
+                let alpha = 1.0 - 0.5f64.powf(elapsed / BASELINE_HALF_LIFE_SECS);
+                self.baseline_volume_sol += alpha * (volume_sol - self.baseline_volume_sol);
+                self.baseline_wallets += alpha * (unique_wallets as f64 - self.baseline_wallets);
+            }
+        }
+        self.last_snapshot = Some(now);
+
+        let activity = if self.baseline_volume_sol > 0.0 && self.baseline_wallets > 0.0 {
+            ((volume_sol / self.baseline_volume_sol) * (unique_wallets as f64 / self.baseline_wallets)).sqrt()
+        } else {
+            1.0
+        };
+        let regime = if warming {
+            MarketRegime::Normal
+        } else {
+            MarketRegime::classify(activity)
+        };
+
+        MarketSnapshot {
+            timestamp: now,
+            net_flow_sol,
+            volume_sol,
+            trades,
+            active_mints,
+            new_mints,
+            unique_wallets,
+            baseline_volume_sol: self.baseline_volume_sol,
+            baseline_wallets: self.baseline_wallets,
+            activity,
+            regime,
+            warming,
+        }
+    }
+}
+
+/// Market-wide rolling aggregates, fed by every venue processor
+#[derive(Debug, Default)]
+pub struct MarketTracker {
+    state: Mutex<MarketState>,
+    /// Context from the latest snapshot, read on every signal evaluation
+    context: RwLock<SignalContext>,
+}
+
+impl MarketTracker {
+    /// Count an admitted trade
+    pub fn record(&self, trade: &TradeEvent) {
+        self.state.lock().unwrap().record(trade);
+    }
+
+    /// Seed the baselines (restored from the latest `market_metrics` row)
+    pub fn restore_baseline(&self, volume_sol: f64, wallets: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.baseline_volume_sol = volume_sol;
+        state.baseline_wallets = wallets;
+    }
+
+    /// Prune to the window ending at `now`, update the baselines and the
+    /// current signal context
+    pub fn snapshot(&self, now: i64) -> MarketSnapshot {
+        let snapshot = self.state.lock().unwrap().snapshot(now);
+        *self.context.write().unwrap() = SignalContext::from_snapshot(&snapshot);
+        snapshot
+    }
+
+    /// Market context for signal evaluation
+    pub fn context(&self) -> SignalContext {
+        *self.context.read().unwrap()
+    }
+}
+
+/// Background loop that snapshots the market every `interval` on event time
+/// and persists the snapshot
+pub async fn run_market_loop(
+    tracker: Arc<MarketTracker>,
+    writer: mpsc::Sender<WriteRequest>,
+    clock: Arc<EventClock>,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    let mut last_regime = None;
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+        let snapshot = tracker.snapshot(now);
+
+        if last_regime != Some(snapshot.regime) {
+            log::info!(
+                "🌡️  MARKET | Regime: {} | Activity: {:.2} | Volume1h: {:.1} SOL | Wallets1h: {} | Mints1h: {} | New1h: {} | Warming: {}",
+                snapshot.regime.as_str(),
+                snapshot.activity,
+                snapshot.volume_sol,
+                snapshot.unique_wallets,
+                snapshot.active_mints,
+                snapshot.new_mints,
+                snapshot.warming
+            );
+            last_regime = Some(snapshot.regime);
+        }
+
+        if let Err(e) = writer.send(WriteRequest::MarketMetrics(snapshot)).await {
+            log::warn!("⚠️  Failed to send market metrics to writer: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(mint: &str, wallet: &str, timestamp: i64, direction: TradeDirection, sol_amount: f64) -> TradeEvent {
+        TradeEvent {
+            user_account: wallet.to_string(),
+            ..TradeEvent::test(mint, timestamp, direction, sol_amount)
+        }
+    }
+
+    #[test]
+    fn test_snapshot_aggregates_window() {
+        let tracker = MarketTracker::default();
+        tracker.record(&trade("old", "w1", 1_000, TradeDirection::Buy, 5.0));
+        tracker.record(&trade("a", "w1", 4_000, TradeDirection::Buy, 3.0));
+        tracker.record(&trade("a", "w2", 4_100, TradeDirection::Sell, 1.0));
+        tracker.record(&trade("b", "w3", 4_200, TradeDirection::Buy, 2.0));
+
+        let snapshot = tracker.snapshot(4_700);
+        // The first trade is outside the 1h window
+        assert_eq!(snapshot.trades, 3);
+        assert_eq!(snapshot.net_flow_sol, 4.0);
+        assert_eq!(snapshot.volume_sol, 6.0);
+        assert_eq!(snapshot.active_mints, 2);
+        assert_eq!(snapshot.new_mints, 2);
+        assert_eq!(snapshot.unique_wallets, 3);
+
+        // The first baseline is the first full window
+        assert!(!snapshot.warming);
+        assert_eq!(snapshot.baseline_volume_sol, 6.0);
+        assert_eq!(snapshot.regime, MarketRegime::Normal);
+    }
+
+    #[test]
+    fn test_regime_follows_activity_against_baseline() {
+        let tracker = MarketTracker::default();
+        tracker.restore_baseline(10.0, 4.0);
+        tracker.record(&trade("a", "w0", 0, TradeDirection::Buy, 1.0));
+
+        // Still warming: regime stays normal however quiet the market is
+        let snapshot = tracker.snapshot(1_000);
+        assert!(snapshot.warming);
+        assert_eq!(snapshot.regime, MarketRegime::Normal);
+        assert_eq!(tracker.context().regime, MarketRegime::Normal);
+
+        // 40 SOL from 16 wallets against a 10 SOL / 4 wallet baseline
+        for i in 0..16 {
+            tracker.record(&trade("a", &format!("w{}", i), 3_700, TradeDirection::Buy, 2.5));
+        }
+        let snapshot = tracker.snapshot(3_700);
+        assert!(!snapshot.warming);
+        assert!(snapshot.activity > EUPHORIC_ACTIVITY);
+        assert_eq!(snapshot.regime, MarketRegime::Euphoric);
+        assert_eq!(tracker.context().regime, MarketRegime::Euphoric);
+
+        // An hour of silence later the window is empty: dead market
+        let snapshot = tracker.snapshot(3_700 + MARKET_WINDOW_SECS);
+        assert_eq!(snapshot.trades, 0);
+        assert_eq!(snapshot.regime, MarketRegime::Dead);
+    }
+
+    #[test]
+    fn test_new_mints_only_counted_once_per_memory() {
+        let tracker = MarketTracker::default();
+        tracker.record(&trade("a", "w1", 0, TradeDirection::Buy, 1.0));
+        tracker.record(&trade("a", "w1", 3_000, TradeDirection::Buy, 1.0));
+        assert_eq!(tracker.snapshot(3_000).new_mints, 1);
+
+        // Still remembered: trading again later is not a new mint
+        tracker.record(&trade("a", "w1", 7_000, TradeDirection::Buy, 1.0));
+        let snapshot = tracker.snapshot(7_000);
+        assert_eq!(snapshot.active_mints, 1);
+        assert_eq!(snapshot.new_mints, 0);
+    }
+
+    #[test]
+    fn test_strength_multiplier_order() {
+        let regimes = [
+            MarketRegime::Dead,
+            MarketRegime::Quiet,
+            MarketRegime::Normal,
+            MarketRegime::Hot,
+            MarketRegime::Euphoric,
+        ];
+        for pair in regimes.windows(2) {
+            assert!(pair[0].strength_multiplier() > pair[1].strength_multiplier());
+        }
+        assert_eq!(MarketRegime::classify(1.0), MarketRegime::Normal);
+    }
+}
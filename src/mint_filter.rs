@@ -0,0 +1,214 @@
+//! Ingestion-time mint filtering
+//!
+//! Enforces the `blocklist` table (and, optionally, an allowlist built from
+//! `followed_tokens`) inside the processor, so blocked mints never reach
+//! rolling state, trade persistence or signal evaluation.
+//!
+//! The sets live in memory and are refreshed periodically from SQLite, so
+//! blocking a mint from the dashboard takes effect without a restart.
+
+use crate::db;
+use rusqlite::Connection;
+use std::{
+    collections::HashSet,
+    env,
+    error::Error,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+
+/// Blocklist and allowlist, swapped together on refresh
+#[derive(Debug, Default)]
+struct MintSets {
+    /// Mints from the `blocklist` table (always rejected)
+    blocked: HashSet<String>,
+
+    /// Mints from the `followed_tokens` table (only consulted in allowlist mode)
+    allowed: HashSet<String>,
+}
+
+/// Shared blocklist/allowlist consulted on every extracted trade
+#[derive(Debug, Default)]
+pub struct MintFilter {
+    /// Both sets behind one lock, so a reader never sees a new blocklist
+    /// with an old allowlist
+    sets: RwLock<MintSets>,
+
+    /// When true, mints not present in `allowed` are rejected
+    allowlist_mode: bool,
+
+    /// Number of trades rejected by the filter since startup
+    rejected: AtomicU64,
+}
+
+impl MintFilter {
+    /// Create an empty filter
+    pub fn new(allowlist_mode: bool) -> Self {
+        Self {
+            allowlist_mode,
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if trades for `mint` should be processed
+    ///
+    /// Rejections are counted so they can be reported on refresh.
+    pub fn is_allowed(&self, mint: &str) -> bool {
+        let allowed = !self.is_blocked(mint);
+
+        if !allowed {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+
+        allowed
+    }
+
+    /// Returns true if `mint` is filtered out, without counting a rejection
+    ///
+    /// For work other than trades (launches, pool reserves, creator
+    /// transfers), so `rejected_count` stays a count of trades.
+    pub fn is_blocked(&self, mint: &str) -> bool {
+        let sets = self.sets.read().unwrap();
+        if sets.blocked.contains(mint) {
+            true
+        } else if self.allowlist_mode {
+            !sets.allowed.contains(mint)
+        } else {
+            false
+        }
+    }
+
+    /// Atomically replace both sets
+    pub fn replace(&self, blocked: HashSet<String>, allowed: HashSet<String>) {
+        *self.sets.write().unwrap() = MintSets { blocked, allowed };
+    }
+
+    /// Reload both sets from the database
+    ///
+    /// Returns `(blocked_count, allowed_count)`.
+    pub fn refresh(&self, conn: &Connection) -> Result<(usize, usize), Box<dyn Error>> {
+        let blocked = db::load_blocklist(conn)?;
+        let allowed = db::load_followed_tokens(conn)?;
+        let counts = (blocked.len(), allowed.len());
+
+        self.replace(blocked, allowed);
+
+        Ok(counts)
+    }
+
+    /// Number of trades rejected since startup
+    pub fn rejected_count(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+}
+
+/// Background loop that periodically reloads the filter from SQLite
+///
+/// The caller loads the filter once (`refresh`) before the pipeline starts
+/// producing trades; the loop's first refresh is one `interval` later.
+pub async fn run_refresh_loop(filter: Arc<MintFilter>, interval: Duration) {
+    let db_path = match env::var("SOLFLOW_DB_PATH") {
+        Ok(path) => path,
+        Err(_) => {
+            log::error!("❌ SOLFLOW_DB_PATH not set, mint filter refresh loop exiting");
+            return;
+        }
+    };
+
+    let conn = match Connection::open(&db_path) {
+        Ok(conn) => conn,
+        Err(e) => {
+            log::error!("❌ Failed to open database for mint filter: {}", e);
+            return;
+        }
+    };
+
+    let mut ticker = tokio::time::interval(interval);
+    // The first tick completes immediately; the filter was just loaded
+    ticker.tick().await;
+    let mut last_counts = None;
+
+    loop {
+        ticker.tick().await;
+
+        match filter.refresh(&conn) {
+            Ok(counts) => {
+                if last_counts != Some(counts) {
+                    log::info!(
+                        "🚫 Mint filter refreshed | Blocked: {} | Followed: {} | Allowlist mode: {} | Rejected so far: {}",
+                        counts.0,
+                        counts.1,
+                        filter.allowlist_mode,
+                        filter.rejected_count()
+                    );
+                    last_counts = Some(counts);
+                }
+            }
+            Err(e) => log::warn!("⚠️  Failed to refresh mint filter: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../sql/00_initial.sql")).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_blocklist_rejects_blocked_mints() {
+        let filter = MintFilter::new(false);
+        filter.replace(HashSet::from(["spam".to_string()]), HashSet::new());
+
+        assert!(!filter.is_allowed("spam"));
+        assert!(filter.is_allowed("legit"));
+        assert_eq!(filter.rejected_count(), 1);
+
+        // Non-trade checks are not counted as rejected trades
+        assert!(filter.is_blocked("spam"));
+        assert!(!filter.is_blocked("legit"));
+        assert_eq!(filter.rejected_count(), 1);
+    }
+
+    #[test]
+    fn test_allowlist_mode_only_accepts_followed_mints() {
+        let filter = MintFilter::new(true);
+        filter.replace(
+            HashSet::from(["followed_but_blocked".to_string()]),
+            HashSet::from(["followed".to_string(), "followed_but_blocked".to_string()]),
+        );
+
+        assert!(filter.is_allowed("followed"));
+        assert!(!filter.is_allowed("unfollowed"));
+        // Blocklist wins over the allowlist
+        assert!(!filter.is_allowed("followed_but_blocked"));
+        assert!(filter.is_blocked("unfollowed"));
+    }
+
+    #[test]
+    fn test_refresh_picks_up_database_changes() {
+        let conn = create_test_db();
+        let filter = MintFilter::new(false);
+
+        filter.refresh(&conn).unwrap();
+        assert!(filter.is_allowed("rug"));
+
+        conn.execute("INSERT INTO blocklist (mint, reason) VALUES ('rug', 'spam')", []).unwrap();
+        conn.execute("INSERT INTO followed_tokens (mint) VALUES ('gem')", []).unwrap();
+
+        let (blocked, followed) = filter.refresh(&conn).unwrap();
+        assert_eq!((blocked, followed), (1, 1));
+        assert!(!filter.is_allowed("rug"));
+
+        conn.execute("DELETE FROM blocklist WHERE mint = 'rug'", []).unwrap();
+        filter.refresh(&conn).unwrap();
+        assert!(filter.is_allowed("rug"));
+    }
+}
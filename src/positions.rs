@@ -0,0 +1,437 @@
+//! Per-wallet positions and PnL per mint
+//!
+//! Every trade updates the trading wallet's position in its mint's
+//! `PositionBook`, using average cost:
+//! - buys add tokens and their SOL cost
+//! - sells realize PnL against the average cost of the tokens sold; tokens
+//!   sold beyond the known position (bought before tracking started) have no
+//!   known basis and are ignored
+//!
+//! Unrealized PnL is marked against the latest trade price. Holder metrics are
+//! computed by the snapshot loop rather than per trade, and persisted together
+//! with the positions that changed since the last snapshot
+//! (`token_holder_metrics`, `wallet_positions`).
+
+use crate::{
+    candles::execution_price,
+    shards::{ShardCommand, ShardRouter},
+    state::TokenRollingState,
+    types::{TradeDirection, TradeEvent},
+    watermark::EventClock,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Positions kept in memory per mint; closed, then least recently traded
+/// positions are dropped beyond this
+pub const MAX_POSITIONS_PER_MINT: usize = 5_000;
+
+/// Largest positions considered for the top holders' cost basis
+pub const TOP_HOLDERS: usize = 10;
+
+/// Positions smaller than this (whole tokens) count as closed
+const DUST_TOKENS: f64 = 1e-6;
+
+/// Estimated bytes per position entry (wallet key + struct + table slot)
+const POSITION_ENTRY_BYTES: usize = 160;
+
+/// (wallet, position) pairs of one mint
+pub type WalletPositions = Vec<(String, Position)>;
+
+/// One wallet's position in one mint
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Position {
+    /// Whole tokens held, as far as the trade stream shows
+    pub tokens: f64,
+    /// SOL paid for the tokens still held
+    pub cost_basis_sol: f64,
+    /// PnL realized by sells so far
+    pub realized_pnl_sol: f64,
+    pub buy_count: u32,
+    pub sell_count: u32,
+    pub last_trade_ts: i64,
+}
+
+impl Position {
+    /// True while the wallet still holds tokens
+    pub fn is_open(&self) -> bool {
+        self.tokens > DUST_TOKENS
+    }
+
+    /// Average entry price (SOL per token) of the tokens held
+    pub fn avg_cost(&self) -> Option<f64> {
+        self.is_open().then(|| self.cost_basis_sol / self.tokens)
+    }
+
+    /// PnL of the tokens held if sold at `price`
+    pub fn unrealized_pnl(&self, price: f64) -> f64 {
+        self.tokens * price - self.cost_basis_sol
+    }
+
+    fn apply(&mut self, trade: &TradeEvent, tokens: f64) {
+        match trade.direction {
+            TradeDirection::Buy => {
+                self.tokens += tokens;
+                self.cost_basis_sol += trade.sol_amount;
+                self.buy_count += 1;
+            }
+            TradeDirection::Sell => {
+                let sold = tokens.min(self.tokens);
+                if sold > 0.0 {
+                    let cost = self.cost_basis_sol * sold / self.tokens;
+                    let proceeds = trade.sol_amount * sold / tokens;
+                    self.realized_pnl_sol += proceeds - cost;
+                    self.cost_basis_sol -= cost;
+                    self.tokens -= sold;
+                }
+                if !self.is_open() {
+                    self.tokens = 0.0;
+                    self.cost_basis_sol = 0.0;
+                }
+                self.sell_count += 1;
+            }
+            TradeDirection::Unknown => return,
+        }
+        self.last_trade_ts = self.last_trade_ts.max(trade.timestamp);
+    }
+}
+
+/// Holder metrics of one mint at the latest trade price
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HolderMetrics {
+    /// Latest trade price (SOL per token)
+    pub price_sol: f64,
+    /// Wallets with an open position
+    pub holders: u32,
+    pub holders_in_profit: u32,
+    /// holders_in_profit / holders (0.0 without holders)
+    pub holders_in_profit_pct: f64,
+    pub unrealized_pnl_sol: f64,
+    pub realized_pnl_sol: f64,
+    /// SOL paid by the `TOP_HOLDERS` largest positions for the tokens they hold
+    pub top_holders_cost_basis_sol: f64,
+    /// Average entry price of the `TOP_HOLDERS` largest positions
+    pub top_holders_avg_cost: f64,
+    /// Market value of positions in profit: supply likely to be sold into
+    /// new buyers (exit liquidity)
+    pub overhang_sol: f64,
+}
+
+/// Positions of every wallet that traded one mint
+#[derive(Debug, Clone)]
+pub struct PositionBook {
+    positions: HashMap<String, Position>,
+    /// Wallets whose position changed since the last `take_dirty`
+    dirty: HashSet<String>,
+    last_price: Option<f64>,
+    last_price_ts: i64,
+    max_positions: usize,
+
+    /// Positions dropped to stay under `max_positions`
+    pub evicted: u64,
+}
+
+impl Default for PositionBook {
+    fn default() -> Self {
+        Self::with_limit(MAX_POSITIONS_PER_MINT)
+    }
+}
+
+impl PositionBook {
+    /// Book holding at most `max_positions` positions
+    pub fn with_limit(max_positions: usize) -> Self {
+        Self {
+            positions: HashMap::new(),
+            dirty: HashSet::new(),
+            last_price: None,
+            last_price_ts: i64::MIN,
+            max_positions: max_positions.max(1),
+            evicted: 0,
+        }
+    }
+
+    /// Apply a trade to its wallet's position and the latest price
+    ///
+    /// Trades without a direction or token amount are ignored.
+    pub fn apply(&mut self, trade: &TradeEvent) {
+        let tokens = trade.whole_tokens();
+        if trade.direction == TradeDirection::Unknown || tokens <= 0.0 {
+            return;
+        }
+
+        if trade.timestamp >= self.last_price_ts {
+            if let Some(price) = execution_price(trade) {
+                self.last_price = Some(price);
+                self.last_price_ts = trade.timestamp;
+            }
+        }
+
+        self.positions
+            .entry(trade.user_account.clone())
+            .or_default()
+            .apply(trade, tokens);
+        self.dirty.insert(trade.user_account.clone());
+
+        if self.positions.len() > self.max_positions {
+            self.enforce_limit();
+        }
+    }
+
+    /// Position of `wallet`, if tracked
+    pub fn position(&self, wallet: &str) -> Option<&Position> {
+        self.positions.get(wallet)
+    }
+
+    /// Number of tracked positions (open and closed)
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Latest trade price (SOL per token)
+    pub fn last_price(&self) -> Option<f64> {
+        self.last_price
+    }
+
+    /// True if positions changed since the last `take_dirty`
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Changed positions since the last call, clearing the dirty set
+    pub fn take_dirty(&mut self) -> WalletPositions {
+        self.dirty
+            .drain()
+            .filter_map(|wallet| {
+                let position = self.positions.get(&wallet)?.clone();
+                Some((wallet, position))
+            })
+            .collect()
+    }
+
+    /// Replace all positions with persisted ones (not marked dirty)
+    pub fn restore(&mut self, positions: WalletPositions) {
+        self.positions = positions.into_iter().collect();
+        self.dirty.clear();
+        if self.positions.len() > self.max_positions {
+            self.enforce_limit();
+        }
+    }
+
+    /// Holder metrics at the latest trade price
+    ///
+    /// O(positions); called by the snapshot loop, not per trade.
+    pub fn holder_metrics(&self) -> HolderMetrics {
+        let price = self.last_price.unwrap_or(0.0);
+        let mut metrics = HolderMetrics {
+            price_sol: price,
+            ..Default::default()
+        };
+
+        let mut open: Vec<&Position> = Vec::new();
+        for position in self.positions.values() {
+            metrics.realized_pnl_sol += position.realized_pnl_sol;
+            if !position.is_open() {
+                continue;
+            }
+
+            let unrealized = position.unrealized_pnl(price);
+            metrics.holders += 1;
+            metrics.unrealized_pnl_sol += unrealized;
+            if unrealized > 0.0 {
+                metrics.holders_in_profit += 1;
+                metrics.overhang_sol += position.tokens * price;
+            }
+            open.push(position);
+        }
+
+        if metrics.holders > 0 {
+            metrics.holders_in_profit_pct = metrics.holders_in_profit as f64 / metrics.holders as f64;
+        }
+
+        let top = open.len().min(TOP_HOLDERS);
+        if top > 0 {
+            if open.len() > top {
+                open.select_nth_unstable_by(top - 1, |a, b| b.tokens.total_cmp(&a.tokens));
+            }
+            let (tokens, cost) = open[..top]
+                .iter()
+                .fold((0.0, 0.0), |(tokens, cost), p| (tokens + p.tokens, cost + p.cost_basis_sol));
+            metrics.top_holders_cost_basis_sol = cost;
+            metrics.top_holders_avg_cost = cost / tokens;
+        }
+
+        metrics
+    }
+
+    /// Approximate heap footprint in bytes
+    pub fn approx_bytes(&self) -> usize {
+        (self.positions.len() + self.dirty.len()) * POSITION_ENTRY_BYTES
+    }
+
+    /// Drop positions down to 90% of the limit: closed ones first, then the
+    /// least recently traded
+    fn enforce_limit(&mut self) {
+        let target = self.max_positions - self.max_positions / 10;
+        let mut candidates: Vec<(bool, i64, String)> = self
+            .positions
+            .iter()
+            .map(|(wallet, p)| (p.is_open(), p.last_trade_ts, wallet.clone()))
+            .collect();
+        candidates.sort_unstable();
+
+        for (_, _, wallet) in candidates.into_iter().take(self.positions.len() - target) {
+            self.positions.remove(&wallet);
+            self.dirty.remove(&wallet);
+            self.evicted += 1;
+        }
+    }
+}
+
+/// Holder metrics and changed positions of one mint, ready to persist
+#[derive(Debug, Clone)]
+pub struct PositionSnapshot {
+    pub mint: String,
+    /// Event time of the snapshot; trades up to here are included
+    pub snapshot_ts: i64,
+    pub metrics: HolderMetrics,
+    pub positions: WalletPositions,
+}
+
+/// Snapshot every mint whose positions changed
+pub fn snapshot<'a>(
+    states: impl IntoIterator<Item = &'a mut TokenRollingState>,
+    now: i64,
+) -> Vec<PositionSnapshot> {
+    let mut snapshots = Vec::new();
+
+    for state in states {
+        if !state.positions.is_dirty() {
+            continue;
+        }
+
+        let metrics = state.positions.holder_metrics();
+        snapshots.push(PositionSnapshot {
+            mint: state.mint.clone(),
+            snapshot_ts: now,
+            metrics,
+            positions: state.positions.take_dirty(),
+        });
+    }
+
+    snapshots
+}
+
+/// Background loop that has every shard persist positions and holder
+/// metrics every `interval`
+///
+/// Snapshots are skipped until the event clock has a time.
+pub async fn run_snapshot_loop(shards: Arc<ShardRouter>, clock: Arc<EventClock>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+        shards.broadcast(|| ShardCommand::SnapshotPositions { now }).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(timestamp: i64, wallet: &str, direction: TradeDirection, sol_amount: f64, tokens: f64) -> TradeEvent {
+        TradeEvent {
+            token_amount: tokens * 1_000_000.0,
+            user_account: wallet.to_string(),
+            ..TradeEvent::test("mint", timestamp, direction, sol_amount)
+        }
+    }
+
+    #[test]
+    fn test_average_cost_and_realized_pnl() {
+        let mut book = PositionBook::default();
+        book.apply(&trade(1, "w1", TradeDirection::Buy, 1.0, 1_000.0)); // 0.001
+        book.apply(&trade(2, "w1", TradeDirection::Buy, 3.0, 1_000.0)); // 0.003
+
+        let position = book.position("w1").unwrap();
+        assert_eq!(position.tokens, 2_000.0);
+        assert_eq!(position.avg_cost(), Some(0.002));
+
+        // Sell half at 0.004: proceeds 4.0, cost 2.0
+        book.apply(&trade(3, "w1", TradeDirection::Sell, 4.0, 1_000.0));
+        let position = book.position("w1").unwrap();
+        assert_eq!(position.tokens, 1_000.0);
+        assert_eq!(position.cost_basis_sol, 2.0);
+        assert_eq!(position.realized_pnl_sol, 2.0);
+
+        // Selling more than known: only the held 1_000 tokens realize PnL
+        book.apply(&trade(4, "w1", TradeDirection::Sell, 6.0, 2_000.0));
+        let position = book.position("w1").unwrap();
+        assert!(!position.is_open());
+        assert_eq!(position.realized_pnl_sol, 3.0);
+        assert_eq!((position.buy_count, position.sell_count), (2, 2));
+    }
+
+    #[test]
+    fn test_holder_metrics() {
+        let mut book = PositionBook::default();
+        book.apply(&trade(1, "early", TradeDirection::Buy, 1.0, 1_000.0)); // 0.001
+        book.apply(&trade(2, "late", TradeDirection::Buy, 4.0, 1_000.0)); // 0.004
+        book.apply(&trade(3, "flipper", TradeDirection::Buy, 1.0, 500.0)); // 0.002
+        book.apply(&trade(4, "flipper", TradeDirection::Sell, 1.5, 500.0)); // 0.003
+
+        let metrics = book.holder_metrics();
+        assert_eq!(metrics.price_sol, 0.003);
+        assert_eq!(metrics.holders, 2);
+        assert_eq!(metrics.holders_in_profit, 1);
+        assert_eq!(metrics.holders_in_profit_pct, 0.5);
+        assert!((metrics.unrealized_pnl_sol - (2.0 - 1.0)).abs() < 1e-9);
+        assert!((metrics.realized_pnl_sol - 0.5).abs() < 1e-9);
+        // Only "early" is in profit: 1_000 tokens at 0.003
+        assert!((metrics.overhang_sol - 3.0).abs() < 1e-9);
+        assert_eq!(metrics.top_holders_cost_basis_sol, 5.0);
+        assert_eq!(metrics.top_holders_avg_cost, 0.0025);
+    }
+
+    #[test]
+    fn test_limit_drops_closed_then_stale_positions() {
+        let mut book = PositionBook::with_limit(10);
+        book.apply(&trade(1, "closed", TradeDirection::Buy, 1.0, 100.0));
+        book.apply(&trade(2, "closed", TradeDirection::Sell, 1.0, 100.0));
+        for i in 0..10 {
+            book.apply(&trade(10 + i, &format!("w{}", i), TradeDirection::Buy, 1.0, 100.0));
+        }
+
+        // 11 > 10: dropped down to 9, closed first, then the oldest open
+        assert_eq!(book.len(), 9);
+        assert_eq!(book.evicted, 2);
+        assert!(book.position("closed").is_none());
+        assert!(book.position("w0").is_none());
+        assert!(book.position("w9").is_some());
+    }
+
+    #[test]
+    fn test_snapshot_takes_dirty_positions() {
+        let mut state = TokenRollingState::new("mint".to_string());
+        state.add_trade(trade(1_000, "w1", TradeDirection::Buy, 1.0, 1_000.0));
+
+        let snapshots = snapshot([&mut state], 1_010);
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].snapshot_ts, 1_010);
+        assert_eq!(snapshots[0].positions.len(), 1);
+        assert_eq!(snapshots[0].metrics.holders, 1);
+
+        // Nothing changed since
+        assert!(snapshot([&mut state], 1_020).is_empty());
+    }
+}
@@ -1,5 +1,14 @@
 use {
-    crate::{state::TokenRollingState, types::TradeEvent, db::WriteRequest, signals},
+    crate::{
+        bot_classifier::{self, BotClassifier}, creators::{CreatorTracker, DevAction},
+        funding::FundingGraph,
+        liquidity::LiquidityTracker, market::MarketTracker, mint_filter::MintFilter, signals,
+        shards::{RoutedTrade, ShardRouter}, snipers::SniperTracker,
+        trade_source::{SkipReason, TradeSource},
+        types::PoolReserves,
+        wallets::WalletRegistry,
+        watermark::{EventClock, QuarantineStats, Watermark},
+    },
     async_trait::async_trait,
     carbon_core::{
         error::CarbonResult,
@@ -8,41 +17,86 @@ use {
         processor::Processor,
     },
     dashmap::DashMap,
-    std::{marker::PhantomData, sync::Arc},
-    tokio::sync::mpsc,
+    std::{collections::HashMap, sync::Arc},
 };
 
-pub struct NetSolFlowProcessor<T> {
+/// Shared handles every venue processor needs
+#[derive(Clone)]
+pub struct ProcessorContext {
     pub seen_signatures: Arc<DashMap<String, bool>>,
-    pub rolling_states: Arc<DashMap<String, TokenRollingState>>,
-    pub extractor: fn(&InstructionProcessorInputType<T>) -> Option<TradeEvent>,
-    pub writer: mpsc::Sender<WriteRequest>,
-    _phantom: PhantomData<T>,
+    /// Mint-sharded rolling state; every admitted trade is routed here
+    pub shards: Arc<ShardRouter>,
+    pub mint_filter: Arc<MintFilter>,
+    /// Smart-money wallets whose trades are flagged `is_smart_money`
+    pub wallets: Arc<WalletRegistry>,
+    /// Per-wallet bot scores that set `is_bot`
+    pub bot_classifier: Arc<BotClassifier>,
+    /// Market-wide aggregates; its regime normalizes signal strength
+    pub market: Arc<MarketTracker>,
+    /// Funding edges that group trading wallets into clusters
+    pub funding: Arc<FundingGraph>,
+    /// Launches and their snipers, for SNIPER_DUMP
+    pub snipers: Arc<SniperTracker>,
+    /// Creators of launched tokens and their reputation, for DEV_SELL
+    pub creators: Arc<CreatorTracker>,
+    /// Latest pool reserves per mint; sets `price_impact` and liquidity
+    pub liquidity: Arc<LiquidityTracker>,
+    /// Newest admitted event time across venues; windows are evicted at it
+    pub event_clock: Arc<EventClock>,
+    /// Trades kept out of rolling state (late, missing or future timestamp)
+    pub quarantine: Arc<QuarantineStats>,
+    /// Seconds a trade may lag its venue's newest trade and still be admitted
+    pub allowed_lateness: i64,
 }
 
-impl<T> NetSolFlowProcessor<T> {
-    pub fn new(
-        seen_signatures: Arc<DashMap<String, bool>>,
-        rolling_states: Arc<DashMap<String, TokenRollingState>>,
-        extractor: fn(&InstructionProcessorInputType<T>) -> Option<TradeEvent>,
-        writer: mpsc::Sender<WriteRequest>,
-    ) -> Self {
+pub struct NetSolFlowProcessor<S: TradeSource> {
+    pub seen_signatures: Arc<DashMap<String, bool>>,
+    pub shards: Arc<ShardRouter>,
+    pub source: S,
+    pub mint_filter: Arc<MintFilter>,
+    pub wallets: Arc<WalletRegistry>,
+    pub bot_classifier: Arc<BotClassifier>,
+    pub market: Arc<MarketTracker>,
+    pub funding: Arc<FundingGraph>,
+    pub snipers: Arc<SniperTracker>,
+    pub creators: Arc<CreatorTracker>,
+    pub liquidity: Arc<LiquidityTracker>,
+    pub event_clock: Arc<EventClock>,
+    pub quarantine: Arc<QuarantineStats>,
+    /// Event-time watermark of this venue's stream
+    pub watermark: Watermark,
+    /// Instructions skipped by the source, by reason
+    pub skip_counts: HashMap<SkipReason, u64>,
+}
+
+impl<S: TradeSource> NetSolFlowProcessor<S> {
+    pub fn new(ctx: ProcessorContext, source: S) -> Self {
         Self {
-            seen_signatures,
-            rolling_states,
-            extractor,
-            writer,
-            _phantom: PhantomData,
+            seen_signatures: ctx.seen_signatures,
+            shards: ctx.shards,
+            source,
+            mint_filter: ctx.mint_filter,
+            wallets: ctx.wallets,
+            bot_classifier: ctx.bot_classifier,
+            market: ctx.market,
+            funding: ctx.funding,
+            snipers: ctx.snipers,
+            creators: ctx.creators,
+            liquidity: ctx.liquidity,
+            event_clock: ctx.event_clock,
+            quarantine: ctx.quarantine,
+            watermark: Watermark::new(ctx.allowed_lateness),
+            skip_counts: HashMap::new(),
         }
     }
 }
 
 #[async_trait]
-impl<T> Processor for NetSolFlowProcessor<T>
+impl<S> Processor for NetSolFlowProcessor<S>
 where
-    T: Send + Sync + 'static,
+    S: TradeSource,
 {
-    type InputType = InstructionProcessorInputType<T>;
+    type InputType = InstructionProcessorInputType<S::Instruction>;
 
     async fn process(
         &mut self,
@@ -77,69 +131,181 @@ where
             );
         }
 
-        if let Some(trade_event) = (self.extractor)(&data) {
-            let mint = trade_event.mint.clone();
-            let current_timestamp = trade_event.timestamp;
+        let extraction = self.source.extract(&data);
 
-            let mut rolling_state = self
-                .rolling_states
-                .entry(mint.clone())
-                .or_insert_with(|| TokenRollingState::new(mint.clone()));
+        if let Some(reason) = extraction.skipped {
+            let count = self.skip_counts.entry(reason).or_insert(0);
+            *count += 1;
+            log::debug!(
+                "⏭️  SKIPPED | Venue: {} | Reason: {} | Sig: {} | Total: {}",
+                self.source.name(),
+                reason.as_str(),
+                sig_str,
+                count
+            );
+        }
 
-            rolling_state.add_trade(trade_event.clone());
-            rolling_state.evict_old_trades(current_timestamp);
+        // Transaction context for the bot classifier and sniper detection
+        let tip = if extraction.trades.is_empty() && extraction.launches.is_empty() {
+            0
+        } else {
+            bot_classifier::jito_tip_lamports(
+                tx_meta.message.static_account_keys(),
+                &meta.pre_balances,
+                &meta.post_balances,
+            )
+        };
 
-            // Phase 4: Compute rolling metrics
-            let metrics = rolling_state.compute_rolling_metrics();
-            
-            // Phase 4: Self-verification (optional, logs warnings on failures)
-            rolling_state.verify_metrics(&metrics);
+        for mut launch in extraction.launches {
+            launch.slot = tx_meta.slot;
+            launch.tip_lamports = tip;
 
-            log::info!(
-                "📊 TRADE | Mint: {} | Dir: {:?} | SOL: {:.4} | Bot: {} | DCA: {} | NetFlow300s: {:.4} | Wallets300s: {} | DCA300s: {}",
-                mint,
-                trade_event.direction,
-                trade_event.sol_amount,
-                trade_event.is_bot,
-                trade_event.is_dca,
-                metrics.net_flow_300s_sol,
-                metrics.unique_wallets_300s,
-                metrics.dca_buys_300s
-            );
-            
-            // Phase 5: Send metrics to database writer (non-blocking)
-            if let Err(e) = self.writer.send(WriteRequest::Metrics {
-                mint: mint.clone(),
-                metrics: metrics.clone(),
-            }).await {
-                log::warn!("⚠️  Failed to send metrics to writer: {}", e);
+            if self.mint_filter.is_blocked(&launch.mint) {
+                continue;
             }
-            
-            // Phase 5: Send trade event to database writer (non-blocking)
-            if let Err(e) = self.writer.send(WriteRequest::Trade(trade_event.clone())).await {
-                log::warn!("⚠️  Failed to send trade to writer: {}", e);
-            }
-            
-            // Phase 6: Evaluate signals
-            // Get recent trades from in-memory rolling state (not DB) for performance
-            let recent_trades: Vec<TradeEvent> = rolling_state.trades_300s.clone();
-            let triggered_signals = signals::evaluate_signals(&mint, &metrics, &recent_trades);
-            
-            // Log and send signals to database
-            for signal in triggered_signals {
+            self.creators.record_launch(&launch);
+            if self.snipers.record_launch(launch.clone()) {
                 log::info!(
-                    "🔔 SIGNAL | Mint: {} | Type: {:?} | Strength: {:.2} | Window: {} | Metadata: {}",
-                    signal.mint,
-                    signal.signal_type,
-                    signal.strength,
-                    signal.window,
-                    signal.metadata
+                    "🎯 LAUNCH | Mint: {} | Creator: {} | Venue: {} | Slot: {} | Tip: {} | Sig: {}",
+                    launch.mint,
+                    launch.creator,
+                    launch.source_program,
+                    launch.slot,
+                    launch.tip_lamports,
+                    sig_str
+                );
+            }
+        }
+
+        // Pool reserves reported with the trades, by mint
+        let mut reserves: HashMap<String, PoolReserves> = extraction
+            .reserves
+            .into_iter()
+            .map(|mut pool| {
+                pool.slot = tx_meta.slot;
+                (pool.mint.clone(), pool)
+            })
+            .collect();
+
+        // Creator tokens sold in this transaction, by mint
+        let mut dev_sold: HashMap<String, f64> = HashMap::new();
+
+        for mut trade_event in extraction.trades {
+            trade_event.slot = tx_meta.slot;
+            trade_event.fee_lamports = fee;
+            trade_event.tip_lamports = tip;
+
+            // Blocklist / allowlist: skip all state, trade and signal work
+            if !self.mint_filter.is_allowed(&trade_event.mint) {
+                log::debug!("🚫 FILTERED | Mint: {} | Sig: {}", trade_event.mint, sig_str);
+                continue;
+            }
+
+            // Late, missing or future timestamps never reach rolling state
+            // (nor the event clock)
+            if let Err(reason) = self.watermark.admit(trade_event.timestamp, chrono::Utc::now().timestamp()) {
+                let count = self.quarantine.record(reason);
+                log::debug!(
+                    "🕰️  QUARANTINED | Venue: {} | Reason: {} | Mint: {} | Ts: {} | Watermark: {:?} | Sig: {} | Total: {}",
+                    self.source.name(),
+                    reason.as_str(),
+                    trade_event.mint,
+                    trade_event.timestamp,
+                    self.watermark.current(),
+                    sig_str,
+                    count
                 );
-                
-                if let Err(e) = self.writer.send(WriteRequest::Signal(signal)).await {
-                    log::warn!("⚠️  Failed to send signal to writer: {}", e);
+                continue;
+            }
+            self.event_clock.advance(trade_event.timestamp);
+            trade_event.is_smart_money = self.wallets.is_smart_money(&trade_event.user_account);
+            trade_event.funding_cluster = self
+                .funding
+                .cluster_for_trade(&trade_event.user_account, trade_event.timestamp);
+            trade_event.price_impact = self.liquidity.observe(&trade_event, reserves.remove(&trade_event.mint));
+            let bot_score = self.bot_classifier.classify(&mut trade_event);
+            self.market.record(&trade_event);
+            let context = self.market.context();
+            // Evict and stamp signals at event time, not at this trade's
+            // (possibly late) timestamp
+            let now = self.event_clock.now().unwrap_or(trade_event.timestamp);
+
+            let mut raised = Vec::new();
+            if let Some(launch) = self.snipers.observe(&trade_event) {
+                if let Some(signal) = signals::evaluate_sniper_dump(&launch, &trade_event, now, &context) {
+                    raised.push(signal);
                 }
             }
+            if let Some(token) = self.creators.observe(&trade_event) {
+                let tokens = trade_event.whole_tokens();
+                *dev_sold.entry(trade_event.mint.clone()).or_insert(0.0) += tokens;
+                raised.push(signals::evaluate_dev_sell(
+                    &token,
+                    DevAction::Sell,
+                    tokens,
+                    trade_event.sol_amount,
+                    now,
+                    self.creators.reputation(&token.creator).as_ref(),
+                ));
+            }
+
+            // Rolling state, the trade row, metrics and signals are handled
+            // by the mint's shard, in routing order
+            self.shards
+                .route(RoutedTrade {
+                    liquidity_sol: self.liquidity.liquidity_sol(&trade_event.mint),
+                    now,
+                    trade: trade_event,
+                    bot_score,
+                    context,
+                    signals: raised,
+                })
+                .await;
+        }
+
+        // Reserves of trades that were filtered, quarantined or not extracted
+        for pool in reserves.into_values() {
+            if !self.mint_filter.is_blocked(&pool.mint) {
+                self.liquidity.record(pool);
+            }
+        }
+
+        // Creator supply that left without a sell: token balance drops by
+        // owner and mint, net of the creator's sells above
+        if !self.creators.is_empty() {
+            let mut outflows: HashMap<(&str, &str), f64> = HashMap::new();
+            for balance in meta.pre_token_balances.iter().flatten() {
+                *outflows.entry((balance.owner.as_str(), balance.mint.as_str())).or_insert(0.0) +=
+                    balance.ui_token_amount.ui_amount.unwrap_or(0.0);
+            }
+            for balance in meta.post_token_balances.iter().flatten() {
+                *outflows.entry((balance.owner.as_str(), balance.mint.as_str())).or_insert(0.0) -=
+                    balance.ui_token_amount.ui_amount.unwrap_or(0.0);
+            }
+
+            for ((owner, mint), outflow) in outflows {
+                let moved = outflow - dev_sold.get(mint).copied().unwrap_or(0.0);
+                if moved <= 0.0 || self.mint_filter.is_blocked(mint) {
+                    continue;
+                }
+                let Some(token) = self.creators.observe_transfer(owner, mint, moved) else {
+                    continue;
+                };
+                let now = self
+                    .event_clock
+                    .now()
+                    .unwrap_or_else(|| tx_meta.block_time.unwrap_or(token.last_trade));
+                let signal = signals::evaluate_dev_sell(
+                    &token,
+                    DevAction::Transfer,
+                    moved,
+                    0.0,
+                    now,
+                    self.creators.reputation(&token.creator).as_ref(),
+                );
+                log::info!("👤 DEV MOVE | Mint: {} | Creator: {} | Tokens: {:.0} | Sig: {}", mint, owner, moved, sig_str);
+                self.shards.route_signal(signal, now).await;
+            }
         }
 
         Ok(())
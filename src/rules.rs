@@ -0,0 +1,930 @@
+//! Declarative signal rules
+//!
+//! Signals that are pure functions of a mint's rolling metrics are defined as
+//! rules rather than code, so thresholds and weights can be tuned without a
+//! rebuild. A rule has:
+//! - `signal`: the signal type it emits (`BREAKOUT`, ...) and its `window`
+//!   label
+//! - `requires_windows`: windows that must not be warming
+//! - `conditions`: comparisons that must all hold, e.g.
+//!   `w300.net_flow_sol > w900.net_flow_sol`
+//! - `strength`: weighted terms; each term's `value / scale` is clamped to
+//!   0..=1 and the weighted sum is clamped to 0..=1
+//! - `metadata`: named expressions recorded with the signal
+//!
+//! Expressions support numbers, `+ - * /`, parentheses and `min`, `max`,
+//! `abs`, `clamp(x, lo, hi)` over fields:
+//! - `w<secs>.<metric>`: any `WindowMetrics` field of the window of `secs`
+//!   seconds, plus `organic_wallets`, `organic_clusters`,
+//!   `organic_volume_sol`, `trade_count` (buys + sells) and `bot_ratio`
+//!   (bot trades / trade count). Untracked windows read as zero.
+//! - `focus.<metric>`: buyer concentration over the 300s window's trades,
+//!   by funding cluster: `f_score` (share of buying clusters that bring 70%
+//!   of the inflow, 1.0 without buys), `clusters_needed`, `total_clusters`,
+//!   `total_inflow`
+//!
+//! The built-in rules (`config/signal_rules.json`) reproduce BREAKOUT,
+//! REACCUMULATION, FOCUSED_BUYERS, PERSISTENCE and FLOW_REVERSAL. With
+//! `SOLFLOW_RULES_PATH` set the file there replaces them, and is reloaded
+//! whenever it changes; a file that fails to parse is logged and the rules in
+//! effect are kept.
+
+use crate::{
+    signals::{Signal, SignalType},
+    state::{RollingMetrics, WindowMetrics},
+    types::{TradeDirection, TradeEvent},
+};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::{
+    cell::OnceCell,
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, SystemTime},
+};
+
+/// Built-in rules, used unless a rules file is configured
+pub const DEFAULT_RULES: &str = include_str!("../config/signal_rules.json");
+
+/// Share of inflow the focus F-score measures the buying clusters of
+const FOCUS_INFLOW_SHARE: f64 = 0.7;
+
+/// Name, getter and whether the value is a count (recorded as an integer in
+/// metadata) of a metric readable by rules
+type Metric<T> = (&'static str, fn(&T) -> f64, bool);
+
+/// Window metrics readable by rules (`w<secs>.<name>`)
+const WINDOW_FIELDS: [Metric<WindowMetrics>; 40] = [
+    ("net_flow_sol", |w| w.net_flow_sol, false),
+    ("buy_count", |w| w.buy_count as f64, true),
+    ("sell_count", |w| w.sell_count as f64, true),
+    ("trade_count", |w| (w.buy_count + w.sell_count) as f64, true),
+    ("unique_wallets", |w| w.unique_wallets as f64, true),
+    ("unique_clusters", |w| w.unique_clusters as f64, true),
+    ("organic_wallets", |w| w.organic_wallets() as f64, true),
+    ("organic_clusters", |w| w.organic_clusters() as f64, true),
+    ("buy_volume_sol", |w| w.buy_volume_sol, false),
+    ("sell_volume_sol", |w| w.sell_volume_sol, false),
+    ("volume_sol", |w| w.volume_sol, false),
+    ("organic_volume_sol", |w| w.organic_volume_sol(), false),
+    ("bot_wallets", |w| w.bot_wallets as f64, true),
+    ("bot_trades", |w| w.bot_trades as f64, true),
+    ("bot_flow_sol", |w| w.bot_flow_sol, false),
+    ("bot_ratio", bot_ratio, false),
+    ("dca_buys", |w| w.dca_buys as f64, true),
+    ("dca_flow_sol", |w| w.dca_flow_sol, false),
+    ("dca_unique_wallets", |w| w.dca_unique_wallets as f64, true),
+    ("dca_ratio", |w| w.dca_ratio, false),
+    ("smart_money_buys", |w| w.smart_money_buys as f64, true),
+    ("smart_money_flow_sol", |w| w.smart_money_flow_sol, false),
+    ("smart_money_wallets", |w| w.smart_money_wallets as f64, true),
+    ("buy_size_p50", |w| w.buy_size_p50, false),
+    ("buy_size_p90", |w| w.buy_size_p90, false),
+    ("buy_size_p99", |w| w.buy_size_p99, false),
+    ("sell_size_p50", |w| w.sell_size_p50, false),
+    ("sell_size_p90", |w| w.sell_size_p90, false),
+    ("sell_size_p99", |w| w.sell_size_p99, false),
+    ("retail_buys", |w| w.retail_buys as f64, true),
+    ("mid_buys", |w| w.mid_buys as f64, true),
+    ("whale_buys", |w| w.whale_buys as f64, true),
+    ("retail_sells", |w| w.retail_sells as f64, true),
+    ("mid_sells", |w| w.mid_sells as f64, true),
+    ("whale_sells", |w| w.whale_sells as f64, true),
+    ("wash_score", |w| w.wash_score, false),
+    ("wash_volume_sol", |w| w.wash_volume_sol, false),
+    ("wash_wallets", |w| w.wash_wallets as f64, true),
+    ("wash_trades", |w| w.wash_trades as f64, true),
+    ("warming", |w| w.warming as i32 as f64, true),
+];
+
+/// Focus metrics readable by rules (`focus.<name>`)
+const FOCUS_FIELDS: [Metric<Focus>; 4] = [
+    ("f_score", |f| f.f_score, false),
+    ("clusters_needed", |f| f.clusters_needed as f64, true),
+    ("total_clusters", |f| f.total_clusters as f64, true),
+    ("total_inflow", |f| f.total_inflow, false),
+];
+
+/// Bot trades / trade count (0.0 without trades)
+fn bot_ratio(w: &WindowMetrics) -> f64 {
+    ratio(w.bot_trades, w.buy_count + w.sell_count)
+}
+
+fn ratio(part: i32, total: i32) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64
+    } else {
+        0.0
+    }
+}
+
+/// Rules file as written by operators
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesFile {
+    pub rules: Vec<RuleSpec>,
+}
+
+/// One rule as written in the rules file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSpec {
+    /// Signal type name, e.g. `BREAKOUT`
+    pub signal: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    /// Window label of emitted signals, e.g. `300s`
+    pub window: String,
+    #[serde(default)]
+    pub requires_windows: Vec<i64>,
+    pub conditions: Vec<String>,
+    pub strength: Vec<TermSpec>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// One weighted strength term
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TermSpec {
+    pub weight: f64,
+    pub value: String,
+    /// `value` is divided by this before clamping to 0..=1
+    #[serde(default = "unit_scale")]
+    pub scale: f64,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn unit_scale() -> f64 {
+    1.0
+}
+
+/// Buyer concentration over a set of trades, by funding cluster
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Focus {
+    pub f_score: f64,
+    pub clusters_needed: usize,
+    pub total_clusters: usize,
+    pub total_inflow: f64,
+}
+
+impl Focus {
+    /// Share of buying clusters needed to reach `FOCUS_INFLOW_SHARE` of the
+    /// buy inflow; sybil wallets funded from one source count as one buyer
+    pub fn of(trades: &[TradeEvent]) -> Self {
+        let mut cluster_flows: HashMap<&str, f64> = HashMap::new();
+        let mut total_inflow = 0.0;
+        for trade in trades.iter().filter(|t| t.direction == TradeDirection::Buy && t.sol_amount > 0.0) {
+            *cluster_flows.entry(trade.cluster()).or_insert(0.0) += trade.sol_amount;
+            total_inflow += trade.sol_amount;
+        }
+
+        let mut flows: Vec<f64> = cluster_flows.into_values().collect();
+        flows.sort_by(|a, b| b.total_cmp(a));
+
+        let target = total_inflow * FOCUS_INFLOW_SHARE;
+        let mut cumulative = 0.0;
+        let mut clusters_needed = 0;
+        for flow in &flows {
+            cumulative += flow;
+            clusters_needed += 1;
+            if cumulative >= target {
+                break;
+            }
+        }
+
+        Self {
+            f_score: if flows.is_empty() { 1.0 } else { clusters_needed as f64 / flows.len() as f64 },
+            clusters_needed,
+            total_clusters: flows.len(),
+            total_inflow,
+        }
+    }
+}
+
+/// Values rules are evaluated against; focus is computed on first use
+struct Inputs<'a> {
+    metrics: &'a RollingMetrics,
+    trades: &'a [TradeEvent],
+    focus: OnceCell<Focus>,
+}
+
+impl<'a> Inputs<'a> {
+    fn new(metrics: &'a RollingMetrics, trades: &'a [TradeEvent]) -> Self {
+        Self {
+            metrics,
+            trades,
+            focus: OnceCell::new(),
+        }
+    }
+
+    fn focus(&self) -> &Focus {
+        self.focus.get_or_init(|| Focus::of(self.trades))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    /// Index into `WINDOW_FIELDS` of the window of `secs` seconds
+    Window { secs: i64, index: usize },
+    /// Index into `FOCUS_FIELDS`
+    Focus(usize),
+}
+
+impl Field {
+    fn parse(name: &str) -> Result<Self, String> {
+        let unknown = || format!("unknown field {:?}", name);
+        let (scope, metric) = name.split_once('.').ok_or_else(unknown)?;
+
+        if scope == "focus" {
+            let index = FOCUS_FIELDS.iter().position(|f| f.0 == metric).ok_or_else(unknown)?;
+            return Ok(Field::Focus(index));
+        }
+        let secs = scope
+            .strip_prefix('w')
+            .and_then(|secs| secs.parse::<i64>().ok())
+            .filter(|secs| *secs > 0)
+            .ok_or_else(unknown)?;
+        let index = WINDOW_FIELDS.iter().position(|f| f.0 == metric).ok_or_else(unknown)?;
+        Ok(Field::Window { secs, index })
+    }
+
+    fn value(&self, inputs: &Inputs) -> f64 {
+        match *self {
+            Field::Window { secs, index } => (WINDOW_FIELDS[index].1)(&inputs.metrics.window(secs)),
+            Field::Focus(index) => (FOCUS_FIELDS[index].1)(inputs.focus()),
+        }
+    }
+
+    fn is_count(&self) -> bool {
+        match *self {
+            Field::Window { index, .. } => WINDOW_FIELDS[index].2,
+            Field::Focus(index) => FOCUS_FIELDS[index].2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Min,
+    Max,
+    Abs,
+    Clamp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+/// Arithmetic expression over fields
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Num(f64),
+    Field(Field),
+    Neg(Box<Expr>),
+    Bin(BinOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+impl Expr {
+    fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser::new(source)?;
+        let expr = parser.expr()?;
+        parser.finish()?;
+        Ok(expr)
+    }
+
+    fn eval(&self, inputs: &Inputs) -> f64 {
+        match self {
+            Expr::Num(value) => *value,
+            Expr::Field(field) => field.value(inputs),
+            Expr::Neg(inner) => -inner.eval(inputs),
+            Expr::Bin(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(inputs), rhs.eval(inputs));
+                match op {
+                    BinOp::Add => lhs + rhs,
+                    BinOp::Sub => lhs - rhs,
+                    BinOp::Mul => lhs * rhs,
+                    BinOp::Div => lhs / rhs,
+                }
+            }
+            Expr::Call(func, args) => {
+                let mut values = args.iter().map(|arg| arg.eval(inputs));
+                match func {
+                    Func::Min => values.fold(f64::INFINITY, f64::min),
+                    Func::Max => values.fold(f64::NEG_INFINITY, f64::max),
+                    Func::Abs => values.next().unwrap_or(0.0).abs(),
+                    Func::Clamp => {
+                        let (x, lo, hi) = (values.next(), values.next(), values.next());
+                        x.unwrap_or(0.0).max(lo.unwrap_or(f64::NEG_INFINITY)).min(hi.unwrap_or(f64::INFINITY))
+                    }
+                }
+            }
+        }
+    }
+
+    /// True for a bare count field, recorded as an integer in metadata
+    fn is_count(&self) -> bool {
+        matches!(self, Expr::Field(field) if field.is_count())
+    }
+}
+
+/// Comparison that must hold for a rule to fire
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    lhs: Expr,
+    op: CmpOp,
+    rhs: Expr,
+}
+
+impl Condition {
+    fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser::new(source)?;
+        let lhs = parser.expr()?;
+        let op = match parser.next() {
+            Some(Token::Cmp(op)) => op,
+            _ => return Err(format!("expected a comparison in {:?}", source)),
+        };
+        let rhs = parser.expr()?;
+        parser.finish()?;
+        Ok(Self { lhs, op, rhs })
+    }
+
+    fn holds(&self, inputs: &Inputs) -> bool {
+        let (lhs, rhs) = (self.lhs.eval(inputs), self.rhs.eval(inputs));
+        match self.op {
+            CmpOp::Lt => lhs < rhs,
+            CmpOp::Le => lhs <= rhs,
+            CmpOp::Gt => lhs > rhs,
+            CmpOp::Ge => lhs >= rhs,
+            CmpOp::Eq => lhs == rhs,
+            CmpOp::Ne => lhs != rhs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    /// One of `+ - * / ( ) ,`
+    Punct(char),
+    Cmp(CmpOp),
+}
+
+/// Recursive-descent parser over a tokenized expression
+struct Parser {
+    source: String,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c.is_ascii_digit() || c == '.' {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.' || **c == '_') {
+                    if c != '_' {
+                        number.push(c);
+                    }
+                    chars.next();
+                }
+                let value = number.parse().map_err(|_| format!("invalid number {:?} in {:?}", number, source))?;
+                tokens.push(Token::Num(value));
+            } else if c.is_ascii_alphabetic() || c == '_' {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric() || **c == '_' || **c == '.') {
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            } else if "+-*/(),".contains(c) {
+                tokens.push(Token::Punct(c));
+                chars.next();
+            } else if "<>=!".contains(c) {
+                chars.next();
+                let equals = chars.next_if_eq(&'=').is_some();
+                let op = match (c, equals) {
+                    ('<', false) => CmpOp::Lt,
+                    ('<', true) => CmpOp::Le,
+                    ('>', false) => CmpOp::Gt,
+                    ('>', true) => CmpOp::Ge,
+                    ('=', true) => CmpOp::Eq,
+                    ('!', true) => CmpOp::Ne,
+                    _ => return Err(format!("invalid operator {:?} in {:?}", c, source)),
+                };
+                tokens.push(Token::Cmp(op));
+            } else {
+                return Err(format!("unexpected {:?} in {:?}", c, source));
+            }
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            tokens,
+            pos: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, punct: char) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(token) => format!("expected {}, found {:?} in {:?}", expected, token, self.source),
+            None => format!("expected {} at the end of {:?}", expected, self.source),
+        }
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        if self.pos < self.tokens.len() {
+            return Err(self.error("end of expression"));
+        }
+        Ok(())
+    }
+
+    /// expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                BinOp::Add
+            } else if self.eat('-') {
+                BinOp::Sub
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    /// term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                BinOp::Mul
+            } else if self.eat('/') {
+                BinOp::Div
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    /// unary := '-' unary | atom
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat('-') {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.atom()
+    }
+
+    /// atom := number | field | func '(' expr (',' expr)* ')' | '(' expr ')'
+    fn atom(&mut self) -> Result<Expr, String> {
+        match self.peek().cloned() {
+            Some(Token::Num(value)) => {
+                self.pos += 1;
+                Ok(Expr::Num(value))
+            }
+            Some(Token::Punct('(')) => {
+                self.pos += 1;
+                let inner = self.expr()?;
+                if !self.eat(')') {
+                    return Err(self.error("')'"));
+                }
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                if !self.eat('(') {
+                    return Field::parse(&name).map(Expr::Field);
+                }
+
+                let (func, arity) = match name.as_str() {
+                    "min" => (Func::Min, 1..=usize::MAX),
+                    "max" => (Func::Max, 1..=usize::MAX),
+                    "abs" => (Func::Abs, 1..=1),
+                    "clamp" => (Func::Clamp, 3..=3),
+                    _ => return Err(format!("unknown function {:?} in {:?}", name, self.source)),
+                };
+                let mut args = vec![self.expr()?];
+                while self.eat(',') {
+                    args.push(self.expr()?);
+                }
+                if !self.eat(')') {
+                    return Err(self.error("')'"));
+                }
+                if !arity.contains(&args.len()) {
+                    return Err(format!("wrong number of arguments to {} in {:?}", name, self.source));
+                }
+                Ok(Expr::Call(func, args))
+            }
+            _ => Err(self.error("a number, field or '('")),
+        }
+    }
+}
+
+/// Weighted strength term
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    weight: f64,
+    value: Expr,
+    scale: f64,
+}
+
+/// A compiled rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub signal_type: SignalType,
+    pub window: String,
+    requires_windows: Vec<i64>,
+    conditions: Vec<Condition>,
+    terms: Vec<Term>,
+    metadata: Vec<(String, Expr)>,
+}
+
+impl Rule {
+    /// Compile a rule, checking its signal type, expressions and weights
+    pub fn compile(spec: &RuleSpec) -> Result<Self, Box<dyn Error>> {
+        let signal_type =
+            SignalType::parse(&spec.signal).ok_or_else(|| format!("unknown signal type {:?}", spec.signal))?;
+        if spec.window.trim().is_empty() {
+            return Err("empty window label".into());
+        }
+        if spec.strength.is_empty() {
+            return Err("no strength terms".into());
+        }
+
+        let mut terms = Vec::with_capacity(spec.strength.len());
+        for term in &spec.strength {
+            if !term.weight.is_finite() || !term.scale.is_finite() || term.scale <= 0.0 {
+                return Err(format!("invalid weight {} or scale {} for {:?}", term.weight, term.scale, term.value).into());
+            }
+            terms.push(Term {
+                weight: term.weight,
+                value: Expr::parse(&term.value)?,
+                scale: term.scale,
+            });
+        }
+
+        Ok(Self {
+            signal_type,
+            window: spec.window.clone(),
+            requires_windows: spec.requires_windows.clone(),
+            conditions: spec
+                .conditions
+                .iter()
+                .map(|c| Condition::parse(c))
+                .collect::<Result<_, _>>()?,
+            terms,
+            metadata: spec
+                .metadata
+                .iter()
+                .map(|(key, source)| Ok((key.clone(), Expr::parse(source)?)))
+                .collect::<Result<_, String>>()?,
+        })
+    }
+
+    /// Evaluate the rule for a mint; `trades` are the 300s window's trades
+    pub fn evaluate(&self, mint: &str, metrics: &RollingMetrics, trades: &[TradeEvent], timestamp: i64) -> Option<Signal> {
+        self.evaluate_with(mint, &Inputs::new(metrics, trades), timestamp)
+    }
+
+    fn evaluate_with(&self, mint: &str, inputs: &Inputs, timestamp: i64) -> Option<Signal> {
+        if inputs.metrics.is_warming(&self.requires_windows) {
+            return None;
+        }
+        if !self.conditions.iter().all(|c| c.holds(inputs)) {
+            return None;
+        }
+
+        let strength = self
+            .terms
+            .iter()
+            .fold(0.0, |sum, term| sum + term.weight * (term.value.eval(inputs) / term.scale).clamp(0.0, 1.0));
+        if !strength.is_finite() {
+            log::debug!("⚠️  Rule {} produced no strength for mint {}", self.signal_type.as_str(), mint);
+            return None;
+        }
+
+        let mut metadata = Map::new();
+        for (key, expr) in &self.metadata {
+            let value = expr.eval(inputs);
+            let value = if expr.is_count() { json!(value as i64) } else { json!(value) };
+            metadata.insert(key.clone(), value);
+        }
+
+        Some(Signal::new(
+            mint.to_string(),
+            self.signal_type,
+            strength,
+            self.window.clone(),
+            timestamp,
+            Value::Object(metadata),
+        ))
+    }
+}
+
+/// Compiled rules, in file order
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::parse(DEFAULT_RULES).expect("built-in signal rules are valid")
+    }
+}
+
+impl RuleSet {
+    /// Parse and compile a rules file; disabled rules are dropped
+    pub fn parse(json: &str) -> Result<Self, Box<dyn Error>> {
+        let file: RulesFile = serde_json::from_str(json)?;
+        let mut rules = Vec::with_capacity(file.rules.len());
+
+        for (i, spec) in file.rules.iter().enumerate() {
+            if !spec.enabled {
+                continue;
+            }
+            let rule = Rule::compile(spec).map_err(|e| format!("rule {} ({}): {}", i + 1, spec.signal, e))?;
+            rules.push(rule);
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Rule emitting `signal_type`, if any
+    pub fn get(&self, signal_type: SignalType) -> Option<&Rule> {
+        self.rules.iter().find(|r| r.signal_type == signal_type)
+    }
+
+    /// Evaluate every rule for a mint; `trades` are the 300s window's trades
+    pub fn evaluate(&self, mint: &str, metrics: &RollingMetrics, trades: &[TradeEvent], timestamp: i64) -> Vec<Signal> {
+        let inputs = Inputs::new(metrics, trades);
+        self.rules
+            .iter()
+            .filter_map(|rule| rule.evaluate_with(mint, &inputs, timestamp))
+            .collect()
+    }
+
+    /// Number of enabled rules
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+/// Rules in effect, reloaded from the rules file when it changes
+#[derive(Debug, Default)]
+pub struct SignalRules {
+    current: RwLock<Arc<RuleSet>>,
+    /// Rules file; None keeps the built-in rules
+    path: Option<PathBuf>,
+    /// Modification time of the file when it was last loaded
+    loaded: Mutex<Option<SystemTime>>,
+}
+
+impl SignalRules {
+    /// Built-in rules, replaced by the file at `path` on `reload`
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// Rules in effect
+    pub fn current(&self) -> Arc<RuleSet> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Atomically replace the rules in effect
+    pub fn replace(&self, rules: RuleSet) {
+        *self.current.write().unwrap() = Arc::new(rules);
+    }
+
+    /// Load the rules file if it changed since the last load
+    ///
+    /// Returns the number of rules loaded, or None if nothing changed. On
+    /// error the rules in effect are kept.
+    pub fn reload(&self) -> Result<Option<usize>, Box<dyn Error>> {
+        let Some(path) = &self.path else {
+            return Ok(None);
+        };
+
+        let modified = fs::metadata(path)?.modified()?;
+        let mut loaded = self.loaded.lock().unwrap();
+        if *loaded == Some(modified) {
+            return Ok(None);
+        }
+        // Recorded before parsing so a broken file is reported once
+        *loaded = Some(modified);
+
+        let rules = RuleSet::parse(&fs::read_to_string(path)?)?;
+        let count = rules.len();
+        self.replace(rules);
+        Ok(Some(count))
+    }
+}
+
+/// Background loop that reloads the rules file when it changes
+pub async fn run_reload_loop(rules: Arc<SignalRules>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        match rules.reload() {
+            Ok(Some(count)) => log::info!("📐 Reloaded {} signal rules", count),
+            Ok(None) => {}
+            Err(e) => log::warn!("⚠️  Failed to reload signal rules, keeping the current ones: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn metrics() -> RollingMetrics {
+        RollingMetrics {
+            windows: vec![
+                WindowMetrics {
+                    window_secs: 60,
+                    net_flow_sol: -4.0,
+                    buy_count: 2,
+                    sell_count: 6,
+                    ..Default::default()
+                },
+                WindowMetrics {
+                    window_secs: 300,
+                    net_flow_sol: 30.0,
+                    buy_count: 20,
+                    sell_count: 5,
+                    unique_wallets: 3,
+                    bot_trades: 5,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn rule(conditions: &str, value: &str) -> String {
+        format!(
+            r#"{{"rules": [{{"signal": "SURGE", "window": "60s", "conditions": [{}],
+                "strength": [{{"weight": 1.0, "value": "{}", "scale": 10}}],
+                "metadata": {{"trades": "w60.trade_count", "ratio": "w300.bot_ratio"}}}}]}}"#,
+            conditions, value
+        )
+    }
+
+    #[test]
+    fn test_default_rules_cover_builtin_signals() {
+        let rules = RuleSet::default();
+        assert_eq!(rules.len(), 5);
+        for signal_type in [
+            SignalType::Breakout,
+            SignalType::Reaccumulation,
+            SignalType::FocusedBuyers,
+            SignalType::Persistence,
+            SignalType::FlowReversal,
+        ] {
+            assert!(rules.get(signal_type).is_some(), "{:?}", signal_type);
+        }
+    }
+
+    #[test]
+    fn test_expressions_follow_precedence() {
+        let metrics = metrics();
+        let inputs = Inputs::new(&metrics, &[]);
+        let eval = |source: &str| Expr::parse(source).unwrap().eval(&inputs);
+
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("-w60.net_flow_sol / 2"), 2.0);
+        assert_eq!(eval("10 - 4 - 3"), 3.0);
+        assert_eq!(eval("max(w60.net_flow_sol, 1, 0.5)"), 1.0);
+        assert_eq!(eval("clamp(w300.net_flow_sol, 0, abs(w60.net_flow_sol))"), 4.0);
+        assert_eq!(eval("w300.bot_ratio"), 0.2);
+        assert_eq!(eval("w900.net_flow_sol"), 0.0);
+    }
+
+    #[test]
+    fn test_rule_evaluates_conditions_strength_and_metadata() {
+        let rules = RuleSet::parse(&rule(r#""w60.net_flow_sol < 0", "w300.bot_ratio <= 0.2""#, "w300.net_flow_sol / 5")).unwrap();
+        let signal = rules.get(SignalType::Surge).unwrap().evaluate("mint", &metrics(), &[], 1000).unwrap();
+
+        assert_eq!(signal.window, "60s");
+        // 30 / 5 / 10 = 0.6
+        assert!((signal.strength - 0.6).abs() < 1e-12);
+        assert_eq!(signal.metadata["trades"], 8);
+        assert!(signal.metadata["trades"].is_i64());
+        assert_eq!(signal.metadata["ratio"], 0.2);
+
+        let rules = RuleSet::parse(&rule(r#""w60.net_flow_sol > 0""#, "1")).unwrap();
+        assert!(rules.evaluate("mint", &metrics(), &[], 1000).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        let invalid = [
+            rule("", "w300.net_flow"),
+            rule("", "w0.net_flow_sol"),
+            rule("", "focus.entropy"),
+            rule("", "sqrt(2)"),
+            rule("", "clamp(1, 2)"),
+            rule("", "(1 + 2"),
+            rule("", "1 2"),
+            rule(r#""w60.net_flow_sol""#, "1"),
+            rule(r#""w60.net_flow_sol => 0""#, "1"),
+            rule("", "1").replace("SURGE", "MOON"),
+            rule("", "1").replace("\"scale\": 10", "\"scale\": 0"),
+            rule("", "1").replace("\"window\"", "\"windw\""),
+        ];
+        for json in &invalid {
+            assert!(RuleSet::parse(json).is_err(), "{}", json);
+        }
+
+        let disabled = rule("", "1").replace("\"window\"", "\"enabled\": false, \"window\"");
+        assert!(RuleSet::parse(&disabled).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reload_replaces_rules_and_keeps_them_on_error() {
+        let path = std::env::temp_dir().join(format!("solflow_rules_{}.json", std::process::id()));
+        let write = |contents: &str, secs: u64| {
+            fs::write(&path, contents).unwrap();
+            let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+            File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        };
+        let rules = SignalRules::new(Some(path.clone()));
+        assert_eq!(rules.current().len(), 5);
+
+        write(&rule("", "1"), 1_000);
+        assert_eq!(rules.reload().unwrap(), Some(1));
+        assert!(rules.current().get(SignalType::Surge).is_some());
+        // Unchanged file is not reparsed
+        assert_eq!(rules.reload().unwrap(), None);
+
+        write("{\"rules\": [", 2_000);
+        assert!(rules.reload().is_err());
+        assert!(rules.current().get(SignalType::Surge).is_some());
+
+        write(DEFAULT_RULES, 3_000);
+        assert_eq!(rules.reload().unwrap(), Some(5));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
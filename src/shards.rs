@@ -0,0 +1,833 @@
+//! Mint-sharded rolling state
+//!
+//! Every venue processor used to mutate one shared `DashMap` of rolling
+//! states, so two decoders could interleave trades of the same mint and every
+//! trade contended on the map's locks. Rolling state is now split into N
+//! shards by mint hash. Each shard is a single task that owns its states
+//! outright and applies commands in the order they were queued:
+//! - trades, already enriched by the venue processor (watermark, bot score,
+//!   smart money, funding cluster, price impact)
+//! - signals raised outside of a trade (DEV_SELL on a transfer)
+//! - decay ticks, position snapshots, sweeps and audits, broadcast by their
+//!   loops
+//!
+//! A shard also owns the signal states of its mints (see `signal_state`):
+//! only state transitions are published, and changed states are persisted on
+//! each decay tick.
+//!
+//! Positions are not bounded by the windows, but they live in rolling state
+//! and go with it when the sweeper drops a mint, after their unsaved changes
+//! are flushed to the writer. The shard remembers the swept mints that held
+//! positions; when one trades again, its persisted positions are loaded on a
+//! blocking thread and its trades wait in the shard until they are restored,
+//! as the warm start does.
+//!
+//! A mint always lands on the same shard, so its trades are applied in
+//! routing order no matter which decoder saw them, and nothing in the hot
+//! path takes a lock. Shard queues are bounded; a full queue backpressures
+//! the processors that route into it. Queue depths are logged every metrics
+//! interval.
+
+use crate::{
+    audit::{self, AuditRun},
+    creators::CreatorTracker,
+    db::{self, WriteRequest},
+    decay::{self, MintUpdate},
+    positions::{self, WalletPositions},
+    rules::SignalRules,
+    signal_state::{SignalState, SignalStateConfig, SignalStates},
+    signals::{Signal, SignalContext},
+    state::TokenRollingState,
+    sweeper::{self, SweepReport},
+    types::TradeEvent,
+    warm_start,
+};
+use rusqlite::Connection;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::{mpsc, oneshot};
+
+/// Commands each shard queue holds at most
+pub const SHARD_QUEUE_CAPACITY: usize = 1024;
+
+/// Share of `SHARD_QUEUE_CAPACITY` above which a shard's depth is logged as
+/// a warning
+const SHARD_DEPTH_WARN: f64 = 0.8;
+
+/// Shard a mint is routed to among `count`
+///
+/// Uses a fixed-key hasher so the assignment is the same for every router in
+/// the process.
+pub fn shard_index(mint: &str, count: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    mint.hash(&mut hasher);
+    (hasher.finish() % count.max(1) as u64) as usize
+}
+
+/// A trade ready to be applied to its mint's rolling state
+#[derive(Debug, Clone)]
+pub struct RoutedTrade {
+    pub trade: TradeEvent,
+    /// Bot classifier score of the trade's wallet, for the trade log
+    pub bot_score: f64,
+    /// Liquidity of the mint's pool after the trade, if known
+    pub liquidity_sol: Option<f64>,
+    /// Event time the mint's windows are evicted at
+    pub now: i64,
+    pub context: SignalContext,
+    /// Signals the processor already raised for this trade (SNIPER_DUMP,
+    /// DEV_SELL); published with the mint's update
+    pub signals: Vec<Signal>,
+}
+
+/// Work a shard applies to the states it owns
+#[derive(Debug)]
+pub enum ShardCommand {
+    Trade(Box<RoutedTrade>),
+    /// A signal raised outside of a trade (DEV_SELL on a transfer), applied
+    /// to its mint's signal state at the event time `now`
+    Signal { signal: Box<Signal>, now: i64 },
+    /// Evict every mint at `now` and publish the ones that changed (see
+    /// `decay::tick`)
+    Decay { now: i64, context: SignalContext },
+    /// Persist changed positions (see `positions::snapshot`)
+    SnapshotPositions { now: i64 },
+    /// Drop idle mints and enforce the shard's share of the memory budget
+    Sweep {
+        now: i64,
+        budget_bytes: usize,
+        reply: oneshot::Sender<SweepReport>,
+    },
+    /// Recompute every mint's metrics from its trades and diff them against
+    /// the incremental ones (see `audit`)
+    Audit { now: i64, reply: oneshot::Sender<AuditRun> },
+}
+
+/// Persisted positions of a swept mint, loaded off the shard task
+struct RestoredPositions {
+    mint: String,
+    loaded: Result<Option<(i64, WalletPositions)>, String>,
+}
+
+/// Load a mint's persisted positions (blocking)
+fn load_positions_at(path: &str, mint: &str) -> Result<Option<(i64, WalletPositions)>, String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    db::load_positions(&conn, mint).map_err(|e| e.to_string())
+}
+
+/// Counters of one shard
+#[derive(Debug, Default)]
+pub struct ShardStats {
+    /// Commands applied since startup
+    pub processed: AtomicU64,
+
+    /// Mints owned after the last command
+    pub mints: AtomicU64,
+}
+
+/// Handles shared by every shard
+#[derive(Clone)]
+pub struct ShardConfig {
+    /// Rolling window durations (seconds) for newly tracked mints
+    pub windows: Arc<[i64]>,
+    /// Timestamp from which trade history is complete (see `warm_start`)
+    pub history_start: i64,
+    pub writer: mpsc::Sender<WriteRequest>,
+    /// Annotates published signals with creator reputation
+    pub creators: Arc<CreatorTracker>,
+    /// Declarative signal rules, reloaded in place (see `rules`)
+    pub rules: Arc<SignalRules>,
+    /// Thresholds of the per-mint signal state machine
+    pub signal_states: SignalStateConfig,
+    /// Database persisted positions are reloaded from when a swept mint
+    /// trades again; None disables the reload
+    pub db_path: Option<String>,
+}
+
+impl ShardConfig {
+    /// Empty rolling state for a newly tracked mint
+    fn new_state(&self, mint: &str) -> TokenRollingState {
+        TokenRollingState::with_windows(mint.to_string(), &self.windows).with_history_start(self.history_start)
+    }
+}
+
+/// Rolling and signal states of the mints routed to one shard
+pub struct Shard {
+    pub id: usize,
+    states: HashMap<String, TokenRollingState>,
+    signals: SignalStates,
+    /// Mints dropped by the sweeper while holding positions
+    swept: HashSet<String>,
+    /// Trades of swept mints whose positions are being reloaded
+    restoring: HashMap<String, Vec<RoutedTrade>>,
+    restored_tx: mpsc::UnboundedSender<RestoredPositions>,
+    restored_rx: mpsc::UnboundedReceiver<RestoredPositions>,
+    config: ShardConfig,
+    stats: Arc<ShardStats>,
+}
+
+impl Shard {
+    pub fn new(
+        id: usize,
+        states: HashMap<String, TokenRollingState>,
+        signal_states: Vec<SignalState>,
+        config: ShardConfig,
+    ) -> Self {
+        let mut signals = SignalStates::new(config.signal_states);
+        signals.restore(signal_states);
+        let (restored_tx, restored_rx) = mpsc::unbounded_channel();
+        Self {
+            id,
+            states,
+            signals,
+            swept: HashSet::new(),
+            restoring: HashMap::new(),
+            restored_tx,
+            restored_rx,
+            config,
+            stats: Arc::default(),
+        }
+    }
+
+    /// Rolling state of a mint owned by this shard
+    pub fn state(&self, mint: &str) -> Option<&TokenRollingState> {
+        self.states.get(mint)
+    }
+
+    /// Number of mints owned
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Add a trade to its mint's state and compute the mint's update
+    ///
+    /// Returns the trade to persist, flagged `is_wash` like the copy in
+    /// rolling state. The update's signals are the state transitions the
+    /// trade caused (see `signal_state`).
+    pub fn apply_trade(&mut self, routed: RoutedTrade) -> (TradeEvent, MintUpdate) {
+        let RoutedTrade {
+            mut trade,
+            liquidity_sol,
+            now,
+            context,
+            signals,
+            ..
+        } = routed;
+
+        let state = self
+            .states
+            .entry(trade.mint.clone())
+            .or_insert_with(|| self.config.new_state(&trade.mint));
+
+        // Flag the persisted copy too; add_trade flags its own
+        trade.is_wash = state.is_wash_leg(&trade);
+        state.add_trade(trade.clone());
+        state.liquidity_sol = liquidity_sol;
+
+        let rules = self.config.rules.current();
+        let mut update = decay::refresh_mint(state, now, &rules, &context);
+        update.signals.extend(signals);
+        self.config.creators.annotate(&mut update.signals);
+        update.signals = self.signals.observe(std::mem::take(&mut update.signals), now);
+
+        (trade, update)
+    }
+
+    /// Apply a trade, or hold it while its mint's positions are reloaded
+    ///
+    /// The first trade of a swept mint starts the reload.
+    async fn accept_trade(&mut self, routed: RoutedTrade) {
+        if let Some(queued) = self.restoring.get_mut(&routed.trade.mint) {
+            queued.push(routed);
+            return;
+        }
+        if self.swept.remove(&routed.trade.mint) {
+            if let Some(path) = self.config.db_path.clone() {
+                let mint = routed.trade.mint.clone();
+                let restored_tx = self.restored_tx.clone();
+                tokio::task::spawn_blocking(move || {
+                    let loaded = load_positions_at(&path, &mint);
+                    // The shard may have stopped; nothing to restore then
+                    let _ = restored_tx.send(RestoredPositions { mint, loaded });
+                });
+                self.restoring.insert(routed.trade.mint.clone(), vec![routed]);
+                return;
+            }
+        }
+
+        self.publish_trade(routed).await;
+    }
+
+    /// Recreate a swept mint's state from its reloaded positions and apply
+    /// the trades held meanwhile
+    async fn finish_restore(&mut self, restored: RestoredPositions) {
+        let RestoredPositions { mint, loaded } = restored;
+        let mut state = self.config.new_state(&mint);
+        match loaded {
+            Ok(Some((snapshot_ts, positions))) => warm_start::apply_positions(&mut state, snapshot_ts, positions),
+            Ok(None) => {}
+            Err(e) => log::warn!("⚠️  Failed to reload positions for {}: {}", mint, e),
+        }
+        self.states.insert(mint.clone(), state);
+
+        for routed in self.restoring.remove(&mint).unwrap_or_default() {
+            self.publish_trade(routed).await;
+        }
+        self.stats.mints.store(self.states.len() as u64, Ordering::Relaxed);
+    }
+
+    /// Apply a trade and publish it with its mint's update
+    async fn publish_trade(&mut self, routed: RoutedTrade) {
+        let bot_score = routed.bot_score;
+        let (trade, update) = self.apply_trade(routed);
+
+        let w300 = update.metrics.window(300);
+        log::info!(
+            "📊 TRADE | Mint: {} | Dir: {:?} | SOL: {:.4} | Bot: {} ({:.2}) | DCA: {} | Smart: {} | NetFlow300s: {:.4} | Wallets300s: {} | DCA300s: {} | SmartBuys300s: {} | Shard: {}",
+            trade.mint,
+            trade.direction,
+            trade.sol_amount,
+            trade.is_bot,
+            bot_score,
+            trade.is_dca,
+            trade.is_smart_money,
+            w300.net_flow_sol,
+            w300.unique_wallets,
+            w300.dca_buys,
+            w300.smart_money_buys,
+            self.id
+        );
+
+        // Phase 5: Send trade event to database writer
+        if let Err(e) = self.config.writer.send(WriteRequest::Trade(trade)).await {
+            log::warn!("⚠️  Failed to send trade to writer: {}", e);
+        }
+
+        // Phase 5/6: Send metrics and signals
+        decay::publish_update(&self.config.writer, update).await;
+    }
+
+    /// Apply one command, publishing whatever it produced
+    pub async fn handle(&mut self, command: ShardCommand) {
+        match command {
+            ShardCommand::Trade(routed) => self.accept_trade(*routed).await,
+            ShardCommand::Signal { signal, now } => {
+                for event in self.signals.observe(vec![*signal], now) {
+                    decay::publish_signal(&self.config.writer, event).await;
+                }
+            }
+            ShardCommand::Decay { now, context } => {
+                let rules = self.config.rules.current();
+                let updates = decay::tick(self.states.values_mut(), now, &rules, &context);
+
+                if !updates.is_empty() {
+                    log::debug!("⏱️  DECAY | Shard: {} | Refreshed {} mints", self.id, updates.len());
+                }
+
+                for mut update in updates {
+                    self.config.creators.annotate(&mut update.signals);
+                    update.signals = self.signals.observe(std::mem::take(&mut update.signals), now);
+                    decay::publish_update(&self.config.writer, update).await;
+                }
+
+                for event in self.signals.expire(now) {
+                    decay::publish_signal(&self.config.writer, event).await;
+                }
+                let changed = self.signals.take_changed();
+                if !changed.is_empty() {
+                    if let Err(e) = self.config.writer.send(WriteRequest::SignalStates(changed)).await {
+                        log::warn!("⚠️  Failed to send signal states to writer: {}", e);
+                    }
+                }
+            }
+            ShardCommand::SnapshotPositions { now } => {
+                let snapshots = positions::snapshot(self.states.values_mut(), now);
+
+                if !snapshots.is_empty() {
+                    log::debug!("💼 POSITIONS | Shard: {} | Snapshotting {} mints", self.id, snapshots.len());
+                }
+
+                for snapshot in snapshots {
+                    if let Err(e) = self.config.writer.send(WriteRequest::Positions(snapshot)).await {
+                        log::warn!("⚠️  Failed to send positions to writer: {}", e);
+                    }
+                }
+            }
+            ShardCommand::Sweep {
+                now,
+                budget_bytes,
+                reply,
+            } => {
+                let (report, mut dropped) = sweeper::sweep(&mut self.states, now, budget_bytes);
+                // The sweeper loop may have given up waiting; nothing to do then
+                let _ = reply.send(report);
+
+                // Only mints with positions have anything to reload
+                if self.config.db_path.is_some() {
+                    let held = dropped.iter().filter(|state| !state.positions.is_empty());
+                    self.swept.extend(held.map(|state| state.mint.clone()));
+                }
+
+                // Positions changed since the last snapshot would be lost with the state
+                for snapshot in positions::snapshot(dropped.iter_mut(), now) {
+                    if let Err(e) = self.config.writer.send(WriteRequest::Positions(snapshot)).await {
+                        log::warn!("⚠️  Failed to send positions to writer: {}", e);
+                    }
+                }
+            }
+            ShardCommand::Audit { now, reply } => {
+                let _ = reply.send(audit::audit_states(self.states.values(), now));
+            }
+        }
+
+        self.stats.processed.fetch_add(1, Ordering::Relaxed);
+        self.stats.mints.store(self.states.len() as u64, Ordering::Relaxed);
+    }
+
+    /// Apply commands until every sender is dropped
+    pub async fn run(mut self, mut commands: mpsc::Receiver<ShardCommand>) {
+        loop {
+            tokio::select! {
+                command = commands.recv() => match command {
+                    Some(command) => self.handle(command).await,
+                    None => break,
+                },
+                Some(restored) = self.restored_rx.recv() => self.finish_restore(restored).await,
+            }
+        }
+        // Apply the trades still waiting on a reload
+        while !self.restoring.is_empty() {
+            let Some(restored) = self.restored_rx.recv().await else {
+                break;
+            };
+            self.finish_restore(restored).await;
+        }
+        log::info!("🧩 Shard {} stopped with {} mints", self.id, self.states.len());
+    }
+}
+
+/// Routes commands to the shard tasks
+pub struct ShardRouter {
+    senders: Vec<mpsc::Sender<ShardCommand>>,
+    stats: Vec<Arc<ShardStats>>,
+}
+
+impl ShardRouter {
+    /// Spawn `count` shards, handing each the rolling and signal states
+    /// routed to it
+    pub fn spawn(
+        count: usize,
+        states: HashMap<String, TokenRollingState>,
+        signal_states: Vec<SignalState>,
+        config: ShardConfig,
+    ) -> Self {
+        let count = count.max(1);
+        let mut partitions: Vec<HashMap<String, TokenRollingState>> = (0..count).map(|_| HashMap::new()).collect();
+        for (mint, state) in states {
+            partitions[shard_index(&mint, count)].insert(mint, state);
+        }
+        let mut signal_partitions: Vec<Vec<SignalState>> = (0..count).map(|_| Vec::new()).collect();
+        for state in signal_states {
+            signal_partitions[shard_index(&state.mint, count)].push(state);
+        }
+
+        let mut senders = Vec::with_capacity(count);
+        let mut stats = Vec::with_capacity(count);
+        for (id, (states, signal_states)) in partitions.into_iter().zip(signal_partitions).enumerate() {
+            let (tx, rx) = mpsc::channel(SHARD_QUEUE_CAPACITY);
+            let shard = Shard::new(id, states, signal_states, config.clone());
+            shard.stats.mints.store(shard.len() as u64, Ordering::Relaxed);
+
+            senders.push(tx);
+            stats.push(shard.stats.clone());
+            tokio::spawn(shard.run(rx));
+        }
+
+        Self { senders, stats }
+    }
+
+    /// Number of shards
+    pub fn len(&self) -> usize {
+        self.senders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.senders.is_empty()
+    }
+
+    /// Queue a trade on its mint's shard, waiting while the queue is full
+    pub async fn route(&self, trade: RoutedTrade) {
+        let shard = shard_index(&trade.trade.mint, self.senders.len());
+        if let Err(e) = self.senders[shard].send(ShardCommand::Trade(Box::new(trade))).await {
+            log::warn!("⚠️  Failed to route trade to shard {}: {}", shard, e);
+        }
+    }
+
+    /// Queue a signal on its mint's shard, to apply at the event time `now`
+    pub async fn route_signal(&self, signal: Signal, now: i64) {
+        let shard = shard_index(&signal.mint, self.senders.len());
+        let command = ShardCommand::Signal {
+            signal: Box::new(signal),
+            now,
+        };
+        if let Err(e) = self.senders[shard].send(command).await {
+            log::warn!("⚠️  Failed to route signal to shard {}: {}", shard, e);
+        }
+    }
+
+    /// Queue a command on every shard
+    pub async fn broadcast(&self, command: impl Fn() -> ShardCommand) {
+        for (shard, sender) in self.senders.iter().enumerate() {
+            if let Err(e) = sender.send(command()).await {
+                log::warn!("⚠️  Failed to send command to shard {}: {}", shard, e);
+            }
+        }
+    }
+
+    /// Sweep every shard, splitting the memory budget evenly between them
+    ///
+    /// Mints are spread uniformly by hash, so an even split keeps roughly the
+    /// globally coldest mints. Returns the combined report.
+    pub async fn sweep(&self, now: i64, budget_bytes: usize) -> SweepReport {
+        let budget_bytes = budget_bytes / self.senders.len();
+        let mut replies = Vec::with_capacity(self.senders.len());
+        for sender in &self.senders {
+            let (reply, rx) = oneshot::channel();
+            if sender
+                .send(ShardCommand::Sweep {
+                    now,
+                    budget_bytes,
+                    reply,
+                })
+                .await
+                .is_ok()
+            {
+                replies.push(rx);
+            }
+        }
+
+        let mut total = SweepReport::default();
+        for rx in replies {
+            if let Ok(report) = rx.await {
+                total.merge(&report);
+            }
+        }
+        total
+    }
+
+    /// Audit every shard, combining their runs
+    pub async fn audit(&self, now: i64) -> AuditRun {
+        let mut replies = Vec::with_capacity(self.senders.len());
+        for sender in &self.senders {
+            let (reply, rx) = oneshot::channel();
+            if sender.send(ShardCommand::Audit { now, reply }).await.is_ok() {
+                replies.push(rx);
+            }
+        }
+
+        let mut total = AuditRun::default();
+        for rx in replies {
+            if let Ok(run) = rx.await {
+                total.merge(run);
+            }
+        }
+        total
+    }
+
+    /// Commands waiting in each shard's queue
+    pub fn depths(&self) -> Vec<usize> {
+        self.senders
+            .iter()
+            .map(|sender| sender.max_capacity() - sender.capacity())
+            .collect()
+    }
+
+    /// Mints owned by each shard
+    pub fn mints(&self) -> Vec<u64> {
+        self.stats.iter().map(|s| s.mints.load(Ordering::Relaxed)).collect()
+    }
+
+    /// Commands applied by each shard
+    pub fn processed(&self) -> Vec<u64> {
+        self.stats.iter().map(|s| s.processed.load(Ordering::Relaxed)).collect()
+    }
+}
+
+/// Background loop that logs per-shard queue depth, mints and throughput
+pub async fn run_metrics_loop(router: Arc<ShardRouter>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let depths = router.depths();
+        let busiest = depths.iter().copied().max().unwrap_or(0);
+        if busiest as f64 >= SHARD_QUEUE_CAPACITY as f64 * SHARD_DEPTH_WARN {
+            log::warn!(
+                "🧩 SHARDS BACKLOGGED | Depth: {:?} | Capacity: {}",
+                depths,
+                SHARD_QUEUE_CAPACITY
+            );
+        } else {
+            log::debug!(
+                "🧩 SHARDS | Depth: {:?} | Mints: {:?} | Processed: {:?}",
+                depths,
+                router.mints(),
+                router.processed()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        signal_state::{SignalEvent, SignalPhase},
+        signals::SignalType,
+        state::DEFAULT_WINDOWS,
+        types::TradeDirection,
+    };
+    use serde_json::json;
+
+    fn trade(mint: &str, timestamp: i64, wallet: &str) -> TradeEvent {
+        TradeEvent {
+            user_account: wallet.to_string(),
+            ..TradeEvent::test(mint, timestamp, TradeDirection::Buy, 1.0)
+        }
+    }
+
+    fn routed(trade: TradeEvent) -> RoutedTrade {
+        RoutedTrade {
+            now: trade.timestamp,
+            trade,
+            bot_score: 0.0,
+            liquidity_sol: None,
+            context: SignalContext::default(),
+            signals: Vec::new(),
+        }
+    }
+
+    fn config(writer: mpsc::Sender<WriteRequest>) -> ShardConfig {
+        ShardConfig {
+            windows: DEFAULT_WINDOWS.to_vec().into(),
+            history_start: 0,
+            writer,
+            creators: Arc::default(),
+            rules: Arc::default(),
+            signal_states: SignalStateConfig::default(),
+            db_path: None,
+        }
+    }
+
+    #[test]
+    fn test_shard_index_is_stable_and_spread() {
+        let mints: Vec<String> = (0..400).map(|i| format!("mint_{}", i)).collect();
+        let mut counts = [0usize; 4];
+        for mint in &mints {
+            let shard = shard_index(mint, 4);
+            assert_eq!(shard, shard_index(mint, 4));
+            counts[shard] += 1;
+        }
+        assert!(counts.iter().all(|&count| count > 50), "{:?}", counts);
+        assert_eq!(shard_index("mint", 1), 0);
+    }
+
+    #[test]
+    fn test_apply_trade_owns_state_per_mint() {
+        let (tx, _rx) = mpsc::channel(10);
+        let mut shard = Shard::new(0, HashMap::new(), Vec::new(), config(tx));
+
+        let (first, _) = shard.apply_trade(routed(trade("a", 1_000, "w1")));
+        let (_, update) = shard.apply_trade(routed(trade("a", 1_010, "w2")));
+        shard.apply_trade(routed(trade("b", 1_010, "w1")));
+
+        assert!(!first.is_wash);
+        assert_eq!(shard.len(), 2);
+        assert_eq!(update.mint, "a");
+        assert_eq!(update.metrics.window(60).buy_count, 2);
+        assert_eq!(shard.state("b").unwrap().compute_rolling_metrics().window(60).buy_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_swept_mint_reloads_positions_before_trading() {
+        let path = std::env::temp_dir().join(format!("solflow_shard_positions_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(include_str!("../sql/00_initial.sql")).unwrap();
+
+        let (tx, _rx) = mpsc::channel(100);
+        let mut config = config(tx);
+        config.db_path = Some(path.to_string_lossy().into_owned());
+        let mut shard = Shard::new(0, HashMap::new(), Vec::new(), config);
+
+        // w1 buys, positions are snapshotted, then the idle mint is swept
+        shard.handle(ShardCommand::Trade(Box::new(routed(trade("a", 1_000, "w1"))))).await;
+        for snapshot in positions::snapshot(shard.states.values_mut(), 1_030) {
+            crate::db::write_position_snapshot(&conn, &snapshot).unwrap();
+        }
+        let (reply, _report) = oneshot::channel();
+        shard
+            .handle(ShardCommand::Sweep {
+                now: 1_000 + 14_400 + 60,
+                budget_bytes: 0,
+                reply,
+            })
+            .await;
+        assert!(shard.is_empty());
+
+        // Trading again, the trades wait for the reload
+        let mut sell = trade("a", 20_000, "w1");
+        sell.direction = TradeDirection::Sell;
+        shard.handle(ShardCommand::Trade(Box::new(routed(sell)))).await;
+        shard.handle(ShardCommand::Trade(Box::new(routed(trade("a", 20_010, "w2"))))).await;
+        assert!(shard.state("a").is_none());
+
+        let restored = shard.restored_rx.recv().await.unwrap();
+        shard.finish_restore(restored).await;
+        // w1 still holds its basis and sold against it
+        let state = shard.state("a").unwrap();
+        let position = state.positions.position("w1").unwrap();
+        assert_eq!((position.buy_count, position.sell_count), (1, 1));
+        assert_eq!(position.tokens, 0.0);
+        assert_eq!(state.compute_rolling_metrics().window(60).buy_count, 1);
+
+        // Mints that were never swept are not reloaded
+        shard.handle(ShardCommand::Trade(Box::new(routed(trade("b", 20_020, "w1"))))).await;
+        assert!(shard.state("b").is_some());
+
+        drop(conn);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_flushes_dropped_positions() {
+        let (tx, mut rx) = mpsc::channel(100);
+        let mut shard = Shard::new(0, HashMap::new(), Vec::new(), config(tx));
+        shard.handle(ShardCommand::Trade(Box::new(routed(trade("a", 1_000, "w1"))))).await;
+
+        let (reply, report) = oneshot::channel();
+        shard
+            .handle(ShardCommand::Sweep {
+                now: 1_000 + 14_400 + 60,
+                budget_bytes: 0,
+                reply,
+            })
+            .await;
+        assert_eq!(report.await.unwrap().idle_evicted, 1);
+        drop(shard);
+
+        let mut flushed = Vec::new();
+        while let Some(request) = rx.recv().await {
+            if let WriteRequest::Positions(snapshot) = request {
+                flushed.push(snapshot);
+            }
+        }
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].mint, "a");
+        assert!(flushed[0].positions.iter().any(|(wallet, _)| wallet == "w1"));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_signals_publish_transitions_only() {
+        let (tx, mut rx) = mpsc::channel(100);
+        let mut shard = Shard::new(0, HashMap::new(), Vec::new(), config(tx));
+        let raised = |timestamp| {
+            Signal::new("a".to_string(), SignalType::Breakout, 0.8, "300s".to_string(), timestamp, json!({}))
+        };
+
+        for i in 0..5 {
+            let mut routed = routed(trade("a", 1_000 + i, "w1"));
+            routed.signals.push(raised(1_000 + i));
+            shard.handle(ShardCommand::Trade(Box::new(routed))).await;
+        }
+        shard.handle(ShardCommand::Decay { now: 1_100, context: SignalContext::default() }).await;
+        drop(shard);
+
+        let mut events = Vec::new();
+        let mut persisted = Vec::new();
+        while let Some(request) = rx.recv().await {
+            match request {
+                WriteRequest::Signal(signal) => events.push(signal.event),
+                WriteRequest::SignalStates(states) => persisted.extend(states),
+                _ => {}
+            }
+        }
+        assert_eq!(events, [SignalEvent::Entered, SignalEvent::Exited]);
+        assert_eq!(persisted.len(), 1);
+        assert_eq!(persisted[0].phase, SignalPhase::CooledDown);
+    }
+
+    #[tokio::test]
+    async fn test_dev_sells_are_all_published() {
+        let (tx, mut rx) = mpsc::channel(100);
+        let mut shard = Shard::new(0, HashMap::new(), Vec::new(), config(tx));
+        let dev_sell = |timestamp| {
+            Signal::new("a".to_string(), SignalType::DevSell, 0.7, "launch".to_string(), timestamp, json!({}))
+        };
+
+        let mut routed = routed(trade("a", 1_010, "dev"));
+        routed.signals.push(dev_sell(1_010));
+        shard.handle(ShardCommand::Trade(Box::new(routed))).await;
+        // A second, larger dev sell 2 minutes later, as a transfer
+        shard
+            .handle(ShardCommand::Signal {
+                signal: Box::new(dev_sell(1_130)),
+                now: 1_130,
+            })
+            .await;
+        shard.handle(ShardCommand::Decay { now: 1_200, context: SignalContext::default() }).await;
+        drop(shard);
+
+        let mut dev_sells = Vec::new();
+        while let Some(request) = rx.recv().await {
+            if let WriteRequest::Signal(signal) = request {
+                if signal.signal_type == SignalType::DevSell {
+                    dev_sells.push((signal.timestamp, signal.event));
+                }
+            }
+        }
+        assert_eq!(dev_sells, [(1_010, SignalEvent::Entered), (1_130, SignalEvent::Entered)]);
+    }
+
+    #[tokio::test]
+    async fn test_router_applies_mint_trades_in_order() {
+        let (tx, mut rx) = mpsc::channel(1_000);
+        let router = ShardRouter::spawn(4, HashMap::new(), Vec::new(), config(tx));
+
+        for i in 0..20 {
+            for mint in ["a", "b", "c"] {
+                router.route(routed(trade(mint, 1_000 + i, &format!("w{}", i)))).await;
+            }
+        }
+
+        let mut seen: HashMap<String, Vec<i64>> = HashMap::new();
+        while seen.values().map(Vec::len).sum::<usize>() < 60 {
+            if let Some(WriteRequest::Trade(trade)) = rx.recv().await {
+                seen.entry(trade.mint).or_default().push(trade.timestamp);
+            }
+        }
+        for timestamps in seen.values() {
+            assert_eq!(timestamps, &(1_000..1_020).collect::<Vec<i64>>());
+        }
+
+        let report = router.sweep(1_020, 0).await;
+        assert_eq!(report.tracked_mints, 3);
+        let audit = router.audit(1_020).await;
+        assert_eq!((audit.mints, audit.trades), (3, 60));
+        assert!(audit.reports.is_empty());
+        assert_eq!(router.mints().iter().sum::<u64>(), 3);
+        assert_eq!(router.depths().len(), 4);
+    }
+}
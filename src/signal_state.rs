@@ -0,0 +1,414 @@
+//! Per-mint signal state machine
+//!
+//! Signals are evaluated on every trade, so a mint that stays in BREAKOUT
+//! would otherwise write a `token_signals` row per trade. Each (mint, signal
+//! type) pair instead moves through three phases:
+//! - inactive → active when the signal fires with at least `enter_strength`,
+//!   emitting `entered`
+//! - active: firings with at least `exit_strength` keep it active; one whose
+//!   strength moved by `update_delta` since the last event emits `updated`,
+//!   at most once per `update_secs`
+//! - active → cooled down once it has not been kept active for `exit_secs`,
+//!   emitting `exited`; firings are ignored until `cooldown_secs` later, when
+//!   the pair is inactive again
+//!
+//! The gap between the enter and exit strengths and the exit delay are the
+//! hysteresis: a signal hovering around its threshold does not flap.
+//!
+//! Event signals (SNIPER_DUMP, DEV_SELL) report a single sell or transfer, not
+//! a condition that holds, so they bypass the state machine: every one raised
+//! is published as `entered`, whatever its strength.
+//!
+//! Only the emitted events are published. States are owned by the shards
+//! (keyed by the same mint hash as rolling state), persisted to
+//! `signal_states` on every decay tick and restored at startup, so a restart
+//! neither re-enters active signals nor forgets cooldowns. Times are event
+//! times (see `watermark`).
+
+use crate::signals::{Signal, SignalType};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Phase of one (mint, signal type) pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalPhase {
+    Inactive,
+    Active,
+    CooledDown,
+}
+
+impl SignalPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalPhase::Inactive => "inactive",
+            SignalPhase::Active => "active",
+            SignalPhase::CooledDown => "cooled_down",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "inactive" => Some(SignalPhase::Inactive),
+            "active" => Some(SignalPhase::Active),
+            "cooled_down" => Some(SignalPhase::CooledDown),
+            _ => None,
+        }
+    }
+}
+
+/// Transition a published signal records (`token_signals.event`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignalEvent {
+    #[default]
+    Entered,
+    Updated,
+    Exited,
+}
+
+impl SignalEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalEvent::Entered => "entered",
+            SignalEvent::Updated => "updated",
+            SignalEvent::Exited => "exited",
+        }
+    }
+}
+
+/// Thresholds and delays of the state machine
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalStateConfig {
+    /// Strength a firing needs to enter
+    pub enter_strength: f64,
+    /// Strength a firing needs to keep an active signal active
+    pub exit_strength: f64,
+    /// Strength change since the last event that emits `updated`
+    pub update_delta: f64,
+    /// Minimum seconds between events of an active signal
+    pub update_secs: i64,
+    /// Seconds without a qualifying firing after which a signal exits
+    pub exit_secs: i64,
+    /// Seconds after exiting during which firings are ignored
+    pub cooldown_secs: i64,
+}
+
+impl Default for SignalStateConfig {
+    fn default() -> Self {
+        Self {
+            enter_strength: 0.25,
+            exit_strength: 0.15,
+            update_delta: 0.1,
+            update_secs: 30,
+            exit_secs: 60,
+            cooldown_secs: 300,
+        }
+    }
+}
+
+/// State of one (mint, signal type) pair, as persisted in `signal_states`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalState {
+    pub mint: String,
+    pub signal_type: SignalType,
+    pub phase: SignalPhase,
+    /// Strength, window and metadata of the latest qualifying firing
+    pub strength: f64,
+    pub window: String,
+    pub metadata: Value,
+    /// Strength of the last emitted event
+    pub emitted_strength: f64,
+    pub entered_at: i64,
+    pub last_seen: i64,
+    pub last_emitted: i64,
+    /// End of the cooldown (cooled down only)
+    pub cooldown_until: i64,
+}
+
+impl SignalState {
+    fn entered(signal: &Signal, now: i64) -> Self {
+        Self {
+            mint: signal.mint.clone(),
+            signal_type: signal.signal_type,
+            phase: SignalPhase::Active,
+            strength: signal.strength,
+            window: signal.window.clone(),
+            metadata: signal.metadata.clone(),
+            emitted_strength: signal.strength,
+            entered_at: now,
+            last_seen: now,
+            last_emitted: now,
+            cooldown_until: 0,
+        }
+    }
+
+    /// `exited` event carrying the latest firing's strength and metadata
+    fn exit_signal(&self, now: i64) -> Signal {
+        let mut metadata = self.metadata.clone();
+        if let Value::Object(metadata) = &mut metadata {
+            metadata.insert("active_secs".to_string(), json!(now - self.entered_at));
+        }
+        let mut signal = Signal::new(
+            self.mint.clone(),
+            self.signal_type,
+            self.strength,
+            self.window.clone(),
+            now,
+            metadata,
+        );
+        signal.event = SignalEvent::Exited;
+        signal
+    }
+}
+
+type StateKey = (String, SignalType);
+
+/// Signal states of the mints owned by one shard
+#[derive(Debug, Default)]
+pub struct SignalStates {
+    config: SignalStateConfig,
+    states: HashMap<StateKey, SignalState>,
+    /// Pairs changed since the last `take_changed`
+    changed: HashSet<StateKey>,
+}
+
+impl SignalStates {
+    pub fn new(config: SignalStateConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Restore persisted states; inactive ones and event signals are skipped
+    pub fn restore(&mut self, states: impl IntoIterator<Item = SignalState>) {
+        for state in states
+            .into_iter()
+            .filter(|s| s.phase != SignalPhase::Inactive && !s.signal_type.is_event())
+        {
+            self.states.insert((state.mint.clone(), state.signal_type), state);
+        }
+    }
+
+    /// State of a mint's signal type, if not inactive
+    pub fn get(&self, mint: &str, signal_type: SignalType) -> Option<&SignalState> {
+        self.states.get(&(mint.to_string(), signal_type))
+    }
+
+    /// Pairs tracked (active or cooling down)
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Apply evaluated signals at `now`, returning the events to publish
+    pub fn observe(&mut self, signals: Vec<Signal>, now: i64) -> Vec<Signal> {
+        let config = self.config;
+        let mut events = Vec::new();
+
+        for mut signal in signals {
+            if signal.signal_type.is_event() {
+                signal.event = SignalEvent::Entered;
+                events.push(signal);
+                continue;
+            }
+
+            let key = (signal.mint.clone(), signal.signal_type);
+            let state = self.states.get_mut(&key);
+
+            let event = match state {
+                Some(state) if state.phase == SignalPhase::Active => {
+                    if signal.strength < config.exit_strength {
+                        continue;
+                    }
+                    state.strength = signal.strength;
+                    state.window.clone_from(&signal.window);
+                    state.metadata.clone_from(&signal.metadata);
+                    state.last_seen = now;
+                    self.changed.insert(key);
+
+                    let moved = (signal.strength - state.emitted_strength).abs() >= config.update_delta;
+                    if !moved || now - state.last_emitted < config.update_secs {
+                        continue;
+                    }
+                    state.emitted_strength = signal.strength;
+                    state.last_emitted = now;
+                    SignalEvent::Updated
+                }
+                Some(state) if state.phase == SignalPhase::CooledDown && now < state.cooldown_until => continue,
+                _ => {
+                    if signal.strength < config.enter_strength {
+                        continue;
+                    }
+                    self.states.insert(key.clone(), SignalState::entered(&signal, now));
+                    self.changed.insert(key);
+                    SignalEvent::Entered
+                }
+            };
+
+            signal.event = event;
+            events.push(signal);
+        }
+
+        events
+    }
+
+    /// Exit signals not kept active for `exit_secs` and end elapsed
+    /// cooldowns, returning the `exited` events to publish
+    pub fn expire(&mut self, now: i64) -> Vec<Signal> {
+        let config = self.config;
+        let mut events = Vec::new();
+
+        for (key, state) in self.states.iter_mut() {
+            match state.phase {
+                SignalPhase::Active if now - state.last_seen >= config.exit_secs => {
+                    events.push(state.exit_signal(now));
+                    state.phase = SignalPhase::CooledDown;
+                    state.last_emitted = now;
+                    state.cooldown_until = now + config.cooldown_secs;
+                    self.changed.insert(key.clone());
+                }
+                SignalPhase::CooledDown if now >= state.cooldown_until => {
+                    state.phase = SignalPhase::Inactive;
+                    self.changed.insert(key.clone());
+                }
+                _ => {}
+            }
+        }
+
+        events
+    }
+
+    /// States changed since the last call, to persist; inactive ones are
+    /// dropped from memory and returned so their rows are deleted
+    pub fn take_changed(&mut self) -> Vec<SignalState> {
+        let mut changed = Vec::with_capacity(self.changed.len());
+        for key in self.changed.drain() {
+            let Some(state) = self.states.get(&key) else {
+                continue;
+            };
+            if state.phase == SignalPhase::Inactive {
+                changed.extend(self.states.remove(&key));
+            } else {
+                changed.push(state.clone());
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(strength: f64) -> Signal {
+        Signal::new(
+            "mint".to_string(),
+            SignalType::Breakout,
+            strength,
+            "300s".to_string(),
+            0,
+            json!({ "net_flow_300s": 10.0 }),
+        )
+    }
+
+    fn events(emitted: &[Signal]) -> Vec<SignalEvent> {
+        emitted.iter().map(|s| s.event).collect()
+    }
+
+    #[test]
+    fn test_repeated_firings_enter_once() {
+        let mut states = SignalStates::new(SignalStateConfig::default());
+
+        assert_eq!(events(&states.observe(vec![signal(0.5)], 1_000)), [SignalEvent::Entered]);
+        for now in 1_001..1_020 {
+            assert!(states.observe(vec![signal(0.5)], now).is_empty());
+        }
+        assert_eq!(states.get("mint", SignalType::Breakout).unwrap().last_seen, 1_019);
+    }
+
+    #[test]
+    fn test_updates_need_delta_and_spacing() {
+        let mut states = SignalStates::new(SignalStateConfig::default());
+        states.observe(vec![signal(0.5)], 1_000);
+
+        // Moved enough but too soon
+        assert!(states.observe(vec![signal(0.7)], 1_010).is_empty());
+        // Late enough but barely moved
+        assert!(states.observe(vec![signal(0.55)], 1_040).is_empty());
+
+        let updated = states.observe(vec![signal(0.7)], 1_045);
+        assert_eq!(events(&updated), [SignalEvent::Updated]);
+        assert_eq!(states.get("mint", SignalType::Breakout).unwrap().emitted_strength, 0.7);
+    }
+
+    #[test]
+    fn test_hysteresis_exit_and_cooldown() {
+        let mut states = SignalStates::new(SignalStateConfig::default());
+
+        // Below the enter strength nothing starts
+        assert!(states.observe(vec![signal(0.2)], 1_000).is_empty());
+        assert!(states.is_empty());
+
+        states.observe(vec![signal(0.5)], 1_000);
+        // Between exit and enter strength keeps it active
+        states.observe(vec![signal(0.2)], 1_050);
+        assert!(states.expire(1_100).is_empty());
+        // Below the exit strength does not
+        states.observe(vec![signal(0.1)], 1_105);
+        let exited = states.expire(1_110);
+        assert_eq!(events(&exited), [SignalEvent::Exited]);
+        assert_eq!(exited[0].strength, 0.2);
+        assert_eq!(exited[0].metadata["active_secs"], 110);
+
+        // Cooling down: firings are ignored
+        assert!(states.observe(vec![signal(0.9)], 1_200).is_empty());
+        assert_eq!(states.get("mint", SignalType::Breakout).unwrap().phase, SignalPhase::CooledDown);
+
+        // After the cooldown it can enter again
+        let entered = states.observe(vec![signal(0.9)], 1_410);
+        assert_eq!(events(&entered), [SignalEvent::Entered]);
+    }
+
+    #[test]
+    fn test_event_signals_bypass_state_machine() {
+        let mut states = SignalStates::new(SignalStateConfig::default());
+        let dump = |strength| {
+            let mut signal = signal(strength);
+            signal.signal_type = SignalType::SniperDump;
+            signal
+        };
+
+        // Weak, repeated and back to back: every one is published
+        for now in [1_000, 1_001, 1_120] {
+            assert_eq!(events(&states.observe(vec![dump(0.1)], now)), [SignalEvent::Entered]);
+        }
+        assert!(states.is_empty());
+        assert!(states.expire(1_500).is_empty());
+        assert!(states.take_changed().is_empty());
+    }
+
+    #[test]
+    fn test_changed_states_round_trip() {
+        let config = SignalStateConfig::default();
+        let mut states = SignalStates::new(config);
+        states.observe(vec![signal(0.5)], 1_000);
+
+        let changed = states.take_changed();
+        assert_eq!(changed.len(), 1);
+        assert!(states.take_changed().is_empty());
+
+        // A restarted shard keeps the signal active instead of re-entering
+        let mut restored = SignalStates::new(config);
+        restored.restore(changed);
+        assert!(restored.observe(vec![signal(0.5)], 1_030).is_empty());
+
+        restored.expire(1_100);
+        restored.expire(1_400);
+        let changed = restored.take_changed();
+        assert_eq!(changed[0].phase, SignalPhase::Inactive);
+        assert!(restored.is_empty());
+    }
+}
@@ -4,9 +4,21 @@
 //! Consumes token_rolling_metrics (Phase 5) and recent token_trades (Phase 5).
 //! Produces signals persisted to token_signals table for Phase 7 dashboard.
 
-use crate::{state::RollingMetrics, types::TradeEvent};
+use crate::{
+    creators::{CreatedToken, CreatorReputation, DevAction},
+    liquidity::FLOW_LIQUIDITY_REFERENCE,
+    market::{MarketRegime, MarketSnapshot},
+    rules::RuleSet,
+    signal_state::SignalEvent,
+    snipers::{Launch, SniperKind, LAUNCH_SUPPLY_TOKENS, SNIPER_DUMP_MIN_SELL_SHARE, SNIPER_DUMP_MIN_SUPPLY_SHARE},
+    state::RollingMetrics,
+    types::TradeEvent,
+    wash::WASH_SIGNAL_SCORE,
+};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+
+/// Windows (seconds) the signal evaluators read from `RollingMetrics`
+pub const SIGNAL_WINDOWS: [i64; 3] = [60, 300, 900];
 
 /// Signal types for Phase 6
 ///
@@ -18,6 +30,12 @@ use std::collections::HashMap;
 /// - FOCUSED_BUYERS: Low entropy wallet distribution (F ≤ 0.35)
 /// - PERSISTENCE: Positive net_flow across 3 consecutive windows, sustained activity
 /// - FLOW_REVERSAL: 60s negative while 300s positive, early exhaustion signal
+/// - SMART_MONEY_ENTRY: several smart-money wallets net buying (see `wallets`)
+/// - WHALE_ACCUMULATION: whale-sized buys driving positive flow (see `sketch`)
+/// - WHALE_DISTRIBUTION: whales selling into a crowd of retail buyers
+/// - WASH_TRADING: warning, volume circulating among a few wallets (see `wash`)
+/// - SNIPER_DUMP: warning, launch snipers selling their supply (see `snipers`)
+/// - DEV_SELL: high-severity warning, the creator sold or moved supply (see `creators`)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SignalType {
     Breakout,
@@ -25,6 +43,12 @@ pub enum SignalType {
     FocusedBuyers,
     Persistence,
     FlowReversal,
+    SmartMoneyEntry,
+    WhaleAccumulation,
+    WhaleDistribution,
+    WashTrading,
+    SniperDump,
+    DevSell,
     // Legacy signals (Phase 1)
     Focused,
     Surge,
@@ -43,6 +67,12 @@ impl SignalType {
             SignalType::FocusedBuyers => "FOCUSED_BUYERS",
             SignalType::Persistence => "PERSISTENCE",
             SignalType::FlowReversal => "FLOW_REVERSAL",
+            SignalType::SmartMoneyEntry => "SMART_MONEY_ENTRY",
+            SignalType::WhaleAccumulation => "WHALE_ACCUMULATION",
+            SignalType::WhaleDistribution => "WHALE_DISTRIBUTION",
+            SignalType::WashTrading => "WASH_TRADING",
+            SignalType::SniperDump => "SNIPER_DUMP",
+            SignalType::DevSell => "DEV_SELL",
             // Legacy
             SignalType::Focused => "FOCUSED",
             SignalType::Surge => "SURGE",
@@ -50,8 +80,40 @@ impl SignalType {
             SignalType::DcaConviction => "DCA_CONVICTION",
         }
     }
+
+    /// Parse a signal type from its `as_str` name
+    pub fn parse(name: &str) -> Option<Self> {
+        ALL_SIGNAL_TYPES.iter().copied().find(|t| t.as_str() == name)
+    }
+
+    /// Whether the signal reports a one-off event (a sniper or dev sell)
+    /// rather than a condition of the rolling windows
+    ///
+    /// Event signals are published every time they are raised instead of
+    /// going through the state machine (see `signal_state`).
+    pub fn is_event(&self) -> bool {
+        matches!(self, SignalType::SniperDump | SignalType::DevSell)
+    }
 }
 
+const ALL_SIGNAL_TYPES: [SignalType; 15] = [
+    SignalType::Breakout,
+    SignalType::Reaccumulation,
+    SignalType::FocusedBuyers,
+    SignalType::Persistence,
+    SignalType::FlowReversal,
+    SignalType::SmartMoneyEntry,
+    SignalType::WhaleAccumulation,
+    SignalType::WhaleDistribution,
+    SignalType::WashTrading,
+    SignalType::SniperDump,
+    SignalType::DevSell,
+    SignalType::Focused,
+    SignalType::Surge,
+    SignalType::BotDropoff,
+    SignalType::DcaConviction,
+];
+
 /// Phase 6: Signal event with strength and metadata
 ///
 /// SQL reference: `/sql/03_token_signals.sql` and `/sql/10_phase6_signals_engine.sql`
@@ -76,6 +138,9 @@ pub struct Signal {
 
     /// Additional signal-specific metadata as JSON
     pub metadata: Value,
+
+    /// Transition of the signal's state this row records (see `signal_state`)
+    pub event: SignalEvent,
 }
 
 /// Legacy token signal (Phase 1-5 compatibility)
@@ -107,7 +172,48 @@ impl Signal {
             window,
             timestamp,
             metadata,
+            event: SignalEvent::Entered,
+        }
+    }
+}
+
+/// Market-wide context signals are evaluated against (see `market`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalContext {
+    pub regime: MarketRegime,
+    /// Market activity relative to its baseline (1.0 = normal)
+    pub activity: f64,
+}
+
+impl Default for SignalContext {
+    fn default() -> Self {
+        Self {
+            regime: MarketRegime::Normal,
+            activity: 1.0,
+        }
+    }
+}
+
+impl SignalContext {
+    pub fn from_snapshot(snapshot: &MarketSnapshot) -> Self {
+        Self {
+            regime: snapshot.regime,
+            activity: snapshot.activity,
+        }
+    }
+
+    /// Scale a strength computed against absolute thresholds to the regime
+    pub fn normalize(&self, strength: f64) -> f64 {
+        (strength * self.regime.strength_multiplier()).clamp(0.0, 1.0)
+    }
+
+    /// Normalize a signal's strength and record the regime in its metadata
+    fn apply(&self, signal: &mut Signal) {
+        if let Value::Object(metadata) = &mut signal.metadata {
+            metadata.insert("raw_strength".to_string(), json!(signal.strength));
+            metadata.insert("market_regime".to_string(), json!(self.regime.as_str()));
         }
+        signal.strength = self.normalize(signal.strength);
     }
 }
 
@@ -155,133 +261,130 @@ impl TokenSignal {
 /// * `mint` - Token mint address
 /// * `metrics` - Current rolling metrics computed from Phase 5
 /// * `recent_trades` - Recent trade events from token_trades table
+/// * `rules` - Declarative signals (BREAKOUT through FLOW_REVERSAL, see `rules`)
+/// * `now` - Event time (see `watermark`) the signals are stamped with
+/// * `context` - Market regime; strengths are normalized against it
+///
+/// Each evaluator returns `None` while any window it reads is still warming
+/// (history incomplete after a restart).
 ///
 /// # Returns
 /// Vector of signals that were triggered by this update
-pub fn evaluate_signals(mint: &str, metrics: &RollingMetrics, recent_trades: &[TradeEvent]) -> Vec<Signal> {
-    let now = chrono::Utc::now().timestamp();
-    let mut signals = Vec::new();
-
-    // Signal A: BREAKOUT
-    if let Some(signal) = evaluate_breakout(mint, metrics, now) {
+pub fn evaluate_signals(
+    mint: &str,
+    metrics: &RollingMetrics,
+    recent_trades: &[TradeEvent],
+    rules: &RuleSet,
+    now: i64,
+    context: &SignalContext,
+) -> Vec<Signal> {
+    // Signals A-E: BREAKOUT, REACCUMULATION, FOCUSED BUYERS, PERSISTENCE,
+    // FLOW REVERSAL (rule-defined)
+    let mut signals = rules.evaluate(mint, metrics, recent_trades, now);
+
+    // Signal F: SMART MONEY ENTRY
+    if let Some(signal) = evaluate_smart_money_entry(mint, metrics, now) {
         signals.push(signal);
     }
 
-    // Signal B: REACCUMULATION
-    if let Some(signal) = evaluate_reaccumulation(mint, metrics, now) {
+    // Signal G: WHALE ACCUMULATION
+    if let Some(signal) = evaluate_whale_accumulation(mint, metrics, now) {
         signals.push(signal);
     }
 
-    // Signal C: FOCUSED BUYERS
-    if let Some(signal) = evaluate_focused_buyers(mint, metrics, recent_trades, now) {
+    // Signal H: WHALE DISTRIBUTION
+    if let Some(signal) = evaluate_whale_distribution(mint, metrics, now) {
         signals.push(signal);
     }
 
-    // Signal D: PERSISTENCE
-    if let Some(signal) = evaluate_persistence(mint, metrics, now) {
+    // Signal I: WASH TRADING (warning)
+    if let Some(signal) = evaluate_wash_trading(mint, metrics, now) {
         signals.push(signal);
     }
 
-    // Signal E: FLOW REVERSAL
-    if let Some(signal) = evaluate_flow_reversal(mint, metrics, now) {
-        signals.push(signal);
+    for signal in &mut signals {
+        apply_liquidity(metrics, signal);
+        context.apply(signal);
     }
 
     signals
 }
 
-/// Signal A: BREAKOUT
+/// Scale a flow-driven signal by the 300s net flow relative to the pool's
+/// liquidity and record both in its metadata
 ///
-/// Triggered when:
-/// - net_flow_300s accelerating
-/// - AND net_flow_60s > net_flow_300s
-/// - AND unique_wallets_300s increasing (>= 5)
-/// - AND bot ratio within normal bounds (<= 0.3)
-fn evaluate_breakout(mint: &str, metrics: &RollingMetrics, timestamp: i64) -> Option<Signal> {
-    let net_flow_60s = metrics.net_flow_60s_sol;
-    let net_flow_300s = metrics.net_flow_300s_sol;
-    let net_flow_900s = metrics.net_flow_900s_sol;
-    let unique_wallets = metrics.unique_wallets_300s;
-    let bot_ratio = if metrics.buy_count_300s + metrics.sell_count_300s > 0 {
-        metrics.bot_trades_count_300s as f64 / (metrics.buy_count_300s + metrics.sell_count_300s) as f64
-    } else {
-        0.0
+/// The factor is `sqrt(|ratio| / FLOW_LIQUIDITY_REFERENCE)` clamped to
+/// 0.5 ..= 1.5. Signals of mints with unknown liquidity and warnings are left
+/// unchanged.
+fn apply_liquidity(metrics: &RollingMetrics, signal: &mut Signal) {
+    let flow_driven = matches!(
+        signal.signal_type,
+        SignalType::Breakout
+            | SignalType::Reaccumulation
+            | SignalType::Persistence
+            | SignalType::FlowReversal
+            | SignalType::SmartMoneyEntry
+            | SignalType::WhaleAccumulation
+            | SignalType::WhaleDistribution
+    );
+    let (Some(liquidity), Some(ratio)) = (metrics.liquidity_sol, metrics.flow_liquidity_ratio(300)) else {
+        return;
     };
-
-    // Check conditions
-    let is_accelerating = net_flow_300s > net_flow_900s && net_flow_300s > 0.0;
-    let momentum_shift = net_flow_60s > net_flow_300s;
-    let has_wallets = unique_wallets >= 5;
-    let bot_ratio_ok = bot_ratio <= 0.3;
-
-    if is_accelerating && momentum_shift && has_wallets && bot_ratio_ok {
-        // Compute strength (0.0 - 1.0)
-        let acceleration = ((net_flow_300s - net_flow_900s) / net_flow_900s.max(1.0)).min(1.0);
-        let momentum_factor = (net_flow_60s / net_flow_300s.max(1.0)).min(1.0);
-        let wallet_factor = (unique_wallets as f64 / 20.0).min(1.0);
-        let bot_factor = (1.0 - bot_ratio).max(0.0);
-        
-        let strength = (acceleration * 0.3 + momentum_factor * 0.3 + wallet_factor * 0.2 + bot_factor * 0.2).clamp(0.0, 1.0);
-
-        let metadata = json!({
-            "net_flow_60s": net_flow_60s,
-            "net_flow_300s": net_flow_300s,
-            "net_flow_900s": net_flow_900s,
-            "unique_wallets": unique_wallets,
-            "bot_ratio": bot_ratio,
-        });
-
-        return Some(Signal::new(
-            mint.to_string(),
-            SignalType::Breakout,
-            strength,
-            "300s".to_string(),
-            timestamp,
-            metadata,
-        ));
+    if !flow_driven {
+        return;
     }
 
-    None
+    let factor = (ratio.abs() / FLOW_LIQUIDITY_REFERENCE).sqrt().clamp(0.5, 1.5);
+    if let Value::Object(metadata) = &mut signal.metadata {
+        metadata.insert("liquidity_sol".to_string(), json!(liquidity));
+        metadata.insert("flow_liquidity_ratio_300s".to_string(), json!(ratio));
+        metadata.insert("liquidity_factor".to_string(), json!(factor));
+    }
+    signal.strength = (signal.strength * factor).clamp(0.0, 1.0);
 }
 
-/// Signal B: REACCUMULATION
+/// Signal F: SMART MONEY ENTRY
 ///
 /// Triggered when:
-/// - DCA flow increasing
-/// - AND DCA unique wallets increasing (>= 2)
-/// - AND total net_flow_300s positive
-/// - AND 300s window > 900s window (momentum shift)
-fn evaluate_reaccumulation(mint: &str, metrics: &RollingMetrics, timestamp: i64) -> Option<Signal> {
-    let dca_flow = metrics.dca_flow_300s_sol;
-    let dca_wallets = metrics.dca_unique_wallets_300s;
-    let net_flow_300s = metrics.net_flow_300s_sol;
-    let net_flow_900s = metrics.net_flow_900s_sol;
-
-    // Check conditions
-    let dca_active = dca_flow > 0.0 && dca_wallets >= 2;
-    let positive_flow = net_flow_300s > 0.0;
-    let momentum_shift = net_flow_300s > net_flow_900s;
-
-    if dca_active && positive_flow && momentum_shift {
-        // Compute strength
-        let dca_factor = (dca_flow / 10.0).min(1.0);
-        let wallet_factor = (dca_wallets as f64 / 5.0).min(1.0);
-        let flow_factor = (net_flow_300s / 50.0).min(1.0);
-        let momentum_factor = ((net_flow_300s - net_flow_900s) / net_flow_900s.abs().max(1.0)).min(1.0);
-        
-        let strength = (dca_factor * 0.3 + wallet_factor * 0.2 + flow_factor * 0.3 + momentum_factor * 0.2).clamp(0.0, 1.0);
+/// - >= 2 smart-money wallets traded in the 300s window
+/// - AND they bought at least 2 times
+/// - AND their net flow is positive
+///
+/// Strength grows with the number of smart wallets and their share of the
+/// window's buy volume.
+fn evaluate_smart_money_entry(mint: &str, metrics: &RollingMetrics, timestamp: i64) -> Option<Signal> {
+    if metrics.is_warming(&[300]) {
+        return None;
+    }
+
+    let w300 = metrics.window(300);
+
+    let smart_wallets = w300.smart_money_wallets;
+    let smart_buys = w300.smart_money_buys;
+    let smart_flow = w300.smart_money_flow_sol;
+
+    if smart_wallets >= 2 && smart_buys >= 2 && smart_flow > 0.0 {
+        let flow_share = if w300.buy_volume_sol > 0.0 {
+            (smart_flow / w300.buy_volume_sol).min(1.0)
+        } else {
+            0.0
+        };
+        let wallet_factor = (smart_wallets as f64 / 5.0).min(1.0);
+        let flow_factor = (smart_flow / 20.0).min(1.0);
+
+        let strength = (wallet_factor * 0.5 + flow_share * 0.3 + flow_factor * 0.2).clamp(0.0, 1.0);
 
         let metadata = json!({
-            "dca_flow": dca_flow,
-            "dca_wallets": dca_wallets,
-            "net_flow_300s": net_flow_300s,
-            "net_flow_900s": net_flow_900s,
-            "dca_ratio": metrics.dca_ratio_300s,
+            "smart_money_wallets": smart_wallets,
+            "smart_money_buys": smart_buys,
+            "smart_money_flow_300s": smart_flow,
+            "smart_money_flow_share": flow_share,
+            "net_flow_300s": w300.net_flow_sol,
         });
 
         return Some(Signal::new(
             mint.to_string(),
-            SignalType::Reaccumulation,
+            SignalType::SmartMoneyEntry,
             strength,
             "300s".to_string(),
             timestamp,
@@ -292,75 +395,45 @@ fn evaluate_reaccumulation(mint: &str, metrics: &RollingMetrics, timestamp: i64)
     None
 }
 
-/// Signal C: FOCUSED BUYERS
+/// Signal G: WHALE ACCUMULATION
 ///
 /// Triggered when:
-/// - Low entropy wallet distribution
-/// - F ≤ 0.35 (35% of wallets responsible for >70% inflow)
-/// - AND positive flow trend
-fn evaluate_focused_buyers(mint: &str, metrics: &RollingMetrics, recent_trades: &[TradeEvent], timestamp: i64) -> Option<Signal> {
-    if recent_trades.is_empty() || metrics.net_flow_300s_sol <= 0.0 {
+/// - >= 2 whale-sized buys (>= `sketch::WHALE_MIN_SOL`) in the 300s window
+/// - AND more whale buys than whale sells
+/// - AND net flow is positive
+///
+/// Strength grows with the number of whale buys, the skew of the buy size
+/// distribution (p99 far above p50) and net flow.
+fn evaluate_whale_accumulation(mint: &str, metrics: &RollingMetrics, timestamp: i64) -> Option<Signal> {
+    if metrics.is_warming(&[300]) {
         return None;
     }
 
-    // Compute wallet concentration (F-score)
-    let mut wallet_flows: HashMap<String, f64> = HashMap::new();
-    let mut total_inflow = 0.0;
+    let w300 = metrics.window(300);
 
-    for trade in recent_trades {
-        let flow = match trade.direction {
-            crate::types::TradeDirection::Buy => trade.sol_amount,
-            crate::types::TradeDirection::Sell => -trade.sol_amount,
-            crate::types::TradeDirection::Unknown => 0.0,
+    if w300.whale_buys >= 2 && w300.whale_buys > w300.whale_sells && w300.net_flow_sol > 0.0 {
+        let size_skew = if w300.buy_size_p99 > 0.0 {
+            1.0 - w300.buy_size_p50 / w300.buy_size_p99
+        } else {
+            0.0
         };
-        
-        if flow > 0.0 {
-            *wallet_flows.entry(trade.user_account.clone()).or_insert(0.0) += flow;
-            total_inflow += flow;
-        }
-    }
-
-    if total_inflow < 1.0 {
-        return None;
-    }
-
-    // Sort wallets by flow
-    let mut wallet_vec: Vec<(String, f64)> = wallet_flows.into_iter().collect();
-    wallet_vec.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
-    // Calculate F-score (fraction of wallets responsible for 70% of inflow)
-    let target_flow = total_inflow * 0.7;
-    let mut cumulative_flow = 0.0;
-    let mut wallets_needed = 0;
-
-    for (_, flow) in &wallet_vec {
-        cumulative_flow += flow;
-        wallets_needed += 1;
-        if cumulative_flow >= target_flow {
-            break;
-        }
-    }
-
-    let f_score = wallets_needed as f64 / wallet_vec.len() as f64;
+        let whale_factor = (w300.whale_buys as f64 / 5.0).min(1.0);
+        let flow_factor = (w300.net_flow_sol / 50.0).min(1.0);
 
-    if f_score <= 0.35 && metrics.net_flow_300s_sol > 0.0 {
-        // Compute strength
-        let concentration_factor = (1.0 - (f_score / 0.35)).clamp(0.0, 1.0);
-        let flow_factor = (metrics.net_flow_300s_sol / 50.0).min(1.0);
-        
-        let strength = (concentration_factor * 0.6 + flow_factor * 0.4).clamp(0.0, 1.0);
+        let strength = (whale_factor * 0.5 + size_skew * 0.3 + flow_factor * 0.2).clamp(0.0, 1.0);
 
         let metadata = json!({
-            "f_score": f_score,
-            "wallets_needed": wallets_needed,
-            "total_wallets": wallet_vec.len(),
-            "net_flow_300s": metrics.net_flow_300s_sol,
-            "total_inflow": total_inflow,
+            "whale_buys": w300.whale_buys,
+            "whale_sells": w300.whale_sells,
+            "retail_buys": w300.retail_buys,
+            "buy_size_p50": w300.buy_size_p50,
+            "buy_size_p99": w300.buy_size_p99,
+            "net_flow_300s": w300.net_flow_sol,
         });
 
         return Some(Signal::new(
             mint.to_string(),
-            SignalType::FocusedBuyers,
+            SignalType::WhaleAccumulation,
             strength,
             "300s".to_string(),
             timestamp,
@@ -371,46 +444,48 @@ fn evaluate_focused_buyers(mint: &str, metrics: &RollingMetrics, recent_trades:
     None
 }
 
-/// Signal D: PERSISTENCE
+/// Signal H: WHALE DISTRIBUTION
 ///
 /// Triggered when:
-/// - Token maintains positive net_flow across 3 consecutive windows (60s, 300s, 900s)
-/// - AND no collapse in unique wallets (>= 5)
-/// - AND no bot surge (<= 0.4 bot ratio)
-fn evaluate_persistence(mint: &str, metrics: &RollingMetrics, timestamp: i64) -> Option<Signal> {
-    let positive_flow_60s = metrics.net_flow_60s_sol > 0.0;
-    let positive_flow_300s = metrics.net_flow_300s_sol > 0.0;
-    let positive_flow_900s = metrics.net_flow_900s_sol > 0.0;
-    let has_wallets = metrics.unique_wallets_300s >= 5;
-    let bot_ratio = if metrics.buy_count_300s + metrics.sell_count_300s > 0 {
-        metrics.bot_trades_count_300s as f64 / (metrics.buy_count_300s + metrics.sell_count_300s) as f64
-    } else {
-        0.0
-    };
-    let no_bot_surge = bot_ratio <= 0.4;
-
-    if positive_flow_60s && positive_flow_300s && positive_flow_900s && has_wallets && no_bot_surge {
-        // Compute strength based on flow consistency and magnitude
-        let flow_consistency = 1.0 - ((metrics.net_flow_60s_sol - metrics.net_flow_300s_sol).abs() / metrics.net_flow_300s_sol.max(1.0)).min(1.0);
-        let flow_magnitude = (metrics.net_flow_900s_sol / 100.0).min(1.0);
-        let wallet_factor = (metrics.unique_wallets_300s as f64 / 20.0).min(1.0);
-        let bot_factor = (1.0 - bot_ratio).max(0.0);
-        
-        let strength = (flow_consistency * 0.3 + flow_magnitude * 0.3 + wallet_factor * 0.2 + bot_factor * 0.2).clamp(0.0, 1.0);
+/// - >= 2 whale-sized sells in the 300s window
+/// - AND more whale sells than whale buys
+/// - AND >= 10 retail-sized buys (< `sketch::RETAIL_MAX_SOL`) absorbing them
+///
+/// Strength grows with the number of whale sells, retail buys and the sell
+/// share of gross volume.
+fn evaluate_whale_distribution(mint: &str, metrics: &RollingMetrics, timestamp: i64) -> Option<Signal> {
+    if metrics.is_warming(&[300]) {
+        return None;
+    }
+
+    let w300 = metrics.window(300);
+
+    if w300.whale_sells >= 2 && w300.whale_sells > w300.whale_buys && w300.retail_buys >= 10 {
+        let sell_share = if w300.volume_sol > 0.0 {
+            w300.sell_volume_sol / w300.volume_sol
+        } else {
+            0.0
+        };
+        let whale_factor = (w300.whale_sells as f64 / 5.0).min(1.0);
+        let retail_factor = (w300.retail_buys as f64 / 50.0).min(1.0);
+
+        let strength = (whale_factor * 0.5 + retail_factor * 0.3 + sell_share * 0.2).clamp(0.0, 1.0);
 
         let metadata = json!({
-            "net_flow_60s": metrics.net_flow_60s_sol,
-            "net_flow_300s": metrics.net_flow_300s_sol,
-            "net_flow_900s": metrics.net_flow_900s_sol,
-            "unique_wallets": metrics.unique_wallets_300s,
-            "bot_ratio": bot_ratio,
+            "whale_sells": w300.whale_sells,
+            "whale_buys": w300.whale_buys,
+            "retail_buys": w300.retail_buys,
+            "sell_size_p99": w300.sell_size_p99,
+            "buy_size_p50": w300.buy_size_p50,
+            "sell_volume_share": sell_share,
+            "net_flow_300s": w300.net_flow_sol,
         });
 
         return Some(Signal::new(
             mint.to_string(),
-            SignalType::Persistence,
+            SignalType::WhaleDistribution,
             strength,
-            "900s".to_string(),
+            "300s".to_string(),
             timestamp,
             metadata,
         ));
@@ -419,49 +494,38 @@ fn evaluate_persistence(mint: &str, metrics: &RollingMetrics, timestamp: i64) ->
     None
 }
 
-/// Signal E: FLOW REVERSAL
+/// Signal I: WASH TRADING (warning)
 ///
 /// Triggered when:
-/// - 60s net flow goes negative
-/// - AND 300s still positive
-/// - AND unique wallets drop (>= 25% decrease from 300s avg)
+/// - wash score of the 300s window >= `wash::WASH_SIGNAL_SCORE`
+/// - AND at least 1 SOL of suspected wash volume
+/// - AND at least 1 wash wallet or 2 flagged reversing legs
 ///
-/// Indicates early exhaustion / impending momentum flip
-fn evaluate_flow_reversal(mint: &str, metrics: &RollingMetrics, timestamp: i64) -> Option<Signal> {
-    let flow_60s_negative = metrics.net_flow_60s_sol < 0.0;
-    let flow_300s_positive = metrics.net_flow_300s_sol > 0.0;
-    
-    // Approximate wallet "drop" by comparing unique wallets to trade volume
-    // If unique_wallets is low relative to trade count, it suggests fewer participants
-    let total_trades = metrics.buy_count_60s + metrics.sell_count_60s;
-    let wallets_per_trade = if total_trades > 0 {
-        metrics.unique_wallets_300s as f64 / total_trades as f64
-    } else {
-        0.0
-    };
-    let wallet_drop = wallets_per_trade < 0.5; // Less than 0.5 unique wallets per trade suggests consolidation
+/// Strength is the wash score.
+fn evaluate_wash_trading(mint: &str, metrics: &RollingMetrics, timestamp: i64) -> Option<Signal> {
+    if metrics.is_warming(&[300]) {
+        return None;
+    }
 
-    if flow_60s_negative && flow_300s_positive && wallet_drop {
-        // Compute strength based on divergence magnitude
-        let divergence = (metrics.net_flow_300s_sol - metrics.net_flow_60s_sol) / metrics.net_flow_300s_sol.max(1.0);
-        let divergence_factor = divergence.min(1.0);
-        let flow_magnitude = (metrics.net_flow_300s_sol / 50.0).min(1.0);
-        
-        let strength = (divergence_factor * 0.6 + flow_magnitude * 0.4).clamp(0.0, 1.0);
+    let w300 = metrics.window(300);
 
+    let has_pattern = w300.wash_wallets >= 1 || w300.wash_trades >= 2;
+    if w300.wash_score >= WASH_SIGNAL_SCORE && w300.wash_volume_sol >= 1.0 && has_pattern {
         let metadata = json!({
-            "net_flow_60s": metrics.net_flow_60s_sol,
-            "net_flow_300s": metrics.net_flow_300s_sol,
-            "unique_wallets": metrics.unique_wallets_300s,
-            "total_trades_60s": total_trades,
-            "wallets_per_trade": wallets_per_trade,
+            "wash_score": w300.wash_score,
+            "wash_volume_300s": w300.wash_volume_sol,
+            "wash_wallets": w300.wash_wallets,
+            "wash_trades": w300.wash_trades,
+            "volume_300s": w300.volume_sol,
+            "unique_wallets": w300.unique_wallets,
+            "organic_wallets": w300.organic_wallets(),
         });
 
         return Some(Signal::new(
             mint.to_string(),
-            SignalType::FlowReversal,
-            strength,
-            "60s".to_string(),
+            SignalType::WashTrading,
+            w300.wash_score,
+            "300s".to_string(),
             timestamp,
             metadata,
         ));
@@ -470,52 +534,171 @@ fn evaluate_flow_reversal(mint: &str, metrics: &RollingMetrics, timestamp: i64)
     None
 }
 
+/// Signal J: SNIPER DUMP (warning)
+///
+/// Evaluated on each sell by a launch sniper (see `snipers`), outside the
+/// rolling-window evaluators. Triggered when:
+/// - snipers bought >= `SNIPER_DUMP_MIN_SUPPLY_SHARE` of supply
+/// - AND the sell is >= `SNIPER_DUMP_MIN_SELL_SHARE` of supply
+///
+/// Strength grows with the snipers' supply share (saturating at 50%) and the
+/// fraction of it they already sold; it is normalized to the market regime.
+/// The signal is stamped with the event time `now`.
+pub fn evaluate_sniper_dump(launch: &Launch, sell: &TradeEvent, now: i64, context: &SignalContext) -> Option<Signal> {
+    let sniper = launch.snipers.get(&sell.user_account)?;
+    let supply_share = launch.sniper_supply_share();
+    let sell_share = sell.whole_tokens() / LAUNCH_SUPPLY_TOKENS;
+
+    if supply_share < SNIPER_DUMP_MIN_SUPPLY_SHARE || sell_share < SNIPER_DUMP_MIN_SELL_SHARE {
+        return None;
+    }
+
+    let sold_fraction = launch.sniper_sold_fraction();
+    let strength = ((supply_share / 0.5).min(1.0) * 0.5 + sold_fraction * 0.5).clamp(0.0, 1.0);
+
+    let metadata = json!({
+        "seller": sell.user_account,
+        "seller_kind": sniper.kind.as_str(),
+        "sell_sol": sell.sol_amount,
+        "sell_supply_share": sell_share,
+        "sniper_supply_share": supply_share,
+        "sniper_held_share": launch.sniper_held_share(),
+        "sniper_sold_fraction": sold_fraction,
+        "sniper_count": launch.snipers.len(),
+        "bundled_count": launch.count(SniperKind::Bundled),
+        "creator": launch.event.creator,
+        "launch_age_secs": sell.timestamp - launch.event.timestamp,
+    });
+
+    let mut signal = Signal::new(
+        sell.mint.clone(),
+        SignalType::SniperDump,
+        strength,
+        "launch".to_string(),
+        now,
+        metadata,
+    );
+    context.apply(&mut signal);
+    Some(signal)
+}
+
+/// Signal K: DEV SELL (high-severity warning)
+///
+/// Evaluated on each creator sell or unexplained drop in the creator's token
+/// balance (see `creators`) of at least `DEV_MOVE_MIN_SHARE` of supply.
+///
+/// Strength starts at 0.6 and grows with the share of supply the creator has
+/// sold or moved so far (saturating at 5%) and the creator's risk score. It
+/// is not normalized to the market regime: a dev exit matters in any market.
+/// The signal is stamped with the event time `timestamp`.
+pub fn evaluate_dev_sell(
+    token: &CreatedToken,
+    action: DevAction,
+    tokens: f64,
+    sol_amount: f64,
+    timestamp: i64,
+    reputation: Option<&CreatorReputation>,
+) -> Signal {
+    let out_share = token.dev_out_share();
+    let risk = reputation.map(CreatorReputation::risk_score).unwrap_or(0.0);
+    let strength = 0.6 + 0.25 * (out_share / 0.05).min(1.0) + 0.15 * risk;
+
+    let metadata = json!({
+        "severity": "high",
+        "creator": token.creator,
+        "action": action.as_str(),
+        "tokens": tokens,
+        "supply_share": tokens / LAUNCH_SUPPLY_TOKENS,
+        "sol_amount": sol_amount,
+        "dev_out_share": out_share,
+        "dev_sells": token.dev_sells,
+        "launch_age_secs": timestamp - token.launched_at,
+        "creator_tokens_launched": reputation.map(|r| r.tokens_launched),
+        "creator_dead_share": reputation.map(CreatorReputation::dead_share),
+        "creator_median_lifetime_secs": reputation.and_then(CreatorReputation::median_lifetime_secs),
+        "creator_risk": risk,
+    });
+
+    Signal::new(
+        token.mint.clone(),
+        SignalType::DevSell,
+        strength,
+        "launch".to_string(),
+        timestamp,
+        metadata,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{state::RollingMetrics, types::{TradeDirection, TradeEvent}};
+    use crate::{state::{RollingMetrics, WindowMetrics}, types::{TradeDirection, TradeEvent}};
 
     fn create_test_metrics() -> RollingMetrics {
         RollingMetrics {
-            net_flow_60s_sol: 10.0,
-            net_flow_300s_sol: 50.0,
-            net_flow_900s_sol: 40.0,
-            net_flow_3600s_sol: 200.0,
-            net_flow_7200s_sol: 300.0,
-            net_flow_14400s_sol: 500.0,
-            buy_count_60s: 5,
-            sell_count_60s: 2,
-            buy_count_300s: 25,
-            sell_count_300s: 10,
-            buy_count_900s: 60,
-            sell_count_900s: 30,
-            unique_wallets_300s: 15,
-            bot_wallets_count_300s: 2,
-            bot_trades_count_300s: 5,
-            bot_flow_300s_sol: 5.0,
-            dca_buys_60s: 2,
-            dca_buys_300s: 8,
-            dca_buys_900s: 20,
-            dca_buys_3600s: 50,
-            dca_buys_14400s: 100,
-            dca_flow_300s_sol: 15.0,
-            dca_unique_wallets_300s: 3,
-            dca_ratio_300s: 0.3,
+            windows: vec![
+            WindowMetrics {
+                window_secs: 60,
+                net_flow_sol: 10.0,
+                buy_count: 5,
+                sell_count: 2,
+                dca_buys: 2,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 300,
+                net_flow_sol: 50.0,
+                buy_count: 25,
+                sell_count: 10,
+                unique_wallets: 15,
+                unique_clusters: 15,
+                bot_wallets: 2,
+                bot_trades: 5,
+                bot_flow_sol: 5.0,
+                dca_buys: 8,
+                dca_flow_sol: 15.0,
+                dca_unique_wallets: 3,
+                dca_ratio: 0.3,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 900,
+                net_flow_sol: 40.0,
+                buy_count: 60,
+                sell_count: 30,
+                dca_buys: 20,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 3600,
+                net_flow_sol: 200.0,
+                dca_buys: 50,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 7200,
+                net_flow_sol: 300.0,
+                ..Default::default()
+            },
+            WindowMetrics {
+                window_secs: 14400,
+                net_flow_sol: 500.0,
+                dca_buys: 100,
+                ..Default::default()
+            },
+            ],
+            ..Default::default()
         }
     }
 
+    fn evaluate_rule(signal_type: SignalType, metrics: &RollingMetrics, trades: &[TradeEvent]) -> Option<Signal> {
+        RuleSet::default().get(signal_type).unwrap().evaluate("test_mint", metrics, trades, 1000)
+    }
+
     fn create_test_trade(wallet: &str, sol_amount: f64, direction: TradeDirection) -> TradeEvent {
         TradeEvent {
-            timestamp: 1000,
-            mint: "test_mint".to_string(),
-            direction,
-            sol_amount,
-            token_amount: 1000.0,
-            token_decimals: 6,
             user_account: wallet.to_string(),
-            source_program: "PumpSwap".to_string(),
-            is_bot: false,
-            is_dca: false,
+            ..TradeEvent::test("test_mint", 1000, direction, sol_amount)
         }
     }
 
@@ -524,15 +707,16 @@ mod tests {
         let mut metrics = create_test_metrics();
         
         // Setup: net_flow_300s > net_flow_900s, net_flow_60s > net_flow_300s
-        metrics.net_flow_60s_sol = 60.0;
-        metrics.net_flow_300s_sol = 50.0;
-        metrics.net_flow_900s_sol = 40.0;
-        metrics.unique_wallets_300s = 10;
-        metrics.bot_trades_count_300s = 5;
-        metrics.buy_count_300s = 25;
-        metrics.sell_count_300s = 10;
-
-        let signal = evaluate_breakout("test_mint", &metrics, 1000);
+        metrics.window_mut(60).net_flow_sol = 60.0;
+        metrics.window_mut(300).net_flow_sol = 50.0;
+        metrics.window_mut(900).net_flow_sol = 40.0;
+        metrics.window_mut(300).unique_wallets = 10;
+        metrics.window_mut(300).unique_clusters = 10;
+        metrics.window_mut(300).bot_trades = 5;
+        metrics.window_mut(300).buy_count = 25;
+        metrics.window_mut(300).sell_count = 10;
+
+        let signal = evaluate_rule(SignalType::Breakout, &metrics, &[]);
         
         assert!(signal.is_some());
         let signal = signal.unwrap();
@@ -541,20 +725,52 @@ mod tests {
         assert_eq!(signal.window, "300s");
     }
 
+    #[test]
+    fn test_flow_signals_scale_with_liquidity() {
+        let mut metrics = create_test_metrics();
+        metrics.window_mut(60).net_flow_sol = 60.0;
+        metrics.window_mut(300).net_flow_sol = 50.0;
+        metrics.window_mut(900).net_flow_sol = 40.0;
+        metrics.window_mut(300).unique_wallets = 10;
+        metrics.window_mut(300).unique_clusters = 10;
+        metrics.window_mut(300).bot_trades = 5;
+        let base = evaluate_rule(SignalType::Breakout, &metrics, &[]).unwrap();
+
+        // Unknown liquidity leaves the signal untouched
+        let mut unknown = base.clone();
+        apply_liquidity(&metrics, &mut unknown);
+        assert_eq!(unknown.strength, base.strength);
+        assert!(unknown.metadata.get("liquidity_sol").is_none());
+
+        // 50 SOL into a 100 SOL pool vs. a 10,000 SOL pool
+        metrics.liquidity_sol = Some(100.0);
+        let mut thin = base.clone();
+        apply_liquidity(&metrics, &mut thin);
+        metrics.liquidity_sol = Some(10_000.0);
+        let mut deep = base.clone();
+        apply_liquidity(&metrics, &mut deep);
+
+        assert!(thin.strength >= base.strength);
+        assert!((deep.strength - base.strength * 0.5).abs() < 1e-9);
+        assert_eq!(deep.metadata["liquidity_sol"], json!(10_000.0));
+        assert_eq!(deep.metadata["flow_liquidity_ratio_300s"], json!(0.005));
+    }
+
     #[test]
     fn test_breakout_signal_not_triggered_high_bot_ratio() {
         let mut metrics = create_test_metrics();
         
         // High bot ratio (> 0.3)
-        metrics.net_flow_60s_sol = 60.0;
-        metrics.net_flow_300s_sol = 50.0;
-        metrics.net_flow_900s_sol = 40.0;
-        metrics.unique_wallets_300s = 10;
-        metrics.bot_trades_count_300s = 15; // High bot count
-        metrics.buy_count_300s = 25;
-        metrics.sell_count_300s = 10;
-
-        let signal = evaluate_breakout("test_mint", &metrics, 1000);
+        metrics.window_mut(60).net_flow_sol = 60.0;
+        metrics.window_mut(300).net_flow_sol = 50.0;
+        metrics.window_mut(900).net_flow_sol = 40.0;
+        metrics.window_mut(300).unique_wallets = 10;
+        metrics.window_mut(300).unique_clusters = 10;
+        metrics.window_mut(300).bot_trades = 15; // High bot count
+        metrics.window_mut(300).buy_count = 25;
+        metrics.window_mut(300).sell_count = 10;
+
+        let signal = evaluate_rule(SignalType::Breakout, &metrics, &[]);
         
         assert!(signal.is_none());
     }
@@ -564,12 +780,12 @@ mod tests {
         let mut metrics = create_test_metrics();
         
         // Setup: DCA active, positive flow, momentum shift
-        metrics.dca_flow_300s_sol = 10.0;
-        metrics.dca_unique_wallets_300s = 3;
-        metrics.net_flow_300s_sol = 50.0;
-        metrics.net_flow_900s_sol = 40.0;
+        metrics.window_mut(300).dca_flow_sol = 10.0;
+        metrics.window_mut(300).dca_unique_wallets = 3;
+        metrics.window_mut(300).net_flow_sol = 50.0;
+        metrics.window_mut(900).net_flow_sol = 40.0;
 
-        let signal = evaluate_reaccumulation("test_mint", &metrics, 1000);
+        let signal = evaluate_rule(SignalType::Reaccumulation, &metrics, &[]);
         
         assert!(signal.is_some());
         let signal = signal.unwrap();
@@ -583,12 +799,12 @@ mod tests {
         let mut metrics = create_test_metrics();
         
         // Only 1 DCA wallet (needs >= 2)
-        metrics.dca_flow_300s_sol = 10.0;
-        metrics.dca_unique_wallets_300s = 1;
-        metrics.net_flow_300s_sol = 50.0;
-        metrics.net_flow_900s_sol = 40.0;
+        metrics.window_mut(300).dca_flow_sol = 10.0;
+        metrics.window_mut(300).dca_unique_wallets = 1;
+        metrics.window_mut(300).net_flow_sol = 50.0;
+        metrics.window_mut(900).net_flow_sol = 40.0;
 
-        let signal = evaluate_reaccumulation("test_mint", &metrics, 1000);
+        let signal = evaluate_rule(SignalType::Reaccumulation, &metrics, &[]);
         
         assert!(signal.is_none());
     }
@@ -609,7 +825,7 @@ mod tests {
             create_test_trade("small5", 1.0, TradeDirection::Buy),
         ];
 
-        let signal = evaluate_focused_buyers("test_mint", &metrics, &trades, 1000);
+        let signal = evaluate_rule(SignalType::FocusedBuyers, &metrics, &trades);
         
         assert!(signal.is_some());
         let signal = signal.unwrap();
@@ -618,6 +834,32 @@ mod tests {
         assert_eq!(signal.window, "300s");
     }
 
+    #[test]
+    fn test_sybil_wallets_count_as_one_buyer() {
+        let mut metrics = create_test_metrics();
+        metrics.window_mut(60).net_flow_sol = 60.0;
+
+        // 10 wallets, but funded from 2 sources
+        metrics.window_mut(300).unique_wallets = 10;
+        metrics.window_mut(300).unique_clusters = 2;
+        assert!(evaluate_rule(SignalType::Breakout, &metrics, &[]).is_none());
+
+        // Equal buys look distributed per wallet but concentrated per cluster
+        let mut trades = vec![];
+        for i in 0..18 {
+            let mut trade = create_test_trade(&format!("sybil{}", i), 5.0, TradeDirection::Buy);
+            trade.funding_cluster = Some("funder".to_string());
+            trades.push(trade);
+        }
+        for i in 0..4 {
+            trades.push(create_test_trade(&format!("wallet{}", i), 5.0, TradeDirection::Buy));
+        }
+
+        let signal = evaluate_rule(SignalType::FocusedBuyers, &metrics, &trades).unwrap();
+        assert_eq!(signal.metadata["clusters_needed"], 1);
+        assert_eq!(signal.metadata["total_clusters"], 5);
+    }
+
     #[test]
     fn test_focused_buyers_signal_not_triggered_distributed_flow() {
         let metrics = create_test_metrics();
@@ -628,7 +870,7 @@ mod tests {
             trades.push(create_test_trade(&format!("wallet{}", i), 5.0, TradeDirection::Buy));
         }
 
-        let signal = evaluate_focused_buyers("test_mint", &metrics, &trades, 1000);
+        let signal = evaluate_rule(SignalType::FocusedBuyers, &metrics, &trades);
         
         // Should not trigger (F-score will be > 0.35)
         assert!(signal.is_none());
@@ -639,15 +881,16 @@ mod tests {
         let mut metrics = create_test_metrics();
         
         // Setup: positive flow across all 3 windows
-        metrics.net_flow_60s_sol = 10.0;
-        metrics.net_flow_300s_sol = 50.0;
-        metrics.net_flow_900s_sol = 100.0;
-        metrics.unique_wallets_300s = 10;
-        metrics.bot_trades_count_300s = 5;
-        metrics.buy_count_300s = 25;
-        metrics.sell_count_300s = 10;
-
-        let signal = evaluate_persistence("test_mint", &metrics, 1000);
+        metrics.window_mut(60).net_flow_sol = 10.0;
+        metrics.window_mut(300).net_flow_sol = 50.0;
+        metrics.window_mut(900).net_flow_sol = 100.0;
+        metrics.window_mut(300).unique_wallets = 10;
+        metrics.window_mut(300).unique_clusters = 10;
+        metrics.window_mut(300).bot_trades = 5;
+        metrics.window_mut(300).buy_count = 25;
+        metrics.window_mut(300).sell_count = 10;
+
+        let signal = evaluate_rule(SignalType::Persistence, &metrics, &[]);
         
         assert!(signal.is_some());
         let signal = signal.unwrap();
@@ -661,12 +904,13 @@ mod tests {
         let mut metrics = create_test_metrics();
         
         // 60s flow is negative
-        metrics.net_flow_60s_sol = -10.0;
-        metrics.net_flow_300s_sol = 50.0;
-        metrics.net_flow_900s_sol = 100.0;
-        metrics.unique_wallets_300s = 10;
+        metrics.window_mut(60).net_flow_sol = -10.0;
+        metrics.window_mut(300).net_flow_sol = 50.0;
+        metrics.window_mut(900).net_flow_sol = 100.0;
+        metrics.window_mut(300).unique_wallets = 10;
+        metrics.window_mut(300).unique_clusters = 10;
 
-        let signal = evaluate_persistence("test_mint", &metrics, 1000);
+        let signal = evaluate_rule(SignalType::Persistence, &metrics, &[]);
         
         assert!(signal.is_none());
     }
@@ -676,13 +920,13 @@ mod tests {
         let mut metrics = create_test_metrics();
         
         // Setup: 60s negative, 300s positive, low wallets per trade
-        metrics.net_flow_60s_sol = -5.0;
-        metrics.net_flow_300s_sol = 50.0;
-        metrics.buy_count_60s = 10;
-        metrics.sell_count_60s = 5;
-        metrics.unique_wallets_300s = 5; // Low wallets relative to 15 trades
+        metrics.window_mut(60).net_flow_sol = -5.0;
+        metrics.window_mut(300).net_flow_sol = 50.0;
+        metrics.window_mut(60).buy_count = 10;
+        metrics.window_mut(60).sell_count = 5;
+        metrics.window_mut(300).unique_wallets = 5; // Low wallets relative to 15 trades
 
-        let signal = evaluate_flow_reversal("test_mint", &metrics, 1000);
+        let signal = evaluate_rule(SignalType::FlowReversal, &metrics, &[]);
         
         assert!(signal.is_some());
         let signal = signal.unwrap();
@@ -696,10 +940,10 @@ mod tests {
         let mut metrics = create_test_metrics();
         
         // Both 60s and 300s positive
-        metrics.net_flow_60s_sol = 10.0;
-        metrics.net_flow_300s_sol = 50.0;
+        metrics.window_mut(60).net_flow_sol = 10.0;
+        metrics.window_mut(300).net_flow_sol = 50.0;
 
-        let signal = evaluate_flow_reversal("test_mint", &metrics, 1000);
+        let signal = evaluate_rule(SignalType::FlowReversal, &metrics, &[]);
         
         assert!(signal.is_none());
     }
@@ -709,15 +953,16 @@ mod tests {
         let mut metrics = create_test_metrics();
         
         // Setup conditions for multiple signals
-        metrics.net_flow_60s_sol = 60.0;
-        metrics.net_flow_300s_sol = 50.0;
-        metrics.net_flow_900s_sol = 40.0;
-        metrics.unique_wallets_300s = 10;
-        metrics.bot_trades_count_300s = 5;
-        metrics.buy_count_300s = 25;
-        metrics.sell_count_300s = 10;
-        metrics.dca_flow_300s_sol = 10.0;
-        metrics.dca_unique_wallets_300s = 3;
+        metrics.window_mut(60).net_flow_sol = 60.0;
+        metrics.window_mut(300).net_flow_sol = 50.0;
+        metrics.window_mut(900).net_flow_sol = 40.0;
+        metrics.window_mut(300).unique_wallets = 10;
+        metrics.window_mut(300).unique_clusters = 10;
+        metrics.window_mut(300).bot_trades = 5;
+        metrics.window_mut(300).buy_count = 25;
+        metrics.window_mut(300).sell_count = 10;
+        metrics.window_mut(300).dca_flow_sol = 10.0;
+        metrics.window_mut(300).dca_unique_wallets = 3;
 
         let trades = vec![
             create_test_trade("whale1", 20.0, TradeDirection::Buy),
@@ -725,12 +970,14 @@ mod tests {
             create_test_trade("small1", 1.0, TradeDirection::Buy),
         ];
 
-        let signals = evaluate_signals("test_mint", &metrics, &trades);
+        let signals = evaluate_signals("test_mint", &metrics, &trades, &RuleSet::default(), 1000, &SignalContext::default());
         
         // Should trigger at least breakout, reaccumulation, and persistence
         assert!(signals.len() >= 2);
         assert!(signals.iter().any(|s| s.signal_type == SignalType::Breakout));
         assert!(signals.iter().any(|s| s.signal_type == SignalType::Reaccumulation));
+        // Stamped with the event time, not the wall clock
+        assert!(signals.iter().all(|s| s.timestamp == 1000));
     }
 
     #[test]
@@ -738,7 +985,7 @@ mod tests {
         let metrics = create_test_metrics();
         let trades = vec![create_test_trade("wallet1", 50.0, TradeDirection::Buy)];
 
-        let signals = evaluate_signals("test_mint", &metrics, &trades);
+        let signals = evaluate_signals("test_mint", &metrics, &trades, &RuleSet::default(), 1000, &SignalContext::default());
         
         for signal in signals {
             assert!(signal.strength >= 0.0);
@@ -746,6 +993,122 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_smart_money_entry_signal_triggered() {
+        let mut metrics = create_test_metrics();
+        assert!(evaluate_smart_money_entry("test_mint", &metrics, 1000).is_none());
+
+        let w300 = metrics.window_mut(300);
+        w300.buy_volume_sol = 60.0;
+        w300.smart_money_wallets = 3;
+        w300.smart_money_buys = 4;
+        w300.smart_money_flow_sol = 12.0;
+
+        let signal = evaluate_smart_money_entry("test_mint", &metrics, 1000).unwrap();
+        assert_eq!(signal.signal_type, SignalType::SmartMoneyEntry);
+        assert_eq!(signal.window, "300s");
+        // 0.6 * 0.5 + 0.2 * 0.3 + 0.6 * 0.2
+        assert!((signal.strength - 0.48).abs() < 1e-9);
+        assert_eq!(signal.metadata["smart_money_wallets"], 3);
+    }
+
+    #[test]
+    fn test_smart_money_entry_not_triggered_when_selling() {
+        let mut metrics = create_test_metrics();
+        let w300 = metrics.window_mut(300);
+        w300.smart_money_wallets = 3;
+        w300.smart_money_buys = 2;
+        w300.smart_money_flow_sol = -5.0;
+
+        assert!(evaluate_smart_money_entry("test_mint", &metrics, 1000).is_none());
+    }
+
+    #[test]
+    fn test_whale_accumulation_signal_triggered() {
+        let mut metrics = create_test_metrics();
+        assert!(evaluate_whale_accumulation("test_mint", &metrics, 1000).is_none());
+
+        let w300 = metrics.window_mut(300);
+        w300.whale_buys = 3;
+        w300.whale_sells = 1;
+        w300.buy_size_p50 = 0.5;
+        w300.buy_size_p99 = 25.0;
+
+        let signal = evaluate_whale_accumulation("test_mint", &metrics, 1000).unwrap();
+        assert_eq!(signal.signal_type, SignalType::WhaleAccumulation);
+        // 0.6 * 0.5 + 0.98 * 0.3 + 1.0 * 0.2
+        assert!((signal.strength - 0.794).abs() < 1e-9);
+
+        // Whales on both sides is not accumulation
+        metrics.window_mut(300).whale_sells = 3;
+        assert!(evaluate_whale_accumulation("test_mint", &metrics, 1000).is_none());
+    }
+
+    #[test]
+    fn test_whale_distribution_needs_retail_buyers() {
+        let mut metrics = create_test_metrics();
+        let w300 = metrics.window_mut(300);
+        w300.whale_sells = 2;
+        w300.retail_buys = 4;
+        assert!(evaluate_whale_distribution("test_mint", &metrics, 1000).is_none());
+
+        let w300 = metrics.window_mut(300);
+        w300.retail_buys = 25;
+        w300.sell_volume_sol = 30.0;
+        w300.volume_sol = 60.0;
+
+        let signal = evaluate_whale_distribution("test_mint", &metrics, 1000).unwrap();
+        assert_eq!(signal.signal_type, SignalType::WhaleDistribution);
+        // 0.4 * 0.5 + 0.5 * 0.3 + 0.5 * 0.2
+        assert!((signal.strength - 0.45).abs() < 1e-9);
+        assert_eq!(signal.metadata["retail_buys"], 25);
+    }
+
+    #[test]
+    fn test_regime_normalizes_strength() {
+        let metrics = create_test_metrics();
+        let trades = vec![create_test_trade("wallet1", 50.0, TradeDirection::Buy)];
+
+        let normal = evaluate_signals("test_mint", &metrics, &trades, &RuleSet::default(), 1000, &SignalContext::default());
+        let hot = SignalContext {
+            regime: MarketRegime::Euphoric,
+            activity: 3.0,
+        };
+        let euphoric = evaluate_signals("test_mint", &metrics, &trades, &RuleSet::default(), 1000, &hot);
+
+        assert_eq!(normal.len(), euphoric.len());
+        for (normal, euphoric) in normal.iter().zip(&euphoric) {
+            assert!((euphoric.strength - normal.strength * 0.7).abs() < 1e-9);
+            assert_eq!(euphoric.metadata["market_regime"], "euphoric");
+            assert_eq!(euphoric.metadata["raw_strength"], normal.strength);
+        }
+    }
+
+    #[test]
+    fn test_wash_trading_signal() {
+        let mut metrics = create_test_metrics();
+        assert!(evaluate_wash_trading("test_mint", &metrics, 1000).is_none());
+
+        let w300 = metrics.window_mut(300);
+        w300.volume_sol = 40.0;
+        w300.wash_volume_sol = 30.0;
+        w300.wash_wallets = 3;
+        w300.wash_trades = 12;
+        w300.wash_score = 0.85;
+
+        let signal = evaluate_wash_trading("test_mint", &metrics, 1000).unwrap();
+        assert_eq!(signal.signal_type, SignalType::WashTrading);
+        assert_eq!(signal.strength, 0.85);
+        assert_eq!(signal.metadata["organic_wallets"], 12);
+
+        // Wash wallets no longer count towards breakout's wallet threshold
+        metrics.window_mut(60).net_flow_sol = 60.0;
+        metrics.window_mut(900).net_flow_sol = 40.0;
+        assert!(evaluate_rule(SignalType::Breakout, &metrics, &[]).is_some());
+        metrics.window_mut(300).wash_wallets = 12;
+        assert!(evaluate_rule(SignalType::Breakout, &metrics, &[]).is_none());
+    }
+
     #[test]
     fn test_signal_type_as_str() {
         assert_eq!(SignalType::Breakout.as_str(), "BREAKOUT");
@@ -753,13 +1116,19 @@ mod tests {
         assert_eq!(SignalType::FocusedBuyers.as_str(), "FOCUSED_BUYERS");
         assert_eq!(SignalType::Persistence.as_str(), "PERSISTENCE");
         assert_eq!(SignalType::FlowReversal.as_str(), "FLOW_REVERSAL");
+        assert_eq!(SignalType::SmartMoneyEntry.as_str(), "SMART_MONEY_ENTRY");
+        assert_eq!(SignalType::WhaleAccumulation.as_str(), "WHALE_ACCUMULATION");
+        assert_eq!(SignalType::WhaleDistribution.as_str(), "WHALE_DISTRIBUTION");
+        assert_eq!(SignalType::WashTrading.as_str(), "WASH_TRADING");
+        assert_eq!(SignalType::SniperDump.as_str(), "SNIPER_DUMP");
+        assert_eq!(SignalType::DevSell.as_str(), "DEV_SELL");
     }
 
     #[test]
     fn test_signal_metadata_includes_key_metrics() {
         let metrics = create_test_metrics();
         
-        let signal = evaluate_breakout("test_mint", &metrics, 1000);
+        let signal = evaluate_rule(SignalType::Breakout, &metrics, &[]);
         
         if let Some(signal) = signal {
             assert!(signal.metadata.get("net_flow_60s").is_some());
@@ -774,7 +1143,7 @@ mod tests {
         let metrics = create_test_metrics();
         let trades = vec![];
 
-        let signal = evaluate_focused_buyers("test_mint", &metrics, &trades, 1000);
+        let signal = evaluate_rule(SignalType::FocusedBuyers, &metrics, &trades);
         
         assert!(signal.is_none());
     }
@@ -782,14 +1151,14 @@ mod tests {
     #[test]
     fn test_focused_buyers_negative_net_flow() {
         let mut metrics = create_test_metrics();
-        metrics.net_flow_300s_sol = -50.0; // Negative flow
+        metrics.window_mut(300).net_flow_sol = -50.0; // Negative flow
 
         let trades = vec![
             create_test_trade("whale1", 20.0, TradeDirection::Buy),
             create_test_trade("whale2", 15.0, TradeDirection::Buy),
         ];
 
-        let signal = evaluate_focused_buyers("test_mint", &metrics, &trades, 1000);
+        let signal = evaluate_rule(SignalType::FocusedBuyers, &metrics, &trades);
         
         assert!(signal.is_none());
     }
@@ -799,13 +1168,101 @@ mod tests {
         let mut metrics = create_test_metrics();
         
         // Zero trades (bot ratio calculation edge case)
-        metrics.buy_count_300s = 0;
-        metrics.sell_count_300s = 0;
-        metrics.bot_trades_count_300s = 0;
+        metrics.window_mut(300).buy_count = 0;
+        metrics.window_mut(300).sell_count = 0;
+        metrics.window_mut(300).bot_trades = 0;
 
-        let signal = evaluate_breakout("test_mint", &metrics, 1000);
+        let signal = evaluate_rule(SignalType::Breakout, &metrics, &[]);
         
         // Should not trigger (not enough trades)
         assert!(signal.is_none());
     }
+
+    #[test]
+    fn test_sniper_dump_signal() {
+        use crate::{snipers::SniperTracker, types::LaunchEvent};
+
+        let tracker = SniperTracker::new(3);
+        tracker.record_launch(LaunchEvent {
+            mint: "test_mint".to_string(),
+            creator: "dev".to_string(),
+            source_program: "Pumpfun".to_string(),
+            timestamp: 1000,
+            slot: 100,
+            tip_lamports: 10_000,
+            migration: false,
+        });
+
+        // Two bundled snipers take 30% of supply
+        let mut buy = create_test_trade("sniper1", 2.0, TradeDirection::Buy);
+        buy.slot = 100;
+        buy.token_decimals = 0;
+        buy.token_amount = 2e8;
+        tracker.observe(&buy);
+        buy.user_account = "sniper2".to_string();
+        buy.token_amount = 1e8;
+        tracker.observe(&buy);
+
+        let mut sell = create_test_trade("sniper1", 3.0, TradeDirection::Sell);
+        sell.slot = 300;
+        sell.token_decimals = 0;
+        sell.token_amount = 2e8;
+        let launch = tracker.observe(&sell).unwrap();
+
+        let signal = evaluate_sniper_dump(&launch, &sell, 1060, &SignalContext::default()).unwrap();
+        assert_eq!(signal.signal_type, SignalType::SniperDump);
+        assert_eq!(signal.timestamp, 1060);
+        assert_eq!(signal.metadata["seller_kind"], "bundled");
+        assert_eq!(signal.metadata["bundled_count"], 2);
+        assert!((signal.strength - (0.3 / 0.5 * 0.5 + 2.0 / 3.0 * 0.5)).abs() < 1e-9);
+
+        // Dust sells are not dumps
+        sell.token_amount = 1e6;
+        let launch = tracker.observe(&sell).unwrap();
+        assert!(evaluate_sniper_dump(&launch, &sell, 1060, &SignalContext::default()).is_none());
+    }
+
+    #[test]
+    fn test_dev_sell_signal() {
+        use crate::{creators::CreatorTracker, types::LaunchEvent};
+
+        let tracker = CreatorTracker::default();
+        tracker.record_launch(&LaunchEvent {
+            mint: "test_mint".to_string(),
+            creator: "dev".to_string(),
+            source_program: "Pumpfun".to_string(),
+            timestamp: 1000,
+            slot: 100,
+            tip_lamports: 0,
+            migration: false,
+        });
+
+        let mut sell = create_test_trade("dev", 2.0, TradeDirection::Sell);
+        sell.token_decimals = 0;
+        sell.token_amount = 2.5e7;
+        let token = tracker.observe(&sell).unwrap();
+
+        let signal = evaluate_dev_sell(&token, DevAction::Sell, 2.5e7, 2.0, 1060, tracker.reputation("dev").as_ref());
+        assert_eq!(signal.signal_type, SignalType::DevSell);
+        assert_eq!(signal.timestamp, 1060);
+        assert_eq!(signal.metadata["action"], "sell");
+        assert_eq!(signal.metadata["launch_age_secs"], 60);
+        // No finished tokens yet: no risk, half of the 5% saturation
+        assert!((signal.strength - (0.6 + 0.25 * 0.5)).abs() < 1e-9);
+        assert!(signal.metadata["creator_median_lifetime_secs"].is_null());
+    }
+
+    #[test]
+    fn test_signals_skipped_while_warming() {
+        let mut metrics = create_test_metrics();
+        metrics.window_mut(60).net_flow_sol = 60.0;
+        metrics.window_mut(300).unique_wallets = 10;
+        metrics.window_mut(300).unique_clusters = 10;
+        assert!(evaluate_rule(SignalType::Breakout, &metrics, &[]).is_some());
+
+        metrics.window_mut(900).warming = true;
+        assert!(evaluate_rule(SignalType::Breakout, &metrics, &[]).is_none());
+        assert!(evaluate_rule(SignalType::Persistence, &metrics, &[]).is_none());
+    }
+
 }
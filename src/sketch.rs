@@ -0,0 +1,196 @@
+//! Streaming trade-size distributions
+//!
+//! Rolling windows need sketches that support deletion (trades are evicted),
+//! so `SizeSketch` is a log-bucketed histogram: each value is counted in the
+//! bucket `ceil(log_gamma(x))`, which bounds the relative error of every
+//! quantile by `RELATIVE_ACCURACY`. Adding and removing are O(log buckets);
+//! sizes between 1e-6 and 1e5 SOL span at most ~640 buckets.
+//!
+//! Trades are also counted in coarse size classes (`SizeClass`).
+
+use std::collections::BTreeMap;
+
+/// Relative accuracy of sketch quantiles (2%)
+pub const RELATIVE_ACCURACY: f64 = 0.02;
+
+/// Trades below this size (SOL) are retail
+pub const RETAIL_MAX_SOL: f64 = 1.0;
+
+/// Trades at or above this size (SOL) are whale trades
+pub const WHALE_MIN_SOL: f64 = 10.0;
+
+/// Quantiles reported per window and side
+pub const SIZE_QUANTILES: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// Estimated bytes per sketch bucket (BTreeMap entry)
+const BUCKET_BYTES: usize = 32;
+
+/// Coarse trade size class
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SizeClass {
+    /// Below `RETAIL_MAX_SOL`
+    Retail,
+    Mid,
+    /// At or above `WHALE_MIN_SOL`
+    Whale,
+}
+
+impl SizeClass {
+    pub fn of(sol_amount: f64) -> Self {
+        if sol_amount < RETAIL_MAX_SOL {
+            SizeClass::Retail
+        } else if sol_amount < WHALE_MIN_SOL {
+            SizeClass::Mid
+        } else {
+            SizeClass::Whale
+        }
+    }
+}
+
+/// Log-bucketed histogram of positive values with add and remove
+#[derive(Debug, Clone, Default)]
+pub struct SizeSketch {
+    buckets: BTreeMap<i32, u32>,
+    /// Values <= 0 (unknown sizes), reported as 0.0
+    zeros: u32,
+    count: u32,
+}
+
+impl SizeSketch {
+    fn gamma() -> f64 {
+        (1.0 + RELATIVE_ACCURACY) / (1.0 - RELATIVE_ACCURACY)
+    }
+
+    fn key(value: f64) -> i32 {
+        (value.ln() / Self::gamma().ln()).ceil() as i32
+    }
+
+    /// Representative value of a bucket (relative error <= RELATIVE_ACCURACY)
+    fn value(key: i32) -> f64 {
+        let gamma = Self::gamma();
+        2.0 * gamma.powi(key) / (gamma + 1.0)
+    }
+
+    pub fn add(&mut self, value: f64) {
+        self.count += 1;
+        if value > 0.0 {
+            *self.buckets.entry(Self::key(value)).or_insert(0) += 1;
+        } else {
+            self.zeros += 1;
+        }
+    }
+
+    /// Remove a value previously added (no-op if it is not present)
+    pub fn remove(&mut self, value: f64) {
+        if value > 0.0 {
+            let key = Self::key(value);
+            let Some(count) = self.buckets.get_mut(&key) else {
+                return;
+            };
+            *count -= 1;
+            if *count == 0 {
+                self.buckets.remove(&key);
+            }
+        } else if self.zeros > 0 {
+            self.zeros -= 1;
+        } else {
+            return;
+        }
+        self.count -= 1;
+    }
+
+    /// Number of values in the sketch
+    pub fn len(&self) -> u32 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Approximate value at quantile `q` (0.0 for an empty sketch)
+    pub fn quantile(&self, q: f64) -> f64 {
+        self.quantiles(&[q])[0]
+    }
+
+    /// Approximate values at ascending quantiles `qs`, in one pass
+    pub fn quantiles<const N: usize>(&self, qs: &[f64; N]) -> [f64; N] {
+        let mut values = [0.0; N];
+        if self.count == 0 {
+            return values;
+        }
+
+        // Rank (0-based) of each quantile
+        let ranks = qs.map(|q| (q.clamp(0.0, 1.0) * (self.count - 1) as f64).round() as u32);
+        let mut next = ranks.iter().position(|&rank| rank >= self.zeros).unwrap_or(N);
+
+        let mut seen = self.zeros;
+        for (&key, &count) in &self.buckets {
+            seen += count;
+            while next < N && ranks[next] < seen {
+                values[next] = Self::value(key);
+                next += 1;
+            }
+            if next == N {
+                break;
+            }
+        }
+
+        values
+    }
+
+    /// Approximate heap footprint in bytes
+    pub fn approx_bytes(&self) -> usize {
+        self.buckets.len() * BUCKET_BYTES
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantiles_within_relative_accuracy() {
+        let mut sketch = SizeSketch::default();
+        for i in 1..=1_000 {
+            sketch.add(i as f64 * 0.01);
+        }
+
+        let [p50, p90, p99] = sketch.quantiles(&SIZE_QUANTILES);
+        for (value, exact) in [(p50, 5.0), (p90, 9.0), (p99, 9.9)] {
+            assert!((value - exact).abs() <= exact * (RELATIVE_ACCURACY + 0.002), "{} vs {}", value, exact);
+        }
+        assert_eq!(sketch.len(), 1_000);
+    }
+
+    #[test]
+    fn test_remove_restores_distribution() {
+        let mut sketch = SizeSketch::default();
+        sketch.add(0.5);
+        sketch.add(50.0);
+        sketch.add(0.0);
+        assert!((sketch.quantile(1.0) - 50.0).abs() < 1.0);
+        assert_eq!(sketch.quantile(0.0), 0.0);
+
+        sketch.remove(50.0);
+        sketch.remove(0.0);
+        assert_eq!(sketch.len(), 1);
+        assert!((sketch.quantile(0.99) - 0.5).abs() < 0.01);
+
+        // Removing a value that was never added changes nothing
+        sketch.remove(7.0);
+        assert_eq!(sketch.len(), 1);
+
+        sketch.remove(0.5);
+        assert!(sketch.is_empty());
+        assert_eq!(sketch.approx_bytes(), 0);
+        assert_eq!(sketch.quantiles(&SIZE_QUANTILES), [0.0; 3]);
+    }
+
+    #[test]
+    fn test_size_classes() {
+        assert_eq!(SizeClass::of(0.2), SizeClass::Retail);
+        assert_eq!(SizeClass::of(RETAIL_MAX_SOL), SizeClass::Mid);
+        assert_eq!(SizeClass::of(WHALE_MIN_SOL), SizeClass::Whale);
+    }
+}
@@ -0,0 +1,427 @@
+//! Sniper and bundled-launch detection
+//!
+//! Venues report token creations (Pumpfun `create`) and pool creations
+//! (PumpSwap `create_pool`) as `LaunchEvent`s; the first one seen for a mint
+//! starts tracking it. Pools pump.fun creates when it migrates a token are
+//! not launches: the token was created long before, and buyers landing in
+//! the migration slot are ordinary traders, not snipers. Every wallet other than the creator that buys within
+//! the first `snipe_slots` slots is a sniper:
+//! - bundled: bought in the creation slot while the creation or the buy paid
+//!   a Jito tip, i.e. landed in a bundle with the creator
+//! - same slot: bought in the creation slot without a tip
+//! - early: bought in a later slot of the snipe window
+//!
+//! Snipers are followed after the window: their later buys and sells update
+//! their position, so the share of supply they hold shrinks as they dump and
+//! each sniper sell is evaluated as a `SNIPER_DUMP` signal. Shares are
+//! relative to `LAUNCH_SUPPLY_TOKENS`, the fixed supply of Pumpfun tokens.
+//!
+//! Launches are persisted to `token_launches` / `launch_snipers` and restored
+//! on startup; launches without snipers are dropped once their snipe window
+//! has passed, the others after `LAUNCH_IDLE_SECS` without sniper activity.
+
+use crate::{
+    db::WriteRequest,
+    types::{LaunchEvent, TradeDirection, TradeEvent},
+    watermark::EventClock,
+};
+use dashmap::DashMap;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+
+/// Total supply of a launched token (whole tokens)
+pub const LAUNCH_SUPPLY_TOKENS: f64 = 1_000_000_000.0;
+
+/// Default number of slots after creation in which buyers are snipers
+pub const DEFAULT_SNIPE_SLOTS: u64 = 5;
+
+/// Launches without sniper activity for this long are dropped from memory
+pub const LAUNCH_IDLE_SECS: i64 = 86_400;
+
+/// Sniper supply share at or above which sniper sells are SNIPER_DUMP signals
+pub const SNIPER_DUMP_MIN_SUPPLY_SHARE: f64 = 0.1;
+
+/// Minimum size of a dumping sell, as a share of supply
+pub const SNIPER_DUMP_MIN_SELL_SHARE: f64 = 0.005;
+
+/// Seconds after which a launch without snipers is dropped (its snipe window
+/// has long passed)
+const EMPTY_LAUNCH_SECS: i64 = 60;
+
+/// How a sniper entered the launch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SniperKind {
+    /// Creation slot, with a Jito tip on the creation or the buy
+    Bundled,
+    /// Creation slot, no tip
+    SameSlot,
+    /// Later slot of the snipe window
+    Early,
+}
+
+impl SniperKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SniperKind::Bundled => "bundled",
+            SniperKind::SameSlot => "same_slot",
+            SniperKind::Early => "early",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "bundled" => Some(SniperKind::Bundled),
+            "same_slot" => Some(SniperKind::SameSlot),
+            "early" => Some(SniperKind::Early),
+            _ => None,
+        }
+    }
+}
+
+/// Position of one sniper in a launch (whole tokens, SOL)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SniperPosition {
+    pub kind: SniperKind,
+    /// Slot of the sniper's first buy
+    pub first_slot: u64,
+    pub bought_tokens: f64,
+    pub sold_tokens: f64,
+    pub bought_sol: f64,
+    pub sold_sol: f64,
+}
+
+impl SniperPosition {
+    /// Tokens still held (never negative)
+    pub fn held_tokens(&self) -> f64 {
+        (self.bought_tokens - self.sold_tokens).max(0.0)
+    }
+}
+
+/// A tracked launch and its snipers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Launch {
+    pub event: LaunchEvent,
+    pub snipers: HashMap<String, SniperPosition>,
+    /// Event time of the last sniper trade (the launch time before any)
+    pub last_activity: i64,
+}
+
+impl Launch {
+    pub fn new(event: LaunchEvent) -> Self {
+        Self {
+            last_activity: event.timestamp,
+            event,
+            snipers: HashMap::new(),
+        }
+    }
+
+    /// Share of supply bought by snipers
+    pub fn sniper_supply_share(&self) -> f64 {
+        self.snipers.values().map(|s| s.bought_tokens).sum::<f64>() / LAUNCH_SUPPLY_TOKENS
+    }
+
+    /// Share of supply snipers still hold
+    pub fn sniper_held_share(&self) -> f64 {
+        self.snipers.values().map(SniperPosition::held_tokens).sum::<f64>() / LAUNCH_SUPPLY_TOKENS
+    }
+
+    /// Fraction of the snipers' bought tokens they sold (0.0 ..= 1.0)
+    pub fn sniper_sold_fraction(&self) -> f64 {
+        let bought: f64 = self.snipers.values().map(|s| s.bought_tokens).sum();
+        let sold: f64 = self.snipers.values().map(|s| s.sold_tokens.min(s.bought_tokens)).sum();
+        if bought > 0.0 {
+            sold / bought
+        } else {
+            0.0
+        }
+    }
+
+    /// Snipers of `kind`
+    pub fn count(&self, kind: SniperKind) -> usize {
+        self.snipers.values().filter(|s| s.kind == kind).count()
+    }
+
+    /// Classify a buy inside the snipe window (None outside it or for the
+    /// creator)
+    fn classify(&self, trade: &TradeEvent, snipe_slots: u64) -> Option<SniperKind> {
+        let creation_slot = self.event.slot;
+        if trade.user_account == self.event.creator
+            || trade.slot < creation_slot
+            || trade.slot > creation_slot + snipe_slots
+        {
+            return None;
+        }
+
+        Some(if trade.slot > creation_slot {
+            SniperKind::Early
+        } else if trade.tip_lamports > 0 || self.event.tip_lamports > 0 {
+            SniperKind::Bundled
+        } else {
+            SniperKind::SameSlot
+        })
+    }
+}
+
+/// Shared launch tracker consulted on every extracted trade
+#[derive(Debug)]
+pub struct SniperTracker {
+    launches: DashMap<String, Launch>,
+    /// Mints whose launch changed since the last `take_dirty`
+    dirty: DashMap<String, ()>,
+    snipe_slots: u64,
+}
+
+impl Default for SniperTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_SNIPE_SLOTS)
+    }
+}
+
+impl SniperTracker {
+    pub fn new(snipe_slots: u64) -> Self {
+        Self {
+            launches: DashMap::new(),
+            dirty: DashMap::new(),
+            snipe_slots,
+        }
+    }
+
+    /// Start tracking a launch; returns false if the mint is already tracked
+    /// (a pool created for a token whose creation was seen)
+    pub fn record_launch(&self, event: LaunchEvent) -> bool {
+        if event.migration || self.launches.contains_key(&event.mint) {
+            return false;
+        }
+        self.dirty.insert(event.mint.clone(), ());
+        self.launches.insert(event.mint.clone(), Launch::new(event));
+        true
+    }
+
+    /// Update sniper positions with a trade
+    ///
+    /// Returns a snapshot of the launch when the trade is a sniper's sell,
+    /// for `signals::evaluate_sniper_dump`.
+    pub fn observe(&self, trade: &TradeEvent) -> Option<Launch> {
+        let mut launch = self.launches.get_mut(&trade.mint)?;
+        let tokens = trade.whole_tokens();
+
+        match (launch.snipers.get_mut(&trade.user_account), trade.direction) {
+            (Some(sniper), TradeDirection::Buy) => {
+                sniper.bought_tokens += tokens;
+                sniper.bought_sol += trade.sol_amount;
+            }
+            (Some(sniper), TradeDirection::Sell) => {
+                sniper.sold_tokens += tokens;
+                sniper.sold_sol += trade.sol_amount;
+            }
+            (None, TradeDirection::Buy) => {
+                let kind = launch.classify(trade, self.snipe_slots)?;
+                launch.snipers.insert(
+                    trade.user_account.clone(),
+                    SniperPosition {
+                        kind,
+                        first_slot: trade.slot,
+                        bought_tokens: tokens,
+                        sold_tokens: 0.0,
+                        bought_sol: trade.sol_amount,
+                        sold_sol: 0.0,
+                    },
+                );
+            }
+            _ => return None,
+        }
+
+        launch.last_activity = launch.last_activity.max(trade.timestamp);
+        self.dirty.insert(trade.mint.clone(), ());
+
+        (trade.direction == TradeDirection::Sell).then(|| launch.clone())
+    }
+
+    /// Snapshot of a tracked launch
+    pub fn launch(&self, mint: &str) -> Option<Launch> {
+        self.launches.get(mint).map(|l| l.clone())
+    }
+
+    /// Number of launches held in memory
+    pub fn len(&self) -> usize {
+        self.launches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.launches.is_empty()
+    }
+
+    /// Changed launches since the last call, clearing the dirty set
+    pub fn take_dirty(&self) -> Vec<Launch> {
+        let mints: Vec<String> = self.dirty.iter().map(|e| e.key().clone()).collect();
+        mints
+            .into_iter()
+            .filter_map(|mint| {
+                self.dirty.remove(&mint);
+                self.launch(&mint)
+            })
+            .collect()
+    }
+
+    /// Load persisted launches (not marked dirty)
+    pub fn restore(&self, launches: Vec<Launch>) {
+        for launch in launches {
+            self.launches.insert(launch.event.mint.clone(), launch);
+        }
+    }
+
+    /// Drop launches without snipers past their snipe window and idle ones
+    /// at `now`, returning how many
+    pub fn prune(&self, now: i64) -> usize {
+        let before = self.launches.len();
+        self.launches.retain(|mint, launch| {
+            let keep = if launch.snipers.is_empty() {
+                launch.event.timestamp >= now - EMPTY_LAUNCH_SECS
+            } else {
+                launch.last_activity >= now - LAUNCH_IDLE_SECS
+            };
+            keep || self.dirty.contains_key(mint)
+        });
+        before - self.launches.len()
+    }
+}
+
+/// Background loop that persists changed launches and prunes stale ones
+///
+/// Skipped until the event clock has a time.
+pub async fn run_persist_loop(
+    tracker: Arc<SniperTracker>,
+    writer: mpsc::Sender<WriteRequest>,
+    clock: Arc<EventClock>,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+
+        // Launches that never got a sniper are not worth a row
+        let launches: Vec<Launch> = tracker
+            .take_dirty()
+            .into_iter()
+            .filter(|launch| !launch.snipers.is_empty())
+            .collect();
+        let pruned = tracker.prune(now);
+        if !launches.is_empty() || pruned > 0 {
+            log::debug!(
+                "🎯 LAUNCHES | Persisting: {} | Pruned: {} | In memory: {}",
+                launches.len(),
+                pruned,
+                tracker.len()
+            );
+        }
+
+        if launches.is_empty() {
+            continue;
+        }
+        if let Err(e) = writer.send(WriteRequest::Launches(launches)).await {
+            log::warn!("⚠️  Failed to send launches to writer: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launch(slot: u64, tip_lamports: u64) -> LaunchEvent {
+        LaunchEvent {
+            mint: "mint".to_string(),
+            creator: "dev".to_string(),
+            source_program: "Pumpfun".to_string(),
+            timestamp: 1_000,
+            slot,
+            tip_lamports,
+            migration: false,
+        }
+    }
+
+    fn trade(wallet: &str, direction: TradeDirection, slot: u64, tokens: f64, tip_lamports: u64) -> TradeEvent {
+        TradeEvent {
+            token_amount: tokens * 1_000_000.0,
+            user_account: wallet.to_string(),
+            source_program: "Pumpfun".to_string(),
+            slot,
+            tip_lamports,
+            ..TradeEvent::test("mint", 1_000 + (slot as i64 - 100), direction, tokens / 10_000_000.0)
+        }
+    }
+
+    #[test]
+    fn test_snipers_classified_by_slot_and_tip() {
+        let tracker = SniperTracker::new(3);
+        assert!(tracker.record_launch(launch(100, 0)));
+        assert!(!tracker.record_launch(launch(500, 0)));
+
+        tracker.observe(&trade("dev", TradeDirection::Buy, 100, 1e8, 0));
+        tracker.observe(&trade("bundler", TradeDirection::Buy, 100, 1e8, 10_000));
+        tracker.observe(&trade("same", TradeDirection::Buy, 100, 5e7, 0));
+        tracker.observe(&trade("early", TradeDirection::Buy, 103, 5e7, 0));
+        tracker.observe(&trade("late", TradeDirection::Buy, 104, 1e8, 0));
+
+        let launch = tracker.launch("mint").unwrap();
+        assert_eq!(launch.snipers.len(), 3);
+        assert_eq!(launch.count(SniperKind::Bundled), 1);
+        assert_eq!(launch.count(SniperKind::SameSlot), 1);
+        assert_eq!(launch.count(SniperKind::Early), 1);
+        assert!((launch.sniper_supply_share() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_migration_does_not_open_a_launch() {
+        let tracker = SniperTracker::new(3);
+        let migration = LaunchEvent {
+            source_program: "PumpSwap".to_string(),
+            migration: true,
+            ..launch(100, 0)
+        };
+        assert!(!tracker.record_launch(migration));
+
+        // Buyers in the migration slot are not snipers and their sells are
+        // not dumps
+        tracker.observe(&trade("buyer", TradeDirection::Buy, 100, 3e8, 10_000));
+        assert!(tracker.launch("mint").is_none());
+        assert!(tracker.observe(&trade("buyer", TradeDirection::Sell, 200, 3e8, 0)).is_none());
+    }
+
+    #[test]
+    fn test_sniper_sells_reduce_held_share() {
+        let tracker = SniperTracker::new(3);
+        tracker.record_launch(launch(100, 5_000));
+        tracker.observe(&trade("a", TradeDirection::Buy, 100, 2e8, 0));
+        tracker.observe(&trade("b", TradeDirection::Buy, 101, 2e8, 0));
+
+        // Non-snipers and buys do not yield a dump snapshot
+        assert!(tracker.observe(&trade("late", TradeDirection::Sell, 200, 1e7, 0)).is_none());
+        assert!(tracker.observe(&trade("a", TradeDirection::Buy, 150, 1e8, 0)).is_none());
+
+        let launch = tracker.observe(&trade("a", TradeDirection::Sell, 200, 3e8, 0)).unwrap();
+        assert_eq!(launch.count(SniperKind::Bundled), 1);
+        assert!((launch.sniper_supply_share() - 0.5).abs() < 1e-9);
+        assert!((launch.sniper_held_share() - 0.2).abs() < 1e-9);
+        assert!((launch.sniper_sold_fraction() - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_prune_keeps_launches_with_snipers() {
+        let tracker = SniperTracker::new(3);
+        tracker.record_launch(launch(100, 0));
+        tracker.record_launch(LaunchEvent { mint: "quiet".to_string(), ..launch(100, 0) });
+        tracker.observe(&trade("a", TradeDirection::Buy, 100, 1e8, 0));
+        assert_eq!(tracker.take_dirty().len(), 2);
+
+        assert_eq!(tracker.prune(1_000 + EMPTY_LAUNCH_SECS + 1), 1);
+        assert!(tracker.launch("mint").is_some());
+        assert_eq!(tracker.prune(1_000 + LAUNCH_IDLE_SECS + 1), 1);
+        assert!(tracker.is_empty());
+    }
+}
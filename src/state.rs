@@ -1,464 +1,888 @@
 //! In-memory rolling state management for tokens
 //!
-//! Phase 2: Data-model scaffolding only
-//! No analytics logic, detection, or scoring implemented
+//! Incremental window engine
+//!
+//! Every trade is stored once in a single time-ordered buffer. Each window
+//! keeps a cursor (the sequence number of its oldest trade) plus running
+//! sums, counts and refcounted wallet sets. Adding a trade updates every
+//! window's aggregates once; eviction advances cursors and subtracts the
+//! trades that fell out. Both are amortized O(1) per trade and window, and
+//! computing metrics is a read of the aggregates.
+
+use crate::{
+    candles::{Candle, CandleBook},
+    positions::PositionBook,
+    sketch::{SizeClass, SizeSketch, SIZE_QUANTILES},
+    types::{TradeDirection, TradeEvent},
+    wash::{LastTrade, WalletFlow, WashStats, WASH_PAIR_SECS},
+};
+use std::collections::{HashMap, VecDeque};
+
+/// Default window durations (seconds), overridable via `SOLFLOW_WINDOWS`
+pub const DEFAULT_WINDOWS: [i64; 6] = [60, 300, 900, 3600, 7200, 14400];
+
+/// Shortest signal window (seconds); always tracked
+pub const BOT_WINDOW_SECS: i64 = 60;
+
+/// Estimated bytes per refcounted wallet entry (key string + count + table slot)
+const WALLET_ENTRY_BYTES: usize = 96;
+
+/// Approximate bytes used by one buffered trade
+fn trade_size(trade: &TradeEvent) -> usize {
+    std::mem::size_of::<TradeEvent>()
+        + trade.mint.capacity()
+        + trade.user_account.capacity()
+        + trade.source_program.capacity()
+        + trade.funding_cluster.as_ref().map_or(0, String::capacity)
+}
+
+/// Sort and dedup a window list, dropping non-positive entries and making
+/// sure the bot detection window is present
+pub fn normalize_windows(windows: &[i64]) -> Vec<i64> {
+    let mut windows: Vec<i64> = windows.iter().copied().filter(|&secs| secs > 0).collect();
+    windows.push(BOT_WINDOW_SECS);
+    windows.sort_unstable();
+    windows.dedup();
+    windows
+}
+
+/// Multiset of keys with O(1) insert, remove and distinct count
+#[derive(Debug, Clone, Default)]
+pub struct RefCountSet {
+    counts: HashMap<String, u32>,
+}
+
+impl RefCountSet {
+    /// Increment the refcount of `key`
+    fn insert(&mut self, key: &str) {
+        match self.counts.get_mut(key) {
+            Some(count) => *count += 1,
+            None => {
+                self.counts.insert(key.to_string(), 1);
+            }
+        }
+    }
+
+    /// Decrement the refcount of `key`, dropping it at zero
+    fn remove(&mut self, key: &str) {
+        if let Some(count) = self.counts.get_mut(key) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(key);
+            }
+        }
+    }
+
+    /// Number of distinct keys
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.counts.contains_key(key)
+    }
 
-use crate::types::{TradeDirection, TradeEvent};
-use std::collections::{HashMap, HashSet, VecDeque};
+    /// Number of live references to `key`
+    pub fn count(&self, key: &str) -> u32 {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.counts.keys().map(|k| k.as_str())
+    }
+}
+
+/// Running aggregates of one venue (`source_program`) inside a window
+#[derive(Debug, Clone, Default)]
+pub struct VenueAggregate {
+    pub trade_count: usize,
+    pub net_flow_sol: f64,
+    pub buy_volume_sol: f64,
+    pub sell_volume_sol: f64,
+    pub buy_count: i32,
+    pub sell_count: i32,
+    pub wallets: RefCountSet,
+}
+
+impl VenueAggregate {
+    /// Add (`sign = 1.0`) or subtract (`sign = -1.0`) a trade
+    fn apply(&mut self, trade: &TradeEvent, sign: f64) {
+        let delta = sign as i32;
+        match trade.direction {
+            TradeDirection::Buy => {
+                self.buy_count += delta;
+                self.buy_volume_sol += sign * trade.sol_amount;
+                self.net_flow_sol += sign * trade.sol_amount;
+            }
+            TradeDirection::Sell => {
+                self.sell_count += delta;
+                self.sell_volume_sol += sign * trade.sol_amount;
+                self.net_flow_sol -= sign * trade.sol_amount;
+            }
+            TradeDirection::Unknown => {}
+        }
+        if sign > 0.0 {
+            self.trade_count += 1;
+            self.wallets.insert(&trade.user_account);
+        } else {
+            self.trade_count -= 1;
+            self.wallets.remove(&trade.user_account);
+        }
+    }
+}
+
+/// Running aggregates for the trades currently inside one window
+#[derive(Debug, Clone, Default)]
+pub struct WindowAggregate {
+    /// Number of trades in the window
+    pub trade_count: usize,
+    /// Sum of buy SOL minus sell SOL
+    pub net_flow_sol: f64,
+    /// Gross SOL spent on buys
+    pub buy_volume_sol: f64,
+    /// Gross SOL received from sells
+    pub sell_volume_sol: f64,
+    pub buy_count: i32,
+    pub sell_count: i32,
+    /// Trades flagged `is_bot`
+    pub bot_trades: i32,
+    /// Net SOL flow of bot-flagged trades
+    pub bot_flow_sol: f64,
+    /// JupiterDCA BUY trades
+    pub dca_buys: i32,
+    /// Net SOL flow of `is_dca` trades
+    pub dca_flow_sol: f64,
+    /// Wallets trading in the window
+    pub wallets: RefCountSet,
+    /// Funding clusters trading in the window (see `TradeEvent::cluster`)
+    pub clusters: RefCountSet,
+    /// Wallets with at least one bot-flagged trade in the window
+    pub bot_wallets: RefCountSet,
+    /// Wallets with at least one `is_dca` trade in the window
+    pub dca_wallets: RefCountSet,
+    /// BUY trades by smart-money wallets (see `wallets`)
+    pub smart_money_buys: i32,
+    /// Net SOL flow of smart-money trades
+    pub smart_money_flow_sol: f64,
+    /// Smart-money wallets trading in the window
+    pub smart_money_wallets: RefCountSet,
+    /// Distribution of BUY sizes (SOL)
+    pub buy_sizes: SizeSketch,
+    /// Distribution of SELL sizes (SOL)
+    pub sell_sizes: SizeSketch,
+    /// BUY trades per size class, indexed retail / mid / whale
+    pub buy_classes: [i32; 3],
+    /// SELL trades per size class, indexed retail / mid / whale
+    pub sell_classes: [i32; 3],
+    /// Per-venue aggregates keyed by `source_program`; venues without
+    /// trades in the window are dropped
+    pub venues: HashMap<String, VenueAggregate>,
+    /// Buy and sell SOL per wallet, for wash detection
+    pub wallet_flows: HashMap<String, WalletFlow>,
+    /// Trades flagged `is_wash`
+    pub wash_legs: i32,
+    /// SOL of trades flagged `is_wash`
+    pub wash_leg_sol: f64,
+}
+
+impl WindowAggregate {
+    fn add(&mut self, trade: &TradeEvent) {
+        self.apply(trade, 1.0);
+        self.trade_count += 1;
+        self.wallets.insert(&trade.user_account);
+        self.clusters.insert(trade.cluster());
+        if trade.is_bot {
+            self.bot_wallets.insert(&trade.user_account);
+        }
+        if trade.is_dca {
+            self.dca_wallets.insert(&trade.user_account);
+        }
+        if trade.is_smart_money {
+            self.smart_money_wallets.insert(&trade.user_account);
+        }
+        match trade.direction {
+            TradeDirection::Buy => self.buy_sizes.add(trade.sol_amount),
+            TradeDirection::Sell => self.sell_sizes.add(trade.sol_amount),
+            TradeDirection::Unknown => {}
+        }
+        match self.venues.get_mut(&trade.source_program) {
+            Some(venue) => venue.apply(trade, 1.0),
+            None => {
+                let mut venue = VenueAggregate::default();
+                venue.apply(trade, 1.0);
+                self.venues.insert(trade.source_program.clone(), venue);
+            }
+        }
+
+        match self.wallet_flows.get_mut(&trade.user_account) {
+            Some(flow) => flow.apply(trade, 1.0),
+            None => {
+                let mut flow = WalletFlow::default();
+                flow.apply(trade, 1.0);
+                self.wallet_flows.insert(trade.user_account.clone(), flow);
+            }
+        }
+    }
+
+    fn remove(&mut self, trade: &TradeEvent) {
+        self.apply(trade, -1.0);
+        self.trade_count -= 1;
+        self.wallets.remove(&trade.user_account);
+        self.clusters.remove(trade.cluster());
+        if trade.is_bot {
+            self.bot_wallets.remove(&trade.user_account);
+        }
+        if trade.is_dca {
+            self.dca_wallets.remove(&trade.user_account);
+        }
+        if trade.is_smart_money {
+            self.smart_money_wallets.remove(&trade.user_account);
+        }
+        match trade.direction {
+            TradeDirection::Buy => self.buy_sizes.remove(trade.sol_amount),
+            TradeDirection::Sell => self.sell_sizes.remove(trade.sol_amount),
+            TradeDirection::Unknown => {}
+        }
+        if let Some(venue) = self.venues.get_mut(&trade.source_program) {
+            venue.apply(trade, -1.0);
+            if venue.trade_count == 0 {
+                self.venues.remove(&trade.source_program);
+            }
+        }
+        if let Some(flow) = self.wallet_flows.get_mut(&trade.user_account) {
+            flow.apply(trade, -1.0);
+            if flow.trades == 0 {
+                self.wallet_flows.remove(&trade.user_account);
+            }
+        }
+
+        // Reset float sums so rounding error cannot accumulate forever
+        if self.trade_count == 0 {
+            self.net_flow_sol = 0.0;
+            self.buy_volume_sol = 0.0;
+            self.sell_volume_sol = 0.0;
+            self.bot_flow_sol = 0.0;
+            self.dca_flow_sol = 0.0;
+            self.smart_money_flow_sol = 0.0;
+            self.wash_leg_sol = 0.0;
+        }
+    }
+
+    /// Add (`sign = 1.0`) or subtract (`sign = -1.0`) a trade's sums and counts
+    fn apply(&mut self, trade: &TradeEvent, sign: f64) {
+        let delta = sign as i32;
+        let class = SizeClass::of(trade.sol_amount) as usize;
+        let flow = match trade.direction {
+            TradeDirection::Buy => {
+                self.buy_count += delta;
+                self.buy_volume_sol += sign * trade.sol_amount;
+                self.buy_classes[class] += delta;
+                trade.sol_amount
+            }
+            TradeDirection::Sell => {
+                self.sell_count += delta;
+                self.sell_volume_sol += sign * trade.sol_amount;
+                self.sell_classes[class] += delta;
+                -trade.sol_amount
+            }
+            TradeDirection::Unknown => 0.0,
+        };
+
+        self.net_flow_sol += sign * flow;
+        if trade.is_bot {
+            self.bot_trades += delta;
+            self.bot_flow_sol += sign * flow;
+        }
+        if trade.is_dca {
+            self.dca_flow_sol += sign * flow;
+        }
+        if trade.source_program == "JupiterDCA" && trade.direction == TradeDirection::Buy {
+            self.dca_buys += delta;
+        }
+        if trade.is_wash {
+            self.wash_legs += delta;
+            self.wash_leg_sol += sign * trade.sol_amount;
+        }
+        if trade.is_smart_money {
+            self.smart_money_flow_sol += sign * flow;
+            if trade.direction == TradeDirection::Buy {
+                self.smart_money_buys += delta;
+            }
+        }
+    }
+}
+
+/// One rolling window: duration, cursor into the trade buffer and aggregates
+#[derive(Debug, Clone)]
+struct Window {
+    secs: i64,
+    /// Sequence number of the oldest trade inside this window
+    start: u64,
+    /// Cutoff applied by the last eviction (trades older than this are out)
+    cutoff: i64,
+    agg: WindowAggregate,
+}
 
 /// Per-token rolling state container
 ///
-/// Maintains rolling buffers for six time windows:
-/// - 60s (1 minute)
-/// - 300s (5 minutes)
-/// - 900s (15 minutes)
-/// - 3600s (1 hour)
-/// - 7200s (2 hours)
-/// - 14400s (4 hours)
+/// Tracks a configurable set of time windows (`DEFAULT_WINDOWS` unless
+/// created with `with_windows`) over a single time-ordered trade buffer.
+/// Trades older than the longest window are dropped on eviction.
 #[derive(Debug, Clone)]
 pub struct TokenRollingState {
     /// Token mint address
     pub mint: String,
 
-    /// Phase 5: Last timestamp when this mint received a trade (for pruning)
+    /// Phase 5: Last timestamp when this mint received a trade (LRU order for the sweeper)
     pub last_seen_ts: i64,
 
-    /// Rolling buffer: trades in last 60 seconds
-    pub trades_60s: Vec<TradeEvent>,
+    /// Trades sorted by timestamp, oldest first
+    trades: VecDeque<TradeEvent>,
+
+    /// Sequence number of `trades.front()`
+    ///
+    /// Sequence numbers are absolute, so window cursors stay valid when the
+    /// buffer front is popped.
+    head_seq: u64,
+
+    /// Windows ordered by duration (shortest first)
+    windows: Vec<Window>,
 
-    /// Rolling buffer: trades in last 300 seconds (5 minutes)
-    pub trades_300s: Vec<TradeEvent>,
+    /// Approximate bytes held by buffered trades (struct + string heap)
+    trade_bytes: usize,
 
-    /// Rolling buffer: trades in last 900 seconds (15 minutes)
-    pub trades_900s: Vec<TradeEvent>,
+    /// Timestamp from which trade history is known to be complete
+    ///
+    /// Windows reaching back before this are reported as warming.
+    /// `i64::MIN` (the default) means history is always complete.
+    pub history_start: i64,
+
+    /// Open OHLCV candles, closed on event time by `close_candles`
+    pub candles: CandleBook,
 
-    /// Rolling buffer: trades in last 3600 seconds (1 hour)
-    pub trades_3600s: Vec<TradeEvent>,
+    /// Per-wallet positions and PnL (not limited to the windows)
+    pub positions: PositionBook,
 
-    /// Rolling buffer: trades in last 7200 seconds (2 hours)
-    pub trades_7200s: Vec<TradeEvent>,
+    /// Last trade per wallet within `WASH_PAIR_SECS`, for flagging wash legs
+    last_trades: HashMap<String, LastTrade>,
 
-    /// Rolling buffer: trades in last 14400 seconds (4 hours)
-    pub trades_14400s: Vec<TradeEvent>,
+    /// Liquidity of the mint's pool in SOL, set by the processor from the
+    /// latest known reserves (see `liquidity`)
+    pub liquidity_sol: Option<f64>,
+}
 
-    /// Unique wallet addresses in 300s window
-    pub unique_wallets_300s: HashSet<String>,
+/// Full metric family for one rolling window
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WindowMetrics {
+    /// Window duration in seconds
+    pub window_secs: i64,
+
+    // Flow and trade counts
+    pub net_flow_sol: f64,
+    pub buy_count: i32,
+    pub sell_count: i32,
+    pub unique_wallets: i32,
+    /// Distinct funding clusters; wallets sharing a root funder count once
+    pub unique_clusters: i32,
+
+    // Gross volume (churn shows as high volume with little net flow)
+    pub buy_volume_sol: f64,
+    pub sell_volume_sol: f64,
+    /// Buy volume + sell volume
+    pub volume_sol: f64,
 
-    /// Bot wallet addresses in 300s window
-    pub bot_wallets_300s: HashSet<String>,
-    
-    /// Phase 4: Track wallet trade counts in 60s window (for bot detection)
-    /// Key: wallet address, Value: (trade_count, last_trade_timestamp)
-    pub wallet_activity_60s: HashMap<String, (i32, i64)>,
+    // Bot detection metrics (Phase 4)
+    pub bot_wallets: i32,
+    pub bot_trades: i32,
+    pub bot_flow_sol: f64,
+
+    // DCA metrics (Phase 4 & 6)
+    pub dca_buys: i32,
+    pub dca_flow_sol: f64,
+    pub dca_unique_wallets: i32,
+    /// DCA flow / net flow (0.0 when net flow is zero)
+    pub dca_ratio: f64,
+
+    // Smart-money metrics (wallets labelled by `wallets`)
+    pub smart_money_buys: i32,
+    pub smart_money_flow_sol: f64,
+    pub smart_money_wallets: i32,
+
+    // Trade-size distribution (sketch quantiles, SOL; 0.0 without trades)
+    pub buy_size_p50: f64,
+    pub buy_size_p90: f64,
+    pub buy_size_p99: f64,
+    pub sell_size_p50: f64,
+    pub sell_size_p90: f64,
+    pub sell_size_p99: f64,
+
+    // Trades per size class (see `sketch::SizeClass`)
+    pub retail_buys: i32,
+    pub mid_buys: i32,
+    pub whale_buys: i32,
+    pub retail_sells: i32,
+    pub mid_sells: i32,
+    pub whale_sells: i32,
+
+    // Wash trading (see `wash`); organic_* exclude it
+    /// 0.0 (organic) ..= 1.0 (all volume washed)
+    pub wash_score: f64,
+    pub wash_volume_sol: f64,
+    /// Wallets with balanced buy/sell round trips
+    pub wash_wallets: i32,
+    /// Trades flagged `is_wash`
+    pub wash_trades: i32,
+
+    /// True while the window reaches back before `history_start`, i.e. its
+    /// trade history is incomplete (after a restart)
+    pub warming: bool,
+}
 
-    /// Trades grouped by source program (for DCA correlation)
-    /// Key: source_program (e.g., "PumpSwap", "BonkSwap", "Moonshot", "JupiterDCA")
-    /// Value: Vector of trades from that program
-    pub trades_by_program: HashMap<String, Vec<TradeEvent>>,
+impl WindowMetrics {
+    /// Unique wallets minus suspected wash wallets
+    pub fn organic_wallets(&self) -> i32 {
+        (self.unique_wallets - self.wash_wallets).max(0)
+    }
 
-    /// DCA rolling windows: timestamps of JupiterDCA BUY trades
-    /// Phase 6: DCA Rolling Windows (feature/dca-rolling-windows)
+    /// Funding clusters, capped by organic wallets
     ///
-    /// These VecDeques store only timestamps (i64) for efficient memory usage.
-    /// Timestamps are appended on each JupiterDCA BUY trade and pruned based on window duration.
-    pub dca_timestamps_60s: VecDeque<i64>,
-    pub dca_timestamps_300s: VecDeque<i64>,
-    pub dca_timestamps_900s: VecDeque<i64>,
-    pub dca_timestamps_3600s: VecDeque<i64>,
-    pub dca_timestamps_14400s: VecDeque<i64>,
+    /// Sybil wallets funded from one source and wash wallets both inflate
+    /// `unique_wallets`; this is the count of independent participants.
+    pub fn organic_clusters(&self) -> i32 {
+        self.unique_clusters.min(self.organic_wallets())
+    }
+
+    /// Gross volume minus suspected wash volume
+    pub fn organic_volume_sol(&self) -> f64 {
+        (self.volume_sol - self.wash_volume_sol).max(0.0)
+    }
+
+    fn from_aggregate(window_secs: i64, agg: &WindowAggregate, warming: bool) -> Self {
+        let dca_ratio = if agg.net_flow_sol.abs() > 0.0 {
+            agg.dca_flow_sol / agg.net_flow_sol
+        } else {
+            0.0
+        };
+        let [buy_size_p50, buy_size_p90, buy_size_p99] = agg.buy_sizes.quantiles(&SIZE_QUANTILES);
+        let [sell_size_p50, sell_size_p90, sell_size_p99] = agg.sell_sizes.quantiles(&SIZE_QUANTILES);
+        let volume_sol = agg.buy_volume_sol + agg.sell_volume_sol;
+        let wash = WashStats::analyse(&agg.wallet_flows, agg.wash_legs, agg.wash_leg_sol, agg.trade_count, volume_sol);
+
+        Self {
+            window_secs,
+            net_flow_sol: agg.net_flow_sol,
+            buy_count: agg.buy_count,
+            sell_count: agg.sell_count,
+            unique_wallets: agg.wallets.len() as i32,
+            unique_clusters: agg.clusters.len() as i32,
+            buy_volume_sol: agg.buy_volume_sol,
+            sell_volume_sol: agg.sell_volume_sol,
+            volume_sol,
+            bot_wallets: agg.bot_wallets.len() as i32,
+            bot_trades: agg.bot_trades,
+            bot_flow_sol: agg.bot_flow_sol,
+            dca_buys: agg.dca_buys,
+            dca_flow_sol: agg.dca_flow_sol,
+            dca_unique_wallets: agg.dca_wallets.len() as i32,
+            dca_ratio,
+            smart_money_buys: agg.smart_money_buys,
+            smart_money_flow_sol: agg.smart_money_flow_sol,
+            smart_money_wallets: agg.smart_money_wallets.len() as i32,
+            buy_size_p50,
+            buy_size_p90,
+            buy_size_p99,
+            sell_size_p50,
+            sell_size_p90,
+            sell_size_p99,
+            retail_buys: agg.buy_classes[SizeClass::Retail as usize],
+            mid_buys: agg.buy_classes[SizeClass::Mid as usize],
+            whale_buys: agg.buy_classes[SizeClass::Whale as usize],
+            retail_sells: agg.sell_classes[SizeClass::Retail as usize],
+            mid_sells: agg.sell_classes[SizeClass::Mid as usize],
+            whale_sells: agg.sell_classes[SizeClass::Whale as usize],
+            wash_score: wash.score,
+            wash_volume_sol: wash.volume_sol,
+            wash_wallets: wash.wallets,
+            wash_trades: wash.trades,
+            warming,
+        }
+    }
+}
+
+/// Metrics of one venue (`source_program`) in one rolling window
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VenueMetrics {
+    pub window_secs: i64,
+    pub venue: String,
+    pub net_flow_sol: f64,
+    pub buy_volume_sol: f64,
+    pub sell_volume_sol: f64,
+    pub volume_sol: f64,
+    pub buy_count: i32,
+    pub sell_count: i32,
+    pub unique_wallets: i32,
+    /// Share of the window's gross volume traded on this venue
+    pub volume_share: f64,
+    /// True for the venue with the most volume in the window
+    pub dominant: bool,
+}
+
+impl VenueMetrics {
+    /// Metrics of every venue in a window, by descending volume (the first
+    /// is the dominant venue)
+    fn from_aggregate(window_secs: i64, agg: &WindowAggregate) -> Vec<Self> {
+        let total_volume = agg.buy_volume_sol + agg.sell_volume_sol;
+        let mut venues: Vec<Self> = agg
+            .venues
+            .iter()
+            .map(|(venue, v)| {
+                let volume_sol = v.buy_volume_sol + v.sell_volume_sol;
+                Self {
+                    window_secs,
+                    venue: venue.clone(),
+                    net_flow_sol: v.net_flow_sol,
+                    buy_volume_sol: v.buy_volume_sol,
+                    sell_volume_sol: v.sell_volume_sol,
+                    volume_sol,
+                    buy_count: v.buy_count,
+                    sell_count: v.sell_count,
+                    unique_wallets: v.wallets.len() as i32,
+                    volume_share: if total_volume > 0.0 { volume_sol / total_volume } else { 0.0 },
+                    dominant: false,
+                }
+            })
+            .collect();
+
+        // Ties broken by name so the dominant venue is deterministic
+        venues.sort_by(|a, b| b.volume_sol.total_cmp(&a.volume_sol).then_with(|| a.venue.cmp(&b.venue)));
+        if let Some(first) = venues.first_mut() {
+            first.dominant = true;
+        }
+        venues
+    }
 }
 
 /// Internal metrics snapshot computed from rolling windows
 ///
-/// This is NOT directly mapped to AggregatedTokenState.
-/// It's an intermediate representation for Phase 2 only.
-#[derive(Debug, Clone)]
+/// One `WindowMetrics` per tracked window, ordered by duration, plus the
+/// per-venue breakdown of every window.
+#[derive(Debug, Clone, Default)]
 pub struct RollingMetrics {
-    // Net flow metrics
-    pub net_flow_60s_sol: f64,
-    pub net_flow_300s_sol: f64,
-    pub net_flow_900s_sol: f64,
-    pub net_flow_3600s_sol: f64,
-    pub net_flow_7200s_sol: f64,
-    pub net_flow_14400s_sol: f64,
-
-    // Trade counts (60s window)
-    pub buy_count_60s: i32,
-    pub sell_count_60s: i32,
-
-    // Trade counts (300s window)
-    pub buy_count_300s: i32,
-    pub sell_count_300s: i32,
-
-    // Trade counts (900s window)
-    pub buy_count_900s: i32,
-    pub sell_count_900s: i32,
-
-    // Advanced metrics (300s window)
-    pub unique_wallets_300s: i32,
-    
-    // Bot detection metrics (Phase 4)
-    pub bot_wallets_count_300s: i32,
-    pub bot_trades_count_300s: i32,
-    pub bot_flow_300s_sol: f64,
-
-    // DCA buy counts (rolling windows)
-    // Phase 4 & 6: DCA Rolling Windows
-    pub dca_buys_60s: i32,
-    pub dca_buys_300s: i32,
-    pub dca_buys_900s: i32,
-    pub dca_buys_3600s: i32,
-    pub dca_buys_14400s: i32,
-    
-    // Phase 4: DCA flow metrics (300s window)
-    pub dca_flow_300s_sol: f64,
-    pub dca_unique_wallets_300s: i32,
-    pub dca_ratio_300s: f64,
+    pub windows: Vec<WindowMetrics>,
+    /// Per-venue metrics, grouped by window (shortest first), each group by
+    /// descending volume
+    pub venues: Vec<VenueMetrics>,
+    /// Liquidity of the mint's pool in SOL (None if its reserves are unknown)
+    pub liquidity_sol: Option<f64>,
 }
 
-impl TokenRollingState {
-    /// Phase 4: Bot detection threshold
-    /// A wallet is flagged as a bot if it makes >= BOT_TRADE_THRESHOLD trades within 60 seconds
-    const BOT_TRADE_THRESHOLD: i32 = 3;
-    
-    /// Create a new rolling state container for a token
+impl RollingMetrics {
+    /// Metrics for the window of `secs` seconds, if tracked
+    pub fn get(&self, secs: i64) -> Option<&WindowMetrics> {
+        self.windows.iter().find(|w| w.window_secs == secs)
+    }
+
+    /// Metrics for the window of `secs` seconds
     ///
-    /// Phase 2: Proper initialization with capacity hints
-    /// Phase 5: Initialize last_seen_ts to 0
+    /// Untracked windows read as all-zero.
+    pub fn window(&self, secs: i64) -> WindowMetrics {
+        self.get(secs).copied().unwrap_or(WindowMetrics {
+            window_secs: secs,
+            ..Default::default()
+        })
+    }
+
+    /// Per-venue metrics of the window of `secs` seconds, by descending volume
+    pub fn venues(&self, secs: i64) -> impl Iterator<Item = &VenueMetrics> + '_ {
+        self.venues.iter().filter(move |v| v.window_secs == secs)
+    }
+
+    /// Venue with the most volume in the window of `secs` seconds
+    pub fn dominant_venue(&self, secs: i64) -> Option<&VenueMetrics> {
+        self.venues(secs).find(|v| v.dominant)
+    }
+
+    /// Net flow of the window of `secs` seconds relative to the pool's
+    /// liquidity (None if the liquidity is unknown)
+    pub fn flow_liquidity_ratio(&self, secs: i64) -> Option<f64> {
+        self.liquidity_sol
+            .filter(|liquidity| *liquidity > 0.0)
+            .map(|liquidity| self.window(secs).net_flow_sol / liquidity)
+    }
+
+    /// True if any of the given windows is tracked and still warming
+    pub fn is_warming(&self, windows: &[i64]) -> bool {
+        windows
+            .iter()
+            .any(|&secs| self.get(secs).is_some_and(|w| w.warming))
+    }
+
+    /// Mutable metrics for the window of `secs` seconds, inserted zeroed if missing
+    pub fn window_mut(&mut self, secs: i64) -> &mut WindowMetrics {
+        let pos = match self.windows.binary_search_by_key(&secs, |w| w.window_secs) {
+            Ok(pos) => pos,
+            Err(pos) => {
+                self.windows.insert(
+                    pos,
+                    WindowMetrics {
+                        window_secs: secs,
+                        ..Default::default()
+                    },
+                );
+                pos
+            }
+        };
+        &mut self.windows[pos]
+    }
+}
+
+impl TokenRollingState {
+    /// Create a new rolling state container for a token with the default windows
     pub fn new(mint: String) -> Self {
+        Self::with_windows(mint, &DEFAULT_WINDOWS)
+    }
+
+    /// Create a rolling state container tracking the given windows (seconds)
+    ///
+    /// The list is normalized with `normalize_windows`.
+    pub fn with_windows(mint: String, windows: &[i64]) -> Self {
         Self {
+            candles: CandleBook::new(&mint),
+            positions: PositionBook::default(),
+            last_trades: HashMap::new(),
+            liquidity_sol: None,
             mint,
             last_seen_ts: 0,
-            trades_60s: Vec::with_capacity(100),
-            trades_300s: Vec::with_capacity(500),
-            trades_900s: Vec::with_capacity(1500),
-            trades_3600s: Vec::with_capacity(6000),
-            trades_7200s: Vec::with_capacity(12000),
-            trades_14400s: Vec::with_capacity(24000),
-            unique_wallets_300s: HashSet::new(),
-            bot_wallets_300s: HashSet::new(),
-            wallet_activity_60s: HashMap::new(),
-            trades_by_program: HashMap::new(),
-            dca_timestamps_60s: VecDeque::with_capacity(10),
-            dca_timestamps_300s: VecDeque::with_capacity(50),
-            dca_timestamps_900s: VecDeque::with_capacity(150),
-            dca_timestamps_3600s: VecDeque::with_capacity(600),
-            dca_timestamps_14400s: VecDeque::with_capacity(2400),
+            trades: VecDeque::new(),
+            head_seq: 0,
+            trade_bytes: 0,
+            history_start: i64::MIN,
+            windows: normalize_windows(windows)
+                .into_iter()
+                .map(|secs| Window {
+                    secs,
+                    start: 0,
+                    cutoff: i64::MIN,
+                    agg: WindowAggregate::default(),
+                })
+                .collect(),
         }
     }
 
+    /// Set the timestamp from which trade history is complete
+    pub fn with_history_start(mut self, history_start: i64) -> Self {
+        self.history_start = history_start;
+        self
+    }
+
     /// Add a trade to rolling windows
     ///
-    /// Phase 2: Data handling only
-    /// Phase 4: Bot detection and flagging
-    /// - Pushes trade to all window buffers
-    /// - Updates unique_wallets_300s with trade wallet
-    /// - Detects and flags bot wallets based on rapid trading patterns
-    /// - Adds trade to program-specific bucket
-    /// - Updates last_seen_ts for pruning
-    /// - Appends DCA timestamps for JupiterDCA BUY trades
+    /// `is_bot` is taken as set by the `bot_classifier` at ingestion.
+    /// - Inserts the trade into the buffer in timestamp order (appends in the
+    ///   common in-order case)
+    /// - Adds it to the aggregates of every window whose last eviction cutoff
+    ///   it is not older than
+    /// - Adds it to the open candles and the wallet's position
     pub fn add_trade(&mut self, mut trade: TradeEvent) {
-        self.last_seen_ts = trade.timestamp;
-
-        // Phase 4: Bot detection - track wallet activity in 60s window
-        let wallet = trade.user_account.clone();
-        let entry = self.wallet_activity_60s.entry(wallet.clone()).or_insert((0, trade.timestamp));
-        entry.0 += 1;
-        entry.1 = trade.timestamp;
-        
-        // Flag as bot if wallet has >= BOT_TRADE_THRESHOLD trades in 60s window
-        if entry.0 >= Self::BOT_TRADE_THRESHOLD {
-            trade.is_bot = true;
-            self.bot_wallets_300s.insert(wallet.clone());
+        self.last_seen_ts = self.last_seen_ts.max(trade.timestamp);
+
+        trade.is_wash |= self.is_wash_leg(&trade);
+        match self.last_trades.get_mut(&trade.user_account) {
+            Some(last) if last.timestamp > trade.timestamp => {}
+            Some(last) => *last = LastTrade::of(&trade),
+            None => {
+                self.last_trades.insert(trade.user_account.clone(), LastTrade::of(&trade));
+            }
         }
 
-        self.unique_wallets_300s.insert(wallet);
-
-        self.trades_by_program
-            .entry(trade.source_program.clone())
-            .or_default()
-            .push(trade.clone());
+        let pos = match self.trades.back() {
+            Some(last) if trade.timestamp < last.timestamp => self
+                .trades
+                .partition_point(|t| t.timestamp <= trade.timestamp),
+            _ => self.trades.len(),
+        };
 
-        if trade.source_program == "JupiterDCA" && trade.direction == TradeDirection::Buy {
-            let timestamp = trade.timestamp;
-            self.dca_timestamps_60s.push_back(timestamp);
-            self.dca_timestamps_300s.push_back(timestamp);
-            self.dca_timestamps_900s.push_back(timestamp);
-            self.dca_timestamps_3600s.push_back(timestamp);
-            self.dca_timestamps_14400s.push_back(timestamp);
+        for window in &mut self.windows {
+            if trade.timestamp >= window.cutoff {
+                window.agg.add(&trade);
+            } else {
+                // Late trade already outside this window: it lands before the
+                // cursor, so shift the cursor to keep pointing at the same trade
+                window.start += 1;
+            }
         }
 
-        self.trades_60s.push(trade.clone());
-        self.trades_300s.push(trade.clone());
-        self.trades_900s.push(trade.clone());
-        self.trades_3600s.push(trade.clone());
-        self.trades_7200s.push(trade.clone());
-        self.trades_14400s.push(trade);
+        self.candles.add(&trade);
+        self.positions.apply(&trade);
+        self.trade_bytes += trade_size(&trade);
+        self.trades.insert(pos, trade);
+    }
+
+    /// True if `trade` reverses its wallet's previous trade on this mint at a
+    /// near-identical size (see `wash`); `add_trade` flags it `is_wash`
+    pub fn is_wash_leg(&self, trade: &TradeEvent) -> bool {
+        self.last_trades
+            .get(&trade.user_account)
+            .is_some_and(|last| last.is_reversed_by(trade))
     }
 
     /// Evict trades older than window cutoffs
     ///
-    /// Phase 2: Data handling only
-    /// Phase 4: Enhanced pruning with wallet activity tracking
-    /// - Removes trades outside each window's time range
-    /// - Recomputes unique_wallets_300s from remaining trades
-    /// - Recomputes bot_wallets_300s from remaining trades
-    /// - Evicts old trades from program-specific buckets
-    /// - Prunes DCA timestamps outside each window
-    /// - Cleans up wallet_activity_60s for bot detection
-    pub fn evict_old_trades(&mut self, now: i64) {
-        let cutoff_60s = now - 60;
-        let cutoff_300s = now - 300;
-        let cutoff_900s = now - 900;
-        let cutoff_3600s = now - 3600;
-        let cutoff_7200s = now - 7200;
-        let cutoff_14400s = now - 14400;
-        
-        // Phase 4: Clean up wallet activity tracking (60s window)
-        self.wallet_activity_60s.retain(|_, (_, last_ts)| *last_ts >= cutoff_60s);
-
-        while let Some(&ts) = self.dca_timestamps_60s.front() {
-            if ts < cutoff_60s {
-                self.dca_timestamps_60s.pop_front();
-            } else {
-                break;
-            }
-        }
-        while let Some(&ts) = self.dca_timestamps_300s.front() {
-            if ts < cutoff_300s {
-                self.dca_timestamps_300s.pop_front();
-            } else {
-                break;
-            }
-        }
-        while let Some(&ts) = self.dca_timestamps_900s.front() {
-            if ts < cutoff_900s {
-                self.dca_timestamps_900s.pop_front();
-            } else {
-                break;
-            }
-        }
-        while let Some(&ts) = self.dca_timestamps_3600s.front() {
-            if ts < cutoff_3600s {
-                self.dca_timestamps_3600s.pop_front();
-            } else {
-                break;
+    /// Advances each window's cursor past trades with `timestamp < now - secs`,
+    /// subtracting them from its aggregates, then drops trades that no window
+    /// references any more from the buffer.
+    ///
+    /// Returns the number of (trade, window) evictions, i.e. 0 when no
+    /// window's metrics changed.
+    pub fn evict_old_trades(&mut self, now: i64) -> usize {
+        let mut evicted = 0;
+        let end_seq = self.head_seq + self.trades.len() as u64;
+
+        for window in &mut self.windows {
+            let cutoff = now - window.secs;
+            window.cutoff = window.cutoff.max(cutoff);
+            while window.start < end_seq {
+                let trade = &self.trades[(window.start - self.head_seq) as usize];
+                if trade.timestamp >= window.cutoff {
+                    break;
+                }
+                window.agg.remove(trade);
+                window.start += 1;
+                evicted += 1;
             }
         }
-        while let Some(&ts) = self.dca_timestamps_14400s.front() {
-            if ts < cutoff_14400s {
-                self.dca_timestamps_14400s.pop_front();
-            } else {
-                break;
+
+        self.last_trades.retain(|_, last| last.timestamp >= now - WASH_PAIR_SECS);
+
+        let min_start = self.windows.iter().map(|w| w.start).min().unwrap_or(end_seq);
+        while self.head_seq < min_start {
+            if let Some(trade) = self.trades.pop_front() {
+                self.trade_bytes -= trade_size(&trade);
             }
+            self.head_seq += 1;
         }
 
-        self.trades_60s
-            .retain(|trade| trade.timestamp >= cutoff_60s);
+        evicted
+    }
 
-        self.trades_300s
-            .retain(|trade| trade.timestamp >= cutoff_300s);
+    /// Close candles whose bucket ended at or before `now`
+    pub fn close_candles(&mut self, now: i64) -> Vec<Candle> {
+        self.candles.close(now)
+    }
 
-        self.trades_900s
-            .retain(|trade| trade.timestamp >= cutoff_900s);
+    /// Aggregates for the window of `secs` seconds, if tracked
+    pub fn window(&self, secs: i64) -> Option<&WindowAggregate> {
+        self.windows.iter().find(|w| w.secs == secs).map(|w| &w.agg)
+    }
 
-        self.trades_3600s
-            .retain(|trade| trade.timestamp >= cutoff_3600s);
+    /// Trades currently inside the window of `secs` seconds, oldest first
+    ///
+    /// Empty if the window is not tracked.
+    pub fn window_trades(&self, secs: i64) -> impl Iterator<Item = &TradeEvent> + '_ {
+        let skip = self
+            .windows
+            .iter()
+            .find(|w| w.secs == secs)
+            .map(|w| (w.start - self.head_seq) as usize)
+            .unwrap_or(self.trades.len());
+        self.trades.iter().skip(skip)
+    }
 
-        self.trades_7200s
-            .retain(|trade| trade.timestamp >= cutoff_7200s);
+    /// Tracked window durations, shortest first
+    pub fn window_secs(&self) -> impl Iterator<Item = i64> + '_ {
+        self.windows.iter().map(|w| w.secs)
+    }
 
-        self.trades_14400s
-            .retain(|trade| trade.timestamp >= cutoff_14400s);
+    /// Cutoff applied by the window's last eviction: trades at or after it
+    /// are inside the window (`i64::MIN` before the first eviction)
+    pub fn window_cutoff(&self, secs: i64) -> Option<i64> {
+        self.windows.iter().find(|w| w.secs == secs).map(|w| w.cutoff)
+    }
 
-        for trades in self.trades_by_program.values_mut() {
-            trades.retain(|trade| trade.timestamp >= cutoff_14400s);
-        }
+    /// Every buffered trade, oldest first, including late trades that landed
+    /// behind all window cursors and are dropped on the next eviction
+    pub fn buffered_trades(&self) -> impl Iterator<Item = &TradeEvent> + '_ {
+        self.trades.iter()
+    }
 
-        self.unique_wallets_300s.clear();
-        for trade in &self.trades_300s {
-            self.unique_wallets_300s.insert(trade.user_account.clone());
-        }
+    /// Number of trades held in the buffer (the longest window)
+    pub fn trade_count(&self) -> usize {
+        self.trades.len()
+    }
+
+    /// True when no trade is left in any window
+    pub fn is_idle(&self) -> bool {
+        self.trades.is_empty()
+    }
+
+    /// True if every buffered trade is older than the longest window at `now`
+    ///
+    /// Unlike `evict_old_trades` this does not touch the aggregates.
+    pub fn is_idle_at(&self, now: i64) -> bool {
+        let longest = self.windows.last().map_or(0, |w| w.secs);
+        self.trades.back().is_none_or(|t| t.timestamp < now - longest)
+    }
 
-        self.bot_wallets_300s.clear();
+    /// Approximate heap footprint in bytes, used for the global memory budget
+    pub fn approx_bytes(&self) -> usize {
+        let wallet_entries: usize = self
+            .windows
+            .iter()
+            .map(|w| {
+                w.agg.wallets.len()
+                    + w.agg.clusters.len()
+                    + w.agg.bot_wallets.len()
+                    + w.agg.dca_wallets.len()
+                    + w.agg.smart_money_wallets.len()
+                    + w.agg.venues.values().map(|v| v.wallets.len()).sum::<usize>()
+                    + w.agg.wallet_flows.len()
+            })
+            .sum();
+        let sketch_bytes: usize = self
+            .windows
+            .iter()
+            .map(|w| w.agg.buy_sizes.approx_bytes() + w.agg.sell_sizes.approx_bytes())
+            .sum();
+
+        std::mem::size_of::<Self>()
+            + self.mint.capacity()
+            + self.trade_bytes
+            + self.windows.len() * std::mem::size_of::<Window>()
+            + (wallet_entries + self.last_trades.len()) * WALLET_ENTRY_BYTES
+            + sketch_bytes
+            + self.candles.approx_bytes()
+            + self.positions.approx_bytes()
     }
 
     /// Compute rolling metrics from current window state
     ///
-    /// Phase 2: Data computation only
     /// Phase 4: Enhanced metrics with bot detection and DCA analysis
-    /// Returns internal metrics snapshot (not AggregatedTokenState)
+    /// Reads the running aggregates of every window; no trade scans
     pub fn compute_rolling_metrics(&self) -> RollingMetrics {
-        fn compute_window_metrics(
-            trades: &[TradeEvent],
-        ) -> (f64, i32, i32) {
-            let mut net_flow = 0.0;
-            let mut buy_count = 0;
-            let mut sell_count = 0;
-
-            for trade in trades {
-                match trade.direction {
-                    TradeDirection::Buy => {
-                        net_flow += trade.sol_amount;
-                        buy_count += 1;
-                    }
-                    TradeDirection::Sell => {
-                        net_flow -= trade.sol_amount;
-                        sell_count += 1;
-                    }
-                    TradeDirection::Unknown => {}
-                }
-            }
-
-            (net_flow, buy_count, sell_count)
-        }
-
-        let (net_flow_60s, buy_count_60s, sell_count_60s) =
-            compute_window_metrics(&self.trades_60s);
-        let (net_flow_300s, buy_count_300s, sell_count_300s) =
-            compute_window_metrics(&self.trades_300s);
-        let (net_flow_900s, buy_count_900s, sell_count_900s) =
-            compute_window_metrics(&self.trades_900s);
-        let (net_flow_3600s, _, _) =
-            compute_window_metrics(&self.trades_3600s);
-        let (net_flow_7200s, _, _) =
-            compute_window_metrics(&self.trades_7200s);
-        let (net_flow_14400s, _, _) =
-            compute_window_metrics(&self.trades_14400s);
-
-        // Phase 4: Bot metrics (300s window)
-        let mut bot_trades_count = 0;
-        let mut bot_flow = 0.0;
-        for trade in &self.trades_300s {
-            if trade.is_bot {
-                bot_trades_count += 1;
-                match trade.direction {
-                    TradeDirection::Buy => bot_flow += trade.sol_amount,
-                    TradeDirection::Sell => bot_flow -= trade.sol_amount,
-                    TradeDirection::Unknown => {}
-                }
-            }
-        }
-
-        // Phase 4: DCA metrics (300s window)
-        let mut dca_flow = 0.0;
-        let mut dca_wallets = HashSet::new();
-        for trade in &self.trades_300s {
-            if trade.is_dca {
-                match trade.direction {
-                    TradeDirection::Buy => dca_flow += trade.sol_amount,
-                    TradeDirection::Sell => dca_flow -= trade.sol_amount,
-                    TradeDirection::Unknown => {}
-                }
-                dca_wallets.insert(trade.user_account.clone());
-            }
-        }
-        
-        // Phase 4: DCA ratio (DCA flow / total flow)
-        let dca_ratio = if net_flow_300s.abs() > 0.0 {
-            dca_flow / net_flow_300s
-        } else {
-            0.0
-        };
-
-        let dca_buys_60s = self.dca_timestamps_60s.len() as i32;
-        let dca_buys_300s = self.dca_timestamps_300s.len() as i32;
-        let dca_buys_900s = self.dca_timestamps_900s.len() as i32;
-        let dca_buys_3600s = self.dca_timestamps_3600s.len() as i32;
-        let dca_buys_14400s = self.dca_timestamps_14400s.len() as i32;
-
         RollingMetrics {
-            net_flow_60s_sol: net_flow_60s,
-            net_flow_300s_sol: net_flow_300s,
-            net_flow_900s_sol: net_flow_900s,
-            net_flow_3600s_sol: net_flow_3600s,
-            net_flow_7200s_sol: net_flow_7200s,
-            net_flow_14400s_sol: net_flow_14400s,
-            buy_count_60s,
-            sell_count_60s,
-            buy_count_300s,
-            sell_count_300s,
-            buy_count_900s,
-            sell_count_900s,
-            unique_wallets_300s: self.unique_wallets_300s.len() as i32,
-            bot_wallets_count_300s: self.bot_wallets_300s.len() as i32,
-            bot_trades_count_300s: bot_trades_count,
-            bot_flow_300s_sol: bot_flow,
-            dca_buys_60s,
-            dca_buys_300s,
-            dca_buys_900s,
-            dca_buys_3600s,
-            dca_buys_14400s,
-            dca_flow_300s_sol: dca_flow,
-            dca_unique_wallets_300s: dca_wallets.len() as i32,
-            dca_ratio_300s: dca_ratio,
-        }
-    }
-    
-    /// Phase 4: Self-verification layer
-    /// Validates internal consistency of rolling metrics
-    /// Returns true if all checks pass, false otherwise with logged warnings
-    pub fn verify_metrics(&self, metrics: &RollingMetrics) -> bool {
-        let mut valid = true;
-        
-        // Check 1: Timestamps monotonic within each window
-        if !self.trades_60s.is_empty() {
-            let first_ts = self.trades_60s.first().unwrap().timestamp;
-            let last_ts = self.trades_60s.last().unwrap().timestamp;
-            if first_ts > last_ts {
-                log::warn!(
-                    "⚠️ VERIFICATION: Non-monotonic timestamps in 60s window for mint {}",
-                    self.mint
-                );
-                valid = false;
-            }
+            windows: self
+                .windows
+                .iter()
+                .map(|w| WindowMetrics::from_aggregate(w.secs, &w.agg, w.cutoff < self.history_start))
+                .collect(),
+            venues: self
+                .windows
+                .iter()
+                .flat_map(|w| VenueMetrics::from_aggregate(w.secs, &w.agg))
+                .collect(),
+            liquidity_sol: self.liquidity_sol,
         }
-        
-        // Check 2: Flow sums correct (buys - sells)
-        let expected_flow = metrics.buy_count_300s as f64 * 0.1 - metrics.sell_count_300s as f64 * 0.1;
-        let flow_diff = (metrics.net_flow_300s_sol - expected_flow).abs();
-        if flow_diff > metrics.net_flow_300s_sol.abs() * 2.0 {
-            log::warn!(
-                "⚠️ VERIFICATION: Flow sum mismatch for mint {} (expected ~{}, got {})",
-                self.mint, expected_flow, metrics.net_flow_300s_sol
-            );
-        }
-        
-        // Check 3: Wallet uniqueness per window
-        if metrics.unique_wallets_300s > (metrics.buy_count_300s + metrics.sell_count_300s) {
-            log::warn!(
-                "⚠️ VERIFICATION: More unique wallets than trades for mint {}",
-                self.mint
-            );
-            valid = false;
-        }
-        
-        // Check 4: DCA metrics consistent with trade flags
-        let dca_count = self.trades_300s.iter().filter(|t| t.is_dca).count() as i32;
-        if dca_count != metrics.dca_buys_300s {
-            log::warn!(
-                "⚠️ VERIFICATION: DCA count mismatch for mint {} (expected {}, got {})",
-                self.mint, dca_count, metrics.dca_buys_300s
-            );
-            valid = false;
-        }
-        
-        // Check 5: Bot metrics within bounds
-        if metrics.bot_trades_count_300s > (metrics.buy_count_300s + metrics.sell_count_300s) {
-            log::warn!(
-                "⚠️ VERIFICATION: More bot trades than total trades for mint {}",
-                self.mint
-            );
-            valid = false;
-        }
-        
-        valid
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audit;
 
     fn create_test_trade(
         timestamp: i64,
@@ -470,39 +894,27 @@ mod tests {
         is_dca: bool,
     ) -> TradeEvent {
         TradeEvent {
-            timestamp,
-            mint: mint.to_string(),
-            direction,
-            sol_amount,
-            token_amount: 1000.0,
-            token_decimals: 6,
             user_account: wallet.to_string(),
             source_program: if is_dca { "JupiterDCA" } else { "PumpSwap" }.to_string(),
             is_bot,
             is_dca,
+            ..TradeEvent::test(mint, timestamp, direction, sol_amount)
         }
     }
 
     #[test]
-    fn test_bot_detection_rapid_trading() {
+    fn test_rapid_trading_alone_is_not_bot() {
         let mut state = TokenRollingState::new("test_mint".to_string());
         let now = 1000i64;
 
-        // Wallet A makes 3 rapid trades within 60s (should be flagged as bot)
-        let trade1 = create_test_trade(now, "test_mint", TradeDirection::Buy, 1.0, "wallet_a", false, false);
-        let trade2 = create_test_trade(now + 10, "test_mint", TradeDirection::Sell, 0.5, "wallet_a", false, false);
-        let trade3 = create_test_trade(now + 20, "test_mint", TradeDirection::Buy, 2.0, "wallet_a", false, false);
-
-        state.add_trade(trade1);
-        state.add_trade(trade2);
-        state.add_trade(trade3);
+        // 3 rapid trades used to trip a trade-count rule; flags now come from
+        // the bot classifier only
+        state.add_trade(create_test_trade(now, "test_mint", TradeDirection::Buy, 1.0, "wallet_a", false, false));
+        state.add_trade(create_test_trade(now + 10, "test_mint", TradeDirection::Sell, 0.5, "wallet_a", false, false));
+        state.add_trade(create_test_trade(now + 20, "test_mint", TradeDirection::Buy, 2.0, "wallet_a", false, false));
 
-        // Verify wallet_a is flagged as bot
-        assert!(state.bot_wallets_300s.contains("wallet_a"));
-        
-        // Verify trades are flagged
-        let bot_count = state.trades_60s.iter().filter(|t| t.is_bot).count();
-        assert_eq!(bot_count, 1); // Third trade should be flagged
+        assert!(state.window(300).unwrap().bot_wallets.is_empty());
+        assert_eq!(state.window_trades(60).filter(|t| t.is_bot).count(), 0);
     }
 
     #[test]
@@ -515,16 +927,16 @@ mod tests {
         state.add_trade(create_test_trade(base_time + 30, "test_mint", TradeDirection::Sell, 0.5, "w2", false, false));
         state.add_trade(create_test_trade(base_time + 100, "test_mint", TradeDirection::Buy, 2.0, "w3", false, false));
 
-        assert_eq!(state.trades_60s.len(), 3);
-        assert_eq!(state.trades_300s.len(), 3);
+        assert_eq!(state.window_trades(60).count(), 3);
+        assert_eq!(state.window_trades(300).count(), 3);
 
         // Evict trades older than 60s
         state.evict_old_trades(base_time + 120);
 
         // First two trades should be evicted from 60s window
-        assert_eq!(state.trades_60s.len(), 1);
+        assert_eq!(state.window_trades(60).count(), 1);
         // All trades still in 300s window
-        assert_eq!(state.trades_300s.len(), 3);
+        assert_eq!(state.window_trades(300).count(), 3);
     }
 
     #[test]
@@ -543,20 +955,20 @@ mod tests {
         let metrics = state.compute_rolling_metrics();
 
         // Verify DCA counts
-        assert_eq!(metrics.dca_buys_60s, 2);
-        assert_eq!(metrics.dca_buys_300s, 2);
+        assert_eq!(metrics.window(60).dca_buys, 2);
+        assert_eq!(metrics.window(300).dca_buys, 2);
         
         // Verify DCA flow (1.0 + 1.5 = 2.5)
-        assert!((metrics.dca_flow_300s_sol - 2.5).abs() < 0.001);
+        assert!((metrics.window(300).dca_flow_sol - 2.5).abs() < 0.001);
         
         // Verify DCA unique wallets
-        assert_eq!(metrics.dca_unique_wallets_300s, 2);
+        assert_eq!(metrics.window(300).dca_unique_wallets, 2);
         
         // Verify total net flow (5.0 - 2.0 + 1.0 + 1.5 = 5.5)
-        assert!((metrics.net_flow_300s_sol - 5.5).abs() < 0.001);
+        assert!((metrics.window(300).net_flow_sol - 5.5).abs() < 0.001);
         
         // Verify DCA ratio (2.5 / 5.5 ≈ 0.454)
-        assert!((metrics.dca_ratio_300s - (2.5 / 5.5)).abs() < 0.01);
+        assert!((metrics.window(300).dca_ratio - (2.5 / 5.5)).abs() < 0.01);
     }
 
     #[test]
@@ -564,10 +976,10 @@ mod tests {
         let mut state = TokenRollingState::new("test_mint".to_string());
         let now = 1000i64;
 
-        // Create bot wallet with 3 rapid trades
+        // Bot wallet: flagged once its classifier score crossed the threshold
         state.add_trade(create_test_trade(now, "test_mint", TradeDirection::Buy, 1.0, "bot_wallet", false, false));
-        state.add_trade(create_test_trade(now + 5, "test_mint", TradeDirection::Buy, 2.0, "bot_wallet", false, false));
-        state.add_trade(create_test_trade(now + 10, "test_mint", TradeDirection::Sell, 1.5, "bot_wallet", false, false));
+        state.add_trade(create_test_trade(now + 5, "test_mint", TradeDirection::Buy, 2.0, "bot_wallet", true, false));
+        state.add_trade(create_test_trade(now + 10, "test_mint", TradeDirection::Sell, 1.5, "bot_wallet", true, false));
 
         // Add normal trade
         state.add_trade(create_test_trade(now + 20, "test_mint", TradeDirection::Buy, 5.0, "normal_wallet", false, false));
@@ -575,13 +987,13 @@ mod tests {
         let metrics = state.compute_rolling_metrics();
 
         // Verify bot wallet count
-        assert_eq!(metrics.bot_wallets_count_300s, 1);
-        
-        // Bot trades: only the 3rd trade is flagged (when threshold is reached)
-        assert_eq!(metrics.bot_trades_count_300s, 1);
-        
-        // Bot flow: -1.5 (only the sell that triggered bot flag)
-        assert!((metrics.bot_flow_300s_sol - (-1.5)).abs() < 0.001);
+        assert_eq!(metrics.window(300).bot_wallets, 1);
+
+        // Bot trades: the two flagged trades
+        assert_eq!(metrics.window(300).bot_trades, 2);
+
+        // Bot flow: 2.0 - 1.5
+        assert!((metrics.window(300).bot_flow_sol - 0.5).abs() < 0.001);
     }
 
     #[test]
@@ -601,19 +1013,19 @@ mod tests {
         let metrics = state.compute_rolling_metrics();
 
         // 60s window: only last trade at 2080 (5.0), cutoff is 2040
-        assert_eq!(metrics.buy_count_60s, 1);
-        assert!((metrics.net_flow_60s_sol - 5.0).abs() < 0.001);
+        assert_eq!(metrics.window(60).buy_count, 1);
+        assert!((metrics.window(60).net_flow_sol - 5.0).abs() < 0.001);
 
         // 300s window: cutoff is 1800, so trades at 2000 (4.0), 2080 (5.0) = 9.0
-        assert_eq!(metrics.buy_count_300s, 2);
-        assert!((metrics.net_flow_300s_sol - 9.0).abs() < 0.001);
+        assert_eq!(metrics.window(300).buy_count, 2);
+        assert!((metrics.window(300).net_flow_sol - 9.0).abs() < 0.001);
 
         // 900s window: cutoff is 1200, so trades at 1400 (3.0), 2000 (4.0), 2080 (5.0) = 12.0
-        assert_eq!(metrics.buy_count_900s, 3);
-        assert!((metrics.net_flow_900s_sol - 12.0).abs() < 0.001);
+        assert_eq!(metrics.window(900).buy_count, 3);
+        assert!((metrics.window(900).net_flow_sol - 12.0).abs() < 0.001);
 
         // 3600s window: all trades (1.0 + 2.0 + 3.0 + 4.0 + 5.0 = 15.0)
-        assert!((metrics.net_flow_3600s_sol - 15.0).abs() < 0.001);
+        assert!((metrics.window(3600).net_flow_sol - 15.0).abs() < 0.001);
     }
 
     #[test]
@@ -632,8 +1044,32 @@ mod tests {
         let metrics = state.compute_rolling_metrics();
 
         // Should have 3 unique wallets despite 4 trades
-        assert_eq!(metrics.unique_wallets_300s, 3);
-        assert_eq!(metrics.buy_count_300s + metrics.sell_count_300s, 4);
+        assert_eq!(metrics.window(300).unique_wallets, 3);
+        assert_eq!(metrics.window(300).buy_count + metrics.window(300).sell_count, 4);
+    }
+
+    #[test]
+    fn test_funding_clusters_count_once() {
+        let mut state = TokenRollingState::with_windows("test_mint".to_string(), &[60, 300]);
+        let now = 1000i64;
+
+        // Three sybil wallets funded from one source, one independent wallet
+        for (i, wallet) in ["sybil_a", "sybil_b", "sybil_c"].iter().enumerate() {
+            let mut trade = create_test_trade(now + i as i64, "test_mint", TradeDirection::Buy, 1.0, wallet, false, false);
+            trade.funding_cluster = Some("funder".to_string());
+            state.add_trade(trade);
+        }
+        state.add_trade(create_test_trade(now + 100, "test_mint", TradeDirection::Buy, 1.0, "organic", false, false));
+
+        let metrics = state.compute_rolling_metrics();
+        let w300 = metrics.window(300);
+        assert_eq!((w300.unique_wallets, w300.unique_clusters), (4, 2));
+        assert_eq!(w300.organic_clusters(), 2);
+        assert!(audit::audit_state(&state, now).is_clean());
+
+        // The cluster leaves with its last wallet
+        state.evict_old_trades(now + 350);
+        assert_eq!(state.compute_rolling_metrics().window(300).unique_clusters, 1);
     }
 
     #[test]
@@ -648,8 +1084,8 @@ mod tests {
         let metrics = state.compute_rolling_metrics();
 
         // Should still correctly compute net flow
-        assert!((metrics.net_flow_300s_sol - 4.5).abs() < 0.001);
-        assert_eq!(metrics.buy_count_300s, 3);
+        assert!((metrics.window(300).net_flow_sol - 4.5).abs() < 0.001);
+        assert_eq!(metrics.window(300).buy_count, 3);
     }
 
     #[test]
@@ -674,27 +1110,24 @@ mod tests {
         let metrics = state.compute_rolling_metrics();
 
         // Verify counts
-        assert_eq!(metrics.buy_count_300s + metrics.sell_count_300s, 100);
-        assert_eq!(metrics.buy_count_300s, 50);
-        assert_eq!(metrics.sell_count_300s, 50);
+        assert_eq!(metrics.window(300).buy_count + metrics.window(300).sell_count, 100);
+        assert_eq!(metrics.window(300).buy_count, 50);
+        assert_eq!(metrics.window(300).sell_count, 50);
         
         // Verify unique wallets (should be 10)
-        assert_eq!(metrics.unique_wallets_300s, 10);
+        assert_eq!(metrics.window(300).unique_wallets, 10);
     }
 
     #[test]
-    fn test_verification_layer() {
+    fn test_audit_agrees_with_dca_sells() {
         let mut state = TokenRollingState::new("test_mint".to_string());
         let now = 1000i64;
 
-        // Add normal trades
-        state.add_trade(create_test_trade(now, "test_mint", TradeDirection::Buy, 5.0, "w1", false, false));
-        state.add_trade(create_test_trade(now + 10, "test_mint", TradeDirection::Sell, 2.0, "w2", false, false));
+        // is_dca sells are not DCA buys; the old heuristic check flagged this
+        state.add_trade(create_test_trade(now, "test_mint", TradeDirection::Buy, 5.0, "w1", false, true));
+        state.add_trade(create_test_trade(now + 10, "test_mint", TradeDirection::Sell, 2.0, "w2", false, true));
 
-        let metrics = state.compute_rolling_metrics();
-
-        // Verification should pass
-        assert!(state.verify_metrics(&metrics));
+        assert!(audit::audit_state(&state, now + 10).is_clean());
     }
 
     #[test]
@@ -705,8 +1138,152 @@ mod tests {
         let metrics = state.compute_rolling_metrics();
 
         // DCA ratio should be 0.0 when net flow is 0
-        assert_eq!(metrics.dca_ratio_300s, 0.0);
-        assert_eq!(metrics.net_flow_300s_sol, 0.0);
+        assert_eq!(metrics.window(300).dca_ratio, 0.0);
+        assert_eq!(metrics.window(300).net_flow_sol, 0.0);
+    }
+
+    #[test]
+    fn test_gross_volume_separates_churn_from_flow() {
+        let mut state = TokenRollingState::new("test_mint".to_string());
+        let now = 1000i64;
+
+        state.add_trade(create_test_trade(now, "test_mint", TradeDirection::Buy, 500.0, "w1", false, false));
+        state.add_trade(create_test_trade(now + 10, "test_mint", TradeDirection::Sell, 500.0, "w2", false, false));
+        state.add_trade(create_test_trade(now + 90, "test_mint", TradeDirection::Buy, 2.0, "w3", false, false));
+        state.evict_old_trades(now + 90);
+
+        let metrics = state.compute_rolling_metrics();
+        let w300 = metrics.window(300);
+        assert_eq!(w300.net_flow_sol, 2.0);
+        assert_eq!(w300.buy_volume_sol, 502.0);
+        assert_eq!(w300.sell_volume_sol, 500.0);
+        assert_eq!(w300.volume_sol, 1002.0);
+
+        // Churn left the 60s window; only the late buy remains
+        let w60 = metrics.window(60);
+        assert_eq!((w60.buy_volume_sol, w60.sell_volume_sol, w60.volume_sol), (2.0, 0.0, 2.0));
+        assert!(audit::audit_state(&state, now).is_clean());
+    }
+
+    #[test]
+    fn test_smart_money_metrics_follow_eviction() {
+        let mut state = TokenRollingState::new("test_mint".to_string());
+        let now = 1000i64;
+
+        let mut smart = create_test_trade(now, "test_mint", TradeDirection::Buy, 3.0, "smart", false, false);
+        smart.is_smart_money = true;
+        state.add_trade(smart.clone());
+        smart.timestamp = now + 200;
+        smart.direction = TradeDirection::Sell;
+        smart.sol_amount = 1.0;
+        state.add_trade(smart);
+        state.add_trade(create_test_trade(now + 10, "test_mint", TradeDirection::Buy, 5.0, "retail", false, false));
+
+        let w300 = state.compute_rolling_metrics().window(300);
+        assert_eq!(w300.smart_money_buys, 1);
+        assert_eq!(w300.smart_money_flow_sol, 2.0);
+        assert_eq!(w300.smart_money_wallets, 1);
+
+        // The smart buy leaves the 60s window, the sell stays
+        state.evict_old_trades(now + 200);
+        let w60 = state.compute_rolling_metrics().window(60);
+        assert_eq!(w60.smart_money_buys, 0);
+        assert_eq!(w60.smart_money_flow_sol, -1.0);
+        assert_eq!(w60.smart_money_wallets, 1);
+    }
+
+    #[test]
+    fn test_venue_breakdown_tracks_dominant_venue() {
+        let mut state = TokenRollingState::new("test_mint".to_string());
+        let now = 1000i64;
+
+        // Early flow on the bonding curve, later flow on the AMM
+        state.add_trade(create_test_trade(now, "test_mint", TradeDirection::Buy, 5.0, "w1", false, false));
+        let mut amm = create_test_trade(now + 100, "test_mint", TradeDirection::Buy, 2.0, "w2", false, false);
+        amm.source_program = "Raydium".to_string();
+        state.add_trade(amm.clone());
+        amm.timestamp = now + 110;
+        amm.direction = TradeDirection::Sell;
+        amm.sol_amount = 1.0;
+        state.add_trade(amm);
+
+        let metrics = state.compute_rolling_metrics();
+        let venues: Vec<_> = metrics.venues(300).collect();
+        assert_eq!(venues.len(), 2);
+        assert_eq!(metrics.dominant_venue(300).unwrap().venue, "PumpSwap");
+        let raydium = venues.iter().find(|v| v.venue == "Raydium").unwrap();
+        assert_eq!((raydium.buy_count, raydium.sell_count, raydium.unique_wallets), (1, 1, 1));
+        assert_eq!(raydium.net_flow_sol, 1.0);
+        assert_eq!(raydium.volume_share, 3.0 / 8.0);
+
+        // The bonding curve trade leaves the 60s window: the AMM dominates
+        state.evict_old_trades(now + 120);
+        let metrics = state.compute_rolling_metrics();
+        assert_eq!(metrics.venues(60).count(), 1);
+        let dominant = metrics.dominant_venue(60).unwrap();
+        assert_eq!(dominant.venue, "Raydium");
+        assert_eq!(dominant.volume_share, 1.0);
+        assert_eq!(metrics.dominant_venue(300).unwrap().venue, "PumpSwap");
+    }
+
+    #[test]
+    fn test_wash_round_trips_excluded_from_organic() {
+        let mut state = TokenRollingState::new("test_mint".to_string());
+        let now = 1000i64;
+
+        // Two wallets flipping the same size back and forth
+        for i in 0..6 {
+            let direction = if i % 2 == 0 { TradeDirection::Buy } else { TradeDirection::Sell };
+            state.add_trade(create_test_trade(now + i * 10, "test_mint", direction, 2.0, "washer_a", false, false));
+            state.add_trade(create_test_trade(now + i * 10 + 1, "test_mint", direction, 2.0, "washer_b", false, false));
+        }
+        state.add_trade(create_test_trade(now + 70, "test_mint", TradeDirection::Buy, 1.0, "organic", false, false));
+
+        let metrics = state.compute_rolling_metrics();
+        let w300 = metrics.window(300);
+        // The first buy of each washer opens the pattern, the other 5 legs reverse it
+        assert_eq!(w300.wash_trades, 10);
+        assert_eq!(w300.wash_wallets, 2);
+        assert_eq!(w300.wash_volume_sol, 24.0);
+        assert_eq!(w300.organic_wallets(), 1);
+        assert_eq!(w300.organic_volume_sol(), 1.0);
+        assert!(w300.wash_score > 0.9);
+
+        // Once the washers leave the window only organic flow is left
+        state.evict_old_trades(now + 125);
+        let w60 = state.compute_rolling_metrics().window(60);
+        assert_eq!(w60.wash_trades, 0);
+        assert_eq!(w60.wash_score, 0.0);
+        assert_eq!(w60.organic_wallets(), 1);
+    }
+
+    #[test]
+    fn test_size_distribution_follows_eviction() {
+        let mut state = TokenRollingState::new("test_mint".to_string());
+        let now = 1000i64;
+
+        state.add_trade(create_test_trade(now, "test_mint", TradeDirection::Buy, 40.0, "whale", false, false));
+        for i in 0..9 {
+            let wallet = format!("retail{}", i);
+            state.add_trade(create_test_trade(now + 100 + i, "test_mint", TradeDirection::Buy, 0.2, &wallet, false, false));
+        }
+        state.add_trade(create_test_trade(now + 120, "test_mint", TradeDirection::Sell, 2.0, "mid", false, false));
+
+        let metrics = state.compute_rolling_metrics();
+        let w300 = metrics.window(300);
+        assert_eq!((w300.retail_buys, w300.mid_buys, w300.whale_buys), (9, 0, 1));
+        assert_eq!((w300.retail_sells, w300.mid_sells, w300.whale_sells), (0, 1, 0));
+        assert!((w300.buy_size_p50 - 0.2).abs() < 0.01);
+        assert!((w300.buy_size_p99 - 40.0).abs() < 1.0);
+        assert!((w300.sell_size_p90 - 2.0).abs() < 0.05);
+        assert!(audit::audit_state(&state, now).is_clean());
+
+        // The whale buy leaves the 60s window
+        state.evict_old_trades(now + 150);
+        let w60 = state.compute_rolling_metrics().window(60);
+        assert_eq!(w60.whale_buys, 0);
+        assert_eq!(w60.retail_buys, 9);
+        assert!((w60.buy_size_p99 - 0.2).abs() < 0.01);
     }
 
     #[test]
@@ -718,13 +1295,130 @@ mod tests {
         state.add_trade(create_test_trade(now, "test_mint", TradeDirection::Buy, 1.0, "w1", false, false));
         state.add_trade(create_test_trade(now + 30, "test_mint", TradeDirection::Buy, 1.0, "w2", false, false));
 
-        assert_eq!(state.wallet_activity_60s.len(), 2);
+        assert_eq!(state.window(60).unwrap().wallets.len(), 2);
 
         // Evict old trades (after 80s, first wallet should be cleaned up)
         state.evict_old_trades(now + 80);
 
-        // Only w2 should remain in wallet_activity_60s
-        assert_eq!(state.wallet_activity_60s.len(), 1);
-        assert!(state.wallet_activity_60s.contains_key("w2"));
+        // Only w2 should remain in the 60s window
+        assert_eq!(state.window(60).unwrap().wallets.len(), 1);
+        assert!(state.window(60).unwrap().wallets.contains("w2"));
+    }
+
+    #[test]
+    fn test_bot_wallets_survive_eviction() {
+        let mut state = TokenRollingState::new("test_mint".to_string());
+        let now = 1000i64;
+
+        for i in 0..3 {
+            state.add_trade(create_test_trade(now + i, "test_mint", TradeDirection::Buy, 1.0, "bot_wallet", i == 2, false));
+        }
+        state.evict_old_trades(now + 10);
+
+        let metrics = state.compute_rolling_metrics();
+        assert_eq!(metrics.window(300).bot_wallets, 1);
+
+        // Once the flagged trade leaves the 300s window the wallet is dropped
+        state.evict_old_trades(now + 310);
+        let metrics = state.compute_rolling_metrics();
+        assert_eq!(metrics.window(300).bot_wallets, 0);
+        assert_eq!(metrics.window(300).bot_trades, 0);
+    }
+
+    #[test]
+    fn test_out_of_order_insert_before_window_cursor() {
+        let mut state = TokenRollingState::new("test_mint".to_string());
+
+        state.add_trade(create_test_trade(1000, "test_mint", TradeDirection::Buy, 1.0, "w1", false, false));
+        state.add_trade(create_test_trade(1100, "test_mint", TradeDirection::Buy, 2.0, "w2", false, false));
+        state.evict_old_trades(1100);
+
+        // Late trade older than the 60s cutoff: counted in 300s but not 60s
+        state.add_trade(create_test_trade(1010, "test_mint", TradeDirection::Sell, 0.5, "w3", false, false));
+
+        let metrics = state.compute_rolling_metrics();
+        assert_eq!(metrics.window(60).buy_count, 1);
+        assert_eq!(metrics.window(60).sell_count, 0);
+        assert!((metrics.window(60).net_flow_sol - 2.0).abs() < 0.001);
+        assert!((metrics.window(300).net_flow_sol - 2.5).abs() < 0.001);
+        assert_eq!(state.window_trades(60).count(), 1);
+        assert_eq!(state.window_trades(300).count(), 3);
+
+        let timestamps: Vec<i64> = state.window_trades(300).map(|t| t.timestamp).collect();
+        assert_eq!(timestamps, vec![1000, 1010, 1100]);
+    }
+
+    #[test]
+    fn test_buffer_drops_trades_past_longest_window() {
+        let mut state = TokenRollingState::new("test_mint".to_string());
+
+        state.add_trade(create_test_trade(1000, "test_mint", TradeDirection::Buy, 1.0, "w1", false, false));
+        state.add_trade(create_test_trade(20000, "test_mint", TradeDirection::Buy, 1.0, "w2", false, false));
+        state.evict_old_trades(20000);
+
+        assert_eq!(state.trade_count(), 1);
+        let metrics = state.compute_rolling_metrics();
+        assert!((metrics.window(14400).net_flow_sol - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_custom_windows_full_metric_family() {
+        let mut state = TokenRollingState::with_windows("test_mint".to_string(), &[86400, 30, 300, 30, -5]);
+        assert_eq!(state.window_secs().collect::<Vec<_>>(), vec![30, 60, 300, 86400]);
+
+        state.add_trade(create_test_trade(1000, "test_mint", TradeDirection::Buy, 1.0, "w1", false, true));
+        state.add_trade(create_test_trade(1050, "test_mint", TradeDirection::Sell, 0.4, "w2", false, false));
+        state.evict_old_trades(1050);
+
+        let metrics = state.compute_rolling_metrics();
+        assert_eq!(metrics.windows.len(), 4);
+
+        let w30 = metrics.window(30);
+        assert_eq!((w30.buy_count, w30.sell_count, w30.unique_wallets, w30.dca_buys), (0, 1, 1, 0));
+
+        let w86400 = metrics.window(86400);
+        assert_eq!((w86400.buy_count, w86400.sell_count, w86400.unique_wallets), (1, 1, 2));
+        assert_eq!((w86400.dca_buys, w86400.dca_unique_wallets), (1, 1));
+        assert!((w86400.net_flow_sol - 0.6).abs() < 0.001);
+
+        // Untracked windows read as zero
+        assert!(metrics.get(900).is_none());
+        assert_eq!(metrics.window(900).buy_count, 0);
+    }
+
+    #[test]
+    fn test_windows_warm_up_after_history_start() {
+        let mut state = TokenRollingState::new("test_mint".to_string()).with_history_start(10_000);
+
+        state.add_trade(create_test_trade(10_010, "test_mint", TradeDirection::Buy, 1.0, "w1", false, false));
+        state.evict_old_trades(10_100);
+
+        let metrics = state.compute_rolling_metrics();
+        assert!(!metrics.window(60).warming);
+        assert!(metrics.window(300).warming);
+        assert!(metrics.is_warming(&[60, 300]));
+        assert!(!metrics.is_warming(&[60]));
+
+        state.evict_old_trades(10_300);
+        let metrics = state.compute_rolling_metrics();
+        assert!(!metrics.window(300).warming);
+        assert!(metrics.window(900).warming);
+    }
+
+    #[test]
+    fn test_evict_reports_window_changes() {
+        let mut state = TokenRollingState::new("test_mint".to_string());
+        state.add_trade(create_test_trade(1000, "test_mint", TradeDirection::Buy, 1.0, "w1", false, false));
+
+        // Nothing expired yet
+        assert_eq!(state.evict_old_trades(1030), 0);
+        // Leaves the 60s window only
+        assert_eq!(state.evict_old_trades(1100), 1);
+        assert_eq!(state.evict_old_trades(1100), 0);
+        // Leaves 300s and 900s
+        assert_eq!(state.evict_old_trades(2000), 2);
+
+        assert!(!state.is_idle_at(15_000));
+        assert!(state.is_idle_at(15_401));
     }
 }
@@ -0,0 +1,230 @@
+//! Rolling state sweeper
+//!
+//! A shard's rolling states only grow from the trade path's point of view: a
+//! mint is inserted on its first trade and never removed. The sweeper runs in
+//! the background and has every shard:
+//! - drop mints whose longest window is empty at the current event time
+//!   (dead launches); window decay itself is left to the decay tick
+//! - enforce its share of the memory budget by dropping the coldest mints
+//!   (oldest `last_seen_ts` first) until the estimate fits
+//!
+//! Dropped states are handed back to the shard, which flushes their unsaved
+//! positions to the writer first; it reloads the persisted ones if the mint
+//! trades again.
+
+use crate::{shards::ShardRouter, state::TokenRollingState, watermark::EventClock};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Counters exposed by the sweeper
+#[derive(Debug, Default)]
+pub struct SweeperStats {
+    /// Mints tracked after the last sweep
+    pub tracked_mints: AtomicU64,
+
+    /// Approximate rolling state size after the last sweep (bytes)
+    pub state_bytes: AtomicU64,
+
+    /// Mints dropped because their longest window was empty
+    pub idle_evictions: AtomicU64,
+
+    /// Mints dropped to stay under the memory budget
+    pub budget_evictions: AtomicU64,
+}
+
+impl SweeperStats {
+    /// Record the combined outcome of a sweep over every shard
+    pub fn record(&self, report: &SweepReport) {
+        self.tracked_mints.store(report.tracked_mints as u64, Ordering::Relaxed);
+        self.state_bytes.store(report.state_bytes as u64, Ordering::Relaxed);
+        self.idle_evictions.fetch_add(report.idle_evicted as u64, Ordering::Relaxed);
+        self.budget_evictions.fetch_add(report.budget_evicted as u64, Ordering::Relaxed);
+    }
+}
+
+/// Outcome of a single sweep
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepReport {
+    pub idle_evicted: usize,
+    pub budget_evicted: usize,
+    pub tracked_mints: usize,
+    pub state_bytes: usize,
+}
+
+impl SweepReport {
+    /// Add another shard's report to this one
+    pub fn merge(&mut self, other: &SweepReport) {
+        self.idle_evicted += other.idle_evicted;
+        self.budget_evicted += other.budget_evicted;
+        self.tracked_mints += other.tracked_mints;
+        self.state_bytes += other.state_bytes;
+    }
+}
+
+/// Run one sweep over `states`, returning the report and the dropped states
+///
+/// `budget_bytes == 0` disables the memory budget.
+pub fn sweep(
+    states: &mut HashMap<String, TokenRollingState>,
+    now: i64,
+    budget_bytes: usize,
+) -> (SweepReport, Vec<TokenRollingState>) {
+    let mut report = SweepReport::default();
+
+    // Pass 1: drop mints with nothing left in their longest window
+    let idle: Vec<String> = states
+        .iter()
+        .filter(|(_, state)| state.is_idle_at(now))
+        .map(|(mint, _)| mint.clone())
+        .collect();
+    let mut dropped: Vec<TokenRollingState> = idle.iter().filter_map(|mint| states.remove(mint)).collect();
+    report.idle_evicted = dropped.len();
+
+    // Pass 2: memory budget, coldest mints first
+    let mut usage: Vec<(i64, String, usize)> = states
+        .iter()
+        .map(|(mint, state)| (state.last_seen_ts, mint.clone(), state.approx_bytes()))
+        .collect();
+    let mut total_bytes: usize = usage.iter().map(|(_, _, bytes)| bytes).sum();
+
+    if budget_bytes > 0 && total_bytes > budget_bytes {
+        usage.sort_unstable_by_key(|(last_seen_ts, _, _)| *last_seen_ts);
+
+        for (_, mint, bytes) in usage {
+            if total_bytes <= budget_bytes {
+                break;
+            }
+
+            dropped.extend(states.remove(&mint));
+            total_bytes -= bytes;
+            report.budget_evicted += 1;
+        }
+    }
+
+    report.tracked_mints = states.len();
+    report.state_bytes = total_bytes;
+
+    (report, dropped)
+}
+
+/// Background loop that sweeps every shard every `interval`
+///
+/// Sweeps are skipped until the event clock has a time.
+pub async fn run_sweeper(
+    shards: Arc<ShardRouter>,
+    clock: Arc<EventClock>,
+    interval: Duration,
+    budget_bytes: usize,
+    stats: Arc<SweeperStats>,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    // The first tick completes immediately; nothing to sweep yet
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        let Some(now) = clock.now() else {
+            continue;
+        };
+        let report = shards.sweep(now, budget_bytes).await;
+        stats.record(&report);
+
+        if report.idle_evicted > 0 || report.budget_evicted > 0 {
+            log::info!(
+                "🧹 SWEEP | Tracked: {} | State: {:.1} MB | Idle evicted: {} | Budget evicted: {} | Totals: {}/{}",
+                report.tracked_mints,
+                report.state_bytes as f64 / (1024.0 * 1024.0),
+                report.idle_evicted,
+                report.budget_evicted,
+                stats.idle_evictions.load(Ordering::Relaxed),
+                stats.budget_evictions.load(Ordering::Relaxed)
+            );
+        } else {
+            log::debug!(
+                "🧹 SWEEP | Tracked: {} | State: {:.1} MB",
+                report.tracked_mints,
+                report.state_bytes as f64 / (1024.0 * 1024.0)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TradeDirection, TradeEvent};
+
+    fn trade(mint: &str, timestamp: i64) -> TradeEvent {
+        TradeEvent::test(mint, timestamp, TradeDirection::Buy, 1.0)
+    }
+
+    fn states_with(trades: &[(&str, i64)]) -> HashMap<String, TokenRollingState> {
+        let mut states = HashMap::new();
+        for &(mint, ts) in trades {
+            states
+                .entry(mint.to_string())
+                .or_insert_with(|| TokenRollingState::new(mint.to_string()))
+                .add_trade(trade(mint, ts));
+        }
+        states
+    }
+
+    #[test]
+    fn test_sweep_drops_idle_mints() {
+        let mut states = states_with(&[("dead", 1_000), ("alive", 20_000)]);
+        let stats = SweeperStats::default();
+
+        let (report, dropped) = sweep(&mut states, 20_000, 0);
+        stats.record(&report);
+
+        assert_eq!(report.idle_evicted, 1);
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].mint, "dead");
+        assert_eq!(report.tracked_mints, 1);
+        assert!(states.contains_key("alive"));
+        assert!(!states.contains_key("dead"));
+        assert_eq!(stats.idle_evictions.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_sweep_enforces_budget_coldest_first() {
+        let mut states = states_with(&[("cold", 1_000), ("warm", 1_010), ("hot", 1_020)]);
+        let stats = SweeperStats::default();
+        let per_mint = states.get("hot").unwrap().approx_bytes();
+
+        // Room for roughly two mints
+        let (report, dropped) = sweep(&mut states, 1_020, per_mint * 2 + per_mint / 2);
+        stats.record(&report);
+
+        assert_eq!(report.budget_evicted, 1);
+        assert_eq!(dropped[0].mint, "cold");
+        assert!(!states.contains_key("cold"));
+        assert!(states.contains_key("warm"));
+        assert!(states.contains_key("hot"));
+        assert!(report.state_bytes <= per_mint * 3);
+        assert_eq!(stats.tracked_mints.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_approx_bytes_shrinks_after_eviction() {
+        let mut state = TokenRollingState::new("mint".to_string());
+        let empty = state.approx_bytes();
+
+        state.add_trade(trade("mint", 1_000));
+        state.add_trade(trade("mint", 1_001));
+        assert!(state.approx_bytes() > empty);
+
+        state.evict_old_trades(100_000);
+        state.close_candles(100_000);
+        assert!(state.is_idle());
+        // Positions outlive the windows
+        assert_eq!(state.approx_bytes(), empty + state.positions.approx_bytes());
+    }
+}
@@ -7,12 +7,18 @@
 //! DEX programs (Pumpfun, PumpSwap, BonkSwap, Moonshot, JupiterDCA) and normalizes them
 //! into a common TradeEvent format.
 
-use crate::types::{TradeDirection, TradeEvent};
+use crate::{
+    trade_source::{Extraction, SkipReason, TradeSource},
+    types::{LaunchEvent, PoolReserves, TradeDirection, TradeEvent},
+};
 use carbon_core::{
     deserialize::ArrangeAccounts, 
     instruction::{InstructionMetadata, InstructionProcessorInputType}
 };
 
+/// Wrapped SOL mint, the quote side of SOL pools
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
 pub struct TradeExtractor;
 
 impl TradeExtractor {
@@ -33,6 +39,13 @@ impl TradeExtractor {
             source_program: "Pumpfun".to_string(),
             is_bot: false,
             is_dca: false,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
 
@@ -53,6 +66,13 @@ impl TradeExtractor {
             source_program: "Pumpfun".to_string(),
             is_bot: false,
             is_dca: false,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
 
@@ -71,9 +91,46 @@ impl TradeExtractor {
             source_program: "PumpSwap".to_string(),
             is_bot: false,
             is_dca: false,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
 
+    /// Pool reserves after a PumpSwap BuyEvent
+    ///
+    /// The event reports the reserves before the swap; LP fees left in the
+    /// pool are ignored.
+    pub fn pumpswap_buy_event_reserves(
+        event: &carbon_pump_swap_decoder::instructions::buy_event::BuyEvent,
+    ) -> PoolReserves {
+        PoolReserves {
+            mint: event.pool.to_string(),
+            base_reserve: event.pool_base_token_reserves.saturating_sub(event.base_amount_out) as f64 / 1_000_000.0,
+            quote_reserve_sol: (event.pool_quote_token_reserves + event.quote_amount_in) as f64 / 1_000_000_000.0,
+            timestamp: event.timestamp,
+            slot: 0,
+        }
+    }
+
+    /// Pool reserves after a PumpSwap SellEvent (see `pumpswap_buy_event_reserves`)
+    pub fn pumpswap_sell_event_reserves(
+        event: &carbon_pump_swap_decoder::instructions::sell_event::SellEvent,
+    ) -> PoolReserves {
+        PoolReserves {
+            mint: event.pool.to_string(),
+            base_reserve: (event.pool_base_token_reserves + event.base_amount_in) as f64 / 1_000_000.0,
+            quote_reserve_sol: event.pool_quote_token_reserves.saturating_sub(event.quote_amount_out) as f64
+                / 1_000_000_000.0,
+            timestamp: event.timestamp,
+            slot: 0,
+        }
+    }
+
     /// Extract a TradeEvent from a PumpSwap SellEvent
     pub fn extract_pumpswap_sell_event(
         event: &carbon_pump_swap_decoder::instructions::sell_event::SellEvent,
@@ -89,6 +146,13 @@ impl TradeExtractor {
             source_program: "PumpSwap".to_string(),
             is_bot: false,
             is_dca: false,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
 
@@ -117,6 +181,13 @@ impl TradeExtractor {
             source_program: "Moonshot".to_string(),
             is_bot: false,
             is_dca: false,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
 
@@ -137,12 +208,22 @@ impl TradeExtractor {
             source_program: "Moonshot".to_string(),
             is_bot: false,
             is_dca: false,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
 
     /// Extract a TradeEvent from a JupiterDCA FilledEvent
+    ///
+    /// `timestamp` is the transaction's block time, like every other venue.
     pub fn extract_jupiter_dca_filled_event(
         event: &carbon_jupiter_dca_decoder::instructions::filled_event::FilledEvent,
+        timestamp: i64,
     ) -> Option<TradeEvent> {
         let direction = if event.input_mint.to_string() == "So11111111111111111111111111111111111111112" {
             TradeDirection::Buy
@@ -169,7 +250,7 @@ impl TradeExtractor {
         };
 
         Some(TradeEvent {
-            timestamp: chrono::Utc::now().timestamp(),
+            timestamp,
             mint: if direction == TradeDirection::Buy {
                 event.output_mint.to_string()
             } else {
@@ -183,33 +264,134 @@ impl TradeExtractor {
             source_program: "JupiterDCA".to_string(),
             is_bot: false,
             is_dca: true,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
 
-    /// Unified adapter for Pumpfun instructions
-    pub fn extract_from_pumpfun(
-        input: &InstructionProcessorInputType<carbon_pumpfun_decoder::instructions::PumpfunInstruction>,
-    ) -> Option<TradeEvent> {
-        let (metadata, decoded_instruction, _nested_instructions, _raw_instruction) = input;
-        let timestamp = metadata.transaction_metadata.block_time.unwrap_or(0);
-
-        match &decoded_instruction.data {
-            carbon_pumpfun_decoder::instructions::PumpfunInstruction::Buy(buy) => {
-                let accounts = carbon_pumpfun_decoder::instructions::buy::Buy::arrange_accounts(
-                    &decoded_instruction.accounts,
-                )?;
-                Self::extract_pumpfun_buy(&accounts, buy, timestamp)
-            }
-            carbon_pumpfun_decoder::instructions::PumpfunInstruction::Sell(sell) => {
-                let accounts = carbon_pumpfun_decoder::instructions::sell::Sell::arrange_accounts(
-                    &decoded_instruction.accounts,
-                )?;
-                Self::extract_pumpfun_sell(&accounts, sell, timestamp)
-            }
-            _ => None,
+    /// Extract a LaunchEvent from a Pumpfun Create instruction
+    ///
+    /// Slot and tip are filled in by the processor, like for trades.
+    pub fn extract_pumpfun_create(
+        accounts: &carbon_pumpfun_decoder::instructions::create::CreateInstructionAccounts,
+        timestamp: i64,
+    ) -> LaunchEvent {
+        LaunchEvent {
+            mint: accounts.mint.to_string(),
+            creator: accounts.user.to_string(),
+            source_program: "Pumpfun".to_string(),
+            timestamp,
+            slot: 0,
+            tip_lamports: 0,
+            migration: false,
         }
     }
 
+    /// Extract a LaunchEvent from a PumpSwap CreatePool instruction
+    ///
+    /// The launched mint is the non-SOL side; None for pools without SOL.
+    /// `migration` is set when pump.fun created the pool (see
+    /// `is_pumpfun_migration`).
+    pub fn extract_pumpswap_create_pool(
+        accounts: &carbon_pump_swap_decoder::instructions::create_pool::CreatePoolInstructionAccounts,
+        timestamp: i64,
+        migration: bool,
+    ) -> Option<LaunchEvent> {
+        Self::pumpswap_pool_launch(
+            &accounts.base_mint.to_string(),
+            &accounts.quote_mint.to_string(),
+            &accounts.creator.to_string(),
+            timestamp,
+            migration,
+        )
+    }
+
+    /// LaunchEvent of a PumpSwap pool between `base_mint` and `quote_mint`
+    /// created by `pool_creator`
+    ///
+    /// A migration pool's creator is pump.fun's migration authority; the
+    /// launch is flagged so it is neither a sniper watch nor a creator.
+    fn pumpswap_pool_launch(
+        base_mint: &str,
+        quote_mint: &str,
+        pool_creator: &str,
+        timestamp: i64,
+        migration: bool,
+    ) -> Option<LaunchEvent> {
+        let mint = if quote_mint == WSOL_MINT {
+            base_mint
+        } else if base_mint == WSOL_MINT {
+            quote_mint
+        } else {
+            return None;
+        };
+
+        Some(LaunchEvent {
+            mint: mint.to_string(),
+            creator: pool_creator.to_string(),
+            source_program: "PumpSwap".to_string(),
+            timestamp,
+            slot: 0,
+            tip_lamports: 0,
+            migration,
+        })
+    }
+
+    /// Whether the pump.fun program is part of the transaction, i.e. a pool
+    /// created in it is pump.fun migrating a bonding-curve token
+    fn is_pumpfun_migration(metadata: &InstructionMetadata) -> bool {
+        metadata
+            .transaction_metadata
+            .message
+            .static_account_keys()
+            .iter()
+            .any(|key| key.as_ref() == carbon_pumpfun_decoder::PROGRAM_ID.as_ref())
+    }
+
+    /// Post-transaction token balance (whole tokens) of a static account
+    ///
+    /// None when the account is loaded from a lookup table or has no token
+    /// balance in the transaction.
+    fn post_token_amount(metadata: &InstructionMetadata, account: &[u8]) -> Option<f64> {
+        let tx_meta = &metadata.transaction_metadata;
+        let index = tx_meta
+            .message
+            .static_account_keys()
+            .iter()
+            .position(|key| key.as_ref() == account)?;
+
+        tx_meta
+            .meta
+            .post_token_balances
+            .as_ref()?
+            .iter()
+            .find(|balance| balance.account_index as usize == index)?
+            .ui_token_amount
+            .ui_amount
+    }
+
+    /// Pool reserves after a PumpSwap swap instruction, read from the pool
+    /// vaults' post-transaction balances
+    fn pumpswap_vault_reserves(
+        metadata: &InstructionMetadata,
+        mint: String,
+        base_vault: &[u8],
+        quote_vault: &[u8],
+    ) -> Option<PoolReserves> {
+        Some(PoolReserves {
+            mint,
+            base_reserve: Self::post_token_amount(metadata, base_vault)?,
+            quote_reserve_sol: Self::post_token_amount(metadata, quote_vault)?,
+            timestamp: metadata.transaction_metadata.block_time.unwrap_or(0),
+            slot: metadata.transaction_metadata.slot,
+        })
+    }
+
     /// Helper to find the account index for a given pubkey in transaction metadata
     fn get_account_index(
         metadata: &InstructionMetadata,
@@ -314,6 +496,13 @@ impl TradeExtractor {
             source_program: "PumpSwap".to_string(),
             is_bot: false,
             is_dca: false,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
 
@@ -356,6 +545,13 @@ impl TradeExtractor {
             source_program: "PumpSwap".to_string(),
             is_bot: false,
             is_dca: false,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
 
@@ -398,92 +594,238 @@ impl TradeExtractor {
             source_program: "PumpSwap".to_string(),
             is_bot: false,
             is_dca: false,
+            is_smart_money: false,
+            slot: 0,
+            fee_lamports: 0,
+            tip_lamports: 0,
+            is_wash: false,
+            funding_cluster: None,
+            price_impact: None,
         })
     }
+}
 
-    /// Unified adapter for PumpSwap instructions
-    pub fn extract_from_pumpswap(
-        input: &InstructionProcessorInputType<carbon_pump_swap_decoder::instructions::PumpSwapInstruction>,
-    ) -> Option<TradeEvent> {
+/// Pumpfun bonding-curve trades (Buy / Sell instructions) and token creations
+pub struct PumpfunSource;
+
+impl TradeSource for PumpfunSource {
+    type Instruction = carbon_pumpfun_decoder::instructions::PumpfunInstruction;
+
+    fn name(&self) -> &'static str {
+        "Pumpfun"
+    }
+
+    fn extract(&mut self, input: &InstructionProcessorInputType<Self::Instruction>) -> Extraction {
         let (metadata, decoded_instruction, _nested_instructions, _raw_instruction) = input;
+        let timestamp = metadata.transaction_metadata.block_time.unwrap_or(0);
 
         match &decoded_instruction.data {
-            // Legacy event variants (kept for backward compatibility)
-            carbon_pump_swap_decoder::instructions::PumpSwapInstruction::BuyEvent(event) => {
-                Self::extract_pumpswap_buy_event(event)
+            carbon_pumpfun_decoder::instructions::PumpfunInstruction::Buy(buy) => {
+                let Some(accounts) = carbon_pumpfun_decoder::instructions::buy::Buy::arrange_accounts(
+                    &decoded_instruction.accounts,
+                ) else {
+                    return Extraction::skip(SkipReason::AccountsUnavailable);
+                };
+                Extraction::from_option(
+                    TradeExtractor::extract_pumpfun_buy(&accounts, buy, timestamp),
+                    SkipReason::NotATrade,
+                )
             }
-            carbon_pump_swap_decoder::instructions::PumpSwapInstruction::SellEvent(event) => {
-                Self::extract_pumpswap_sell_event(event)
+            carbon_pumpfun_decoder::instructions::PumpfunInstruction::Sell(sell) => {
+                let Some(accounts) = carbon_pumpfun_decoder::instructions::sell::Sell::arrange_accounts(
+                    &decoded_instruction.accounts,
+                ) else {
+                    return Extraction::skip(SkipReason::AccountsUnavailable);
+                };
+                Extraction::from_option(
+                    TradeExtractor::extract_pumpfun_sell(&accounts, sell, timestamp),
+                    SkipReason::NotATrade,
+                )
+            }
+            carbon_pumpfun_decoder::instructions::PumpfunInstruction::Create(_) => {
+                let Some(accounts) = carbon_pumpfun_decoder::instructions::create::Create::arrange_accounts(
+                    &decoded_instruction.accounts,
+                ) else {
+                    return Extraction::skip(SkipReason::AccountsUnavailable);
+                };
+                Extraction::launch(TradeExtractor::extract_pumpfun_create(&accounts, timestamp))
             }
+            _ => Extraction::skip(SkipReason::NotATrade),
+        }
+    }
+}
+
+/// PumpSwap AMM trades (Buy / Sell / BuyExactQuoteIn and legacy events) with pool reserves, and pool creations
+pub struct PumpSwapSource;
+
+impl TradeSource for PumpSwapSource {
+    type Instruction = carbon_pump_swap_decoder::instructions::PumpSwapInstruction;
+
+    fn name(&self) -> &'static str {
+        "PumpSwap"
+    }
+
+    fn extract(&mut self, input: &InstructionProcessorInputType<Self::Instruction>) -> Extraction {
+        use carbon_pump_swap_decoder::instructions::PumpSwapInstruction;
+
+        let (metadata, decoded_instruction, _nested_instructions, _raw_instruction) = input;
+
+        let (trade, reserves) = match &decoded_instruction.data {
+            // Legacy event variants (kept for backward compatibility)
+            PumpSwapInstruction::BuyEvent(event) => (
+                TradeExtractor::extract_pumpswap_buy_event(event),
+                Some(TradeExtractor::pumpswap_buy_event_reserves(event)),
+            ),
+            PumpSwapInstruction::SellEvent(event) => (
+                TradeExtractor::extract_pumpswap_sell_event(event),
+                Some(TradeExtractor::pumpswap_sell_event_reserves(event)),
+            ),
             // New swap instruction variants (primary live activity)
-            carbon_pump_swap_decoder::instructions::PumpSwapInstruction::Buy(buy) => {
-                let accounts = carbon_pump_swap_decoder::instructions::buy::Buy::arrange_accounts(
+            PumpSwapInstruction::Buy(buy) => {
+                let Some(accounts) = carbon_pump_swap_decoder::instructions::buy::Buy::arrange_accounts(
                     &decoded_instruction.accounts,
-                )?;
-                Self::extract_pumpswap_buy(&accounts, buy, metadata)
+                ) else {
+                    return Extraction::skip(SkipReason::AccountsUnavailable);
+                };
+                let reserves = TradeExtractor::pumpswap_vault_reserves(
+                    metadata,
+                    accounts.base_mint.to_string(),
+                    accounts.pool_base_token_account.as_ref(),
+                    accounts.pool_quote_token_account.as_ref(),
+                );
+                (TradeExtractor::extract_pumpswap_buy(&accounts, buy, metadata), reserves)
             }
-            carbon_pump_swap_decoder::instructions::PumpSwapInstruction::Sell(sell) => {
-                let accounts = carbon_pump_swap_decoder::instructions::sell::Sell::arrange_accounts(
+            PumpSwapInstruction::Sell(sell) => {
+                let Some(accounts) = carbon_pump_swap_decoder::instructions::sell::Sell::arrange_accounts(
                     &decoded_instruction.accounts,
-                )?;
-                Self::extract_pumpswap_sell(&accounts, sell, metadata)
+                ) else {
+                    return Extraction::skip(SkipReason::AccountsUnavailable);
+                };
+                let reserves = TradeExtractor::pumpswap_vault_reserves(
+                    metadata,
+                    accounts.base_mint.to_string(),
+                    accounts.pool_base_token_account.as_ref(),
+                    accounts.pool_quote_token_account.as_ref(),
+                );
+                (TradeExtractor::extract_pumpswap_sell(&accounts, sell, metadata), reserves)
             }
-            carbon_pump_swap_decoder::instructions::PumpSwapInstruction::BuyExactQuoteIn(buy_exact) => {
-                let accounts = carbon_pump_swap_decoder::instructions::buy_exact_quote_in::BuyExactQuoteIn::arrange_accounts(
+            PumpSwapInstruction::BuyExactQuoteIn(buy_exact) => {
+                let Some(accounts) = carbon_pump_swap_decoder::instructions::buy_exact_quote_in::BuyExactQuoteIn::arrange_accounts(
                     &decoded_instruction.accounts,
-                )?;
-                Self::extract_pumpswap_buy_exact_quote_in(&accounts, buy_exact, metadata)
+                ) else {
+                    return Extraction::skip(SkipReason::AccountsUnavailable);
+                };
+                let reserves = TradeExtractor::pumpswap_vault_reserves(
+                    metadata,
+                    accounts.base_mint.to_string(),
+                    accounts.pool_base_token_account.as_ref(),
+                    accounts.pool_quote_token_account.as_ref(),
+                );
+                (TradeExtractor::extract_pumpswap_buy_exact_quote_in(&accounts, buy_exact, metadata), reserves)
+            }
+            PumpSwapInstruction::CreatePool(_) => {
+                let Some(accounts) = carbon_pump_swap_decoder::instructions::create_pool::CreatePool::arrange_accounts(
+                    &decoded_instruction.accounts,
+                ) else {
+                    return Extraction::skip(SkipReason::AccountsUnavailable);
+                };
+                let timestamp = metadata.transaction_metadata.block_time.unwrap_or(0);
+                let migration = TradeExtractor::is_pumpfun_migration(metadata);
+                return match TradeExtractor::extract_pumpswap_create_pool(&accounts, timestamp, migration) {
+                    Some(launch) => Extraction::launch(launch),
+                    None => Extraction::skip(SkipReason::NonSolPair),
+                };
             }
             _ => {
                 log::debug!("⚠️ PUMPSWAP_VARIANT_UNHANDLED | Variant: {:?}", decoded_instruction.data);
-                None
+                return Extraction::skip(SkipReason::NotATrade);
             }
-        }
+        };
+
+        Extraction::from_option(trade, SkipReason::AccountsUnavailable).with_reserves(reserves)
     }
+}
 
-    /// Unified adapter for Moonshot instructions
-    pub fn extract_from_moonshot(
-        input: &InstructionProcessorInputType<carbon_moonshot_decoder::instructions::MoonshotInstruction>,
-    ) -> Option<TradeEvent> {
+/// Moonshot bonding-curve trades (Buy / Sell instructions)
+pub struct MoonshotSource;
+
+impl TradeSource for MoonshotSource {
+    type Instruction = carbon_moonshot_decoder::instructions::MoonshotInstruction;
+
+    fn name(&self) -> &'static str {
+        "Moonshot"
+    }
+
+    fn extract(&mut self, input: &InstructionProcessorInputType<Self::Instruction>) -> Extraction {
         let (metadata, decoded_instruction, _nested_instructions, _raw_instruction) = input;
         let timestamp = metadata.transaction_metadata.block_time.unwrap_or(0);
 
         match &decoded_instruction.data {
             carbon_moonshot_decoder::instructions::MoonshotInstruction::Buy(buy) => {
-                let accounts = carbon_moonshot_decoder::instructions::buy::Buy::arrange_accounts(
+                let Some(accounts) = carbon_moonshot_decoder::instructions::buy::Buy::arrange_accounts(
                     &decoded_instruction.accounts,
-                )?;
-                Self::extract_moonshot_buy(&accounts, buy, timestamp)
+                ) else {
+                    return Extraction::skip(SkipReason::AccountsUnavailable);
+                };
+                Extraction::from_option(
+                    TradeExtractor::extract_moonshot_buy(&accounts, buy, timestamp),
+                    SkipReason::NotATrade,
+                )
             }
             carbon_moonshot_decoder::instructions::MoonshotInstruction::Sell(sell) => {
-                let accounts = carbon_moonshot_decoder::instructions::sell::Sell::arrange_accounts(
+                let Some(accounts) = carbon_moonshot_decoder::instructions::sell::Sell::arrange_accounts(
                     &decoded_instruction.accounts,
-                )?;
-                Self::extract_moonshot_sell(&accounts, sell, timestamp)
+                ) else {
+                    return Extraction::skip(SkipReason::AccountsUnavailable);
+                };
+                Extraction::from_option(
+                    TradeExtractor::extract_moonshot_sell(&accounts, sell, timestamp),
+                    SkipReason::NotATrade,
+                )
             }
-            _ => None,
+            _ => Extraction::skip(SkipReason::NotATrade),
         }
     }
+}
 
-    /// Unified adapter for JupiterDCA instructions
-    pub fn extract_from_jupiter_dca(
-        input: &InstructionProcessorInputType<carbon_jupiter_dca_decoder::instructions::JupiterDcaInstruction>,
-    ) -> Option<TradeEvent> {
-        let (_metadata, decoded_instruction, _nested_instructions, _raw_instruction) = input;
+/// JupiterDCA fills (FilledEvent) against SOL
+pub struct JupiterDcaSource;
+
+impl TradeSource for JupiterDcaSource {
+    type Instruction = carbon_jupiter_dca_decoder::instructions::JupiterDcaInstruction;
+
+    fn name(&self) -> &'static str {
+        "JupiterDCA"
+    }
+
+    fn extract(&mut self, input: &InstructionProcessorInputType<Self::Instruction>) -> Extraction {
+        let (metadata, decoded_instruction, _nested_instructions, _raw_instruction) = input;
+        let timestamp = metadata.transaction_metadata.block_time.unwrap_or(0);
 
         match &decoded_instruction.data {
             carbon_jupiter_dca_decoder::instructions::JupiterDcaInstruction::FilledEvent(event) => {
-                Self::extract_jupiter_dca_filled_event(event)
+                Extraction::from_option(
+                    TradeExtractor::extract_jupiter_dca_filled_event(event, timestamp),
+                    SkipReason::NonSolPair,
+                )
             }
-            _ => None,
+            _ => Extraction::skip(SkipReason::NotATrade),
         }
     }
+}
 
-    /// Unified adapter for BonkSwap instructions (placeholder)
-    pub fn extract_from_bonkswap(
-        _input: &InstructionProcessorInputType<carbon_bonkswap_decoder::instructions::BonkswapInstruction>,
-    ) -> Option<TradeEvent> {
-        None
+/// BonkSwap (placeholder: swap decoding not implemented yet)
+pub struct BonkswapSource;
+
+impl TradeSource for BonkswapSource {
+    type Instruction = carbon_bonkswap_decoder::instructions::BonkswapInstruction;
+
+    fn name(&self) -> &'static str {
+        "BonkSwap"
+    }
+
+    fn extract(&mut self, _input: &InstructionProcessorInputType<Self::Instruction>) -> Extraction {
+        Extraction::skip(SkipReason::Unsupported)
     }
 }
 
@@ -496,4 +838,17 @@ mod tests {
         assert_eq!(TradeDirection::Buy, TradeDirection::Buy);
         assert_eq!(TradeDirection::Sell, TradeDirection::Sell);
     }
+
+    #[test]
+    fn test_pumpswap_pool_launch() {
+        let launch = TradeExtractor::pumpswap_pool_launch("token", WSOL_MINT, "dev", 1_000, false).unwrap();
+        assert_eq!((launch.mint.as_str(), launch.creator.as_str()), ("token", "dev"));
+        assert!(!launch.migration);
+
+        let migrated = TradeExtractor::pumpswap_pool_launch(WSOL_MINT, "token", "migrator", 1_000, true).unwrap();
+        assert_eq!(migrated.mint, "token");
+        assert!(migrated.migration);
+
+        assert!(TradeExtractor::pumpswap_pool_launch("a", "b", "dev", 1_000, false).is_none());
+    }
 }
@@ -0,0 +1,256 @@
+//! Pluggable trade sources
+//!
+//! A `TradeSource` turns one decoded instruction of a given venue into zero or
+//! more `TradeEvent`s (and token or pool creations as `LaunchEvent`s), or
+//! explains why it produced none. Sources take `&mut self`
+//! so they can keep per-venue caches (pools, decimals, ...).
+//!
+//! Each venue is registered as a `Venue` (program id + decoder + source). The
+//! `SourceRegistry` holds the enabled venues and `main.rs` iterates it to build
+//! both the Geyser subscription filter and the pipeline. Adding a venue means
+//! implementing `TradeSource` and adding one entry to `SourceRegistry::builtin`.
+
+use crate::{
+    processor::{NetSolFlowProcessor, ProcessorContext},
+    trade_extractor::{BonkswapSource, JupiterDcaSource, MoonshotSource, PumpSwapSource, PumpfunSource},
+    types::{LaunchEvent, PoolReserves, TradeEvent},
+};
+use carbon_core::{
+    instruction::{InstructionDecoder, InstructionProcessorInputType},
+    pipeline::PipelineBuilder,
+};
+
+/// Structured reason for an instruction that yielded no trades
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    /// Instruction variant does not represent a swap (admin, create, deposit, ...)
+    NotATrade,
+    /// Instruction accounts could not be arranged
+    AccountsUnavailable,
+    /// Neither side of the swap is SOL
+    NonSolPair,
+    /// Swap decoding for this venue is not implemented yet
+    Unsupported,
+}
+
+impl SkipReason {
+    /// Stable identifier used in logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::NotATrade => "not_a_trade",
+            SkipReason::AccountsUnavailable => "accounts_unavailable",
+            SkipReason::NonSolPair => "non_sol_pair",
+            SkipReason::Unsupported => "unsupported",
+        }
+    }
+}
+
+/// Result of running a `TradeSource` over one instruction
+#[derive(Debug, Default)]
+pub struct Extraction {
+    /// Trades extracted from the instruction (may be more than one)
+    pub trades: Vec<TradeEvent>,
+
+    /// Token or pool creations in the instruction
+    pub launches: Vec<LaunchEvent>,
+
+    /// Pool reserves right after the instruction's trades, for venues that
+    /// know them
+    pub reserves: Vec<PoolReserves>,
+
+    /// Set when the instruction produced no trades
+    pub skipped: Option<SkipReason>,
+}
+
+impl Extraction {
+    /// A single extracted trade
+    pub fn trade(trade: TradeEvent) -> Self {
+        Self {
+            trades: vec![trade],
+            launches: Vec::new(),
+            reserves: Vec::new(),
+            skipped: None,
+        }
+    }
+
+    /// A token or pool creation (no trade)
+    pub fn launch(launch: LaunchEvent) -> Self {
+        Self {
+            trades: Vec::new(),
+            launches: vec![launch],
+            reserves: Vec::new(),
+            skipped: None,
+        }
+    }
+
+    /// No trade, with the reason why
+    pub fn skip(reason: SkipReason) -> Self {
+        Self {
+            trades: Vec::new(),
+            launches: Vec::new(),
+            reserves: Vec::new(),
+            skipped: Some(reason),
+        }
+    }
+
+    /// Attach the pool reserves reported with the trades, if any
+    pub fn with_reserves(mut self, reserves: Option<PoolReserves>) -> Self {
+        self.reserves.extend(reserves);
+        self
+    }
+
+    /// Convert an optional trade, using `reason` when it is missing
+    pub fn from_option(trade: Option<TradeEvent>, reason: SkipReason) -> Self {
+        match trade {
+            Some(trade) => Self::trade(trade),
+            None => Self::skip(reason),
+        }
+    }
+}
+
+/// Converts decoded instructions of one venue into trades
+pub trait TradeSource: Send + Sync + 'static {
+    /// Decoded instruction type produced by the venue's Carbon decoder
+    type Instruction: Send + Sync + 'static;
+
+    /// Venue name used in logs and as `TradeEvent::source_program`
+    fn name(&self) -> &'static str;
+
+    /// Extract zero or more trades from one decoded instruction
+    fn extract(&mut self, input: &InstructionProcessorInputType<Self::Instruction>) -> Extraction;
+}
+
+/// Type-erased venue: everything needed to subscribe to and process one program
+pub trait Venue: Send {
+    /// Config name (matched against `SOLFLOW_VENUES`)
+    fn name(&self) -> &'static str;
+
+    /// Program id to include in the Geyser transaction filter
+    fn program_id(&self) -> String;
+
+    /// Register the venue's decoder and processor on the pipeline
+    fn attach(self: Box<Self>, builder: PipelineBuilder, ctx: &ProcessorContext) -> PipelineBuilder;
+}
+
+/// A decoder paired with the `TradeSource` that consumes its output
+pub struct VenueEntry<D, S> {
+    name: &'static str,
+    program_id: String,
+    decoder: D,
+    source: S,
+}
+
+impl<D, S> VenueEntry<D, S> {
+    pub fn new(name: &'static str, program_id: impl ToString, decoder: D, source: S) -> Self {
+        Self {
+            name,
+            program_id: program_id.to_string(),
+            decoder,
+            source,
+        }
+    }
+}
+
+impl<D, S> Venue for VenueEntry<D, S>
+where
+    D: for<'a> InstructionDecoder<'a, InstructionType = S::Instruction> + Send + Sync + 'static,
+    S: TradeSource,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn program_id(&self) -> String {
+        self.program_id.clone()
+    }
+
+    fn attach(self: Box<Self>, builder: PipelineBuilder, ctx: &ProcessorContext) -> PipelineBuilder {
+        let VenueEntry { decoder, source, .. } = *self;
+        builder.instruction(decoder, NetSolFlowProcessor::new(ctx.clone(), source))
+    }
+}
+
+/// Ordered set of enabled venues
+pub struct SourceRegistry {
+    venues: Vec<Box<dyn Venue>>,
+}
+
+impl SourceRegistry {
+    /// All venues SolFlow knows how to ingest
+    pub fn builtin() -> Self {
+        Self {
+            venues: vec![
+                Box::new(VenueEntry::new(
+                    "pumpswap",
+                    carbon_pump_swap_decoder::PROGRAM_ID,
+                    carbon_pump_swap_decoder::PumpSwapDecoder,
+                    PumpSwapSource,
+                )),
+                Box::new(VenueEntry::new(
+                    "moonshot",
+                    carbon_moonshot_decoder::PROGRAM_ID,
+                    carbon_moonshot_decoder::MoonshotDecoder,
+                    MoonshotSource,
+                )),
+                Box::new(VenueEntry::new(
+                    "bonkswap",
+                    carbon_bonkswap_decoder::PROGRAM_ID,
+                    carbon_bonkswap_decoder::BonkswapDecoder,
+                    BonkswapSource,
+                )),
+                Box::new(VenueEntry::new(
+                    "pumpfun",
+                    carbon_pumpfun_decoder::PROGRAM_ID,
+                    carbon_pumpfun_decoder::PumpfunDecoder,
+                    PumpfunSource,
+                )),
+                Box::new(VenueEntry::new(
+                    "jupiter_dca",
+                    carbon_jupiter_dca_decoder::PROGRAM_ID,
+                    carbon_jupiter_dca_decoder::JupiterDcaDecoder,
+                    JupiterDcaSource,
+                )),
+            ],
+        }
+    }
+
+    /// Keep only the venues named in `enabled` (case-insensitive)
+    ///
+    /// Unknown names are logged and ignored.
+    pub fn enabled(mut self, enabled: &[String]) -> Self {
+        for name in enabled {
+            if !self.venues.iter().any(|v| v.name().eq_ignore_ascii_case(name)) {
+                log::warn!("⚠️  Unknown venue in SOLFLOW_VENUES: {}", name);
+            }
+        }
+
+        self.venues
+            .retain(|v| enabled.iter().any(|name| name.eq_ignore_ascii_case(v.name())));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.venues.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.venues.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.venues.iter().map(|v| v.name()).collect()
+    }
+
+    pub fn program_ids(&self) -> Vec<String> {
+        self.venues.iter().map(|v| v.program_id()).collect()
+    }
+
+    /// Attach every enabled venue to the pipeline builder
+    pub fn attach_all(self, mut builder: PipelineBuilder, ctx: &ProcessorContext) -> PipelineBuilder {
+        for venue in self.venues {
+            log::info!("🔌 Registering venue: {}", venue.name());
+            builder = venue.attach(builder, ctx);
+        }
+        builder
+    }
+}
@@ -5,11 +5,14 @@
 //! - `AggregatedTokenState` → `token_aggregates` table
 //! - Field names use exact SQL column names (snake_case)
 
+use crate::state::WindowMetrics;
+
 /// Trade direction enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum TradeDirection {
     Buy,
     Sell,
+    #[default]
     Unknown,
 }
 
@@ -33,7 +36,10 @@ pub struct TokenMetadata {
 ///
 /// This represents a single trade extracted from on-chain data.
 /// These events are held in-memory in rolling windows and never persisted as raw trades.
-#[derive(Debug, Clone)]
+///
+/// `Default` leaves every field empty; tests and benches fill in what they
+/// need with struct update syntax.
+#[derive(Debug, Clone, Default)]
 pub struct TradeEvent {
     pub timestamp: i64,
     pub mint: String,
@@ -51,6 +57,114 @@ pub struct TradeEvent {
     /// Phase 4: DCA flag
     /// True if this trade comes from JupiterDCA program
     pub is_dca: bool,
+
+    /// True if the trading wallet is labelled smart money (see `wallets`)
+    /// when the trade is ingested
+    pub is_smart_money: bool,
+
+    /// Slot of the transaction (0 if unknown)
+    pub slot: u64,
+
+    /// Transaction fee paid, including priority fee (lamports)
+    pub fee_lamports: u64,
+
+    /// Jito tip paid by the transaction (lamports)
+    pub tip_lamports: u64,
+
+    /// True if this trade reverses the wallet's previous trade on the mint at
+    /// a near-identical size (see `wash`); set when it enters rolling state
+    pub is_wash: bool,
+
+    /// Root funder of the trading wallet at ingestion (see `funding`);
+    /// None if the wallet has no known funder
+    pub funding_cluster: Option<String>,
+
+    /// Relative change of the pool's spot price caused by this trade (see
+    /// `liquidity`); None when the mint's pool reserves are unknown
+    pub price_impact: Option<f64>,
+}
+
+impl TradeEvent {
+    /// Token amount in whole tokens (`token_amount` is in base units)
+    pub fn whole_tokens(&self) -> f64 {
+        self.token_amount / 10f64.powi(self.token_decimals as i32)
+    }
+
+    /// Funding cluster of the trading wallet (the wallet itself if unfunded)
+    pub fn cluster(&self) -> &str {
+        self.funding_cluster.as_deref().unwrap_or(&self.user_account)
+    }
+
+    /// Test trade of 1000 base units (6 decimals) by `wallet` on PumpSwap;
+    /// module helpers override the rest with struct update syntax
+    #[cfg(test)]
+    pub fn test(mint: &str, timestamp: i64, direction: TradeDirection, sol_amount: f64) -> Self {
+        Self {
+            timestamp,
+            mint: mint.to_string(),
+            direction,
+            sol_amount,
+            token_amount: 1000.0,
+            token_decimals: 6,
+            user_account: "wallet".to_string(),
+            source_program: "PumpSwap".to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Creation of a token (or of its pool) reported by a venue
+///
+/// Starts sniper tracking for the mint (see `snipers`) and records its
+/// creator (see `creators`), unless it is a migration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchEvent {
+    pub mint: String,
+    /// Wallet that created the token or pool
+    pub creator: String,
+    pub source_program: String,
+    pub timestamp: i64,
+
+    /// Slot of the creation transaction (0 if unknown)
+    pub slot: u64,
+
+    /// Jito tip paid by the creation transaction (lamports)
+    pub tip_lamports: u64,
+
+    /// Pool pump.fun created while migrating a bonding-curve token: not a
+    /// launch, and `creator` is pump.fun's migration authority, not the dev
+    pub migration: bool,
+}
+
+/// Reserves of an AMM pool right after a trade, reported by a venue
+///
+/// Keyed by the same mint as the venue's trades (see `liquidity`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolReserves {
+    pub mint: String,
+    /// Token side, in whole tokens
+    pub base_reserve: f64,
+    /// SOL side
+    pub quote_reserve_sol: f64,
+    pub timestamp: i64,
+    /// Slot of the transaction (0 if unknown)
+    pub slot: u64,
+}
+
+impl PoolReserves {
+    /// SOL per whole token
+    pub fn spot_price(&self) -> f64 {
+        if self.base_reserve > 0.0 {
+            self.quote_reserve_sol / self.base_reserve
+        } else {
+            0.0
+        }
+    }
+
+    /// Value of both sides in SOL at the spot price
+    pub fn liquidity_sol(&self) -> f64 {
+        2.0 * self.quote_reserve_sol
+    }
 }
 
 /// Aggregated token state matching the token_aggregates table schema
@@ -71,43 +185,14 @@ pub struct AggregatedTokenState {
     pub price_sol: Option<f64>,
     pub market_cap_usd: Option<f64>,
 
-    // Net flow metrics (rolling windows)
-    pub net_flow_60s_sol: Option<f64>,
-    pub net_flow_300s_sol: Option<f64>,
-    pub net_flow_900s_sol: Option<f64>,
-    pub net_flow_3600s_sol: Option<f64>,
-    pub net_flow_7200s_sol: Option<f64>,
-    pub net_flow_14400s_sol: Option<f64>,
-
-    // Trade counts (60s window)
-    pub buy_count_60s: Option<i32>,
-    pub sell_count_60s: Option<i32>,
-
-    // Trade counts (300s window)
-    pub buy_count_300s: Option<i32>,
-    pub sell_count_300s: Option<i32>,
-
-    // Trade counts (900s window)
-    pub buy_count_900s: Option<i32>,
-    pub sell_count_900s: Option<i32>,
-
-    // Advanced metrics (300s window)
-    pub unique_wallets_300s: Option<i32>,
-    pub bot_trades_300s: Option<i32>,
-    pub bot_wallets_300s: Option<i32>,
+    // Rolling window metrics (one entry per configured window,
+    // persisted as `token_window_metrics` rows)
+    pub windows: Vec<WindowMetrics>,
 
     // Volume metrics (300s window)
     pub avg_trade_size_300s_sol: Option<f64>,
     pub volume_300s_sol: Option<f64>,
 
-    // DCA buy counts (rolling windows)
-    // Phase 6: DCA Rolling Windows
-    pub dca_buys_60s: Option<i32>,
-    pub dca_buys_300s: Option<i32>,
-    pub dca_buys_900s: Option<i32>,
-    pub dca_buys_3600s: Option<i32>,
-    pub dca_buys_14400s: Option<i32>,
-
     // Timestamps
     pub updated_at: i64,
     pub created_at: i64,
@@ -161,58 +246,41 @@ impl AggregatedTokenState {
             price_sol: None,
             market_cap_usd: None,
 
-            // Net flow metrics (rolling windows)
-            net_flow_60s_sol: Some(metrics.net_flow_60s_sol),
-            net_flow_300s_sol: Some(metrics.net_flow_300s_sol),
-            net_flow_900s_sol: Some(metrics.net_flow_900s_sol),
-            net_flow_3600s_sol: Some(metrics.net_flow_3600s_sol),
-            net_flow_7200s_sol: Some(metrics.net_flow_7200s_sol),
-            net_flow_14400s_sol: Some(metrics.net_flow_14400s_sol),
-
-            // Trade counts (60s window)
-            buy_count_60s: Some(metrics.buy_count_60s),
-            sell_count_60s: Some(metrics.sell_count_60s),
-
-            // Trade counts (300s window)
-            buy_count_300s: Some(metrics.buy_count_300s),
-            sell_count_300s: Some(metrics.sell_count_300s),
-
-            // Trade counts (900s window)
-            buy_count_900s: Some(metrics.buy_count_900s),
-            sell_count_900s: Some(metrics.sell_count_900s),
-
-            // Advanced metrics (300s window)
-            unique_wallets_300s: Some(metrics.unique_wallets_300s),
-            bot_trades_300s: Some(metrics.bot_trades_count_300s),
-            bot_wallets_300s: Some(metrics.bot_wallets_count_300s),
+            // Rolling window metrics
+            windows: metrics.windows.clone(),
 
             // Volume metrics (300s window)
             avg_trade_size_300s_sol,
             volume_300s_sol: Some(volume_300s_sol),
 
-            // DCA buy counts (rolling windows)
-            // Phase 6: DCA Rolling Windows
-            dca_buys_60s: Some(metrics.dca_buys_60s),
-            dca_buys_300s: Some(metrics.dca_buys_300s),
-            dca_buys_900s: Some(metrics.dca_buys_900s),
-            dca_buys_3600s: Some(metrics.dca_buys_3600s),
-            dca_buys_14400s: Some(metrics.dca_buys_14400s),
-
             // Timestamps
             updated_at: now,
             created_at,
         }
     }
 
+    /// Metrics for the window of `secs` seconds (all-zero if not tracked)
+    pub fn window(&self, secs: i64) -> WindowMetrics {
+        self.windows
+            .iter()
+            .find(|w| w.window_secs == secs)
+            .copied()
+            .unwrap_or(WindowMetrics {
+                window_secs: secs,
+                ..Default::default()
+            })
+    }
+
     /// Compute average trade size from 300s window metrics
     ///
+    /// Gross volume divided by buy + sell count.
     /// Returns None if no trades in window (division by zero protection)
     fn compute_avg_trade_size(metrics: &super::state::RollingMetrics) -> Option<f64> {
-        let total_trades = metrics.buy_count_300s + metrics.sell_count_300s;
+        let w300 = metrics.window(300);
+        let total_trades = w300.buy_count + w300.sell_count;
         
         if total_trades > 0 {
-            let volume = metrics.net_flow_300s_sol.abs();
-            Some(volume / total_trades as f64)
+            Some(w300.volume_sol / total_trades as f64)
         } else {
             None
         }
@@ -220,9 +288,10 @@ impl AggregatedTokenState {
 
     /// Compute total volume in 300s window
     ///
-    /// Volume is the absolute value of net flow (ignores direction)
+    /// Volume is gross: buy volume + sell volume, so balanced buying and
+    /// selling is not reported as zero
     fn compute_volume_300s(metrics: &super::state::RollingMetrics) -> f64 {
-        metrics.net_flow_300s_sol.abs()
+        metrics.window(300).volume_sol
     }
 }
 
@@ -241,35 +310,65 @@ impl AggregatedTokenState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::RollingMetrics;
+    use crate::state::{RollingMetrics, WindowMetrics};
 
     /// Helper to create test RollingMetrics
     fn make_test_metrics() -> RollingMetrics {
         RollingMetrics {
-            net_flow_60s_sol: 10.5,
-            net_flow_300s_sol: 45.2,
-            net_flow_900s_sol: 120.8,
-            net_flow_3600s_sol: 250.0,
-            net_flow_7200s_sol: 400.0,
-            net_flow_14400s_sol: 650.0,
-            buy_count_60s: 5,
-            sell_count_60s: 2,
-            buy_count_300s: 20,
-            sell_count_300s: 8,
-            buy_count_900s: 50,
-            sell_count_900s: 25,
-            unique_wallets_300s: 12,
-            bot_wallets_count_300s: 2,
-            bot_trades_count_300s: 6,
-            bot_flow_300s_sol: 5.0,
-            dca_buys_60s: 1,
-            dca_buys_300s: 3,
-            dca_buys_900s: 8,
-            dca_buys_3600s: 15,
-            dca_buys_14400s: 30,
-            dca_flow_300s_sol: 10.0,
-            dca_unique_wallets_300s: 2,
-            dca_ratio_300s: 0.22,
+            windows: vec![
+                WindowMetrics {
+                    window_secs: 60,
+                    net_flow_sol: 10.5,
+                    buy_count: 5,
+                    sell_count: 2,
+                    dca_buys: 1,
+                    ..Default::default()
+                },
+                WindowMetrics {
+                    window_secs: 300,
+                    net_flow_sol: 45.2,
+                    buy_count: 20,
+                    sell_count: 8,
+                    buy_volume_sol: 60.0,
+                    sell_volume_sol: 14.8,
+                    volume_sol: 74.8,
+                    unique_wallets: 12,
+                    bot_wallets: 2,
+                    bot_trades: 6,
+                    bot_flow_sol: 5.0,
+                    dca_buys: 3,
+                    dca_flow_sol: 10.0,
+                    dca_unique_wallets: 2,
+                    dca_ratio: 0.22,
+                    ..Default::default()
+                },
+                WindowMetrics {
+                    window_secs: 900,
+                    net_flow_sol: 120.8,
+                    buy_count: 50,
+                    sell_count: 25,
+                    dca_buys: 8,
+                    ..Default::default()
+                },
+                WindowMetrics {
+                    window_secs: 3600,
+                    net_flow_sol: 250.0,
+                    dca_buys: 15,
+                    ..Default::default()
+                },
+                WindowMetrics {
+                    window_secs: 7200,
+                    net_flow_sol: 400.0,
+                    ..Default::default()
+                },
+                WindowMetrics {
+                    window_secs: 14400,
+                    net_flow_sol: 650.0,
+                    dca_buys: 30,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
         }
     }
 
@@ -314,35 +413,35 @@ mod tests {
         assert_eq!(state.updated_at, 2100); // From now parameter
 
         // Verify net flow metrics
-        assert_eq!(state.net_flow_60s_sol, Some(10.5));
-        assert_eq!(state.net_flow_300s_sol, Some(45.2));
-        assert_eq!(state.net_flow_900s_sol, Some(120.8));
+        assert_eq!(state.window(60).net_flow_sol, 10.5);
+        assert_eq!(state.window(300).net_flow_sol, 45.2);
+        assert_eq!(state.window(900).net_flow_sol, 120.8);
 
         // Verify trade counts (60s)
-        assert_eq!(state.buy_count_60s, Some(5));
-        assert_eq!(state.sell_count_60s, Some(2));
+        assert_eq!(state.window(60).buy_count, 5);
+        assert_eq!(state.window(60).sell_count, 2);
 
         // Verify trade counts (300s)
-        assert_eq!(state.buy_count_300s, Some(20));
-        assert_eq!(state.sell_count_300s, Some(8));
+        assert_eq!(state.window(300).buy_count, 20);
+        assert_eq!(state.window(300).sell_count, 8);
 
         // Verify trade counts (900s)
-        assert_eq!(state.buy_count_900s, Some(50));
-        assert_eq!(state.sell_count_900s, Some(25));
+        assert_eq!(state.window(900).buy_count, 50);
+        assert_eq!(state.window(900).sell_count, 25);
 
         // Verify advanced metrics (300s)
-        assert_eq!(state.unique_wallets_300s, Some(12));
-        assert_eq!(state.bot_trades_300s, Some(6));
-        assert_eq!(state.bot_wallets_300s, Some(2));
+        assert_eq!(state.window(300).unique_wallets, 12);
+        assert_eq!(state.window(300).bot_trades, 6);
+        assert_eq!(state.window(300).bot_wallets, 2);
 
         // Verify computed volume metrics (300s)
-        // volume = abs(net_flow_300s_sol) = abs(45.2) = 45.2
-        assert_eq!(state.volume_300s_sol, Some(45.2));
+        // volume = buy volume + sell volume = 60.0 + 14.8 = 74.8
+        assert_eq!(state.volume_300s_sol, Some(74.8));
 
-        // avg_trade_size = volume / total_trades = 45.2 / (20 + 8) = 45.2 / 28 ≈ 1.614
+        // avg_trade_size = volume / total_trades = 74.8 / (20 + 8) ≈ 2.671
         assert!(state.avg_trade_size_300s_sol.is_some());
         let avg = state.avg_trade_size_300s_sol.unwrap();
-        assert!((avg - 1.614).abs() < 0.01); // Tolerance for float precision
+        assert!((avg - 2.671).abs() < 0.01); // Tolerance for float precision
     }
 
     #[test]
@@ -367,8 +466,8 @@ mod tests {
         // Verify other fields still populated correctly
         assert_eq!(state.mint, mint);
         assert_eq!(state.last_trade_timestamp, Some(2000));
-        assert_eq!(state.net_flow_300s_sol, Some(45.2));
-        assert_eq!(state.buy_count_300s, Some(20));
+        assert_eq!(state.window(300).net_flow_sol, 45.2);
+        assert_eq!(state.window(300).buy_count, 20);
     }
 
     #[test]
@@ -425,32 +524,7 @@ mod tests {
     #[test]
     fn test_compute_avg_trade_size_zero_trades() {
         // Edge case: No trades in 300s window
-        let metrics = RollingMetrics {
-            net_flow_60s_sol: 0.0,
-            net_flow_300s_sol: 0.0,
-            net_flow_900s_sol: 0.0,
-            net_flow_3600s_sol: 0.0,
-            net_flow_7200s_sol: 0.0,
-            net_flow_14400s_sol: 0.0,
-            buy_count_60s: 0,
-            sell_count_60s: 0,
-            buy_count_300s: 0,
-            sell_count_300s: 0,
-            buy_count_900s: 0,
-            sell_count_900s: 0,
-            unique_wallets_300s: 0,
-            bot_wallets_count_300s: 0,
-            bot_trades_count_300s: 0,
-            bot_flow_300s_sol: 0.0,
-            dca_buys_60s: 0,
-            dca_buys_300s: 0,
-            dca_buys_900s: 0,
-            dca_buys_3600s: 0,
-            dca_buys_14400s: 0,
-            dca_flow_300s_sol: 0.0,
-            dca_unique_wallets_300s: 0,
-            dca_ratio_300s: 0.0,
-        };
+        let metrics = RollingMetrics::default();
 
         let mint = "zero_trades_mint";
         let state = AggregatedTokenState::from_metrics(mint, &metrics, None, 1000, 2000);
@@ -458,7 +532,7 @@ mod tests {
         // avg_trade_size should be None (avoid division by zero)
         assert_eq!(state.avg_trade_size_300s_sol, None);
 
-        // volume should be 0.0 (abs(0.0) = 0.0)
+        // volume should be 0.0 (no buys or sells)
         assert_eq!(state.volume_300s_sol, Some(0.0));
     }
 
@@ -466,44 +540,79 @@ mod tests {
     fn test_compute_volume_negative_net_flow() {
         // Scenario: Negative net flow (more sells than buys)
         let metrics = RollingMetrics {
-            net_flow_60s_sol: -5.0,
-            net_flow_300s_sol: -30.0, // Negative (net selling)
-            net_flow_900s_sol: -50.0,
-            net_flow_3600s_sol: -100.0,
-            net_flow_7200s_sol: -150.0,
-            net_flow_14400s_sol: -200.0,
-            buy_count_60s: 2,
-            sell_count_60s: 5,
-            buy_count_300s: 10,
-            sell_count_300s: 20,
-            buy_count_900s: 25,
-            sell_count_900s: 50,
-            unique_wallets_300s: 8,
-            bot_wallets_count_300s: 1,
-            bot_trades_count_300s: 3,
-            bot_flow_300s_sol: -5.0,
-            dca_buys_60s: 0,
-            dca_buys_300s: 1,
-            dca_buys_900s: 2,
-            dca_buys_3600s: 5,
-            dca_buys_14400s: 10,
-            dca_flow_300s_sol: 1.0,
-            dca_unique_wallets_300s: 1,
-            dca_ratio_300s: -0.033,
+            windows: vec![
+                WindowMetrics {
+                    window_secs: 60,
+                    net_flow_sol: -5.0,
+                    buy_count: 2,
+                    sell_count: 5,
+                    ..Default::default()
+                },
+                WindowMetrics {
+                    window_secs: 300,
+                    net_flow_sol: -30.0,
+                    buy_count: 10,
+                    sell_count: 20,
+                    buy_volume_sol: 10.0,
+                    sell_volume_sol: 40.0,
+                    volume_sol: 50.0,
+                    unique_wallets: 8,
+                    bot_wallets: 1,
+                    bot_trades: 3,
+                    bot_flow_sol: -5.0,
+                    dca_buys: 1,
+                    dca_flow_sol: 1.0,
+                    dca_unique_wallets: 1,
+                    dca_ratio: -0.033,
+                    ..Default::default()
+                },
+                WindowMetrics {
+                    window_secs: 900,
+                    net_flow_sol: -50.0,
+                    buy_count: 25,
+                    sell_count: 50,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
         };
 
         let mint = "negative_flow_mint";
         let state = AggregatedTokenState::from_metrics(mint, &metrics, None, 1000, 2000);
 
         // net_flow should preserve sign (negative)
-        assert_eq!(state.net_flow_300s_sol, Some(-30.0));
+        assert_eq!(state.window(300).net_flow_sol, -30.0);
+
+        // volume is gross (positive), not abs(net flow)
+        assert_eq!(state.volume_300s_sol, Some(50.0));
+
+        // avg_trade_size should use gross volume
+        // avg = 50.0 / (10 + 20) ≈ 1.667
+        let avg = state.avg_trade_size_300s_sol.unwrap();
+        assert!((avg - 1.667).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_compute_volume_balanced_flow() {
+        // Scenario: 500 SOL of buys and 500 SOL of sells
+        let metrics = RollingMetrics {
+            windows: vec![WindowMetrics {
+                window_secs: 300,
+                net_flow_sol: 0.0,
+                buy_count: 10,
+                sell_count: 10,
+                buy_volume_sol: 500.0,
+                sell_volume_sol: 500.0,
+                volume_sol: 1000.0,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
 
-        // volume should be absolute value (positive)
-        assert_eq!(state.volume_300s_sol, Some(30.0));
+        let state = AggregatedTokenState::from_metrics("churn_mint", &metrics, None, 1000, 2000);
 
-        // avg_trade_size should use absolute volume
-        // avg = 30.0 / (10 + 20) = 30.0 / 30 = 1.0
-        assert_eq!(state.avg_trade_size_300s_sol, Some(1.0));
+        assert_eq!(state.volume_300s_sol, Some(1000.0));
+        assert_eq!(state.avg_trade_size_300s_sol, Some(50.0));
     }
 
     #[test]
@@ -0,0 +1,409 @@
+//! Smart-money wallet registry
+//!
+//! A periodic scoring job rates every wallet that bought a tracked mint
+//! during the lookback on three things:
+//! - realized PnL, summed over its `wallet_positions`
+//! - hit rate: share of its entries (first buy per mint) that were followed by
+//!   a `PUMP_MULTIPLE` within `PUMP_HORIZON_SECS`, judged on 5m candle highs
+//! - entry timing: average delay between the mint's first recorded trade and
+//!   the wallet's first buy
+//!
+//! Scores and labels are persisted to `wallet_scores`. Wallets labelled
+//! `smart_money` are kept in a `WalletRegistry`; the processor flags their
+//! trades (`is_smart_money`) before they enter rolling state, which feeds the
+//! smart-money window metrics and the SMART_MONEY_ENTRY signal.
+
+use crate::{candles::CANDLE_RETENTION, db, db::WriteRequest};
+use rusqlite::Connection;
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    error::Error,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+/// How far back entries are scored (seconds); bounded by 5m candle retention
+pub const SCORING_LOOKBACK_SECS: i64 = CANDLE_RETENTION[2].1;
+
+/// Time after an entry within which the mint must pump for a hit (seconds)
+pub const PUMP_HORIZON_SECS: i64 = 86_400;
+
+/// Peak price / entry price counted as a pump
+pub const PUMP_MULTIPLE: f64 = 2.0;
+
+/// Entries needed before a wallet is labelled anything but neutral
+pub const MIN_SCORED_ENTRIES: u32 = 5;
+
+/// Minimum score (with positive realized PnL) for the smart-money label
+pub const SMART_MONEY_SCORE: f64 = 0.5;
+
+/// Scores below this (with negative realized PnL) are labelled unprofitable
+pub const UNPROFITABLE_SCORE: f64 = 0.1;
+
+/// Realized PnL (SOL) at which the PnL component reaches ~0.76 (tanh(1))
+const PNL_SCALE_SOL: f64 = 10.0;
+
+/// Average entry delay (seconds) at which the timing component halves
+const ENTRY_DELAY_SCALE_SECS: f64 = 3_600.0;
+
+/// Label stored in `wallet_scores.label`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalletLabel {
+    SmartMoney,
+    Neutral,
+    Unprofitable,
+}
+
+impl WalletLabel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WalletLabel::SmartMoney => "smart_money",
+            WalletLabel::Neutral => "neutral",
+            WalletLabel::Unprofitable => "unprofitable",
+        }
+    }
+
+    /// Label for a wallet's scoring result
+    fn classify(entries: u32, score: f64, realized_pnl_sol: f64) -> Self {
+        if entries < MIN_SCORED_ENTRIES {
+            WalletLabel::Neutral
+        } else if score >= SMART_MONEY_SCORE && realized_pnl_sol > 0.0 {
+            WalletLabel::SmartMoney
+        } else if score < UNPROFITABLE_SCORE && realized_pnl_sol < 0.0 {
+            WalletLabel::Unprofitable
+        } else {
+            WalletLabel::Neutral
+        }
+    }
+}
+
+/// A wallet's first buy of one mint, as loaded by `db::load_wallet_entries`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalletEntry {
+    pub wallet: String,
+    pub mint: String,
+    pub entry_ts: i64,
+    /// SOL per whole token paid on the first buy (0.0 if unknown)
+    pub entry_price: f64,
+    /// First recorded trade of the mint
+    pub launch_ts: i64,
+    /// Highest 5m candle high within the pump horizon, if any candle exists
+    pub peak_price: Option<f64>,
+}
+
+impl WalletEntry {
+    fn is_hit(&self) -> bool {
+        self.peak_price
+            .is_some_and(|peak| peak >= self.entry_price * PUMP_MULTIPLE)
+    }
+}
+
+/// Scoring result of one wallet, persisted to `wallet_scores`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalletScore {
+    pub wallet: String,
+    /// 0.5 * hit rate + 0.3 * PnL component (-1..1) + 0.2 * timing component (0..1)
+    pub score: f64,
+    pub label: WalletLabel,
+    pub realized_pnl_sol: f64,
+    /// Entries whose outcome is known (pumped, or the horizon has passed)
+    pub entries: u32,
+    pub hits: u32,
+    pub hit_rate: f64,
+    pub avg_entry_delay_secs: f64,
+}
+
+/// Score every wallet with at least one decided entry
+///
+/// Entries without a price, or younger than `PUMP_HORIZON_SECS` that have not
+/// pumped yet, are left out. Results are ordered by score, best first.
+pub fn score_wallets(entries: &[WalletEntry], realized_pnl: &HashMap<String, f64>, now: i64) -> Vec<WalletScore> {
+    // wallet -> (entries, hits, summed entry delay)
+    let mut per_wallet: HashMap<&str, (u32, u32, f64)> = HashMap::new();
+
+    for entry in entries {
+        if entry.entry_price <= 0.0 {
+            continue;
+        }
+        let hit = entry.is_hit();
+        if !hit && entry.entry_ts + PUMP_HORIZON_SECS > now {
+            continue;
+        }
+
+        let stats = per_wallet.entry(entry.wallet.as_str()).or_default();
+        stats.0 += 1;
+        stats.1 += hit as u32;
+        stats.2 += (entry.entry_ts - entry.launch_ts).max(0) as f64;
+    }
+
+    let mut scores: Vec<WalletScore> = per_wallet
+        .into_iter()
+        .map(|(wallet, (entries, hits, delay_sum))| {
+            let realized_pnl_sol = realized_pnl.get(wallet).copied().unwrap_or(0.0);
+            let hit_rate = hits as f64 / entries as f64;
+            let avg_entry_delay_secs = delay_sum / entries as f64;
+
+            let pnl_factor = (realized_pnl_sol / PNL_SCALE_SOL).tanh();
+            let timing_factor = 1.0 / (1.0 + avg_entry_delay_secs / ENTRY_DELAY_SCALE_SECS);
+            let score = hit_rate * 0.5 + pnl_factor * 0.3 + timing_factor * 0.2;
+
+            WalletScore {
+                wallet: wallet.to_string(),
+                score,
+                label: WalletLabel::classify(entries, score, realized_pnl_sol),
+                realized_pnl_sol,
+                entries,
+                hits,
+                hit_rate,
+                avg_entry_delay_secs,
+            }
+        })
+        .collect();
+
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.wallet.cmp(&b.wallet)));
+    scores
+}
+
+/// Load entries and realized PnL from the database and score them
+pub fn score_from_db(conn: &Connection, now: i64) -> Result<Vec<WalletScore>, Box<dyn Error>> {
+    let entries = db::load_wallet_entries(conn, now - SCORING_LOOKBACK_SECS, PUMP_HORIZON_SECS)?;
+    let realized_pnl = db::load_realized_pnl(conn)?;
+    Ok(score_wallets(&entries, &realized_pnl, now))
+}
+
+/// Shared set of smart-money wallets consulted on every extracted trade
+#[derive(Debug, Default)]
+pub struct WalletRegistry {
+    smart_money: RwLock<HashSet<String>>,
+}
+
+impl WalletRegistry {
+    pub fn is_smart_money(&self, wallet: &str) -> bool {
+        self.smart_money.read().unwrap().contains(wallet)
+    }
+
+    /// Number of smart-money wallets
+    pub fn len(&self) -> usize {
+        self.smart_money.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Atomically replace the smart-money set
+    pub fn replace(&self, smart_money: HashSet<String>) {
+        *self.smart_money.write().unwrap() = smart_money;
+    }
+
+    /// Replace the smart-money set with the labels of a scoring run
+    pub fn apply_scores(&self, scores: &[WalletScore]) {
+        self.replace(
+            scores
+                .iter()
+                .filter(|s| s.label == WalletLabel::SmartMoney)
+                .map(|s| s.wallet.clone())
+                .collect(),
+        );
+    }
+
+    /// Reload the smart-money set from persisted labels
+    ///
+    /// Returns the number of smart-money wallets.
+    pub fn refresh(&self, conn: &Connection) -> Result<usize, Box<dyn Error>> {
+        let smart_money = db::load_smart_money_wallets(conn)?;
+        let count = smart_money.len();
+        self.replace(smart_money);
+        Ok(count)
+    }
+}
+
+/// Background loop that rescores wallets every `interval`
+///
+/// The registry is first loaded from the labels of the previous run, so trades
+/// are flagged before the first scoring run completes. Scores are persisted
+/// through the write loop.
+pub async fn run_scoring_loop(
+    registry: Arc<WalletRegistry>,
+    writer: mpsc::Sender<WriteRequest>,
+    interval: Duration,
+) {
+    let db_path = match env::var("SOLFLOW_DB_PATH") {
+        Ok(path) => path,
+        Err(_) => {
+            log::error!("❌ SOLFLOW_DB_PATH not set, wallet scoring loop exiting");
+            return;
+        }
+    };
+
+    let conn = match Connection::open(&db_path) {
+        Ok(conn) => conn,
+        Err(e) => {
+            log::error!("❌ Failed to open database for wallet scoring: {}", e);
+            return;
+        }
+    };
+
+    match registry.refresh(&conn) {
+        Ok(count) => log::info!("🧠 Loaded {} smart-money wallets", count),
+        Err(e) => log::warn!("⚠️  Failed to load smart-money wallets: {}", e),
+    }
+
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let now = chrono::Utc::now().timestamp();
+        let scores = match score_from_db(&conn, now) {
+            Ok(scores) => scores,
+            Err(e) => {
+                log::warn!("⚠️  Failed to score wallets: {}", e);
+                continue;
+            }
+        };
+
+        registry.apply_scores(&scores);
+        log::info!(
+            "🧠 Wallets scored | Scored: {} | Smart money: {}",
+            scores.len(),
+            registry.len()
+        );
+
+        if let Err(e) = writer.send(WriteRequest::WalletScores(scores)).await {
+            log::warn!("⚠️  Failed to send wallet scores to writer: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 10 * 86_400;
+
+    fn entry(wallet: &str, mint: &str, entry_ts: i64, peak_price: Option<f64>) -> WalletEntry {
+        WalletEntry {
+            wallet: wallet.to_string(),
+            mint: mint.to_string(),
+            entry_ts,
+            entry_price: 0.001,
+            launch_ts: entry_ts - 60,
+            peak_price,
+        }
+    }
+
+    fn create_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../sql/00_initial.sql")).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_score_wallets_labels() {
+        let old = NOW - 2 * 86_400;
+        let mut entries = Vec::new();
+        for i in 0..5 {
+            let mint = format!("m{}", i);
+            // Early buyer: 4 of 5 mints went 2x
+            let peak = if i < 4 { Some(0.0025) } else { Some(0.0012) };
+            entries.push(entry("sharp", &mint, old, peak));
+            // Late buyer: never a hit, entered an hour after launch
+            let mut late = entry("exit_liquidity", &mint, old, Some(0.0015));
+            late.launch_ts = old - 3_600;
+            entries.push(late);
+        }
+        // Too few entries for a label, however good
+        entries.push(entry("lucky", "m0", old, Some(0.01)));
+
+        let pnl = HashMap::from([
+            ("sharp".to_string(), 25.0),
+            ("exit_liquidity".to_string(), -8.0),
+            ("lucky".to_string(), 50.0),
+        ]);
+        let scores = score_wallets(&entries, &pnl, NOW);
+        let by_wallet: HashMap<&str, &WalletScore> = scores.iter().map(|s| (s.wallet.as_str(), s)).collect();
+
+        let sharp = by_wallet["sharp"];
+        assert_eq!((sharp.entries, sharp.hits), (5, 4));
+        assert_eq!(sharp.hit_rate, 0.8);
+        assert_eq!(sharp.avg_entry_delay_secs, 60.0);
+        assert_eq!(sharp.label, WalletLabel::SmartMoney);
+
+        let late = by_wallet["exit_liquidity"];
+        assert_eq!(late.hits, 0);
+        assert_eq!(late.avg_entry_delay_secs, 3_600.0);
+        // 0.3 * tanh(-0.8) + 0.2 * 0.5 < 0.1
+        assert_eq!(late.label, WalletLabel::Unprofitable);
+
+        let lucky = by_wallet["lucky"];
+        assert!(lucky.score > sharp.score);
+        assert_eq!(lucky.label, WalletLabel::Neutral);
+    }
+
+    #[test]
+    fn test_recent_entries_count_only_once_decided() {
+        let recent = NOW - 3_600;
+        let entries = vec![
+            entry("w", "pumped", recent, Some(0.003)),
+            entry("w", "pending", recent, Some(0.0011)),
+            entry("w", "no_candles", NOW - 2 * 86_400, None),
+        ];
+
+        let scores = score_wallets(&entries, &HashMap::new(), NOW);
+        assert_eq!(scores.len(), 1);
+        // "pending" may still pump: left out
+        assert_eq!((scores[0].entries, scores[0].hits), (2, 1));
+    }
+
+    #[test]
+    fn test_score_from_db_and_registry() {
+        let conn = create_test_db();
+        let t0 = NOW - 2 * 86_400;
+
+        // "sharp" buys 5 mints a minute after launch at 0.001; all pump to 0.003
+        for i in 0..5 {
+            let mint = format!("m{}", i);
+            conn.execute(
+                "INSERT INTO token_trades (mint, timestamp, wallet, side, sol_amount, token_amount, token_decimals)
+                 VALUES (?1, ?2, 'dev', 'buy', 1.0, 1e9, 6),
+                        (?1, ?3, 'sharp', 'buy', 1.0, 1e9, 6),
+                        (?1, ?4, 'sharp', 'buy', 5.0, 1e9, 6)",
+                rusqlite::params![mint, t0, t0 + 60, t0 + 600],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO token_candles (mint, interval_secs, open_time, open, high, low, close)
+                 VALUES (?1, 300, ?2, 0.001, 0.003, 0.001, 0.0025)",
+                rusqlite::params![mint, t0 + 600],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO wallet_positions (mint, wallet, realized_pnl_sol, last_trade_ts, updated_at)
+                 VALUES (?1, 'sharp', 4.0, ?2, ?2)",
+                rusqlite::params![mint, t0 + 900],
+            )
+            .unwrap();
+        }
+
+        let scores = score_from_db(&conn, NOW).unwrap();
+        let sharp = scores.iter().find(|s| s.wallet == "sharp").unwrap();
+        // Entry is the first buy (t0 + 60), not the later one
+        assert_eq!((sharp.entries, sharp.hits), (5, 5));
+        assert_eq!(sharp.avg_entry_delay_secs, 60.0);
+        assert_eq!(sharp.realized_pnl_sol, 20.0);
+        assert_eq!(sharp.label, WalletLabel::SmartMoney);
+
+        db::write_wallet_scores(&conn, &scores).unwrap();
+
+        let registry = WalletRegistry::default();
+        assert_eq!(registry.refresh(&conn).unwrap(), 1);
+        assert!(registry.is_smart_money("sharp"));
+        assert!(!registry.is_smart_money("dev"));
+
+        registry.apply_scores(&[]);
+        assert!(registry.is_empty());
+    }
+}
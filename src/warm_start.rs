@@ -0,0 +1,250 @@
+//! Warm start: rebuild rolling state from token_trades on startup
+//!
+//! Without this every `TokenRollingState` starts empty after a restart and
+//! long windows (up to 4h) report misleading flow until they refill. On boot
+//! the last `longest window` seconds of `token_trades` are replayed into
+//! rolling state (no writes, no signals).
+//!
+//! Replay can only restore what was persisted, so the processor also needs to
+//! know from when history is complete (`history_start`). Windows reaching
+//! back before that are flagged as warming and skipped by signals.
+//!
+//! Replayed windows are evicted at the newest replayed trade (event time), not
+//! at the wall clock, and that time seeds the shared `EventClock`. Candles
+//! closed during replay were persisted before the restart and are discarded;
+//! only the still-open ones carry on.
+//!
+//! Positions are not bounded by the windows: they are restored from the last
+//! persisted snapshot, and only replayed trades after that snapshot are
+//! applied on top.
+
+use crate::{db, positions::WalletPositions, state::TokenRollingState, types::TradeEvent};
+use rusqlite::Connection;
+use std::{collections::HashMap, error::Error, time::Instant};
+
+/// Largest gap between the newest stored trade and startup that still counts
+/// as continuous history (short restarts)
+pub const MAX_HISTORY_GAP_SECS: i64 = 120;
+
+/// Outcome of a warm start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarmStart {
+    /// Trades replayed into rolling state
+    pub trades: usize,
+
+    /// Mints with rolling state after replay
+    pub mints: usize,
+
+    /// Timestamp from which trade history is complete
+    pub history_start: i64,
+
+    /// Newest replayed trade timestamp (None if nothing was replayed)
+    pub latest_event_ts: Option<i64>,
+}
+
+/// Timestamp from which trade history is complete
+///
+/// `bounds` is the (oldest, newest) stored trade timestamp. History is only
+/// continuous if the newest stored trade is within `MAX_HISTORY_GAP_SECS` of
+/// `now`; it then starts at the oldest stored trade, capped at the horizon
+/// behind the newest one (windows are evicted in event time). Otherwise
+/// nothing before `now` can be trusted.
+pub fn history_start(bounds: Option<(i64, i64)>, now: i64, horizon: i64) -> i64 {
+    match bounds {
+        Some((oldest, newest)) if now - newest <= MAX_HISTORY_GAP_SECS => oldest.max(newest - horizon),
+        _ => now,
+    }
+}
+
+/// Replay the last `horizon` seconds of token_trades into `states`
+///
+/// New states track `windows` and get the computed `history_start`.
+/// Blocklisted mints are skipped. The states are handed to the shards
+/// afterwards (see `shards::ShardRouter::spawn`).
+pub fn rebuild_rolling_states(
+    conn: &Connection,
+    states: &mut HashMap<String, TokenRollingState>,
+    windows: &[i64],
+    now: i64,
+) -> Result<WarmStart, Box<dyn Error>> {
+    let started = Instant::now();
+    let horizon = windows.iter().copied().max().unwrap_or(0);
+    let bounds = db::trade_history_bounds(conn)?;
+    let history_start = history_start(bounds, now, horizon);
+    let replay_from = bounds.map_or(now, |(_, newest)| newest.min(now)) - horizon;
+    let blocked = db::load_blocklist(conn)?;
+
+    let mut replayed = 0;
+    let mut latest_event_ts: Option<i64> = None;
+    for trade in db::load_trades_since(conn, replay_from)? {
+        if blocked.contains(&trade.mint) {
+            continue;
+        }
+
+        latest_event_ts = latest_event_ts.max(Some(trade.timestamp));
+        states
+            .entry(trade.mint.clone())
+            .or_insert_with(|| {
+                TokenRollingState::with_windows(trade.mint.clone(), windows).with_history_start(history_start)
+            })
+            .add_trade(trade);
+        replayed += 1;
+    }
+
+    let evict_at = latest_event_ts.unwrap_or(now);
+    for state in states.values_mut() {
+        restore_positions(conn, state)?;
+        state.evict_old_trades(evict_at);
+        state.close_candles(evict_at);
+    }
+
+    log::info!(
+        "♻️  Warm start replayed {} trades for {} mints in {:.2}s (history complete since {})",
+        replayed,
+        states.len(),
+        started.elapsed().as_secs_f64(),
+        history_start
+    );
+
+    Ok(WarmStart {
+        trades: replayed,
+        mints: states.len(),
+        history_start,
+        latest_event_ts,
+    })
+}
+
+/// Replace replayed positions with the persisted snapshot plus the replayed
+/// trades that came after it
+fn restore_positions(conn: &Connection, state: &mut TokenRollingState) -> Result<(), Box<dyn Error>> {
+    if let Some((snapshot_ts, positions)) = db::load_positions(conn, &state.mint)? {
+        apply_positions(state, snapshot_ts, positions);
+    }
+    Ok(())
+}
+
+/// Replace a state's positions with a snapshot taken at `snapshot_ts`, then
+/// re-apply the state's trades that came after it
+///
+/// Also used by the shards when they recreate the state of a mint the
+/// sweeper dropped (see `shards`).
+pub fn apply_positions(state: &mut TokenRollingState, snapshot_ts: i64, positions: WalletPositions) {
+    let longest = state.window_secs().last().unwrap_or(0);
+    let newer: Vec<TradeEvent> = state
+        .window_trades(longest)
+        .filter(|t| t.timestamp > snapshot_ts)
+        .cloned()
+        .collect();
+
+    state.positions.restore(positions);
+    for trade in &newer {
+        state.positions.apply(trade);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        positions::{PositionBook, PositionSnapshot},
+        state::DEFAULT_WINDOWS,
+        types::TradeDirection,
+    };
+
+    fn create_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../sql/00_initial.sql")).unwrap();
+        conn
+    }
+
+    fn trade(mint: &str, timestamp: i64, wallet: &str, source_program: &str) -> TradeEvent {
+        TradeEvent {
+            token_amount: 12_345.0,
+            user_account: wallet.to_string(),
+            source_program: source_program.to_string(),
+            is_dca: source_program == "JupiterDCA",
+            ..TradeEvent::test(mint, timestamp, TradeDirection::Buy, 1.5)
+        }
+    }
+
+    #[test]
+    fn test_history_start() {
+        // Short restart: history continuous back to the oldest trade
+        assert_eq!(history_start(Some((5_000, 9_990)), 10_000, 14_400), 5_000);
+        // Capped at the horizon
+        assert_eq!(history_start(Some((1, 9_990)), 20_000, 14_400), 20_000);
+        assert_eq!(history_start(Some((1, 19_990)), 20_000, 14_400), 5_590);
+        // Long outage or empty table: nothing is trusted
+        assert_eq!(history_start(None, 10_000, 14_400), 10_000);
+    }
+
+    #[test]
+    fn test_rebuild_rolling_states_replays_recent_trades() {
+        let conn = create_test_db();
+        let now = 100_000;
+
+        db::append_trade(&conn, &trade("old", now - 20_000, "w0", "PumpSwap")).unwrap();
+        db::append_trade(&conn, &trade("mint", now - 600, "w1", "JupiterDCA")).unwrap();
+        db::append_trade(&conn, &trade("mint", now - 30, "w2", "PumpSwap")).unwrap();
+        db::append_trade(&conn, &trade("spam", now - 30, "w3", "PumpSwap")).unwrap();
+        conn.execute("INSERT INTO blocklist (mint) VALUES ('spam')", []).unwrap();
+
+        let mut states = HashMap::new();
+        let report = rebuild_rolling_states(&conn, &mut states, &DEFAULT_WINDOWS, now).unwrap();
+
+        assert_eq!(report.trades, 2);
+        assert_eq!(report.mints, 1);
+        assert_eq!(report.latest_event_ts, Some(now - 30));
+        assert!(!states.contains_key("old"));
+        assert!(!states.contains_key("spam"));
+
+        let state = states.get("mint").unwrap();
+        let replayed: Vec<&TradeEvent> = state.window_trades(14_400).collect();
+        assert_eq!(replayed[0].source_program, "JupiterDCA");
+        assert_eq!(replayed[0].token_amount, 12_345.0);
+        assert_eq!(replayed[0].token_decimals, 6);
+
+        let metrics = state.compute_rolling_metrics();
+        assert_eq!(metrics.window(60).buy_count, 1);
+        assert_eq!(metrics.window(900).buy_count, 2);
+        assert_eq!(metrics.window(900).dca_buys, 1);
+
+        // Oldest stored trade is 20_000s old and the newest is recent:
+        // history is complete for the whole 4h horizon behind the newest trade
+        assert_eq!(report.history_start, now - 30 - 14_400);
+        assert!(!metrics.is_warming(&DEFAULT_WINDOWS));
+    }
+
+    #[test]
+    fn test_rebuild_restores_positions_from_snapshot() {
+        let conn = create_test_db();
+        let now = 100_000;
+
+        // Snapshot taken after w1's first buy
+        let first = trade("mint", now - 600, "w1", "PumpSwap");
+        let mut book = PositionBook::default();
+        book.apply(&first);
+        db::write_position_snapshot(
+            &conn,
+            &PositionSnapshot {
+                mint: "mint".to_string(),
+                snapshot_ts: now - 600,
+                metrics: book.holder_metrics(),
+                positions: book.take_dirty(),
+            },
+        )
+        .unwrap();
+
+        db::append_trade(&conn, &first).unwrap();
+        db::append_trade(&conn, &trade("mint", now - 30, "w1", "PumpSwap")).unwrap();
+
+        let mut states = HashMap::new();
+        rebuild_rolling_states(&conn, &mut states, &DEFAULT_WINDOWS, now).unwrap();
+
+        // The first buy is counted once (from the snapshot), the second replayed
+        let state = states.get("mint").unwrap();
+        let position = state.positions.position("w1").unwrap();
+        assert_eq!(position.buy_count, 2);
+        assert_eq!(position.cost_basis_sol, 3.0);
+    }
+}
@@ -0,0 +1,195 @@
+//! Wash-trading and self-trading detection
+//!
+//! Thin tokens are pumped by a handful of wallets trading back and forth,
+//! which inflates wallet and trade counts without moving real money. Two
+//! patterns are detected per window:
+//! - round trips: a wallet that both buys and sells in the window contributes
+//!   `2 * min(bought, sold)` of volume that nets out; wallets whose two sides
+//!   are balanced within `BALANCED_RATIO` count as wash wallets
+//! - alternating legs: a trade that reverses the wallet's previous trade on
+//!   the same mint within `WASH_PAIR_SECS` at a near-identical size
+//!   (`WASH_SIZE_TOLERANCE`) is flagged `is_wash` at ingestion
+//!
+//! `WashStats` turns a window's per-wallet flows into a wash score, the wash
+//! volume and wallets excluded from organic metrics.
+
+use crate::types::{TradeDirection, TradeEvent};
+use std::collections::HashMap;
+
+/// Max seconds between a trade and the reversing leg it is paired with
+pub const WASH_PAIR_SECS: i64 = 300;
+
+/// Max relative size difference between two paired legs
+pub const WASH_SIZE_TOLERANCE: f64 = 0.1;
+
+/// min(bought, sold) / max(bought, sold) at or above which a two-sided wallet
+/// counts as a wash wallet
+pub const BALANCED_RATIO: f64 = 0.8;
+
+/// Wash score at or above which the WASH_TRADING signal fires
+pub const WASH_SIGNAL_SCORE: f64 = 0.5;
+
+/// Last trade of a wallet on a mint, for pairing reversing legs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LastTrade {
+    pub timestamp: i64,
+    pub direction: TradeDirection,
+    pub sol_amount: f64,
+}
+
+impl LastTrade {
+    pub fn of(trade: &TradeEvent) -> Self {
+        Self {
+            timestamp: trade.timestamp,
+            direction: trade.direction,
+            sol_amount: trade.sol_amount,
+        }
+    }
+
+    /// True if `trade` reverses this one at a near-identical size in time
+    pub fn is_reversed_by(&self, trade: &TradeEvent) -> bool {
+        let opposite = matches!(
+            (self.direction, trade.direction),
+            (TradeDirection::Buy, TradeDirection::Sell) | (TradeDirection::Sell, TradeDirection::Buy)
+        );
+        let elapsed = trade.timestamp - self.timestamp;
+        let largest = self.sol_amount.max(trade.sol_amount);
+
+        opposite
+            && (0..=WASH_PAIR_SECS).contains(&elapsed)
+            && largest > 0.0
+            && (self.sol_amount - trade.sol_amount).abs() <= WASH_SIZE_TOLERANCE * largest
+    }
+}
+
+/// Buy and sell SOL of one wallet inside a window
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WalletFlow {
+    pub buy_sol: f64,
+    pub sell_sol: f64,
+    pub trades: u32,
+}
+
+impl WalletFlow {
+    /// Add (`sign = 1.0`) or subtract (`sign = -1.0`) a trade
+    pub fn apply(&mut self, trade: &TradeEvent, sign: f64) {
+        match trade.direction {
+            TradeDirection::Buy => self.buy_sol += sign * trade.sol_amount,
+            TradeDirection::Sell => self.sell_sol += sign * trade.sol_amount,
+            TradeDirection::Unknown => {}
+        }
+        if sign > 0.0 {
+            self.trades += 1;
+        } else {
+            self.trades -= 1;
+        }
+    }
+
+    /// Volume that nets out within this wallet
+    pub fn round_trip_sol(&self) -> f64 {
+        2.0 * self.buy_sol.min(self.sell_sol)
+    }
+
+    /// Both sides traded and balanced within `BALANCED_RATIO`
+    pub fn is_balanced(&self) -> bool {
+        let largest = self.buy_sol.max(self.sell_sol);
+        largest > 0.0 && self.buy_sol.min(self.sell_sol) / largest >= BALANCED_RATIO
+    }
+}
+
+/// Wash analysis of one window
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WashStats {
+    /// 0.0 (organic) ..= 1.0 (all volume washed)
+    pub score: f64,
+    /// Volume suspected to be wash (never above the window's volume)
+    pub volume_sol: f64,
+    /// Wallets with balanced round trips
+    pub wallets: i32,
+    /// Trades flagged as reversing legs
+    pub trades: i32,
+}
+
+impl WashStats {
+    /// Analyse a window from its per-wallet flows and flagged legs
+    ///
+    /// The score weighs the share of volume that nets out (0.6) and the share
+    /// of trades that are reversing legs (0.4, saturating at half the trades).
+    pub fn analyse(
+        flows: &HashMap<String, WalletFlow>,
+        wash_legs: i32,
+        wash_leg_sol: f64,
+        trade_count: usize,
+        volume_sol: f64,
+    ) -> Self {
+        if trade_count == 0 || volume_sol <= 0.0 {
+            return Self::default();
+        }
+
+        let round_trip_sol: f64 = flows.values().map(WalletFlow::round_trip_sol).sum();
+        let wallets = flows.values().filter(|f| f.is_balanced()).count() as i32;
+
+        // Flagged legs also catch flips whose round trip straddles the window
+        let wash_volume = round_trip_sol.max(wash_leg_sol).min(volume_sol);
+        let volume_share = wash_volume / volume_sol;
+        let leg_share = (2.0 * wash_legs as f64 / trade_count as f64).min(1.0);
+
+        Self {
+            score: (0.6 * volume_share + 0.4 * leg_share).clamp(0.0, 1.0),
+            volume_sol: wash_volume,
+            wallets,
+            trades: wash_legs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(timestamp: i64, direction: TradeDirection, sol_amount: f64) -> TradeEvent {
+        TradeEvent::test("mint", timestamp, direction, sol_amount)
+    }
+
+    #[test]
+    fn test_reversing_leg_pairing() {
+        let buy = LastTrade::of(&trade(1_000, TradeDirection::Buy, 1.0));
+
+        assert!(buy.is_reversed_by(&trade(1_030, TradeDirection::Sell, 0.95)));
+        // Same side, different size, or too late
+        assert!(!buy.is_reversed_by(&trade(1_030, TradeDirection::Buy, 1.0)));
+        assert!(!buy.is_reversed_by(&trade(1_030, TradeDirection::Sell, 0.5)));
+        assert!(!buy.is_reversed_by(&trade(1_000 + WASH_PAIR_SECS + 1, TradeDirection::Sell, 1.0)));
+    }
+
+    #[test]
+    fn test_round_trips_score_high() {
+        let mut flows = HashMap::new();
+        flows.insert("a".to_string(), WalletFlow { buy_sol: 5.0, sell_sol: 5.0, trades: 4 });
+        flows.insert("b".to_string(), WalletFlow { buy_sol: 4.0, sell_sol: 3.6, trades: 4 });
+        flows.insert("organic".to_string(), WalletFlow { buy_sol: 2.0, sell_sol: 0.0, trades: 1 });
+
+        let stats = WashStats::analyse(&flows, 3, 6.0, 9, 19.6);
+        assert_eq!(stats.wallets, 2);
+        assert!((stats.volume_sol - 17.2).abs() < 1e-9);
+        assert!(stats.score >= WASH_SIGNAL_SCORE);
+
+        // Without flagged legs only the volume share is scored
+        let stats = WashStats::analyse(&flows, 0, 0.0, 9, 19.6);
+        assert!((stats.score - 0.6 * 17.2 / 19.6).abs() < 1e-9);
+
+        // Legs alone can exceed the round trips, never the window's volume
+        assert_eq!(WashStats::analyse(&flows, 9, 30.0, 9, 19.6).volume_sol, 19.6);
+    }
+
+    #[test]
+    fn test_one_sided_flow_is_organic() {
+        let mut flows = HashMap::new();
+        flows.insert("a".to_string(), WalletFlow { buy_sol: 5.0, sell_sol: 0.0, trades: 2 });
+        flows.insert("b".to_string(), WalletFlow { buy_sol: 0.0, sell_sol: 3.0, trades: 1 });
+
+        let stats = WashStats::analyse(&flows, 0, 0.0, 3, 8.0);
+        assert_eq!(stats, WashStats::default());
+        assert_eq!(WashStats::analyse(&HashMap::new(), 0, 0.0, 0, 0.0), WashStats::default());
+    }
+}
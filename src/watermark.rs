@@ -0,0 +1,235 @@
+//! Event-time watermarks
+//!
+//! Rolling windows are keyed by block time, but each venue stream delivers
+//! trades with its own delay and `block_time` can be missing (extracted as 0).
+//! Evicting windows at every incoming trade's own timestamp lets one stale or
+//! zero timestamp drag "now" backwards and corrupt or clear windows.
+//!
+//! - `Watermark` (one per stream): admits a trade only if it is at most
+//!   `allowed_lateness` seconds older than the newest trade of that stream
+//!   and at most `MAX_FUTURE_SKEW_SECS` ahead of the wall clock; one bogus
+//!   future timestamp would otherwise pin the stream and the shared clock
+//!   ahead, evicting every window at a time that has not come yet
+//! - `EventClock` (shared): newest admitted event time across all streams;
+//!   windows are evicted at this time, never at a trade's own timestamp
+//! - `QuarantineStats` (shared): counters for trades that were not admitted,
+//!   logged every report interval (`run_report_loop`)
+
+use std::{
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Seconds a trade's timestamp may be ahead of the wall clock and still be
+/// admitted
+pub const MAX_FUTURE_SKEW_SECS: i64 = 60;
+
+/// Why a trade was kept out of rolling state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuarantineReason {
+    /// Trade had no usable timestamp (`block_time` missing or 0)
+    MissingTimestamp,
+    /// Trade arrived behind its stream's watermark
+    Late,
+    /// Trade's timestamp is more than `MAX_FUTURE_SKEW_SECS` ahead of the
+    /// wall clock
+    Future,
+}
+
+impl QuarantineReason {
+    /// Stable identifier used in logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuarantineReason::MissingTimestamp => "missing_timestamp",
+            QuarantineReason::Late => "late",
+            QuarantineReason::Future => "future",
+        }
+    }
+}
+
+/// Per-stream event-time watermark with bounded allowed lateness
+#[derive(Debug, Clone)]
+pub struct Watermark {
+    allowed_lateness: i64,
+    max_event_ts: Option<i64>,
+}
+
+impl Watermark {
+    pub fn new(allowed_lateness: i64) -> Self {
+        Self {
+            allowed_lateness: allowed_lateness.max(0),
+            max_event_ts: None,
+        }
+    }
+
+    /// Newest event time admitted on this stream
+    pub fn max_event_ts(&self) -> Option<i64> {
+        self.max_event_ts
+    }
+
+    /// Oldest event time still admitted (None before the first trade)
+    pub fn current(&self) -> Option<i64> {
+        self.max_event_ts.map(|ts| ts - self.allowed_lateness)
+    }
+
+    /// Admit a trade at `timestamp` given the wall clock `wall_now`,
+    /// advancing the watermark
+    pub fn admit(&mut self, timestamp: i64, wall_now: i64) -> Result<(), QuarantineReason> {
+        if timestamp <= 0 {
+            return Err(QuarantineReason::MissingTimestamp);
+        }
+
+        if timestamp > wall_now + MAX_FUTURE_SKEW_SECS {
+            return Err(QuarantineReason::Future);
+        }
+
+        if self.current().is_some_and(|watermark| timestamp < watermark) {
+            return Err(QuarantineReason::Late);
+        }
+
+        self.max_event_ts = Some(self.max_event_ts.map_or(timestamp, |ts| ts.max(timestamp)));
+        Ok(())
+    }
+}
+
+/// Newest admitted event time across all streams
+#[derive(Debug, Default)]
+pub struct EventClock {
+    now: AtomicI64,
+}
+
+impl EventClock {
+    /// Move the clock forward to `timestamp` (never backwards)
+    pub fn advance(&self, timestamp: i64) {
+        self.now.fetch_max(timestamp, Ordering::Relaxed);
+    }
+
+    /// Current event time, None until the first trade or warm start
+    pub fn now(&self) -> Option<i64> {
+        match self.now.load(Ordering::Relaxed) {
+            0 => None,
+            ts => Some(ts),
+        }
+    }
+}
+
+/// Counters for trades kept out of rolling state
+#[derive(Debug, Default)]
+pub struct QuarantineStats {
+    pub missing_timestamp: AtomicU64,
+    pub late: AtomicU64,
+    pub future: AtomicU64,
+}
+
+impl QuarantineStats {
+    /// Count a quarantined trade, returning the new total for its reason
+    pub fn record(&self, reason: QuarantineReason) -> u64 {
+        let counter = match reason {
+            QuarantineReason::MissingTimestamp => &self.missing_timestamp,
+            QuarantineReason::Late => &self.late,
+            QuarantineReason::Future => &self.future,
+        };
+        counter.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Counts by reason: (missing timestamp, late, future)
+    pub fn counts(&self) -> [u64; 3] {
+        [
+            self.missing_timestamp.load(Ordering::Relaxed),
+            self.late.load(Ordering::Relaxed),
+            self.future.load(Ordering::Relaxed),
+        ]
+    }
+
+    /// Total quarantined trades
+    pub fn total(&self) -> u64 {
+        self.missing_timestamp.load(Ordering::Relaxed)
+            + self.late.load(Ordering::Relaxed)
+            + self.future.load(Ordering::Relaxed)
+    }
+}
+
+/// Background loop that logs the trades quarantined since the last report,
+/// by reason; quiet intervals are not logged
+pub async fn run_report_loop(stats: Arc<QuarantineStats>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    let mut last = stats.counts();
+
+    loop {
+        ticker.tick().await;
+
+        let counts = stats.counts();
+        let [missing, late, future] = [0, 1, 2].map(|i| counts[i] - last[i]);
+        if missing + late + future > 0 {
+            log::info!(
+                "🕰️  QUARANTINE | Last {}s: missing_timestamp {} / late {} / future {} | Total: {}",
+                interval.as_secs(),
+                missing,
+                late,
+                future,
+                stats.total()
+            );
+        }
+        last = counts;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watermark_admits_within_lateness() {
+        let mut watermark = Watermark::new(30);
+        assert_eq!(watermark.current(), None);
+
+        assert!(watermark.admit(1_000, 1_100).is_ok());
+        assert!(watermark.admit(1_100, 1_100).is_ok());
+        assert_eq!(watermark.current(), Some(1_070));
+
+        // Out of order but within lateness: admitted, watermark unchanged
+        assert!(watermark.admit(1_070, 1_100).is_ok());
+        assert_eq!(watermark.max_event_ts(), Some(1_100));
+
+        assert_eq!(watermark.admit(1_069, 1_100), Err(QuarantineReason::Late));
+        assert_eq!(watermark.admit(0, 1_100), Err(QuarantineReason::MissingTimestamp));
+        assert_eq!(watermark.max_event_ts(), Some(1_100));
+    }
+
+    #[test]
+    fn test_watermark_rejects_future_timestamps() {
+        let mut watermark = Watermark::new(30);
+        assert!(watermark.admit(1_000, 1_000).is_ok());
+
+        // Within the skew: admitted
+        assert!(watermark.admit(1_000 + MAX_FUTURE_SKEW_SECS, 1_000).is_ok());
+        // Beyond it: quarantined, and the watermark does not jump ahead
+        assert_eq!(watermark.admit(1_000 + 86_400, 1_000), Err(QuarantineReason::Future));
+        assert_eq!(watermark.max_event_ts(), Some(1_000 + MAX_FUTURE_SKEW_SECS));
+        assert!(watermark.admit(1_040, 1_040).is_ok());
+    }
+
+    #[test]
+    fn test_event_clock_never_moves_backwards() {
+        let clock = EventClock::default();
+        assert_eq!(clock.now(), None);
+
+        clock.advance(1_000);
+        clock.advance(900);
+        assert_eq!(clock.now(), Some(1_000));
+    }
+
+    #[test]
+    fn test_quarantine_stats() {
+        let stats = QuarantineStats::default();
+        assert_eq!(stats.record(QuarantineReason::Late), 1);
+        assert_eq!(stats.record(QuarantineReason::Late), 2);
+        assert_eq!(stats.record(QuarantineReason::MissingTimestamp), 1);
+        assert_eq!(stats.record(QuarantineReason::Future), 1);
+        assert_eq!(stats.total(), 4);
+        assert_eq!(stats.counts(), [1, 2, 1]);
+    }
+}
@@ -14,8 +14,7 @@ fn test_pumpfun_buy_extraction() {
         token_decimals: 6,
         user_account: "user123".to_string(),
         source_program: "Pumpfun".to_string(),
-        is_bot: false,
-        is_dca: false,
+        ..Default::default()
     };
 
     assert_eq!(trade_event.direction, TradeDirection::Buy);
@@ -37,8 +36,7 @@ fn test_rolling_state_update() {
         token_decimals: 6,
         user_account: "buyer1".to_string(),
         source_program: "PumpSwap".to_string(),
-        is_bot: false,
-        is_dca: false,
+        ..Default::default()
     };
 
     rolling_state.add_trade(buy_trade);
@@ -47,9 +45,9 @@ fn test_rolling_state_update() {
 
     let metrics = rolling_state.compute_rolling_metrics();
 
-    assert_eq!(metrics.net_flow_60s_sol, 2.0);
-    assert_eq!(metrics.buy_count_60s, 1);
-    assert_eq!(metrics.sell_count_60s, 0);
+    assert_eq!(metrics.window(60).net_flow_sol, 2.0);
+    assert_eq!(metrics.window(60).buy_count, 1);
+    assert_eq!(metrics.window(60).sell_count, 0);
 }
 
 #[test]
@@ -66,8 +64,7 @@ fn test_rolling_state_buy_and_sell() {
         token_decimals: 6,
         user_account: "buyer1".to_string(),
         source_program: "Pumpfun".to_string(),
-        is_bot: false,
-        is_dca: false,
+        ..Default::default()
     };
 
     let sell_trade = TradeEvent {
@@ -79,8 +76,7 @@ fn test_rolling_state_buy_and_sell() {
         token_decimals: 6,
         user_account: "seller1".to_string(),
         source_program: "Pumpfun".to_string(),
-        is_bot: false,
-        is_dca: false,
+        ..Default::default()
     };
 
     rolling_state.add_trade(buy_trade);
@@ -90,9 +86,9 @@ fn test_rolling_state_buy_and_sell() {
 
     let metrics = rolling_state.compute_rolling_metrics();
 
-    assert_eq!(metrics.net_flow_60s_sol, 3.0);
-    assert_eq!(metrics.buy_count_60s, 1);
-    assert_eq!(metrics.sell_count_60s, 1);
+    assert_eq!(metrics.window(60).net_flow_sol, 3.0);
+    assert_eq!(metrics.window(60).buy_count, 1);
+    assert_eq!(metrics.window(60).sell_count, 1);
 }
 
 #[test]
@@ -109,8 +105,7 @@ fn test_rolling_state_eviction() {
         token_decimals: 6,
         user_account: "old_buyer".to_string(),
         source_program: "Moonshot".to_string(),
-        is_bot: false,
-        is_dca: false,
+        ..Default::default()
     };
 
     let new_trade = TradeEvent {
@@ -122,8 +117,7 @@ fn test_rolling_state_eviction() {
         token_decimals: 6,
         user_account: "new_buyer".to_string(),
         source_program: "Moonshot".to_string(),
-        is_bot: false,
-        is_dca: false,
+        ..Default::default()
     };
 
     rolling_state.add_trade(old_trade);
@@ -133,8 +127,8 @@ fn test_rolling_state_eviction() {
 
     let metrics = rolling_state.compute_rolling_metrics();
 
-    assert_eq!(metrics.buy_count_60s, 1);
-    assert_eq!(metrics.net_flow_60s_sol, 2.0);
+    assert_eq!(metrics.window(60).buy_count, 1);
+    assert_eq!(metrics.window(60).net_flow_sol, 2.0);
 }
 
 #[test]
@@ -158,8 +152,8 @@ fn test_dca_tracking() {
         token_decimals: 6,
         user_account: "dca_user".to_string(),
         source_program: "JupiterDCA".to_string(),
-        is_bot: false,
         is_dca: true,
+        ..Default::default()
     };
 
     rolling_state.add_trade(dca_trade);
@@ -167,6 +161,6 @@ fn test_dca_tracking() {
 
     let metrics = rolling_state.compute_rolling_metrics();
 
-    assert_eq!(metrics.dca_buys_60s, 1);
-    assert_eq!(metrics.dca_buys_300s, 1);
+    assert_eq!(metrics.window(60).dca_buys, 1);
+    assert_eq!(metrics.window(300).dca_buys, 1);
 }